@@ -16,7 +16,7 @@ fn main() {
     };
     println!("Cover art ID is: {}", cover_art_id);
 
-    let size = Some(5);
-    let final_size = size.unwrap_or(300).max(50).min(2000) as u32;
+    let size: Option<i32> = std::env::args().nth(1).and_then(|s| s.parse().ok());
+    let final_size = size.unwrap_or(300).clamp(50, 2000) as u32;
     println!("size={:?} final_size={}", size, final_size);
 }
@@ -0,0 +1,5 @@
+-- 正在播放表增加播放器名称 (来自客户端参数 c)
+-- 每个用户的每个播放器只保留一条正在播放记录
+ALTER TABLE now_playing ADD COLUMN player_name TEXT NOT NULL DEFAULT '';
+
+CREATE UNIQUE INDEX idx_now_playing_user_player ON now_playing(user_id, player_name);
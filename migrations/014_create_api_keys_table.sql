@@ -0,0 +1,13 @@
+-- API 密钥表 (OpenSubsonic apiKey 认证)
+-- 只保存密钥的 SHA-256 哈希，明文仅在创建时返回一次
+CREATE TABLE api_keys (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    key_hash TEXT NOT NULL UNIQUE,
+    key_prefix TEXT NOT NULL,           -- 密钥前几位，便于用户识别
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    last_used_at TEXT
+);
+
+CREATE INDEX idx_api_keys_user ON api_keys(user_id);
@@ -0,0 +1,18 @@
+-- 音乐文件夹表 (音乐库根目录)
+-- 已从配置中移除的文件夹只标记为缺失 (missing)，保留其专辑、歌曲与用户授权，
+-- 重新加入配置后恢复；管理员可通过 purgeMusicFolders 显式清除
+CREATE TABLE music_folders (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    path TEXT NOT NULL UNIQUE,
+    missing BOOLEAN NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- 专辑与歌曲所属的音乐文件夹 (历史数据在启动同步文件夹时按路径回填)
+ALTER TABLE albums ADD COLUMN music_folder_id INTEGER REFERENCES music_folders(id) ON DELETE CASCADE;
+ALTER TABLE songs ADD COLUMN music_folder_id INTEGER REFERENCES music_folders(id) ON DELETE CASCADE;
+
+-- 索引
+CREATE INDEX idx_albums_music_folder_id ON albums(music_folder_id);
+CREATE INDEX idx_songs_music_folder_id ON songs(music_folder_id);
@@ -0,0 +1,13 @@
+-- 邀请码表 (REGISTRATION_MODE=invite 时凭邀请码自助注册)
+-- 每个邀请码只能使用一次
+CREATE TABLE invite_codes (
+    id TEXT PRIMARY KEY,
+    code TEXT NOT NULL UNIQUE,
+    created_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TEXT,                    -- 为空表示永不过期
+    used_by TEXT REFERENCES users(id) ON DELETE SET NULL,
+    used_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_invite_codes_code ON invite_codes(code);
@@ -0,0 +1,15 @@
+-- 用户可访问的音乐文件夹 (仅对非管理员生效，管理员始终可访问全部文件夹)
+CREATE TABLE user_music_folders (
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    music_folder_id INTEGER NOT NULL REFERENCES music_folders(id) ON DELETE CASCADE,
+    PRIMARY KEY (user_id, music_folder_id)
+);
+
+CREATE INDEX idx_user_music_folders_folder ON user_music_folders(music_folder_id);
+
+-- 现有用户默认可访问全部文件夹
+INSERT INTO user_music_folders (user_id, music_folder_id)
+SELECT u.id, f.id FROM users u, music_folders f;
+
+-- 新用户不自动获得访问权限: createUser 未指定 musicFolderId 时由服务层授权当前全部文件夹，
+-- 自助注册和反向代理自动创建的用户需要管理员通过 updateUser 分配
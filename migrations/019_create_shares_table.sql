@@ -0,0 +1,22 @@
+-- 分享表 (通过公开链接 /share/{token} 免登录收听)
+CREATE TABLE shares (
+    id TEXT PRIMARY KEY,
+    token TEXT NOT NULL UNIQUE,         -- 公开链接中的随机令牌
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    description TEXT,
+    expires_at TEXT,                    -- 为空表示永不过期
+    last_visited TEXT,
+    visit_count INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX idx_shares_user ON shares(user_id);
+
+-- 分享内容 (歌曲、专辑或播放列表)，访问时展开为歌曲列表
+CREATE TABLE share_entries (
+    share_id TEXT NOT NULL REFERENCES shares(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    item_type TEXT NOT NULL CHECK (item_type IN ('song', 'album', 'playlist')),
+    item_id TEXT NOT NULL,
+    PRIMARY KEY (share_id, position)
+);
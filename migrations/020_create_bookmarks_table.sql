@@ -0,0 +1,10 @@
+-- 书签表 (记录歌曲的续播位置，每个用户每首歌曲一个书签)
+CREATE TABLE bookmarks (
+    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    song_id TEXT NOT NULL REFERENCES songs(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL DEFAULT 0,    -- 播放位置（毫秒）
+    comment TEXT,
+    created_at TEXT NOT NULL,
+    changed_at TEXT NOT NULL,
+    PRIMARY KEY (user_id, song_id)
+);
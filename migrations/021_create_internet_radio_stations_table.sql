@@ -0,0 +1,9 @@
+-- 网络电台表
+CREATE TABLE internet_radio_stations (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    stream_url TEXT NOT NULL,
+    homepage_url TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
@@ -0,0 +1,37 @@
+-- 播客频道表
+CREATE TABLE podcast_channels (
+    id TEXT PRIMARY KEY,
+    url TEXT NOT NULL UNIQUE,               -- RSS 订阅源地址
+    title TEXT,
+    description TEXT,
+    image_url TEXT,
+    status TEXT NOT NULL DEFAULT 'new' CHECK (status IN ('new', 'completed', 'error')),
+    error_message TEXT,
+    last_refreshed_at TEXT,
+    created_at TEXT NOT NULL
+);
+
+-- 播客单集表
+-- status: new 未下载 / downloading 排队或下载中 / completed 已下载 / error 下载失败 / deleted 已删除
+CREATE TABLE podcast_episodes (
+    id TEXT PRIMARY KEY,
+    channel_id TEXT NOT NULL REFERENCES podcast_channels(id) ON DELETE CASCADE,
+    guid TEXT NOT NULL,                     -- 订阅源中的唯一标识，刷新时去重
+    title TEXT NOT NULL,
+    description TEXT,
+    stream_url TEXT NOT NULL,               -- 订阅源中的音频地址
+    content_type TEXT,
+    file_size INTEGER,
+    duration INTEGER,
+    publish_date TEXT,
+    status TEXT NOT NULL DEFAULT 'new'
+        CHECK (status IN ('new', 'downloading', 'completed', 'error', 'deleted')),
+    file_path TEXT,                         -- 下载后的本地文件
+    error_message TEXT,
+    created_at TEXT NOT NULL,
+    updated_at TEXT NOT NULL,
+    UNIQUE (channel_id, guid)
+);
+
+CREATE INDEX idx_podcast_episodes_channel ON podcast_episodes(channel_id, publish_date);
+CREATE INDEX idx_podcast_episodes_status ON podcast_episodes(status);
@@ -0,0 +1,2 @@
+-- 点唱机权限 (jukeboxControl)
+ALTER TABLE users ADD COLUMN jukebox_role BOOLEAN NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+-- 视频记录所属的音乐文件夹，按用户可访问的文件夹过滤
+ALTER TABLE videos ADD COLUMN music_folder_id INTEGER REFERENCES music_folders(id) ON DELETE CASCADE;
+
+CREATE UNIQUE INDEX idx_videos_file_path ON videos(file_path);
+CREATE INDEX idx_videos_music_folder ON videos(music_folder_id);
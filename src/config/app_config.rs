@@ -2,19 +2,308 @@
 #![allow(dead_code)]
 
 use dotenvy::dotenv;
+use std::collections::HashMap;
 use std::env;
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
 
-/// 应用配置结构体
+use crate::utils::ip_cidr::IpCidr;
+use crate::utils::password_cipher::{self, LEGACY_DEFAULT_PASSWORD_KEY};
+
+/// 转码命令环境变量前缀，例如 `TRANSCODER_MP3`
+const TRANSCODER_ENV_PREFIX: &str = "TRANSCODER_";
+
+/// 音乐文件夹配置 (音乐库根目录)
+#[derive(Debug, Clone, PartialEq)]
+pub struct MusicFolderConfig {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// 自助注册策略 (`/api/auth/register`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistrationMode {
+    /// 关闭自助注册，只能由管理员创建用户
+    Disabled,
+    /// 开放注册
+    Open,
+    /// 凭管理员签发的邀请码注册
+    Invite,
+}
+
+impl RegistrationMode {
+    /// 解析 `REGISTRATION_MODE` 取值
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "disabled" => Some(Self::Disabled),
+            "open" => Some(Self::Open),
+            "invite" => Some(Self::Invite),
+            _ => None,
+        }
+    }
+}
+
+/// 新用户的默认角色 (反向代理自动创建用户时使用)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DefaultUserRoles {
+    pub download: bool,
+    pub upload: bool,
+    pub playlist: bool,
+    pub cover_art: bool,
+    pub comment: bool,
+    pub podcast: bool,
+    pub share: bool,
+    pub video_conversion: bool,
+    pub jukebox: bool,
+    pub scrobbling: bool,
+}
+
+impl Default for DefaultUserRoles {
+    /// 与新建用户的数据库默认值一致
+    fn default() -> Self {
+        Self {
+            download: true,
+            upload: false,
+            playlist: true,
+            cover_art: true,
+            comment: false,
+            podcast: false,
+            share: true,
+            video_conversion: false,
+            jukebox: false,
+            scrobbling: true,
+        }
+    }
+}
+
+impl DefaultUserRoles {
+    /// 解析逗号分隔的角色列表，例如 `download,playlist,coverArt`
+    ///
+    /// 列表中的角色开启，其余关闭；遇到未知角色时返回 `None`
+    pub fn parse(value: &str) -> Option<Self> {
+        let mut roles = Self {
+            download: false,
+            upload: false,
+            playlist: false,
+            cover_art: false,
+            comment: false,
+            podcast: false,
+            share: false,
+            video_conversion: false,
+            jukebox: false,
+            scrobbling: false,
+        };
+
+        for name in value.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+            let role = match name {
+                "download" => &mut roles.download,
+                "upload" => &mut roles.upload,
+                "playlist" => &mut roles.playlist,
+                "coverArt" => &mut roles.cover_art,
+                "comment" => &mut roles.comment,
+                "podcast" => &mut roles.podcast,
+                "share" => &mut roles.share,
+                "videoConversion" => &mut roles.video_conversion,
+                "jukebox" => &mut roles.jukebox,
+                "scrobbling" => &mut roles.scrobbling,
+                _ => return None,
+            };
+            *role = true;
+        }
+
+        Some(roles)
+    }
+}
+
+/// 反向代理认证配置
+///
+/// 信任受信任代理转发的用户名请求头 (例如 Authelia / oauth2-proxy 的 `Remote-User`)
 #[derive(Debug, Clone)]
+pub struct ReverseProxyConfig {
+    /// 用户名请求头
+    pub user_header: String,
+    /// 受信任的代理网段，只有来自这些地址的请求头才会被采信
+    pub trusted_proxies: Vec<IpCidr>,
+    /// 自动创建用户时的默认角色
+    pub default_roles: DefaultUserRoles,
+}
+
+/// 日志输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogFormat {
+    /// 人类可读的文本格式
+    Text,
+    /// 结构化 JSON 格式 (每行一条记录，便于日志采集)
+    Json,
+}
+
+impl LogFormat {
+    /// 解析 `LOG_FORMAT` 取值
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "text" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+}
+
+/// 点唱机音频输出 (`JUKEBOX_SINK`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JukeboxSink {
+    /// 丢弃音频，按实时速度播放 (没有声卡的服务器)
+    Null,
+    /// 写入 WAV 文件
+    WavFile(PathBuf),
+    /// 通过声卡播放，需要启用 `jukebox-output` 特性
+    Device,
+}
+
+impl JukeboxSink {
+    /// 解析 `JUKEBOX_SINK` 取值: `null`、`wav:<文件路径>` 或 `device`
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Some(path) = value.strip_prefix("wav:") {
+            let path = path.trim();
+            return (!path.is_empty()).then(|| Self::WavFile(PathBuf::from(path)));
+        }
+        match value.to_ascii_lowercase().as_str() {
+            "null" => Some(Self::Null),
+            "device" if cfg!(feature = "jukebox-output") => Some(Self::Device),
+            _ => None,
+        }
+    }
+}
+
+/// 应用配置结构体
+#[derive(Clone)]
 pub struct AppConfig {
     pub database_url: String,
     pub port: u16,
     pub host: String,
     pub music_library_path: PathBuf,
+    /// 音乐文件夹列表，未配置 `MUSIC_FOLDERS` 时为 `music_library_path`
+    pub music_folders: Vec<MusicFolderConfig>,
+    /// 监听音乐文件夹的文件变化并自动增量扫描，默认关闭
+    pub watch_library: bool,
+    /// 文件变化停止多久后开始扫描 (毫秒)
+    pub watch_debounce_ms: u64,
     pub rust_log: String,
+    /// 日志输出格式，默认文本
+    pub log_format: LogFormat,
     pub app_name: String,
     pub app_version: String,
+    /// 转码命令模板 (目标格式 -> 命令)，`%s` 为源文件路径，`%b` 为码率 (kbps)
+    pub transcoders: HashMap<String, String>,
+    /// 仅指定 maxBitRate 时使用的默认转码格式
+    pub transcode_default_format: String,
+    /// HLS 分片转码命令模板，额外支持 `%t` 起始秒数与 `%l` 时长秒数
+    pub hls_segment_transcoder: String,
+    /// 转码缓存目录
+    pub transcode_cache_dir: PathBuf,
+    /// 转码缓存总大小上限 (字节)，0 表示禁用缓存
+    pub transcode_cache_max_size: u64,
+    /// 正在播放记录的保留时长 (分钟)
+    pub now_playing_ttl_minutes: i64,
+    /// 密码加密密钥
+    pub password_encryption_key: String,
+    /// 轮换前的旧密码加密密钥 (仅用于解密并重新加密)
+    pub password_encryption_old_keys: Vec<String>,
+    /// 会话令牌签名密钥，未设置时使用密码加密密钥
+    pub session_secret: String,
+    /// 会话令牌有效期 (小时)
+    pub session_ttl_hours: i64,
+    /// 自助注册策略，默认关闭
+    pub registration_mode: RegistrationMode,
+    /// 触发登录锁定的连续失败次数，0 表示不限流
+    pub login_max_attempts: u32,
+    /// 首次登录锁定时长 (秒)，之后每次失败翻倍
+    pub login_lockout_seconds: i64,
+    /// 最长登录锁定时长 (秒)
+    pub login_lockout_max_seconds: i64,
+    /// 受信任的反向代理网段，来自这些地址的请求按 `X-Forwarded-For` 识别客户端 IP
+    pub trusted_proxies: Vec<IpCidr>,
+    /// 反向代理认证，未配置 `REVERSE_PROXY_USER_HEADER` 时关闭
+    pub reverse_proxy: Option<ReverseProxyConfig>,
+    /// 分享链接的外部访问地址 (例如 `https://music.example.com`)，为空时按请求的 Host 生成
+    pub share_base_url: Option<String>,
+    /// 每个客户端 IP 每分钟可访问公开分享页面的次数，0 表示不限流
+    pub share_rate_limit: u32,
+    /// 每个客户端 IP 每分钟可请求同一分享中歌曲流的次数 (含续传请求)，0 表示不限流
+    pub share_stream_rate_limit: u32,
+    /// 播客单集下载目录
+    pub podcast_dir: PathBuf,
+    /// 每个播客频道保留的已下载单集数，0 表示不限制
+    pub podcast_retention: u32,
+    /// 刷新频道时自动下载的最新单集数，0 表示不自动下载
+    pub podcast_auto_download: u32,
+    /// 自动刷新所有播客频道的间隔 (分钟)，0 表示不自动刷新
+    pub podcast_refresh_interval_minutes: u64,
+    /// 单个播客单集下载的总时长上限 (分钟)，0 表示不限制
+    pub podcast_download_timeout_minutes: u64,
+    /// 单个播客单集的大小上限 (MB)，0 表示不限制
+    pub podcast_max_episode_size_mb: u64,
+    /// 点唱机音频输出，默认丢弃音频
+    pub jukebox_sink: JukeboxSink,
+}
+
+/// 调试输出时隐藏密钥
+impl fmt::Debug for AppConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const REDACTED: &str = "***";
+
+        f.debug_struct("AppConfig")
+            .field("database_url", &self.database_url)
+            .field("port", &self.port)
+            .field("host", &self.host)
+            .field("music_library_path", &self.music_library_path)
+            .field("music_folders", &self.music_folders)
+            .field("watch_library", &self.watch_library)
+            .field("watch_debounce_ms", &self.watch_debounce_ms)
+            .field("rust_log", &self.rust_log)
+            .field("log_format", &self.log_format)
+            .field("app_name", &self.app_name)
+            .field("app_version", &self.app_version)
+            .field("transcoders", &self.transcoders)
+            .field("transcode_default_format", &self.transcode_default_format)
+            .field("hls_segment_transcoder", &self.hls_segment_transcoder)
+            .field("transcode_cache_dir", &self.transcode_cache_dir)
+            .field("transcode_cache_max_size", &self.transcode_cache_max_size)
+            .field("now_playing_ttl_minutes", &self.now_playing_ttl_minutes)
+            .field("password_encryption_key", &REDACTED)
+            .field(
+                "password_encryption_old_keys",
+                &vec![REDACTED; self.password_encryption_old_keys.len()],
+            )
+            .field("session_secret", &REDACTED)
+            .field("session_ttl_hours", &self.session_ttl_hours)
+            .field("registration_mode", &self.registration_mode)
+            .field("login_max_attempts", &self.login_max_attempts)
+            .field("login_lockout_seconds", &self.login_lockout_seconds)
+            .field("login_lockout_max_seconds", &self.login_lockout_max_seconds)
+            .field("trusted_proxies", &self.trusted_proxies)
+            .field("reverse_proxy", &self.reverse_proxy)
+            .field("share_base_url", &self.share_base_url)
+            .field("share_rate_limit", &self.share_rate_limit)
+            .field("share_stream_rate_limit", &self.share_stream_rate_limit)
+            .field("podcast_dir", &self.podcast_dir)
+            .field("podcast_retention", &self.podcast_retention)
+            .field("podcast_auto_download", &self.podcast_auto_download)
+            .field(
+                "podcast_refresh_interval_minutes",
+                &self.podcast_refresh_interval_minutes,
+            )
+            .field(
+                "podcast_download_timeout_minutes",
+                &self.podcast_download_timeout_minutes,
+            )
+            .field(
+                "podcast_max_episode_size_mb",
+                &self.podcast_max_episode_size_mb,
+            )
+            .field("jukebox_sink", &self.jukebox_sink)
+            .finish()
+    }
 }
 
 impl AppConfig {
@@ -23,6 +312,75 @@ impl AppConfig {
         // 加载 .env 文件
         dotenv().ok();
 
+        let music_library_path = PathBuf::from(
+            env::var("MUSIC_LIBRARY_PATH").unwrap_or_else(|_| "/path/to/music".to_string()),
+        );
+        let mut password_encryption_old_keys: Vec<String> =
+            env::var("PASSWORD_ENCRYPTION_OLD_KEYS")
+                .map(|v| {
+                    v.split(',')
+                        .map(|k| k.trim().to_string())
+                        .filter(|k| !k.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default();
+        // 未配置密钥时使用密钥文件 (首次启动时生成随机密钥)，
+        // 并保留旧版内置密钥用于解密，启动时重新加密为新密钥
+        let password_encryption_key = match env::var("PASSWORD_ENCRYPTION_KEY") {
+            Ok(key) if !key.is_empty() => key,
+            _ => {
+                let key_file = PathBuf::from(
+                    env::var("PASSWORD_ENCRYPTION_KEY_FILE")
+                        .unwrap_or_else(|_| "./data/password_encryption.key".to_string()),
+                );
+                password_encryption_old_keys.push(LEGACY_DEFAULT_PASSWORD_KEY.to_string());
+                password_cipher::load_or_create_key_file(&key_file).map_err(|e| {
+                    anyhow::anyhow!(
+                        "Failed to load password key file {}: {}",
+                        key_file.display(),
+                        e
+                    )
+                })?
+            }
+        };
+        let session_secret = env::var("SESSION_SECRET")
+            .ok()
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| password_encryption_key.clone());
+        if password_cipher::is_compromised_key(&password_encryption_key) {
+            return Err(anyhow::anyhow!(
+                "The password encryption key has been published and must not be used. \
+                 Generate a new key and move the old one to PASSWORD_ENCRYPTION_OLD_KEYS \
+                 so stored passwords are re-encrypted"
+            ));
+        }
+        if password_cipher::is_compromised_key(&session_secret) {
+            return Err(anyhow::anyhow!(
+                "SESSION_SECRET has been published and must not be used"
+            ));
+        }
+        let music_folders = Self::parse_music_folders(
+            &env::var("MUSIC_FOLDERS").unwrap_or_default(),
+            &music_library_path,
+        );
+        let log_format = match env::var("LOG_FORMAT") {
+            Ok(value) if !value.trim().is_empty() => LogFormat::parse(&value)
+                .ok_or_else(|| anyhow::anyhow!("Invalid LOG_FORMAT: {}", value))?,
+            _ => LogFormat::Text,
+        };
+        let trusted_proxies = Self::load_trusted_proxies()?;
+        let reverse_proxy = Self::load_reverse_proxy(&trusted_proxies)?;
+        let registration_mode = match env::var("REGISTRATION_MODE") {
+            Ok(value) if !value.trim().is_empty() => RegistrationMode::parse(&value)
+                .ok_or_else(|| anyhow::anyhow!("Invalid REGISTRATION_MODE: {}", value))?,
+            _ => RegistrationMode::Disabled,
+        };
+        let jukebox_sink = match env::var("JUKEBOX_SINK") {
+            Ok(value) if !value.trim().is_empty() => JukeboxSink::parse(&value)
+                .ok_or_else(|| anyhow::anyhow!("Invalid JUKEBOX_SINK: {}", value))?,
+            _ => JukeboxSink::Null,
+        };
+
         Ok(Self {
             database_url: env::var("DATABASE_URL")
                 .unwrap_or_else(|_| "sqlite:data/music_flow.db".to_string()),
@@ -31,34 +389,241 @@ impl AppConfig {
                 .parse()
                 .unwrap_or(4040),
             host: env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
-            music_library_path: PathBuf::from(
-                env::var("MUSIC_LIBRARY_PATH").unwrap_or_else(|_| "/path/to/music".to_string()),
-            ),
+            music_library_path,
+            music_folders,
+            watch_library: env::var("WATCH_LIBRARY")
+                .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                .unwrap_or(false),
+            watch_debounce_ms: env::var("WATCH_DEBOUNCE_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(2000),
             rust_log: env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
+            log_format,
             app_name: env::var("APP_NAME").unwrap_or_else(|_| "MusicFlowServer".to_string()),
             app_version: env::var("APP_VERSION").unwrap_or_else(|_| "1.0.0".to_string()),
+            transcoders: Self::load_transcoders(),
+            transcode_default_format: env::var("TRANSCODE_DEFAULT_FORMAT")
+                .unwrap_or_else(|_| "mp3".to_string())
+                .to_lowercase(),
+            hls_segment_transcoder: env::var("HLS_SEGMENT_TRANSCODER").unwrap_or_else(|_| {
+                "ffmpeg -v 0 -ss %t -t %l -i %s -map 0:a:0 -c:a aac -b:a %bk -output_ts_offset %t -f mpegts -"
+                    .to_string()
+            }),
+            transcode_cache_dir: PathBuf::from(
+                env::var("TRANSCODE_CACHE_DIR").unwrap_or_else(|_| "./coverArt/transcode".to_string()),
+            ),
+            transcode_cache_max_size: env::var("TRANSCODE_CACHE_SIZE_MB")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(1024)
+                * 1024
+                * 1024,
+            now_playing_ttl_minutes: env::var("NOW_PLAYING_TTL_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(15),
+            password_encryption_old_keys,
+            session_secret,
+            session_ttl_hours: env::var("SESSION_TTL_HOURS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|hours| *hours > 0)
+                .unwrap_or(24),
+            registration_mode,
+            login_max_attempts: env::var("LOGIN_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5),
+            login_lockout_seconds: env::var("LOGIN_LOCKOUT_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|seconds| *seconds > 0)
+                .unwrap_or(30),
+            login_lockout_max_seconds: env::var("LOGIN_LOCKOUT_MAX_SECONDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .filter(|seconds| *seconds > 0)
+                .unwrap_or(3600),
+            trusted_proxies,
+            reverse_proxy,
+            share_base_url: env::var("SHARE_BASE_URL")
+                .ok()
+                .map(|v| v.trim().trim_end_matches('/').to_string())
+                .filter(|v| !v.is_empty()),
+            share_rate_limit: env::var("SHARE_RATE_LIMIT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            share_stream_rate_limit: env::var("SHARE_STREAM_RATE_LIMIT")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(600),
+            podcast_dir: PathBuf::from(
+                env::var("PODCAST_DIR").unwrap_or_else(|_| "./podcasts".to_string()),
+            ),
+            podcast_retention: env::var("PODCAST_RETENTION")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10),
+            podcast_auto_download: env::var("PODCAST_AUTO_DOWNLOAD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1),
+            podcast_refresh_interval_minutes: env::var("PODCAST_REFRESH_INTERVAL_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            podcast_download_timeout_minutes: env::var("PODCAST_DOWNLOAD_TIMEOUT_MINUTES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            podcast_max_episode_size_mb: env::var("PODCAST_MAX_EPISODE_SIZE_MB")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1024),
+            jukebox_sink,
+            password_encryption_key,
         })
     }
 
-    /// 获取服务器地址
-    pub fn server_address(&self) -> String {
-        format!("{}:{}", self.host, self.port)
+    /// 加载受信任的反向代理网段 (`REVERSE_PROXY_TRUSTED_CIDRS`，逗号分隔)
+    fn load_trusted_proxies() -> Result<Vec<IpCidr>, anyhow::Error> {
+        env::var("REVERSE_PROXY_TRUSTED_CIDRS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|cidr| !cidr.is_empty())
+            .map(|cidr| {
+                IpCidr::parse(cidr).ok_or_else(|| {
+                    anyhow::anyhow!("Invalid CIDR in REVERSE_PROXY_TRUSTED_CIDRS: {}", cidr)
+                })
+            })
+            .collect()
     }
 
-    /// 检查音乐库路径是否存在
-    pub fn validate_music_library(&self) -> Result<(), anyhow::Error> {
-        if !self.music_library_path.exists() {
+    /// 加载反向代理认证配置
+    ///
+    /// 启用时必须配置受信任的代理网段，防止任意客户端伪造用户名请求头
+    fn load_reverse_proxy(
+        trusted_proxies: &[IpCidr],
+    ) -> Result<Option<ReverseProxyConfig>, anyhow::Error> {
+        let user_header = match env::var("REVERSE_PROXY_USER_HEADER") {
+            Ok(value) if !value.trim().is_empty() => value.trim().to_string(),
+            _ => return Ok(None),
+        };
+        if axum::http::HeaderName::from_bytes(user_header.as_bytes()).is_err() {
             return Err(anyhow::anyhow!(
-                "Music library path does not exist: {}",
-                self.music_library_path.display()
+                "Invalid REVERSE_PROXY_USER_HEADER: {}",
+                user_header
             ));
         }
-        if !self.music_library_path.is_dir() {
+
+        if trusted_proxies.is_empty() {
             return Err(anyhow::anyhow!(
-                "Music library path is not a directory: {}",
-                self.music_library_path.display()
+                "REVERSE_PROXY_TRUSTED_CIDRS is required when REVERSE_PROXY_USER_HEADER is set"
             ));
         }
+
+        let default_roles = match env::var("REVERSE_PROXY_DEFAULT_ROLES") {
+            Ok(value) => DefaultUserRoles::parse(&value)
+                .ok_or_else(|| anyhow::anyhow!("Invalid REVERSE_PROXY_DEFAULT_ROLES: {}", value))?,
+            Err(_) => DefaultUserRoles::default(),
+        };
+
+        Ok(Some(ReverseProxyConfig {
+            user_header,
+            trusted_proxies: trusted_proxies.to_vec(),
+            default_roles,
+        }))
+    }
+
+    /// 加载转码命令模板
+    ///
+    /// 内置 mp3 / opus 的 ffmpeg 模板，可通过 `TRANSCODER_<FORMAT>` 环境变量覆盖或新增，
+    /// 值为空时禁用对应格式。
+    fn load_transcoders() -> HashMap<String, String> {
+        let mut transcoders = HashMap::from([
+            (
+                "mp3".to_string(),
+                "ffmpeg -v 0 -i %s -map 0:a:0 -b:a %bk -f mp3 -".to_string(),
+            ),
+            (
+                "opus".to_string(),
+                "ffmpeg -v 0 -i %s -map 0:a:0 -b:a %bk -c:a libopus -f opus -".to_string(),
+            ),
+        ]);
+
+        for (key, value) in env::vars() {
+            if let Some(format) = key.strip_prefix(TRANSCODER_ENV_PREFIX) {
+                let format = format.to_lowercase();
+                if value.trim().is_empty() {
+                    transcoders.remove(&format);
+                } else {
+                    transcoders.insert(format, value);
+                }
+            }
+        }
+
+        transcoders
+    }
+
+    /// 解析音乐文件夹配置
+    ///
+    /// 格式为 `名称=路径`，多个文件夹以 `;` 分隔，省略名称时使用目录名；
+    /// 未配置时使用 `MUSIC_LIBRARY_PATH` 作为唯一的音乐文件夹。
+    fn parse_music_folders(value: &str, music_library_path: &Path) -> Vec<MusicFolderConfig> {
+        let folders: Vec<MusicFolderConfig> = value
+            .split(';')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let (name, path) = match entry.split_once('=') {
+                    Some((name, path)) => (name.trim().to_string(), PathBuf::from(path.trim())),
+                    None => {
+                        let path = PathBuf::from(entry);
+                        let name = path
+                            .file_name()
+                            .map(|n| n.to_string_lossy().to_string())
+                            .unwrap_or_else(|| entry.to_string());
+                        (name, path)
+                    }
+                };
+                MusicFolderConfig { name, path }
+            })
+            .collect();
+
+        if folders.is_empty() {
+            vec![MusicFolderConfig {
+                name: "Music".to_string(),
+                path: music_library_path.to_path_buf(),
+            }]
+        } else {
+            folders
+        }
+    }
+
+    /// 获取服务器地址
+    pub fn server_address(&self) -> String {
+        format!("{}:{}", self.host, self.port)
+    }
+
+    /// 检查音乐文件夹路径是否存在
+    pub fn validate_music_library(&self) -> Result<(), anyhow::Error> {
+        for folder in &self.music_folders {
+            if !folder.path.exists() {
+                return Err(anyhow::anyhow!(
+                    "Music library path does not exist: {}",
+                    folder.path.display()
+                ));
+            }
+            if !folder.path.is_dir() {
+                return Err(anyhow::anyhow!(
+                    "Music library path is not a directory: {}",
+                    folder.path.display()
+                ));
+            }
+        }
         Ok(())
     }
 
@@ -70,9 +635,42 @@ impl AppConfig {
             port: 4040,
             host: "127.0.0.1".to_string(),
             music_library_path: PathBuf::from("/tmp/test_music"),
+            music_folders: vec![MusicFolderConfig {
+                name: "Music".to_string(),
+                path: PathBuf::from("/tmp/test_music"),
+            }],
+            watch_library: false,
+            watch_debounce_ms: 2000,
             rust_log: "error".to_string(),
+            log_format: LogFormat::Text,
             app_name: "TestServer".to_string(),
             app_version: "0.1.0".to_string(),
+            transcoders: HashMap::new(),
+            transcode_default_format: "mp3".to_string(),
+            hls_segment_transcoder: String::new(),
+            transcode_cache_dir: PathBuf::from("/tmp/test_transcode_cache"),
+            transcode_cache_max_size: 0,
+            now_playing_ttl_minutes: 15,
+            password_encryption_key: "test-password-key".to_string(),
+            password_encryption_old_keys: vec![],
+            session_secret: "test-session-secret".to_string(),
+            session_ttl_hours: 24,
+            registration_mode: RegistrationMode::Disabled,
+            login_max_attempts: 5,
+            login_lockout_seconds: 30,
+            login_lockout_max_seconds: 3600,
+            trusted_proxies: vec![],
+            reverse_proxy: None,
+            share_base_url: None,
+            share_rate_limit: 60,
+            share_stream_rate_limit: 600,
+            podcast_dir: PathBuf::from("/tmp/test_podcasts"),
+            podcast_retention: 10,
+            podcast_auto_download: 1,
+            podcast_refresh_interval_minutes: 60,
+            podcast_download_timeout_minutes: 60,
+            podcast_max_episode_size_mb: 1024,
+            jukebox_sink: JukeboxSink::Null,
         }
     }
 }
@@ -88,6 +686,12 @@ mod tests {
         std::env::set_var("PORT", "8080");
         std::env::set_var("HOST", "0.0.0.0");
         std::env::set_var("MUSIC_LIBRARY_PATH", "/tmp/music");
+        // 避免在工作目录下生成密钥文件
+        let key_dir = std::env::temp_dir().join(format!("config_key_{}", std::process::id()));
+        std::env::set_var(
+            "PASSWORD_ENCRYPTION_KEY_FILE",
+            key_dir.join("password.key").to_string_lossy().to_string(),
+        );
 
         let config = AppConfig::from_env().unwrap();
         assert_eq!(config.database_url, "sqlite:test.db");
@@ -99,6 +703,8 @@ mod tests {
         std::env::remove_var("PORT");
         std::env::remove_var("HOST");
         std::env::remove_var("MUSIC_LIBRARY_PATH");
+        std::env::remove_var("PASSWORD_ENCRYPTION_KEY_FILE");
+        std::fs::remove_dir_all(&key_dir).ok();
     }
 
     #[test]
@@ -108,11 +714,123 @@ mod tests {
             port: 4040,
             host: "127.0.0.1".to_string(),
             music_library_path: PathBuf::from("/tmp/music"),
+            music_folders: vec![],
+            watch_library: false,
+            watch_debounce_ms: 2000,
             rust_log: "info".to_string(),
+            log_format: LogFormat::Text,
             app_name: "Test".to_string(),
             app_version: "1.0.0".to_string(),
+            transcoders: HashMap::new(),
+            transcode_default_format: "mp3".to_string(),
+            hls_segment_transcoder: String::new(),
+            transcode_cache_dir: PathBuf::from("/tmp/test_transcode_cache"),
+            transcode_cache_max_size: 0,
+            now_playing_ttl_minutes: 15,
+            password_encryption_key: "test-password-key".to_string(),
+            password_encryption_old_keys: vec![],
+            session_secret: "test-session-secret".to_string(),
+            session_ttl_hours: 24,
+            registration_mode: RegistrationMode::Disabled,
+            login_max_attempts: 5,
+            login_lockout_seconds: 30,
+            login_lockout_max_seconds: 3600,
+            trusted_proxies: vec![],
+            reverse_proxy: None,
+            share_base_url: None,
+            share_rate_limit: 60,
+            share_stream_rate_limit: 600,
+            podcast_dir: PathBuf::from("/tmp/test_podcasts"),
+            podcast_retention: 10,
+            podcast_auto_download: 1,
+            podcast_refresh_interval_minutes: 60,
+            podcast_download_timeout_minutes: 60,
+            podcast_max_episode_size_mb: 1024,
+            jukebox_sink: JukeboxSink::Null,
         };
 
         assert_eq!(config.server_address(), "127.0.0.1:4040");
     }
+
+    #[test]
+    fn test_parse_music_folders() {
+        let default_path = PathBuf::from("/music");
+
+        let folders = AppConfig::parse_music_folders("", &default_path);
+        assert_eq!(folders.len(), 1);
+        assert_eq!(folders[0].name, "Music");
+        assert_eq!(folders[0].path, default_path);
+
+        let folders = AppConfig::parse_music_folders(
+            "Classical=/data/classical; /data/kids ;",
+            &default_path,
+        );
+        assert_eq!(
+            folders,
+            vec![
+                MusicFolderConfig {
+                    name: "Classical".to_string(),
+                    path: PathBuf::from("/data/classical"),
+                },
+                MusicFolderConfig {
+                    name: "kids".to_string(),
+                    path: PathBuf::from("/data/kids"),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_registration_mode() {
+        assert_eq!(
+            RegistrationMode::parse("disabled"),
+            Some(RegistrationMode::Disabled)
+        );
+        assert_eq!(
+            RegistrationMode::parse(" Open "),
+            Some(RegistrationMode::Open)
+        );
+        assert_eq!(
+            RegistrationMode::parse("INVITE"),
+            Some(RegistrationMode::Invite)
+        );
+        assert_eq!(RegistrationMode::parse("public"), None);
+    }
+
+    #[test]
+    fn test_debug_redacts_secrets() {
+        let mut config = AppConfig::test_config();
+        config.password_encryption_old_keys = vec!["old-password-key".to_string()];
+
+        let output = format!("{:?}", config);
+        assert!(!output.contains("test-password-key"));
+        assert!(!output.contains("old-password-key"));
+        assert!(!output.contains("test-session-secret"));
+        assert!(output.contains("database_url"));
+    }
+
+    #[test]
+    fn test_parse_default_user_roles() {
+        let roles = DefaultUserRoles::parse("download, coverArt,scrobbling").unwrap();
+        assert!(roles.download && roles.cover_art && roles.scrobbling);
+        assert!(!roles.playlist && !roles.share && !roles.upload);
+
+        let none = DefaultUserRoles::parse("").unwrap();
+        assert!(!none.download && !none.playlist && !none.scrobbling);
+        assert!(DefaultUserRoles::parse("download,admin").is_none());
+    }
+
+    #[test]
+    fn test_parse_jukebox_sink() {
+        assert_eq!(JukeboxSink::parse("NULL"), Some(JukeboxSink::Null));
+        assert_eq!(
+            JukeboxSink::parse("wav:/tmp/jukebox.wav"),
+            Some(JukeboxSink::WavFile(PathBuf::from("/tmp/jukebox.wav")))
+        );
+        assert_eq!(JukeboxSink::parse("wav:"), None);
+        assert_eq!(
+            JukeboxSink::parse("device").is_some(),
+            cfg!(feature = "jukebox-output")
+        );
+    }
 }
@@ -2,4 +2,7 @@
 
 pub mod app_config;
 
-pub use app_config::AppConfig;
+pub use app_config::{
+    AppConfig, DefaultUserRoles, JukeboxSink, LogFormat, MusicFolderConfig, RegistrationMode,
+    ReverseProxyConfig,
+};
@@ -8,15 +8,47 @@ use axum::{
 use serde::Serialize;
 use std::fmt;
 
+use crate::models::response::API_VERSION;
+
+/// Subsonic 错误码 (含 OpenSubsonic 扩展)
+pub mod error_code {
+    /// 通用错误
+    pub const GENERIC: i32 = 0;
+    /// 缺少必需参数
+    pub const MISSING_PARAMETER: i32 = 10;
+    /// 客户端协议版本过低，需要升级客户端
+    pub const CLIENT_TOO_OLD: i32 = 20;
+    /// 服务端协议版本过低，需要升级服务端
+    pub const SERVER_TOO_OLD: i32 = 30;
+    /// 用户名或密码错误
+    pub const WRONG_CREDENTIALS: i32 = 40;
+    /// 该用户不支持 token 认证
+    pub const TOKEN_NOT_SUPPORTED: i32 = 41;
+    /// 同时提供了多种互斥的认证方式 (OpenSubsonic)
+    pub const CONFLICTING_AUTH: i32 = 43;
+    /// API 密钥无效 (OpenSubsonic)
+    pub const INVALID_API_KEY: i32 = 44;
+    /// 用户无权执行该操作
+    pub const NOT_AUTHORIZED: i32 = 50;
+    /// 请求的数据不存在
+    pub const NOT_FOUND: i32 = 70;
+}
+
 /// 应用错误类型
 #[derive(Debug)]
 pub enum AppError {
     // Subsonic 错误码
     MissingParameter(String),
+    IncompatibleClient(String),
+    IncompatibleServer(String),
     AuthFailed(String),
+    TokenNotSupported(String),
+    ConflictingAuth(String),
+    InvalidApiKey(String),
     AccessDenied(String),
     NotFound(String),
     ServerBusy(String),
+    TooManyRequests(String),
 
     // 内部错误
     DatabaseError(sqlx::Error),
@@ -40,15 +72,30 @@ struct SubsonicErrorPayload {
     message: String,
 }
 
+/// 写入错误响应扩展中的 Subsonic 错误信息
+///
+/// `/rest/*` 请求由错误格式中间件按客户端请求的格式 (f 参数) 重新渲染
+#[derive(Debug, Clone)]
+pub struct SubsonicErrorInfo {
+    pub code: i32,
+    pub message: String,
+}
+
 // 实现 Display trait
 impl fmt::Display for AppError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             AppError::MissingParameter(msg) => write!(f, "Missing parameter: {}", msg),
+            AppError::IncompatibleClient(msg) => write!(f, "Incompatible client: {}", msg),
+            AppError::IncompatibleServer(msg) => write!(f, "Incompatible server: {}", msg),
             AppError::AuthFailed(msg) => write!(f, "Authentication failed: {}", msg),
+            AppError::TokenNotSupported(msg) => write!(f, "Token not supported: {}", msg),
+            AppError::ConflictingAuth(msg) => write!(f, "Conflicting authentication: {}", msg),
+            AppError::InvalidApiKey(msg) => write!(f, "Invalid API key: {}", msg),
             AppError::AccessDenied(msg) => write!(f, "Access denied: {}", msg),
             AppError::NotFound(msg) => write!(f, "Not found: {}", msg),
             AppError::ServerBusy(msg) => write!(f, "Server busy: {}", msg),
+            AppError::TooManyRequests(msg) => write!(f, "Too many requests: {}", msg),
             AppError::DatabaseError(err) => write!(f, "Database error: {}", err),
             AppError::IoError(err) => write!(f, "IO error: {}", err),
             AppError::AuthError(err) => write!(f, "Auth error: {}", err),
@@ -61,52 +108,98 @@ impl fmt::Display for AppError {
 // 实现 std::error::Error trait
 impl std::error::Error for AppError {}
 
-// 转换为 HTTP 响应
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let (status_code, error_code, message) = match self {
-            AppError::MissingParameter(msg) => (StatusCode::BAD_REQUEST, 10, msg),
-            AppError::AuthFailed(msg) => (StatusCode::UNAUTHORIZED, 30, msg),
-            AppError::AccessDenied(msg) => (StatusCode::FORBIDDEN, 40, msg),
-            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, 50, msg),
-            AppError::ServerBusy(msg) => (StatusCode::INTERNAL_SERVER_ERROR, 60, msg),
+impl AppError {
+    /// 映射为 (HTTP 状态码, Subsonic 错误码, 错误信息)
+    fn into_parts(self) -> (StatusCode, i32, String) {
+        match self {
+            AppError::MissingParameter(msg) => {
+                (StatusCode::BAD_REQUEST, error_code::MISSING_PARAMETER, msg)
+            }
+            AppError::IncompatibleClient(msg) => {
+                (StatusCode::BAD_REQUEST, error_code::CLIENT_TOO_OLD, msg)
+            }
+            AppError::IncompatibleServer(msg) => {
+                (StatusCode::BAD_REQUEST, error_code::SERVER_TOO_OLD, msg)
+            }
+            AppError::AuthFailed(msg) => {
+                (StatusCode::UNAUTHORIZED, error_code::WRONG_CREDENTIALS, msg)
+            }
+            AppError::TokenNotSupported(msg) => (
+                StatusCode::UNAUTHORIZED,
+                error_code::TOKEN_NOT_SUPPORTED,
+                msg,
+            ),
+            AppError::ConflictingAuth(msg) => {
+                (StatusCode::BAD_REQUEST, error_code::CONFLICTING_AUTH, msg)
+            }
+            AppError::InvalidApiKey(msg) => {
+                (StatusCode::UNAUTHORIZED, error_code::INVALID_API_KEY, msg)
+            }
+            AppError::AccessDenied(msg) => (StatusCode::FORBIDDEN, error_code::NOT_AUTHORIZED, msg),
+            AppError::NotFound(msg) => (StatusCode::NOT_FOUND, error_code::NOT_FOUND, msg),
+            AppError::ServerBusy(msg) => {
+                (StatusCode::SERVICE_UNAVAILABLE, error_code::GENERIC, msg)
+            }
+            AppError::TooManyRequests(msg) => {
+                (StatusCode::TOO_MANY_REQUESTS, error_code::GENERIC, msg)
+            }
             AppError::DatabaseError(err) => {
                 tracing::error!("Database error: {}", err);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
-                    0,
+                    error_code::GENERIC,
                     "Database error".to_string(),
                 )
             }
             AppError::IoError(err) => {
                 tracing::error!("IO error: {}", err);
-                (StatusCode::INTERNAL_SERVER_ERROR, 0, "IO error".to_string())
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    error_code::GENERIC,
+                    "IO error".to_string(),
+                )
             }
             AppError::AuthError(err) => {
                 tracing::error!("Auth error: {}", err);
                 (
                     StatusCode::UNAUTHORIZED,
-                    30,
+                    error_code::WRONG_CREDENTIALS,
                     "Authentication error".to_string(),
                 )
             }
-            AppError::ValidationError(msg) => (StatusCode::BAD_REQUEST, 0, msg),
+            AppError::ValidationError(msg) => (StatusCode::BAD_REQUEST, error_code::GENERIC, msg),
             AppError::ConfigError(msg) => {
                 tracing::error!("Config error: {}", msg);
-                (StatusCode::INTERNAL_SERVER_ERROR, 0, msg)
+                (StatusCode::INTERNAL_SERVER_ERROR, error_code::GENERIC, msg)
             }
-        };
+        }
+    }
+}
+
+// 转换为 HTTP 响应
+//
+// 默认输出带 HTTP 错误状态码的 JSON (供 /api/* 管理接口使用)，
+// 同时把错误信息写入响应扩展，/rest/* 请求由错误格式中间件改写为
+// 客户端请求格式的 Subsonic 错误响应 (HTTP 200)。
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status_code, error_code, message) = self.into_parts();
 
         let error_response = ErrorResponse {
             status: "failed".to_string(),
-            version: "1.16.1".to_string(),
+            version: API_VERSION.to_string(),
             error: SubsonicErrorPayload {
                 code: error_code,
-                message,
+                message: message.clone(),
             },
         };
 
-        (status_code, Json(error_response)).into_response()
+        let mut response = (status_code, Json(error_response)).into_response();
+        response.extensions_mut().insert(SubsonicErrorInfo {
+            code: error_code,
+            message,
+        });
+        response
     }
 }
 
@@ -157,9 +250,17 @@ impl AppError {
         AppError::ServerBusy(msg.to_string())
     }
 
+    pub fn too_many_requests(msg: &str) -> Self {
+        AppError::TooManyRequests(msg.to_string())
+    }
+
     pub fn validation_error(msg: &str) -> Self {
         AppError::ValidationError(msg.to_string())
     }
+
+    pub fn token_not_supported(msg: &str) -> Self {
+        AppError::TokenNotSupported(msg.to_string())
+    }
 }
 
 #[cfg(test)]
@@ -183,4 +284,41 @@ mod tests {
             _ => panic!("Wrong error type"),
         }
     }
+
+    fn error_code_of(err: AppError) -> i32 {
+        let response = err.into_response();
+        response
+            .extensions()
+            .get::<SubsonicErrorInfo>()
+            .unwrap()
+            .code
+    }
+
+    #[test]
+    fn test_error_codes() {
+        assert_eq!(error_code_of(AppError::missing_parameter("id")), 10);
+        assert_eq!(
+            error_code_of(AppError::IncompatibleClient(String::new())),
+            20
+        );
+        assert_eq!(
+            error_code_of(AppError::IncompatibleServer(String::new())),
+            30
+        );
+        assert_eq!(error_code_of(AppError::auth_failed("x")), 40);
+        assert_eq!(error_code_of(AppError::token_not_supported("x")), 41);
+        assert_eq!(error_code_of(AppError::access_denied("x")), 50);
+        assert_eq!(error_code_of(AppError::not_found("Song")), 70);
+        assert_eq!(error_code_of(AppError::validation_error("x")), 0);
+    }
+
+    #[test]
+    fn test_error_response_info() {
+        let response = AppError::not_found("Song").into_response();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let info = response.extensions().get::<SubsonicErrorInfo>().unwrap();
+        assert_eq!(info.code, 70);
+        assert_eq!(info.message, "Song not found");
+    }
 }
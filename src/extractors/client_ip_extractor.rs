@@ -0,0 +1,55 @@
+//! 客户端 IP 提取器
+//!
+//! 对端是受信任的反向代理时按 `X-Forwarded-For` 识别真实客户端，
+//! 并只采信受信任代理设置的 `X-Forwarded-*` 请求头
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use std::convert::Infallible;
+use std::net::IpAddr;
+
+use crate::middleware::auth_middleware;
+
+/// 客户端 IP，无法获取连接信息时为 `None`
+///
+/// # 使用示例
+///
+/// ```rust,ignore
+/// async fn handler(ClientIp(ip): ClientIp) {
+///     let key = ip.map(LockoutKey::Ip);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp(pub Option<IpAddr>);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(ClientIp(auth_middleware::client_ip(
+            &parts.headers,
+            &parts.extensions,
+        )))
+    }
+}
+
+/// 对端是否为受信任的反向代理，为 `true` 时可采信 `X-Forwarded-Host` 等请求头
+#[derive(Debug, Clone, Copy)]
+pub struct TrustedProxy(pub bool);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for TrustedProxy
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(TrustedProxy(auth_middleware::from_trusted_proxy(
+            &parts.extensions,
+        )))
+    }
+}
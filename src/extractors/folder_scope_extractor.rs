@@ -0,0 +1,54 @@
+//! 音乐文件夹范围提取器
+//!
+//! 根据当前认证用户查询可访问的音乐文件夹，同一请求内只查询一次
+
+use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+use crate::error::AppError;
+use crate::middleware::auth_middleware::Claims;
+use crate::services::{FolderScope, MusicFolderService, ServiceContext};
+
+/// 从请求中提取当前用户的音乐文件夹范围 (需在认证中间件之后使用)
+///
+/// # 使用示例
+///
+/// ```rust,ignore
+/// async fn handler(scope: FolderScope, Params(params): Params<P>) {
+///     let scope = scope.narrow(params.music_folder_id);
+/// }
+/// ```
+#[async_trait]
+impl<S> FromRequestParts<S> for FolderScope
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(scope) = parts.extensions.get::<FolderScope>() {
+            return Ok(scope.clone());
+        }
+
+        let claims = parts
+            .extensions
+            .get::<Claims>()
+            .cloned()
+            .ok_or_else(|| AppError::auth_failed("Authentication required"))?;
+        let pool = parts
+            .extensions
+            .get::<Arc<SqlitePool>>()
+            .cloned()
+            .ok_or_else(|| {
+                AppError::ConfigError("Authentication is not initialized".to_string())
+            })?;
+
+        let scope = MusicFolderService::new(Arc::new(ServiceContext::new((*pool).clone())))
+            .get_folder_scope(&claims.sub, claims.is_admin)
+            .await?;
+        parts.extensions.insert(scope.clone());
+
+        Ok(scope)
+    }
+}
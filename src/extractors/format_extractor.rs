@@ -2,16 +2,17 @@
 //!
 //! 从请求中提取客户端期望的响应格式 (JSON/XML)
 //! 支持两种方式:
-//! 1. 查询参数 f=json 或 f=xml (Subsonic API 标准)
+//! 1. 请求参数 f=json 或 f=xml (Subsonic API 标准，查询字符串或表单)
 //! 2. Accept 请求头
 
 use axum::{
     async_trait,
-    extract::{FromRequestParts, Query},
-    http::request::Parts,
+    extract::FromRequestParts,
+    http::{request::Parts, HeaderMap},
 };
 use serde::Deserialize;
 
+use super::RequestParams;
 use crate::models::response::ResponseFormat;
 
 /// 格式查询参数
@@ -26,9 +27,12 @@ struct FormatParam {
 /// # 使用示例
 ///
 /// ```rust
-/// use crate::extractors::Format;
+/// use musicflow_server::extractors::Format;
+/// # use musicflow_server::response::ApiResponse;
+/// # struct Data;
 ///
 /// async fn handler(Format(format): Format) -> ApiResponse<Data> {
+/// #   let data = Data;
 ///     ApiResponse::ok(Some(data), format)
 /// }
 /// ```
@@ -41,30 +45,38 @@ where
 {
     type Rejection = std::convert::Infallible;
 
-    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        // 1. 优先从查询参数 f 获取 (Subsonic API 标准)
-        if let Ok(Query(param)) = Query::<FormatParam>::from_request_parts(parts, state).await {
-            if let Some(format_str) = param.format {
-                let format = ResponseFormat::parse_str(&format_str);
-                tracing::debug!("Format from query parameter: {:?}", format);
-                return Ok(Format(format));
-            }
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let params = RequestParams::from_request(&parts.extensions, &parts.uri);
+        Ok(Format(detect_format(&params, &parts.headers)))
+    }
+}
+
+/// 根据请求参数和请求头确定响应格式
+///
+/// 错误格式中间件在无法使用提取器时也通过此函数确定格式
+pub fn detect_format(params: &RequestParams, headers: &HeaderMap) -> ResponseFormat {
+    // 1. 优先从请求参数 f 获取 (Subsonic API 标准)
+    if let Ok(param) = params.parse::<FormatParam>() {
+        if let Some(format_str) = param.format {
+            let format = ResponseFormat::parse_str(&format_str);
+            tracing::debug!("Format from request parameter: {:?}", format);
+            return format;
         }
+    }
 
-        // 2. 其次检查 Accept 请求头
-        if let Some(accept) = parts.headers.get("accept") {
-            if let Ok(accept_str) = accept.to_str() {
-                if accept_str.contains("application/xml") || accept_str.contains("text/xml") {
-                    tracing::debug!("Format from Accept header: XML");
-                    return Ok(Format(ResponseFormat::Xml));
-                }
+    // 2. 其次检查 Accept 请求头
+    if let Some(accept) = headers.get("accept") {
+        if let Ok(accept_str) = accept.to_str() {
+            if accept_str.contains("application/xml") || accept_str.contains("text/xml") {
+                tracing::debug!("Format from Accept header: XML");
+                return ResponseFormat::Xml;
             }
         }
-
-        // 3. 默认返回 JSON
-        tracing::debug!("Format: default XML");
-        Ok(Format(ResponseFormat::Xml))
     }
+
+    // 3. 默认返回 XML (Subsonic API 标准)
+    tracing::debug!("Format: default XML");
+    ResponseFormat::Xml
 }
 
 #[cfg(test)]
@@ -95,6 +107,6 @@ mod tests {
         let (mut parts, _) = req.into_parts();
         let Format(format) = Format::from_request_parts(&mut parts, &()).await.unwrap();
 
-        assert_eq!(format, ResponseFormat::Json);
+        assert_eq!(format, ResponseFormat::Xml);
     }
 }
@@ -1,5 +1,10 @@
 //! Axum 请求提取器模块
 
+mod client_ip_extractor;
+mod folder_scope_extractor;
 mod format_extractor;
+mod params_extractor;
 
-pub use format_extractor::Format;
+pub use client_ip_extractor::{ClientIp, TrustedProxy};
+pub use format_extractor::{detect_format, Format};
+pub use params_extractor::{Params, RequestParams};
@@ -0,0 +1,203 @@
+//! 请求参数提取器
+//!
+//! Subsonic 客户端既可以把参数放在查询字符串中，也可以通过
+//! `application/x-www-form-urlencoded` 表单 POST 提交 (避免凭据出现在访问日志中)。
+//! 参数中间件会把两者合并为 [`RequestParams`] 放入请求扩展，
+//! 处理器通过 [`Params`] 统一读取，支持 `id`/`songId` 等重复参数。
+
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{request::Parts, Extensions, Uri},
+};
+use serde::de::DeserializeOwned;
+use std::ops::Deref;
+
+use crate::error::AppError;
+
+/// 合并后的原始请求参数 (查询字符串 + 表单请求体，URL 编码格式)
+#[derive(Debug, Clone, Default)]
+pub struct RequestParams(pub String);
+
+impl RequestParams {
+    /// 合并查询字符串与表单请求体
+    ///
+    /// 同名参数以表单请求体为准，避免同一参数在两处重复出现导致解析失败
+    pub fn merge(query: Option<&str>, form: &str) -> Self {
+        let query = query.unwrap_or("");
+        if form.is_empty() {
+            return Self(query.to_string());
+        }
+        if query.is_empty() {
+            return Self(form.to_string());
+        }
+
+        let form_pairs: Vec<(String, String)> =
+            serde_urlencoded::from_str(form).unwrap_or_default();
+        let query_pairs: Vec<(String, String)> =
+            serde_urlencoded::from_str(query).unwrap_or_default();
+
+        let pairs: Vec<&(String, String)> = query_pairs
+            .iter()
+            .filter(|(key, _)| !form_pairs.iter().any(|(k, _)| k == key))
+            .chain(form_pairs.iter())
+            .collect();
+
+        Self(serde_urlencoded::to_string(pairs).unwrap_or_default())
+    }
+
+    /// 从请求扩展中读取，未经过参数中间件时退回到查询字符串
+    pub fn from_request(extensions: &Extensions, uri: &Uri) -> Self {
+        extensions
+            .get::<RequestParams>()
+            .cloned()
+            .unwrap_or_else(|| Self(uri.query().unwrap_or("").to_string()))
+    }
+
+    /// 原始参数字符串
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// 反序列化为参数结构体
+    ///
+    /// 缺少必需参数时返回 [`AppError::MissingParameter`] (Subsonic 错误码 10)
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T, AppError> {
+        serde_html_form::from_str(&self.0).map_err(|e| {
+            let message = e.to_string();
+            match message
+                .strip_prefix("missing field `")
+                .and_then(|rest| rest.strip_suffix('`'))
+            {
+                Some(field) => AppError::missing_parameter(field),
+                None => AppError::validation_error(&format!("Invalid parameters: {}", message)),
+            }
+        })
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for RequestParams
+where
+    S: Send + Sync,
+{
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_request(&parts.extensions, &parts.uri))
+    }
+}
+
+/// 请求参数提取器 (查询字符串与表单请求体合并)
+///
+/// # 使用示例
+///
+/// ```rust,ignore
+/// use crate::extractors::Params;
+///
+/// async fn handler(Params(params): Params<SongParams>) -> ApiResponse<Data> {
+///     // params.id ...
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Params<T>(pub T);
+
+impl<T> Deref for Params<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for Params<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        RequestParams::from_request(&parts.extensions, &parts.uri)
+            .parse()
+            .map(Params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct TestParams {
+        id: String,
+        #[serde(rename = "songId", default)]
+        song_ids: Vec<String>,
+        count: Option<i32>,
+    }
+
+    #[tokio::test]
+    async fn test_params_from_query() {
+        let req = Request::builder()
+            .uri("http://localhost/rest/test?id=1&songId=a&songId=b")
+            .body(())
+            .unwrap();
+
+        let (mut parts, _) = req.into_parts();
+        let Params(params) = Params::<TestParams>::from_request_parts(&mut parts, &())
+            .await
+            .unwrap();
+
+        assert_eq!(params.id, "1");
+        assert_eq!(params.song_ids, vec!["a", "b"]);
+        assert_eq!(params.count, None);
+    }
+
+    #[tokio::test]
+    async fn test_params_merged_with_form() {
+        let mut req = Request::builder()
+            .uri("http://localhost/rest/test?id=1&count=1")
+            .body(())
+            .unwrap();
+        let params = RequestParams::merge(req.uri().query(), "songId=a&songId=b&count=5");
+        req.extensions_mut().insert(params);
+
+        let (mut parts, _) = req.into_parts();
+        let Params(params) = Params::<TestParams>::from_request_parts(&mut parts, &())
+            .await
+            .unwrap();
+
+        assert_eq!(params.id, "1");
+        assert_eq!(params.song_ids, vec!["a", "b"]);
+        assert_eq!(params.count, Some(5));
+    }
+
+    #[tokio::test]
+    async fn test_params_missing_field() {
+        let req = Request::builder()
+            .uri("http://localhost/rest/test")
+            .body(())
+            .unwrap();
+
+        let (mut parts, _) = req.into_parts();
+        let err = Params::<TestParams>::from_request_parts(&mut parts, &())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AppError::MissingParameter(msg) if msg.contains("'id'")));
+    }
+
+    #[test]
+    fn test_merge() {
+        assert_eq!(RequestParams::merge(Some("a=1"), "b=2").as_str(), "a=1&b=2");
+        assert_eq!(RequestParams::merge(None, "b=2").as_str(), "b=2");
+        assert_eq!(RequestParams::merge(Some("a=1"), "").as_str(), "a=1");
+        // 同名参数以表单为准
+        assert_eq!(
+            RequestParams::merge(Some("f=xml&a=1"), "f=json&id=1&id=2").as_str(),
+            "a=1&f=json&id=1&id=2"
+        );
+    }
+}
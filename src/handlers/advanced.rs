@@ -3,57 +3,47 @@
 #![allow(dead_code)]
 
 use axum::{
-    extract::Query,
-    routing::{get, post},
-    Router,
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Router,
 };
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use crate::extractors::Format;
+use crate::config::AppConfig;
+use crate::extractors::{Format, Params};
+use crate::middleware::auth_middleware::Claims;
 use crate::models::response::{
-    ChatMessage, ChatMessages, Hls, NowPlaying, NowPlayingEntry, ToXml, Video, VideoInfo, Videos,
+    Captions, ChatMessage, ChatMessages, MusicFolders, NowPlaying, NowPlayingEntry, Song, ToXml,
+    Video, VideoInfo, Videos,
 };
 use crate::response::ApiResponse;
+use crate::services::{
+    EventService, FolderScope, MusicFolderService, ServerEvent, ServiceContext, SongService,
+    VideoService,
+};
+use crate::utils::captions::CaptionFormat;
 use crate::{error::AppError, utils::id_builder};
 
 /// 通用参数
 #[derive(Debug, Deserialize)]
 pub struct CommonParams {
-    pub u: String,
-    pub t: Option<String>,
-    pub s: Option<String>,
-    pub p: Option<String>,
-    pub v: String,
-    pub c: String,
-    pub f: Option<String>,
+    // 认证参数已由中间件处理,这里不需要
 }
 
 /// 聊天消息参数
 #[derive(Debug, Deserialize)]
 pub struct GetChatMessagesParams {
     pub since: Option<i64>, // 时间戳
-    pub u: String,
-    pub t: Option<String>,
-    pub s: Option<String>,
-    pub p: Option<String>,
-    pub v: String,
-    pub c: String,
-    pub f: Option<String>,
 }
 
 /// 添加聊天消息参数
 #[derive(Debug, Deserialize)]
 pub struct AddChatMessageParams {
     pub message: String,
-    pub u: String,
-    pub t: Option<String>,
-    pub s: Option<String>,
-    pub p: Option<String>,
-    pub v: String,
-    pub c: String,
-    pub f: Option<String>,
 }
 
 /// 视频参数
@@ -61,40 +51,24 @@ pub struct AddChatMessageParams {
 pub struct GetVideosParams {
     pub size: Option<i32>,
     pub offset: Option<i32>,
-    pub u: String,
-    pub t: Option<String>,
-    pub s: Option<String>,
-    pub p: Option<String>,
-    pub v: String,
-    pub c: String,
-    pub f: Option<String>,
 }
 
 /// 视频信息参数
 #[derive(Debug, Deserialize)]
 pub struct GetVideoInfoParams {
     pub id: String,
-    pub u: String,
-    pub t: Option<String>,
-    pub s: Option<String>,
-    pub p: Option<String>,
-    pub v: String,
-    pub c: String,
-    pub f: Option<String>,
 }
 
-/// HLS 参数
+/// 字幕参数
 #[derive(Debug, Deserialize)]
-pub struct HlsParams {
+pub struct GetCaptionsParams {
+    /// 视频 ID
     pub id: String,
-    pub bit_rate: Option<Vec<i32>>,
-    pub u: String,
-    pub t: Option<String>,
-    pub s: Option<String>,
-    pub p: Option<String>,
-    pub v: String,
-    pub c: String,
-    pub f: Option<String>,
+    /// srt 或 vtt，默认为字幕文件的原始格式
+    pub format: Option<String>,
+    /// getVideoInfo 返回的字幕 ID，默认为第一个字幕
+    #[serde(rename = "captionId")]
+    pub caption_id: Option<usize>,
 }
 
 /// 系统信息响应
@@ -108,53 +82,55 @@ pub struct SystemInfoResponse {
     pub scan_date: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
-pub struct MusicFolders {
-    #[serde(rename = "musicFolder")]
-    pub music_folder: Vec<MusicFolder>,
-}
-
-#[derive(Debug, Clone, Serialize)]
-pub struct MusicFolder {
-    pub id: String,
-    pub name: String,
-}
-
 /// GET /rest/getNowPlaying - 获取正在播放列表
 pub async fn get_now_playing(
+    claims: Claims,
     Format(format): Format,
+    Extension(config): Extension<AppConfig>,
     axum::extract::State(pool): axum::extract::State<Arc<SqlitePool>>,
-    Query(_params): Query<CommonParams>,
+    Params(_params): Params<CommonParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<NowPlaying>, AppError> {
-    // 查询最近15分钟内播放的歌曲
+    // 过期记录由后台任务清理，这里再按保留时长过滤一次
     let now = chrono::Utc::now();
-    let fifteen_minutes_ago = now - chrono::Duration::minutes(15);
-
-    let entries = sqlx::query_as::<_, (String, String, String, String, String)>(
-        "SELECT np.id, s.title, ar.name as artist, np.username, np.started_at
-         FROM now_playing np
-         JOIN songs s ON np.song_id = s.id
-         JOIN artists ar ON s.artist_id = ar.id
-         WHERE np.started_at >= ?
-         ORDER BY np.started_at DESC",
+    let expire_before = now - chrono::Duration::minutes(config.now_playing_ttl_minutes);
+
+    let entries = sqlx::query_as::<_, (i64, String, String, String, i64)>(
+        "SELECT rowid, song_id, username, player_name, CAST(started_at AS INTEGER)
+         FROM now_playing
+         WHERE CAST(started_at AS INTEGER) >= ?
+         ORDER BY CAST(started_at AS INTEGER) DESC",
     )
-    .bind(fifteen_minutes_ago.timestamp())
+    .bind(expire_before.timestamp())
     .fetch_all(&*pool)
     .await?;
 
+    // 批量查询歌曲完整信息 (评分、收藏以请求用户为准，不可访问的歌曲被忽略)
+    let song_ids: Vec<String> = entries
+        .iter()
+        .map(|(_, song_id, ..)| song_id.clone())
+        .collect();
+    let song_service = SongService::new(Arc::new(ServiceContext::new((*pool).clone())));
+    let songs: HashMap<String, Song> = song_service
+        .get_complex_songs_by_ids(&claims.sub, &song_ids, &scope)
+        .await?
+        .into_iter()
+        .map(|dto| (dto.song.id.clone(), Song::from(dto)))
+        .collect();
+
     let now_playing_entries = entries
         .into_iter()
-        .map(|(id, title, artist, username, started_at)| {
-            let started_timestamp = started_at.parse::<i64>().unwrap_or(0);
-            let minutes_ago = ((now.timestamp() - started_timestamp) / 60) as i32;
-
-            NowPlayingEntry {
-                id,
-                title,
-                artist,
+        .filter_map(|(player_id, song_id, username, player_name, started_at)| {
+            let song = songs.get(&song_id)?.clone();
+            let minutes_ago = ((now.timestamp() - started_at) / 60) as i32;
+
+            Some(NowPlayingEntry {
+                song,
                 username,
                 minutes_ago,
-            }
+                player_id,
+                player_name: (!player_name.is_empty()).then_some(player_name),
+            })
         })
         .collect();
 
@@ -168,18 +144,20 @@ pub async fn get_now_playing(
 /// GET /rest/getSystemInfo - 获取系统信息
 pub async fn get_system_info(
     Format(format): Format,
-    axum::extract::State(_pool): axum::extract::State<Arc<SqlitePool>>,
-    Query(_params): Query<CommonParams>,
+    axum::extract::State(pool): axum::extract::State<Arc<SqlitePool>>,
+    Params(_params): Params<CommonParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<SystemInfoResponse>, AppError> {
-    // 获取音乐库统计
-    let music_folder = MusicFolder {
-        id: "1".to_string(),
-        name: "Music".to_string(),
-    };
+    // 获取当前用户可访问的音乐文件夹
+    let music_folder_service =
+        MusicFolderService::new(Arc::new(ServiceContext::new((*pool).clone())));
+    let folders = music_folder_service
+        .get_music_folders_in_scope(&scope)
+        .await?;
 
     let result = SystemInfoResponse {
         music_folders: MusicFolders {
-            music_folder: vec![music_folder],
+            music_folder: folders.into_iter().map(Into::into).collect(),
         },
         indexing: false,
         scan_date: Some(
@@ -196,7 +174,7 @@ pub async fn get_system_info(
 pub async fn get_chat_messages(
     Format(format): Format,
     axum::extract::State(pool): axum::extract::State<Arc<SqlitePool>>,
-    Query(params): Query<GetChatMessagesParams>,
+    Params(params): Params<GetChatMessagesParams>,
 ) -> Result<ApiResponse<ChatMessages>, AppError> {
     let since = params.since.unwrap_or(0);
 
@@ -233,7 +211,8 @@ pub async fn add_chat_message(
     Format(format): Format,
     claims: crate::middleware::auth_middleware::Claims,
     axum::extract::State(pool): axum::extract::State<Arc<SqlitePool>>,
-    Query(params): Query<AddChatMessageParams>,
+    Extension(events): Extension<Arc<EventService>>,
+    Params(params): Params<AddChatMessageParams>,
 ) -> Result<ApiResponse<()>, AppError> {
     let user_id = &claims.sub;
 
@@ -259,6 +238,12 @@ pub async fn add_chat_message(
     .execute(&*pool)
     .await?;
 
+    events.publish(ServerEvent::ChatMessage {
+        username,
+        message: params.message,
+        time: timestamp,
+    });
+
     Ok(ApiResponse::ok(None, format))
 }
 
@@ -266,81 +251,105 @@ pub async fn add_chat_message(
 pub async fn get_videos(
     Format(format): Format,
     axum::extract::State(pool): axum::extract::State<Arc<SqlitePool>>,
-    Query(params): Query<GetVideosParams>,
+    Params(params): Params<GetVideosParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<Videos>, AppError> {
     let size = params.size.unwrap_or(20).min(500);
     let offset = params.offset.unwrap_or(0);
 
-    let videos = sqlx::query_as::<_, (String, String, String)>(
-        "SELECT id, title, content_type
-         FROM videos
-         ORDER BY created_at DESC
-         LIMIT ? OFFSET ?",
-    )
-    .bind(size)
-    .bind(offset)
-    .fetch_all(&*pool)
-    .await?;
-
-    let video_list = videos
-        .into_iter()
-        .map(|(id, title, content_type)| Video {
-            id,
-            title,
-            content_type,
-        })
-        .collect();
+    let video_service = VideoService::new(Arc::new(ServiceContext::new((*pool).clone())));
+    let videos = video_service.get_videos(&scope, size, offset).await?;
 
-    let result = Videos { videos: video_list };
+    let result = Videos {
+        videos: videos.into_iter().map(Video::from).collect(),
+    };
 
     Ok(ApiResponse::ok(Some(result), format))
 }
 
-/// GET /rest/getVideoInfo - 获取视频信息
+/// GET /rest/getVideoInfo - 获取视频信息 (包含外挂字幕列表)
 pub async fn get_video_info(
     Format(format): Format,
     axum::extract::State(pool): axum::extract::State<Arc<SqlitePool>>,
-    Query(params): Query<GetVideoInfoParams>,
+    Params(params): Params<GetVideoInfoParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<VideoInfo>, AppError> {
-    let video = sqlx::query_as::<_, (String, String)>("SELECT id, title FROM videos WHERE id = ?")
-        .bind(&params.id)
-        .fetch_optional(&*pool)
-        .await?
-        .ok_or_else(|| AppError::not_found("Video"))?;
+    let video_service = VideoService::new(Arc::new(ServiceContext::new((*pool).clone())));
+    let (video, captions) = video_service.get_captions(&params.id, &scope).await?;
 
     let result = VideoInfo {
-        id: video.0,
-        title: video.1,
-        captions: None,
+        id: video.id,
+        title: video.title,
+        captions: captions
+            .into_iter()
+            .enumerate()
+            .map(|(index, caption)| Captions {
+                id: index.to_string(),
+                name: caption.name,
+            })
+            .collect(),
     };
 
     Ok(ApiResponse::ok(Some(result), format))
 }
 
-/// GET /rest/hls.m3u8 - 获取 HLS 流
-pub async fn hls(
-    Format(format): Format,
-    axum::extract::State(_pool): axum::extract::State<Arc<SqlitePool>>,
-    Query(params): Query<HlsParams>,
-) -> Result<ApiResponse<Hls>, AppError> {
-    // HLS 流需要生成 m3u8 播放列表
-    // 这里简化处理，返回一个 URL
-    let url = std::format!("/rest/stream?id={}", params.id);
-
-    let result = Hls { url };
-
-    Ok(ApiResponse::ok(Some(result), format))
+/// GET /rest/getCaptions - 获取视频字幕文件
+///
+/// 指定 `format` 时在 SRT 与 WebVTT 之间转换
+pub async fn get_captions(
+    axum::extract::State(pool): axum::extract::State<Arc<SqlitePool>>,
+    Params(params): Params<GetCaptionsParams>,
+    scope: FolderScope,
+) -> Result<Response, AppError> {
+    let format = params
+        .format
+        .as_deref()
+        .map(|value| {
+            CaptionFormat::parse(value).ok_or_else(|| {
+                AppError::validation_error(&format!("Unsupported captions format: {}", value))
+            })
+        })
+        .transpose()?;
+
+    let video_service = VideoService::new(Arc::new(ServiceContext::new((*pool).clone())));
+    let (format, text) = video_service
+        .read_caption(&params.id, params.caption_id, format, &scope)
+        .await?;
+
+    Ok((
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(format.content_type()),
+        )],
+        text,
+    )
+        .into_response())
 }
 
 pub fn routes() -> Router<Arc<SqlitePool>> {
     Router::new()
-        .route("/rest/getNowPlaying", get(get_now_playing))
-        .route("/rest/getSystemInfo", get(get_system_info))
-        .route("/rest/getChatMessages", get(get_chat_messages))
-        .route("/rest/addChatMessage", post(add_chat_message))
-        .route("/rest/getVideos", get(get_videos))
-        .route("/rest/getVideoInfo", get(get_video_info))
-        .route("/rest/hls.m3u8", get(hls))
+        .route(
+            "/rest/getNowPlaying",
+            get(get_now_playing).post(get_now_playing),
+        )
+        .route(
+            "/rest/getSystemInfo",
+            get(get_system_info).post(get_system_info),
+        )
+        .route(
+            "/rest/getChatMessages",
+            get(get_chat_messages).post(get_chat_messages),
+        )
+        .route(
+            "/rest/addChatMessage",
+            get(add_chat_message).post(add_chat_message),
+        )
+        .route("/rest/getVideos", get(get_videos).post(get_videos))
+        .route(
+            "/rest/getVideoInfo",
+            get(get_video_info).post(get_video_info),
+        )
+        .route("/rest/getCaptions", get(get_captions).post(get_captions))
 }
 
 // ============================================================================
@@ -367,24 +376,3 @@ impl ToXml for SystemInfoResponse {
         xml
     }
 }
-
-/// MusicFolders ToXml 实现
-impl ToXml for MusicFolders {
-    fn to_xml_element(&self) -> String {
-        let mut xml = String::from("<musicFolders>");
-
-        for folder in &self.music_folder {
-            xml.push_str(&folder.to_xml_element());
-        }
-
-        xml.push_str("</musicFolders>");
-        xml
-    }
-}
-
-/// MusicFolder ToXml 实现
-impl ToXml for MusicFolder {
-    fn to_xml_element(&self) -> String {
-        format!(r#"<musicFolder id="{}" name="{}"/>"#, self.id, self.name)
-    }
-}
@@ -0,0 +1,96 @@
+//! API 密钥端点处理器 (OpenSubsonic apiKey 认证)
+#![allow(dead_code)]
+
+use axum::{routing::get, Router};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::error::AppError;
+use crate::extractors::{Format, Params};
+use crate::middleware::auth_middleware::Claims;
+use crate::models::response::{ApiKeyInfo, ApiKeyList, ApiKeyResponse, ApiKeysResponse};
+use crate::response::ApiResponse;
+use crate::services::ApiKeyService;
+
+/// 创建 API 密钥参数
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyParams {
+    pub name: Option<String>,
+}
+
+/// 吊销 API 密钥参数
+#[derive(Debug, Deserialize)]
+pub struct DeleteApiKeyParams {
+    pub id: Option<String>,
+}
+
+/// GET /rest/createApiKey - 为当前用户创建 API 密钥
+///
+/// 明文密钥只在此响应中返回一次
+pub async fn create_api_key(
+    claims: Claims,
+    axum::extract::State(api_key_service): axum::extract::State<Arc<ApiKeyService>>,
+    Params(params): Params<CreateApiKeyParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<ApiKeyResponse>, AppError> {
+    let name = params
+        .name
+        .ok_or_else(|| AppError::missing_parameter("name"))?;
+
+    let (api_key, key) = api_key_service.create_api_key(&claims.sub, &name).await?;
+
+    let mut info = ApiKeyInfo::from(api_key);
+    info.key = Some(key);
+
+    Ok(ApiResponse::ok(
+        Some(ApiKeyResponse { api_key: info }),
+        format,
+    ))
+}
+
+/// GET /rest/getApiKeys - 获取当前用户的 API 密钥列表
+pub async fn get_api_keys(
+    claims: Claims,
+    axum::extract::State(api_key_service): axum::extract::State<Arc<ApiKeyService>>,
+    Format(format): Format,
+) -> Result<ApiResponse<ApiKeysResponse>, AppError> {
+    let api_keys = api_key_service.get_api_keys(&claims.sub).await?;
+
+    let result = ApiKeysResponse {
+        api_keys: ApiKeyList {
+            api_keys: api_keys.into_iter().map(ApiKeyInfo::from).collect(),
+        },
+    };
+
+    Ok(ApiResponse::ok(Some(result), format))
+}
+
+/// GET /rest/deleteApiKey - 吊销 API 密钥(所有者或管理员)
+pub async fn delete_api_key(
+    claims: Claims,
+    axum::extract::State(api_key_service): axum::extract::State<Arc<ApiKeyService>>,
+    Params(params): Params<DeleteApiKeyParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    let id = params.id.ok_or_else(|| AppError::missing_parameter("id"))?;
+
+    // 调用 Service 层 (包含权限检查)
+    api_key_service
+        .delete_api_key(&id, &claims.sub, claims.is_admin)
+        .await?;
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+pub fn routes() -> Router<Arc<ApiKeyService>> {
+    Router::new()
+        .route(
+            "/rest/createApiKey",
+            get(create_api_key).post(create_api_key),
+        )
+        .route("/rest/getApiKeys", get(get_api_keys).post(get_api_keys))
+        .route(
+            "/rest/deleteApiKey",
+            get(delete_api_key).post(delete_api_key),
+        )
+}
@@ -1,10 +1,20 @@
 //! 认证相关处理器，用于服务器后台的认证和授权
 #![allow(dead_code)]
 
+use crate::config::RegistrationMode;
 use crate::error::AppError;
+use crate::extractors::ClientIp;
+use crate::middleware::auth_middleware::{is_credential_failure, lockout_error};
 use crate::models::dto::{CreateUserRequest, LoginRequest};
-use crate::services::{AuthService, UserWithToken};
-use axum::{routing::post, Json, Router};
+use crate::services::{
+    AuthService, InviteCodeService, LockoutKey, LoginThrottleService, SessionService, UserWithToken,
+};
+use crate::utils::session_token::bearer_token;
+use axum::{
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Json, Router,
+};
 use serde::Deserialize;
 use std::sync::Arc;
 
@@ -19,26 +29,108 @@ pub struct SubsonicAuthParams {
     pub c: String,         // client name
 }
 
-/// 用户注册
+/// 认证处理器状态
+#[derive(Clone)]
+pub struct AuthState {
+    pub auth_service: Arc<AuthService>,
+    pub session_service: Arc<SessionService>,
+    pub invite_code_service: Arc<InviteCodeService>,
+    pub login_throttle: Arc<LoginThrottleService>,
+    pub registration_mode: RegistrationMode,
+}
+
+/// 用户自助注册
+///
+/// 按 `REGISTRATION_MODE` 决定是否开放，自助注册的用户不能是管理员
 pub async fn register(
-    axum::extract::State(auth_service): axum::extract::State<Arc<AuthService>>,
-    Json(req): Json<CreateUserRequest>,
+    axum::extract::State(state): axum::extract::State<AuthState>,
+    Json(mut req): Json<CreateUserRequest>,
 ) -> Result<Json<UserWithToken>, AppError> {
-    let user = auth_service.register(req).await?;
-    Ok(Json(user))
+    req.is_admin = None;
+
+    let user = match state.registration_mode {
+        RegistrationMode::Disabled => {
+            return Err(AppError::access_denied("Registration is disabled"));
+        }
+        RegistrationMode::Open => state.auth_service.register(req).await?,
+        RegistrationMode::Invite => {
+            let code = req
+                .invite_code
+                .take()
+                .ok_or_else(|| AppError::missing_parameter("invite_code"))?;
+
+            state.invite_code_service.claim(&code).await?;
+            match state.auth_service.register(req).await {
+                Ok(user) => {
+                    state.invite_code_service.mark_used(&code, &user.id).await?;
+                    user
+                }
+                Err(e) => {
+                    state.invite_code_service.release(&code).await?;
+                    return Err(e);
+                }
+            }
+        }
+    };
+
+    let session = state.session_service.create_session(&user.id).await?;
+    Ok(Json(UserWithToken::from(user).with_session(session)))
 }
 
-/// 用户登录
+/// 用户登录，签发会话令牌
 pub async fn login(
-    axum::extract::State(auth_service): axum::extract::State<Arc<AuthService>>,
+    axum::extract::State(state): axum::extract::State<AuthState>,
+    ClientIp(client_ip): ClientIp,
     Json(req): Json<LoginRequest>,
 ) -> Result<Json<UserWithToken>, AppError> {
-    let user = auth_service.login(req).await?;
-    Ok(Json(user))
+    // 与 Subsonic 认证共用登录限流
+    let keys: Vec<LockoutKey> = std::iter::once(LockoutKey::User(req.username.clone()))
+        .chain(client_ip.map(LockoutKey::Ip))
+        .collect();
+    if let Some(retry_after) = state.login_throttle.check(&keys) {
+        return Err(lockout_error(retry_after));
+    }
+
+    let user = match state.auth_service.login(req).await {
+        Ok(user) => user,
+        Err(e) => {
+            if is_credential_failure(&e) {
+                state.login_throttle.record_failure(&keys);
+            }
+            return Err(e);
+        }
+    };
+    state.login_throttle.record_success(&user.username);
+    let session = state.session_service.create_session(&user.id).await?;
+    Ok(Json(UserWithToken::from(user).with_session(session)))
+}
+
+/// 刷新会话令牌，旧令牌随即失效
+pub async fn refresh(
+    axum::extract::State(state): axum::extract::State<AuthState>,
+    headers: HeaderMap,
+) -> Result<Json<UserWithToken>, AppError> {
+    let token =
+        bearer_token(&headers).ok_or_else(|| AppError::auth_failed("Missing bearer token"))?;
+    let (user, session) = state.session_service.refresh(token).await?;
+    Ok(Json(UserWithToken::from(user).with_session(session)))
+}
+
+/// 退出登录，吊销当前会话令牌
+pub async fn logout(
+    axum::extract::State(state): axum::extract::State<AuthState>,
+    headers: HeaderMap,
+) -> Result<StatusCode, AppError> {
+    let token =
+        bearer_token(&headers).ok_or_else(|| AppError::auth_failed("Missing bearer token"))?;
+    state.session_service.logout(token).await?;
+    Ok(StatusCode::NO_CONTENT)
 }
 
-pub fn routes() -> Router<Arc<AuthService>> {
+pub fn routes() -> Router<AuthState> {
     Router::new()
         .route("/api/auth/register", post(register))
         .route("/api/auth/login", post(login))
+        .route("/api/auth/refresh", post(refresh))
+        .route("/api/auth/logout", post(logout))
 }
@@ -0,0 +1,105 @@
+//! 书签端点处理器
+
+use axum::{routing::get, Router};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::extractors::{Format, Params};
+use crate::models::response::{BookmarkInfo, BookmarkList, BookmarksResponse};
+use crate::response::ApiResponse;
+use crate::services::{BookmarkService, FolderScope};
+use crate::{error::AppError, middleware::auth_middleware};
+
+/// 书签处理器状态
+#[derive(Clone)]
+pub struct BookmarkState {
+    pub bookmark_service: Arc<BookmarkService>,
+}
+
+/// 创建书签参数
+#[derive(Debug, Deserialize)]
+pub struct CreateBookmarkParams {
+    pub id: Option<String>,      // 歌曲 ID
+    pub position: Option<i64>,   // 播放位置（毫秒）
+    pub comment: Option<String>, // 备注
+}
+
+/// 删除书签参数
+#[derive(Debug, Deserialize)]
+pub struct DeleteBookmarkParams {
+    pub id: Option<String>, // 歌曲 ID
+}
+
+/// GET /rest/getBookmarks - 获取用户的所有书签
+pub async fn get_bookmarks(
+    claims: auth_middleware::Claims,
+    scope: FolderScope,
+    axum::extract::State(state): axum::extract::State<BookmarkState>,
+    Format(format): Format,
+) -> Result<ApiResponse<BookmarksResponse>, AppError> {
+    let bookmarks = state
+        .bookmark_service
+        .get_bookmarks(&claims.sub, &scope)
+        .await?;
+
+    let result = BookmarksResponse {
+        bookmarks: BookmarkList {
+            bookmarks: bookmarks.into_iter().map(BookmarkInfo::from).collect(),
+        },
+    };
+
+    Ok(ApiResponse::ok(Some(result), format))
+}
+
+/// GET/POST /rest/createBookmark - 创建或更新书签
+pub async fn create_bookmark(
+    claims: auth_middleware::Claims,
+    scope: FolderScope,
+    axum::extract::State(state): axum::extract::State<BookmarkState>,
+    Params(params): Params<CreateBookmarkParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    let id = params.id.ok_or_else(|| AppError::missing_parameter("id"))?;
+    let position = params
+        .position
+        .ok_or_else(|| AppError::missing_parameter("position"))?;
+
+    state
+        .bookmark_service
+        .create_bookmark(&claims.sub, &id, position, params.comment, &scope)
+        .await?;
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+/// GET/POST /rest/deleteBookmark - 删除书签
+pub async fn delete_bookmark(
+    claims: auth_middleware::Claims,
+    axum::extract::State(state): axum::extract::State<BookmarkState>,
+    Params(params): Params<DeleteBookmarkParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    let id = params.id.ok_or_else(|| AppError::missing_parameter("id"))?;
+
+    state
+        .bookmark_service
+        .delete_bookmark(&claims.sub, &id)
+        .await?;
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+/// 注册书签路由
+pub fn routes(state: BookmarkState) -> Router {
+    Router::new()
+        .route("/rest/getBookmarks", get(get_bookmarks).post(get_bookmarks))
+        .route(
+            "/rest/createBookmark",
+            get(create_bookmark).post(create_bookmark),
+        )
+        .route(
+            "/rest/deleteBookmark",
+            get(delete_bookmark).post(delete_bookmark),
+        )
+        .with_state(state)
+}
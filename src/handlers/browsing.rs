@@ -2,18 +2,20 @@
 #![allow(dead_code)]
 
 use crate::error::AppError;
-use crate::extractors::Format;
+use crate::extractors::{Format, Params};
+use crate::middleware::admin_middleware;
 use crate::models::response::{
     AlbumDetail, AlbumDetailResponse, AlbumList2, AlbumList2Response, AlbumResponse, ArtistDetail,
     ArtistDetailResponse, ArtistIndex, ArtistResponse, Artists, ArtistsResponse, Directory, Genre,
-    Genres, GenresResponse, Index, Indexes, RandomSongs, RandomSongsResponse, Song, SongResponse,
-    SongsByGenreResponse, SongsResponse, TopSongs, TopSongsResponse,
+    Genres, GenresResponse, Index, Indexes, MusicFolders, MusicFoldersResponse, RandomSongs,
+    RandomSongsResponse, Song, SongResponse, SongsByGenreResponse, SongsResponse, TopSongs,
+    TopSongsResponse,
 };
 use crate::response::ApiResponse;
 use crate::services::browsing_service::AlbumListType;
-use crate::services::BrowsingService;
+use crate::services::{BrowsingService, FolderScope, MusicFolderService};
 use crate::utils::Pinyin;
-use axum::{extract::Query, routing::get, Router};
+use axum::{routing::get, Router};
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::str::FromStr;
@@ -27,6 +29,7 @@ pub struct GetArtistsParams {
 }
 /// 获取艺术家索引参数
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GetIndexesParams {
     pub music_folder_id: Option<i32>,
     pub if_modified_since: Option<i64>,
@@ -56,13 +59,57 @@ pub struct GetSongParams {
     pub id: String,
 }
 
+/// 清除音乐文件夹参数，未指定 id 时清除全部已从配置中移除的文件夹
+#[derive(Debug, Deserialize)]
+pub struct PurgeMusicFoldersParams {
+    pub id: Option<i64>,
+}
+
+/// GET /rest/getMusicFolders - 仅返回当前用户可访问的文件夹
+pub async fn get_music_folders(
+    Format(format): Format,
+    axum::extract::State(state): axum::extract::State<BrowsingState>,
+    scope: FolderScope,
+) -> Result<ApiResponse<MusicFoldersResponse>, AppError> {
+    let folders = state
+        .music_folder_service
+        .get_music_folders_in_scope(&scope)
+        .await?;
+
+    let result = MusicFoldersResponse {
+        music_folders: MusicFolders {
+            music_folder: folders.into_iter().map(Into::into).collect(),
+        },
+    };
+
+    Ok(ApiResponse::ok(Some(result), format))
+}
+
+/// GET /rest/purgeMusicFolders - 清除已从配置中移除的音乐文件夹及其内容 (仅管理员)
+pub async fn purge_music_folders(
+    Params(params): Params<PurgeMusicFoldersParams>,
+    Format(format): Format,
+    axum::extract::State(state): axum::extract::State<BrowsingState>,
+) -> Result<ApiResponse<()>, AppError> {
+    state
+        .music_folder_service
+        .purge_missing_folders(params.id)
+        .await?;
+
+    Ok(ApiResponse::ok(None, format))
+}
+
 /// GET /rest/getIndexes
 pub async fn get_indexes(
     Format(format): Format,
     axum::extract::State(state): axum::extract::State<BrowsingState>,
-    Query(_params): Query<GetIndexesParams>,
+    Params(params): Params<GetIndexesParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<Indexes>, AppError> {
-    let artists = state.browseing_service.get_artist_indexes().await?;
+    let artists = state
+        .browseing_service
+        .get_artist_indexes(&scope.narrow(params.music_folder_id))
+        .await?;
 
     // 按首字母分组
     let mut index_map: std::collections::HashMap<String, Vec<ArtistResponse>> =
@@ -99,25 +146,28 @@ pub async fn get_indexes(
 pub async fn get_music_directory(
     Format(format): Format,
     axum::extract::State(state): axum::extract::State<BrowsingState>,
-    Query(params): Query<GetMusicDirectoryParams>,
+    Params(params): Params<GetMusicDirectoryParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<Directory>, AppError> {
     // 判断是艺术家还是专辑
     // 如果ID以'a'开头可能是艺术家，以'b'开头可能是专辑
     // 这里简化处理，查询数据库判断
 
     // 尝试作为专辑查询
-    let album = sqlx::query_as::<_, (String, String, String, i32)>(
-        "SELECT id, artist_id, name, song_count FROM albums WHERE id = ?",
-    )
+    let album = sqlx::query_as::<_, (String, String, String, i32)>(&format!(
+        "SELECT id, artist_id, name, song_count FROM albums WHERE id = ? AND {}",
+        scope.sql_filter("music_folder_id")
+    ))
     .bind(&params.id)
     .fetch_optional(&*state.pool)
     .await?;
 
     if let Some((id, artist_id, name, _song_count)) = album {
         // 查询该专辑下的歌曲
-        let songs = sqlx::query_as::<_, (String, String)>(
-            "SELECT id, title FROM songs WHERE album_id = ? ORDER BY track_number",
-        )
+        let songs = sqlx::query_as::<_, (String, String)>(&format!(
+            "SELECT id, title FROM songs WHERE album_id = ? AND {} ORDER BY track_number",
+            scope.sql_filter("music_folder_id")
+        ))
         .bind(&id)
         .fetch_all(&*state.pool)
         .await?;
@@ -148,16 +198,20 @@ pub async fn get_music_directory(
     }
 
     // 尝试作为艺术家查询
-    let artist = sqlx::query_as::<_, (String, String)>("SELECT id, name FROM artists WHERE id = ?")
-        .bind(&params.id)
-        .fetch_optional(&*state.pool)
-        .await?;
+    let artist = sqlx::query_as::<_, (String, String)>(&format!(
+        "SELECT id, name FROM artists WHERE id = ? AND {}",
+        scope.artist_filter("artists.id")
+    ))
+    .bind(&params.id)
+    .fetch_optional(&*state.pool)
+    .await?;
 
     if let Some((id, name)) = artist {
         // 查询该艺术家下的专辑
-        let albums = sqlx::query_as::<_, (String, String)>(
-            "SELECT id, name FROM albums WHERE artist_id = ? ORDER BY name",
-        )
+        let albums = sqlx::query_as::<_, (String, String)>(&format!(
+            "SELECT id, name FROM albums WHERE artist_id = ? AND {} ORDER BY name",
+            scope.sql_filter("music_folder_id")
+        ))
         .bind(&id)
         .fetch_all(&*state.pool)
         .await?;
@@ -194,10 +248,14 @@ pub async fn get_music_directory(
 pub async fn get_artists(
     Format(format): Format,
     axum::extract::State(state): axum::extract::State<BrowsingState>,
-    Query(_params): Query<GetArtistsParams>,
+    Params(params): Params<GetArtistsParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<ArtistsResponse>, AppError> {
     // 查询艺术家信息
-    let artist = state.browseing_service.get_artist_indexes().await?;
+    let artist = state
+        .browseing_service
+        .get_artist_indexes(&scope.narrow(params.music_folder_id))
+        .await?;
 
     let mut index_map: HashMap<String, Vec<ArtistResponse>> = HashMap::new();
     artist.into_iter().for_each(|a| {
@@ -231,9 +289,13 @@ pub async fn get_artists(
 pub async fn get_artist(
     Format(format): Format,
     axum::extract::State(state): axum::extract::State<BrowsingState>,
-    Query(params): Query<GetArtistParams>,
+    Params(params): Params<GetArtistParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<ArtistDetailResponse>, AppError> {
-    let (artist, album_list) = state.browseing_service.get_artist(&params.id).await?;
+    let (artist, album_list) = state
+        .browseing_service
+        .get_artist(&params.id, &scope)
+        .await?;
 
     let result = ArtistDetailResponse {
         artist: ArtistDetail {
@@ -252,9 +314,13 @@ pub async fn get_artist(
 pub async fn get_album(
     Format(format): Format,
     axum::extract::State(state): axum::extract::State<BrowsingState>,
-    Query(params): Query<GetAlbumParams>,
+    Params(params): Params<GetAlbumParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<AlbumDetailResponse>, AppError> {
-    let (album, songs) = state.browseing_service.get_album(&params.id).await?;
+    let (album, songs) = state
+        .browseing_service
+        .get_album(&params.id, &scope)
+        .await?;
     // 计算总时长
     let total_duration: i32 = songs.iter().map(|s| s.duration).sum();
     // tracing::info!("al = {:?}", album);
@@ -282,13 +348,14 @@ pub async fn get_song(
     claims: crate::middleware::auth_middleware::Claims,
     Format(format): Format,
     axum::extract::State(state): axum::extract::State<BrowsingState>,
-    Query(params): Query<GetSongParams>,
+    Params(params): Params<GetSongParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<SongResponse>, AppError> {
     let user_id = claims.sub;
     // 查询歌曲信息
     let song = state
         .browseing_service
-        .get_song(&user_id, &params.id)
+        .get_song(&user_id, &params.id, &scope)
         .await?;
 
     let result = SongResponse { song: song.into() };
@@ -298,6 +365,7 @@ pub async fn get_song(
 
 /// 获取专辑列表参数
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GetAlbumListParams {
     pub r#type: String, // random, newest, highest, frequent, recent, starred, alphabetical
     pub size: Option<i32>,
@@ -305,17 +373,18 @@ pub struct GetAlbumListParams {
     pub from_year: Option<i32>,
     pub to_year: Option<i32>,
     pub genre: Option<String>,
-    pub music_folder_id: Option<String>,
+    pub music_folder_id: Option<i32>,
 }
 
 /// 获取随机歌曲参数
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GetRandomSongsParams {
     pub size: Option<i32>,
     pub genre: Option<String>,
     pub from_year: Option<i32>,
     pub to_year: Option<i32>,
-    pub music_folder_id: Option<String>,
+    pub music_folder_id: Option<i32>,
 }
 
 /// 获取艺术家信息参数
@@ -330,7 +399,8 @@ pub struct GetArtistInfoParams {
 pub async fn get_album_list2(
     Format(format): Format,
     axum::extract::State(state): axum::extract::State<BrowsingState>,
-    Query(params): Query<GetAlbumListParams>,
+    Params(params): Params<GetAlbumListParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<AlbumList2Response>, AppError> {
     let size = params.size.unwrap_or(10).min(500); // 限制最大500
     let offset = params.offset.unwrap_or(0);
@@ -340,6 +410,7 @@ pub async fn get_album_list2(
             AlbumListType::from_str(&params.r#type).unwrap_or_default(),
             size,
             offset,
+            &scope.narrow(params.music_folder_id),
         )
         .await?;
 
@@ -358,8 +429,9 @@ pub async fn get_album_list2(
 pub async fn get_random_songs(
     Format(format): Format,
     axum::extract::State(state): axum::extract::State<BrowsingState>,
-    Query(params): Query<GetRandomSongsParams>,
-    claims: crate::middleware::auth_middleware::Claims
+    Params(params): Params<GetRandomSongsParams>,
+    claims: crate::middleware::auth_middleware::Claims,
+    scope: FolderScope,
 ) -> Result<ApiResponse<RandomSongsResponse>, AppError> {
     let size = params.size.unwrap_or(10).min(500);
     let songs = state
@@ -370,6 +442,7 @@ pub async fn get_random_songs(
             params.genre.as_deref(),
             params.from_year,
             params.to_year,
+            &scope.narrow(params.music_folder_id),
         )
         .await?;
 
@@ -388,14 +461,16 @@ pub async fn get_random_songs(
 pub async fn get_artist_info(
     Format(format): Format,
     axum::extract::State(state): axum::extract::State<BrowsingState>,
-    Query(params): Query<GetArtistInfoParams>,
+    Params(params): Params<GetArtistInfoParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<crate::models::response::ArtistInfo>, AppError> {
     // let artist = state.browseing_service.get_artist_info(&params.id, params.count, params.include_not_present).await?;
 
     // 查询艺术家基本信息
-    let artist = sqlx::query_as::<_, (String, Option<String>)>(
-        "SELECT name, music_brainz_id FROM artists WHERE id = ?",
-    )
+    let artist = sqlx::query_as::<_, (String, Option<String>)>(&format!(
+        "SELECT name, music_brainz_id FROM artists WHERE id = ? AND {}",
+        scope.artist_filter("artists.id")
+    ))
     .bind(&params.id)
     .fetch_optional(&*state.pool)
     .await?;
@@ -420,10 +495,11 @@ pub async fn get_artist_info(
 pub async fn get_artist_info2(
     format: Format,
     state: axum::extract::State<BrowsingState>,
-    params: Query<GetArtistInfoParams>,
+    params: Params<GetArtistInfoParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<crate::models::response::ArtistInfo>, AppError> {
     // ArtistInfo2 与 ArtistInfo 结构相同
-    get_artist_info(format, state, params).await
+    get_artist_info(format, state, params, scope).await
 }
 
 /// 获取热门歌曲参数
@@ -435,23 +511,25 @@ pub struct GetTopSongsParams {
 
 /// 获取流派歌曲参数
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct GetSongsByGenreParams {
     pub genre: String,
     pub count: Option<i32>,
     pub offset: Option<i32>,
-    pub music_folder_id: Option<String>,
+    pub music_folder_id: Option<i32>,
 }
 
 /// GET /rest/getTopSongs - 获取艺术家热门歌曲
 pub async fn get_top_songs(
     Format(format): Format,
     axum::extract::State(state): axum::extract::State<BrowsingState>,
-    Query(params): Query<GetTopSongsParams>,
+    Params(params): Params<GetTopSongsParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<TopSongsResponse>, AppError> {
     let count = params.count.unwrap_or(50).min(5000); // 默认50首，最多5000首
     let songs = state
         .browseing_service
-        .get_top_songs(&params.artist, count)
+        .get_top_songs(&params.artist, count, &scope)
         .await?;
     let song_responses: Vec<Song> = songs.into_iter().map(|dto| dto.into()).collect();
 
@@ -468,14 +546,20 @@ pub async fn get_top_songs(
 pub async fn get_songs_by_genre(
     Format(format): Format,
     axum::extract::State(state): axum::extract::State<BrowsingState>,
-    Query(params): Query<GetSongsByGenreParams>,
+    Params(params): Params<GetSongsByGenreParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<SongsByGenreResponse>, AppError> {
     let count = params.count.unwrap_or(10).min(500); // 默认10首，最多500首
     let offset = params.offset.unwrap_or(0);
 
     let songs = state
         .browseing_service
-        .get_songs_by_genre(&params.genre, count, offset)
+        .get_songs_by_genre(
+            &params.genre,
+            count,
+            offset,
+            &scope.narrow(params.music_folder_id),
+        )
         .await?;
     let song_responses: Vec<Song> = songs.into_iter().map(|dto| dto.into()).collect();
 
@@ -499,12 +583,13 @@ pub struct GetSimilarSongsParams {
 pub async fn get_similar_songs2(
     Format(format): Format,
     axum::extract::State(state): axum::extract::State<BrowsingState>,
-    Query(params): Query<GetSimilarSongsParams>,
+    Params(params): Params<GetSimilarSongsParams>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<RandomSongsResponse>, AppError> {
     let count = params.count.unwrap_or(50).min(500);
     let songs = state
         .browseing_service
-        .get_similar_songs(&params.id, count)
+        .get_similar_songs(&params.id, count, &scope)
         .await?;
 
     let song_responses = Song::from_detail_dtos(songs);
@@ -522,9 +607,10 @@ pub async fn get_similar_songs2(
 pub async fn get_genres(
     Format(format): Format,
     axum::extract::State(state): axum::extract::State<BrowsingState>,
+    scope: FolderScope,
 ) -> Result<ApiResponse<GenresResponse>, AppError> {
     // 从歌曲和专辑中统计流派及其计数
-    let genres = state.browseing_service.get_genres().await?;
+    let genres = state.browseing_service.get_genres(&scope).await?;
 
     let genre_list: Vec<Genre> = genres
         .into_iter()
@@ -547,29 +633,72 @@ pub async fn get_genres(
 pub struct BrowsingState {
     pub pool: Arc<sqlx::SqlitePool>,
     pub browseing_service: Arc<BrowsingService>,
+    pub music_folder_service: Arc<MusicFolderService>,
 }
 
-pub fn routes(pool: Arc<sqlx::SqlitePool>, browseing_service: Arc<BrowsingService>) -> Router {
+pub fn routes(
+    pool: Arc<sqlx::SqlitePool>,
+    browseing_service: Arc<BrowsingService>,
+    music_folder_service: Arc<MusicFolderService>,
+) -> Router {
     let browsing_state = BrowsingState {
         pool: pool.clone(),
         browseing_service,
+        music_folder_service,
     };
 
+    // 清除音乐文件夹仅管理员可访问
+    let admin_routes = Router::new()
+        .route(
+            "/rest/purgeMusicFolders",
+            get(purge_music_folders).post(purge_music_folders),
+        )
+        .route_layer(axum::middleware::from_fn(admin_middleware));
+
     Router::new()
-        .route("/rest/getIndexes", get(get_indexes))
-        .route("/rest/getMusicDirectory", get(get_music_directory))
-        .route("/rest/getGenres", get(get_genres))
-        .route("/rest/getArtists", get(get_artists))
-        .route("/rest/getArtist", get(get_artist))
-        .route("/rest/getAlbum", get(get_album))
-        .route("/rest/getSong", get(get_song))
-        .route("/rest/getAlbumList", get(get_album_list2))
-        .route("/rest/getAlbumList2", get(get_album_list2))
-        .route("/rest/getRandomSongs", get(get_random_songs))
-        .route("/rest/getArtistInfo", get(get_artist_info))
-        .route("/rest/getArtistInfo2", get(get_artist_info2))
-        .route("/rest/getTopSongs", get(get_top_songs))
-        .route("/rest/getSongsByGenre", get(get_songs_by_genre))
-        .route("/rest/getSimilarSongs2", get(get_similar_songs2))
+        .route(
+            "/rest/getMusicFolders",
+            get(get_music_folders).post(get_music_folders),
+        )
+        .merge(admin_routes)
+        .route("/rest/getIndexes", get(get_indexes).post(get_indexes))
+        .route(
+            "/rest/getMusicDirectory",
+            get(get_music_directory).post(get_music_directory),
+        )
+        .route("/rest/getGenres", get(get_genres).post(get_genres))
+        .route("/rest/getArtists", get(get_artists).post(get_artists))
+        .route("/rest/getArtist", get(get_artist).post(get_artist))
+        .route("/rest/getAlbum", get(get_album).post(get_album))
+        .route("/rest/getSong", get(get_song).post(get_song))
+        .route(
+            "/rest/getAlbumList",
+            get(get_album_list2).post(get_album_list2),
+        )
+        .route(
+            "/rest/getAlbumList2",
+            get(get_album_list2).post(get_album_list2),
+        )
+        .route(
+            "/rest/getRandomSongs",
+            get(get_random_songs).post(get_random_songs),
+        )
+        .route(
+            "/rest/getArtistInfo",
+            get(get_artist_info).post(get_artist_info),
+        )
+        .route(
+            "/rest/getArtistInfo2",
+            get(get_artist_info2).post(get_artist_info2),
+        )
+        .route("/rest/getTopSongs", get(get_top_songs).post(get_top_songs))
+        .route(
+            "/rest/getSongsByGenre",
+            get(get_songs_by_genre).post(get_songs_by_genre),
+        )
+        .route(
+            "/rest/getSimilarSongs2",
+            get(get_similar_songs2).post(get_similar_songs2),
+        )
         .with_state(browsing_state)
 }
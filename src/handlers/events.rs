@@ -0,0 +1,122 @@
+//! 服务器事件 WebSocket 端点
+//!
+//! `/ws` 使用与 REST 端点相同的认证参数 (u/p、u/t/s 或 apiKey)，
+//! 连接后以 JSON 文本帧推送 [`ServerEvent`]。
+//! 重连时传入 `lastEventId` 补发错过的事件。
+#![allow(dead_code)]
+
+use axum::{
+    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    extract::State,
+    response::Response,
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::extractors::Params;
+use crate::middleware::auth_middleware::Claims;
+use crate::services::{EventEnvelope, EventService, FolderScope, ServerEvent, Subscription};
+
+/// 事件订阅参数
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventsParams {
+    /// 上次收到的事件 ID
+    pub last_event_id: Option<u64>,
+}
+
+/// GET /ws - 订阅服务器事件
+pub async fn events(
+    ws: WebSocketUpgrade,
+    claims: Claims,
+    scope: FolderScope,
+    State(events): State<Arc<EventService>>,
+    Params(params): Params<EventsParams>,
+) -> Response {
+    // 在升级前订阅，握手期间发布的事件不会丢失
+    let subscription = events.subscribe(params.last_event_id);
+    tracing::debug!("用户 {} 订阅服务器事件", claims.username);
+
+    ws.on_upgrade(move |socket| forward_events(socket, events, scope, subscription))
+}
+
+/// 先发送补发事件，再转发实时事件，直到客户端断开
+async fn forward_events(
+    mut socket: WebSocket,
+    events: Arc<EventService>,
+    scope: FolderScope,
+    subscription: Subscription,
+) {
+    let Subscription {
+        replay,
+        mut receiver,
+    } = subscription;
+    let mut last_sent = 0;
+
+    for envelope in replay {
+        if send_event(&mut socket, &events, &scope, &envelope, &mut last_sent)
+            .await
+            .is_err()
+        {
+            return;
+        }
+    }
+
+    loop {
+        tokio::select! {
+            received = receiver.recv() => {
+                let envelope = match received {
+                    Ok(envelope) => envelope,
+                    // 客户端处理太慢，错过的事件已被丢弃
+                    Err(RecvError::Lagged(_)) => events.resync(),
+                    Err(RecvError::Closed) => break,
+                };
+                if send_event(&mut socket, &events, &scope, &envelope, &mut last_sent)
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                // 客户端不需要发送消息，Ping 由底层自动回复
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+/// 发送一个事件，跳过已发送过的事件和用户不可见的事件
+async fn send_event(
+    socket: &mut WebSocket,
+    events: &EventService,
+    scope: &FolderScope,
+    envelope: &EventEnvelope,
+    last_sent: &mut u64,
+) -> Result<(), axum::Error> {
+    let is_resync = envelope.event == ServerEvent::Resync;
+    if envelope.id <= *last_sent && !is_resync {
+        return Ok(());
+    }
+    *last_sent = envelope.id;
+
+    match events.is_visible(&envelope.event, scope).await {
+        Ok(true) => {}
+        Ok(false) => return Ok(()),
+        Err(e) => {
+            tracing::warn!("检查事件可见性失败: {}", e);
+            return Ok(());
+        }
+    }
+
+    let text = serde_json::to_string(envelope).unwrap_or_default();
+    socket.send(Message::Text(text)).await
+}
+
+pub fn routes() -> Router<Arc<EventService>> {
+    Router::new().route("/ws", get(events))
+}
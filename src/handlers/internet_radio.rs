@@ -0,0 +1,210 @@
+//! 网络电台端点处理器
+//!
+//! 查询和导出对所有用户开放，创建、修改、删除和导入仅管理员可用
+#![allow(dead_code)]
+
+use axum::{
+    http::{header, HeaderValue},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::error::AppError;
+use crate::extractors::{Format, Params};
+use crate::middleware::admin_middleware;
+use crate::models::entities::InternetRadioStation;
+use crate::models::response::{
+    InternetRadioStationInfo, InternetRadioStationList, InternetRadioStationsResponse,
+};
+use crate::response::ApiResponse;
+use crate::services::InternetRadioService;
+use crate::utils::radio_playlist::RadioPlaylistFormat;
+
+/// 创建电台参数
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateStationParams {
+    pub stream_url: Option<String>,
+    pub name: Option<String>,
+    pub homepage_url: Option<String>,
+}
+
+/// 修改电台参数
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateStationParams {
+    pub id: Option<String>,
+    pub stream_url: Option<String>,
+    pub name: Option<String>,
+    pub homepage_url: Option<String>,
+}
+
+/// 删除电台参数
+#[derive(Debug, Deserialize)]
+pub struct DeleteStationParams {
+    pub id: Option<String>,
+}
+
+/// 导出电台参数
+#[derive(Debug, Deserialize)]
+pub struct ExportStationsParams {
+    /// 播放列表格式 (m3u / pls)，默认为 m3u
+    pub format: Option<String>,
+}
+
+fn stations_response(stations: Vec<InternetRadioStation>) -> InternetRadioStationsResponse {
+    InternetRadioStationsResponse {
+        internet_radio_stations: InternetRadioStationList {
+            stations: stations
+                .into_iter()
+                .map(InternetRadioStationInfo::from)
+                .collect(),
+        },
+    }
+}
+
+/// GET /rest/getInternetRadioStations - 获取所有电台
+pub async fn get_internet_radio_stations(
+    axum::extract::State(radio_service): axum::extract::State<Arc<InternetRadioService>>,
+    Format(format): Format,
+) -> Result<ApiResponse<InternetRadioStationsResponse>, AppError> {
+    let stations = radio_service.get_stations().await?;
+
+    Ok(ApiResponse::ok(Some(stations_response(stations)), format))
+}
+
+/// GET /rest/createInternetRadioStation - 创建电台
+pub async fn create_internet_radio_station(
+    axum::extract::State(radio_service): axum::extract::State<Arc<InternetRadioService>>,
+    Params(params): Params<CreateStationParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    let stream_url = params
+        .stream_url
+        .ok_or_else(|| AppError::missing_parameter("streamUrl"))?;
+    let name = params
+        .name
+        .ok_or_else(|| AppError::missing_parameter("name"))?;
+
+    radio_service
+        .create_station(&name, &stream_url, params.homepage_url.as_deref())
+        .await?;
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+/// GET /rest/updateInternetRadioStation - 修改电台
+pub async fn update_internet_radio_station(
+    axum::extract::State(radio_service): axum::extract::State<Arc<InternetRadioService>>,
+    Params(params): Params<UpdateStationParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    let id = params.id.ok_or_else(|| AppError::missing_parameter("id"))?;
+    let stream_url = params
+        .stream_url
+        .ok_or_else(|| AppError::missing_parameter("streamUrl"))?;
+    let name = params
+        .name
+        .ok_or_else(|| AppError::missing_parameter("name"))?;
+
+    radio_service
+        .update_station(&id, &name, &stream_url, params.homepage_url.as_deref())
+        .await?;
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+/// GET /rest/deleteInternetRadioStation - 删除电台
+pub async fn delete_internet_radio_station(
+    axum::extract::State(radio_service): axum::extract::State<Arc<InternetRadioService>>,
+    Params(params): Params<DeleteStationParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    let id = params.id.ok_or_else(|| AppError::missing_parameter("id"))?;
+
+    radio_service.delete_station(&id).await?;
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+/// POST /rest/importInternetRadioStations - 从 M3U/PLS 播放列表导入电台
+///
+/// 请求体为播放列表文件内容，返回新导入的电台
+pub async fn import_internet_radio_stations(
+    axum::extract::State(radio_service): axum::extract::State<Arc<InternetRadioService>>,
+    Format(format): Format,
+    body: String,
+) -> Result<ApiResponse<InternetRadioStationsResponse>, AppError> {
+    let stations = radio_service.import_stations(&body).await?;
+
+    Ok(ApiResponse::ok(Some(stations_response(stations)), format))
+}
+
+/// GET /rest/exportInternetRadioStations - 导出所有电台为 M3U/PLS 播放列表
+pub async fn export_internet_radio_stations(
+    axum::extract::State(radio_service): axum::extract::State<Arc<InternetRadioService>>,
+    Params(params): Params<ExportStationsParams>,
+) -> Result<Response, AppError> {
+    let playlist_format = match params.format.as_deref() {
+        None => RadioPlaylistFormat::M3u,
+        Some(value) => RadioPlaylistFormat::from_param(value)
+            .ok_or_else(|| AppError::validation_error("format must be m3u or pls"))?,
+    };
+
+    let playlist = radio_service.export_stations(playlist_format).await?;
+    let disposition = format!(
+        "attachment; filename=\"internet-radio.{}\"",
+        playlist_format.extension()
+    );
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(playlist_format.content_type()),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                HeaderValue::from_str(&disposition).unwrap(),
+            ),
+        ],
+        playlist,
+    )
+        .into_response())
+}
+
+pub fn routes() -> Router<Arc<InternetRadioService>> {
+    // 电台管理端点仅管理员可访问
+    let admin_routes = Router::new()
+        .route(
+            "/rest/createInternetRadioStation",
+            get(create_internet_radio_station).post(create_internet_radio_station),
+        )
+        .route(
+            "/rest/updateInternetRadioStation",
+            get(update_internet_radio_station).post(update_internet_radio_station),
+        )
+        .route(
+            "/rest/deleteInternetRadioStation",
+            get(delete_internet_radio_station).post(delete_internet_radio_station),
+        )
+        .route(
+            "/rest/importInternetRadioStations",
+            post(import_internet_radio_stations),
+        )
+        .route_layer(axum::middleware::from_fn(admin_middleware));
+
+    Router::new()
+        .route(
+            "/rest/getInternetRadioStations",
+            get(get_internet_radio_stations).post(get_internet_radio_stations),
+        )
+        .route(
+            "/rest/exportInternetRadioStations",
+            get(export_internet_radio_stations).post(export_internet_radio_stations),
+        )
+        .merge(admin_routes)
+}
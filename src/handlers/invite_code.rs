@@ -0,0 +1,96 @@
+//! 邀请码端点处理器 (仅管理员)
+#![allow(dead_code)]
+
+use axum::{routing::get, Router};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::error::AppError;
+use crate::extractors::{Format, Params};
+use crate::middleware::admin_middleware;
+use crate::middleware::auth_middleware::Claims;
+use crate::models::response::{
+    InviteCodeInfo, InviteCodeList, InviteCodeResponse, InviteCodesResponse,
+};
+use crate::response::ApiResponse;
+use crate::services::InviteCodeService;
+
+/// 签发邀请码参数
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateInviteCodeParams {
+    /// 有效期 (小时)，为空表示永不过期
+    pub expires_in_hours: Option<i64>,
+}
+
+/// 吊销邀请码参数
+#[derive(Debug, Deserialize)]
+pub struct DeleteInviteCodeParams {
+    pub id: Option<String>,
+}
+
+/// GET /rest/createInviteCode - 签发邀请码
+pub async fn create_invite_code(
+    claims: Claims,
+    axum::extract::State(invite_code_service): axum::extract::State<Arc<InviteCodeService>>,
+    Params(params): Params<CreateInviteCodeParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<InviteCodeResponse>, AppError> {
+    let invite = invite_code_service
+        .create_invite_code(&claims.sub, params.expires_in_hours)
+        .await?;
+
+    Ok(ApiResponse::ok(
+        Some(InviteCodeResponse {
+            invite_code: invite.into(),
+        }),
+        format,
+    ))
+}
+
+/// GET /rest/getInviteCodes - 获取邀请码列表
+pub async fn get_invite_codes(
+    axum::extract::State(invite_code_service): axum::extract::State<Arc<InviteCodeService>>,
+    Format(format): Format,
+) -> Result<ApiResponse<InviteCodesResponse>, AppError> {
+    let invites = invite_code_service.get_invite_codes().await?;
+
+    let result = InviteCodesResponse {
+        invite_codes: InviteCodeList {
+            invite_codes: invites.into_iter().map(InviteCodeInfo::from).collect(),
+        },
+    };
+
+    Ok(ApiResponse::ok(Some(result), format))
+}
+
+/// GET /rest/deleteInviteCode - 吊销邀请码
+pub async fn delete_invite_code(
+    axum::extract::State(invite_code_service): axum::extract::State<Arc<InviteCodeService>>,
+    Params(params): Params<DeleteInviteCodeParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    let id = params.id.ok_or_else(|| AppError::missing_parameter("id"))?;
+
+    invite_code_service.delete_invite_code(&id).await?;
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+pub fn routes() -> Router<Arc<InviteCodeService>> {
+    Router::new()
+        .route(
+            "/rest/createInviteCode",
+            get(create_invite_code).post(create_invite_code),
+        )
+        .route(
+            "/rest/getInviteCodes",
+            get(get_invite_codes).post(get_invite_codes),
+        )
+        .route(
+            "/rest/deleteInviteCode",
+            get(delete_invite_code).post(delete_invite_code),
+        )
+        // 仅管理员可管理邀请码
+        .route_layer(axum::middleware::from_fn(admin_middleware))
+}
@@ -0,0 +1,111 @@
+//! 点唱机端点处理器
+//!
+//! jukeboxControl 需要 jukeboxRole (由角色中间件检查)。
+#![allow(dead_code)]
+
+use axum::{
+    response::{IntoResponse, Response},
+    routing::get,
+    Router,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::error::AppError;
+use crate::extractors::{Format, Params};
+use crate::middleware::auth_middleware;
+use crate::models::response::{JukeboxPlaylistResponse, JukeboxStatusResponse};
+use crate::response::ApiResponse;
+use crate::services::{FolderScope, JukeboxService};
+
+/// 点唱机控制参数
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JukeboxControlParams {
+    /// get、status、set、start、stop、skip、add、clear、remove、shuffle、setGain
+    pub action: Option<String>,
+    /// 歌曲 ID (set / add，可重复)
+    #[serde(default)]
+    pub id: Vec<String>,
+    /// 队列位置 (skip / remove)
+    pub index: Option<i32>,
+    /// 起始秒数 (skip)
+    pub offset: Option<i32>,
+    /// 音量 0.0 - 1.0 (setGain)
+    pub gain: Option<f32>,
+}
+
+/// GET /rest/jukeboxControl - 控制服务器端播放
+///
+/// `get` 返回播放列表，其余操作返回播放状态
+pub async fn jukebox_control(
+    claims: auth_middleware::Claims,
+    scope: FolderScope,
+    axum::extract::State(jukebox_service): axum::extract::State<Arc<JukeboxService>>,
+    Params(params): Params<JukeboxControlParams>,
+    Format(format): Format,
+) -> Result<Response, AppError> {
+    let action = params
+        .action
+        .ok_or_else(|| AppError::missing_parameter("action"))?;
+
+    let status = match action.as_str() {
+        "get" => {
+            let playlist = jukebox_service.playlist(&claims.sub, &scope).await?;
+            let result = JukeboxPlaylistResponse {
+                jukebox_playlist: playlist.into(),
+            };
+            return Ok(ApiResponse::ok(Some(result), format).into_response());
+        }
+        "status" => jukebox_service.status().await?,
+        "set" => jukebox_service.set(&params.id, &scope).await?,
+        "add" => {
+            if params.id.is_empty() {
+                return Err(AppError::missing_parameter("id"));
+            }
+            jukebox_service.add(&params.id, &scope).await?
+        }
+        "start" => jukebox_service.start_playback().await?,
+        "stop" => jukebox_service.stop_playback().await?,
+        "skip" => {
+            let index = params
+                .index
+                .ok_or_else(|| AppError::missing_parameter("index"))?;
+            jukebox_service
+                .skip(index, params.offset.unwrap_or(0))
+                .await?
+        }
+        "clear" => jukebox_service.clear().await?,
+        "remove" => {
+            let index = params
+                .index
+                .ok_or_else(|| AppError::missing_parameter("index"))?;
+            jukebox_service.remove(index).await?
+        }
+        "shuffle" => jukebox_service.shuffle().await?,
+        "setGain" => {
+            let gain = params
+                .gain
+                .ok_or_else(|| AppError::missing_parameter("gain"))?;
+            jukebox_service.set_gain(gain).await?
+        }
+        _ => {
+            return Err(AppError::validation_error(&format!(
+                "Unknown jukebox action: {}",
+                action
+            )))
+        }
+    };
+
+    let result = JukeboxStatusResponse {
+        jukebox_status: status.into(),
+    };
+    Ok(ApiResponse::ok(Some(result), format).into_response())
+}
+
+pub fn routes() -> Router<Arc<JukeboxService>> {
+    Router::new().route(
+        "/rest/jukeboxControl",
+        get(jukebox_control).post(jukebox_control),
+    )
+}
@@ -2,14 +2,18 @@
 #![allow(dead_code)]
 
 use crate::error::AppError;
-use crate::extractors::Format;
+use crate::extractors::{Format, Params};
+use crate::middleware::admin_middleware;
 use crate::models::response::{
     AlbumResponse, ArtistResponse, RatingResponse, RatingResponseWrapper, Song, Starred2Response,
     Starred2ResponseWrapper, StarredResponse, StarredResponseWrapper, ToXml,
 };
 use crate::response::ApiResponse;
-use crate::services::{LibraryService, ScanService, StarItemType};
-use axum::{extract::Query, routing::get, Router};
+use crate::services::scan_service::ScanResult;
+use crate::services::{
+    EventService, FolderScope, LibraryService, ScanService, ServerEvent, StarItemType,
+};
+use axum::{routing::get, Router};
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -73,6 +77,8 @@ pub struct ScrobbleParams {
     pub id: String,
     pub submission: Option<String>,
     pub time: Option<i64>,
+    /// 客户端名称，作为正在播放的播放器名称
+    pub c: Option<String>,
 }
 
 /// 扫描状态（共享）
@@ -81,6 +87,57 @@ pub struct ScanState {
     pub scanning: Arc<Mutex<bool>>,
     pub current: Arc<Mutex<usize>>,
     pub count: Arc<Mutex<usize>>,
+    pub events: Arc<EventService>,
+}
+
+impl ScanState {
+    pub fn new(events: Arc<EventService>) -> Self {
+        Self {
+            scanning: Arc::new(Mutex::new(false)),
+            current: Arc::new(Mutex::new(0)),
+            count: Arc::new(Mutex::new(0)),
+            events,
+        }
+    }
+
+    /// 标记扫描开始，已有扫描 (手动或监听触发) 进行中时返回 false
+    pub async fn begin(&self) -> bool {
+        let mut scanning = self.scanning.lock().await;
+        if *scanning {
+            return false;
+        }
+        *scanning = true;
+        *self.current.lock().await = 0;
+        *self.count.lock().await = 0;
+        drop(scanning);
+        self.publish_progress().await;
+        true
+    }
+
+    /// 标记扫描结束，`changes` 为扫描结果时推送媒体库变化
+    pub async fn finish(&self, changes: Option<&ScanResult>) {
+        if let Some(result) = changes {
+            self.events.publish(ServerEvent::LibraryChanged {
+                songs: result.songs,
+                videos: result.videos,
+                deleted: result.deleted,
+            });
+        }
+        *self.scanning.lock().await = false;
+        self.publish_progress().await;
+    }
+
+    /// 推送当前扫描进度
+    pub async fn publish_progress(&self) {
+        let scanning = *self.scanning.lock().await;
+        let current = *self.current.lock().await;
+        let count = *self.count.lock().await;
+        self.events.publish(ServerEvent::ScanProgress {
+            scanning,
+            current,
+            count,
+        });
+    }
 }
 
 /// 组合状态,用于 library 路由
@@ -116,14 +173,12 @@ pub async fn get_scan_status(
 /// POST /rest/startScan
 pub async fn start_scan(
     axum::extract::State(state): axum::extract::State<LibraryState>,
-    _params: Query<ScanParams>,
+    _params: Params<ScanParams>,
     Format(format): Format,
 ) -> Result<ApiResponse<()>, AppError> {
-    let mut scanning = state.scan_state.scanning.lock().await;
-    if *scanning {
+    if !state.scan_state.begin().await {
         return Err(AppError::server_busy("Scan already in progress"));
     }
-    *scanning = true;
 
     // 启动后台扫描任务
     let service = state.scan_service.clone();
@@ -133,14 +188,13 @@ pub async fn start_scan(
         match service.scan_library(scan_state.clone()).await {
             Ok(result) => {
                 tracing::info!("扫描完成: {:?}", result);
+                scan_state.finish(Some(&result)).await;
             }
             Err(e) => {
                 tracing::error!("扫描失败: {}", e);
+                scan_state.finish(None).await;
             }
         }
-        // 更新状态
-        let mut scanning = scan_state.scanning.lock().await;
-        *scanning = false;
     });
 
     Ok(ApiResponse::ok(None, format))
@@ -150,30 +204,40 @@ pub async fn start_scan(
 pub async fn scrobble(
     claims: crate::middleware::auth_middleware::Claims,
     axum::extract::State(state): axum::extract::State<LibraryState>,
-    Query(params): Query<ScrobbleParams>,
+    Params(params): Params<ScrobbleParams>,
     Format(format): Format,
 ) -> Result<ApiResponse<()>, AppError> {
-    // 检查Scrobble权限
-    let permissions =
-        crate::middleware::auth_middleware::get_user_permissions(&state.pool, &claims.sub)
-            .await
-            .map_err(|_| AppError::access_denied("Failed to check permissions"))?;
-
-    if !permissions.can_scrobble() {
-        return Err(AppError::access_denied("Scrobbling permission required"));
-    }
-
     let timestamp = params
         .time
         .unwrap_or_else(|| chrono::Utc::now().timestamp());
     let submission = params.submission.unwrap_or("True".to_string());
-    let submission = submission == "True";
-
-    // 调用 Service 层 (带事务保护)
-    state
-        .library_service
-        .submit_scrobble(&claims.sub, &params.id, timestamp, submission)
-        .await?;
+    let submission = submission.eq_ignore_ascii_case("true");
+
+    if submission {
+        // 调用 Service 层 (带事务保护)
+        state
+            .library_service
+            .submit_scrobble(&claims.sub, &params.id, timestamp, submission)
+            .await?;
+    } else {
+        // 非正式提交仅更新正在播放
+        state
+            .library_service
+            .update_now_playing(
+                &claims.sub,
+                &claims.username,
+                &params.id,
+                params.c.as_deref().unwrap_or(""),
+                chrono::Utc::now().timestamp(),
+            )
+            .await?;
+
+        state.scan_state.events.publish(ServerEvent::NowPlaying {
+            username: claims.username.clone(),
+            song_id: params.id.clone(),
+            player_name: params.c.clone(),
+        });
+    }
 
     Ok(ApiResponse::ok(None, format))
 }
@@ -182,7 +246,7 @@ pub async fn scrobble(
 pub async fn star(
     claims: crate::middleware::auth_middleware::Claims,
     axum::extract::State(state): axum::extract::State<LibraryState>,
-    Query(params): Query<StarParams>,
+    Params(params): Params<StarParams>,
     Format(format): Format,
 ) -> Result<ApiResponse<()>, AppError> {
     let user_id = &claims.sub;
@@ -216,7 +280,7 @@ pub async fn star(
 pub async fn unstar(
     claims: crate::middleware::auth_middleware::Claims,
     axum::extract::State(state): axum::extract::State<LibraryState>,
-    Query(params): Query<StarParams>,
+    Params(params): Params<StarParams>,
     Format(format): Format,
 ) -> Result<ApiResponse<()>, AppError> {
     let user_id = &claims.sub;
@@ -250,7 +314,7 @@ pub async fn unstar(
 pub async fn set_rating(
     claims: crate::middleware::auth_middleware::Claims,
     axum::extract::State(state): axum::extract::State<LibraryState>,
-    Query(params): Query<SetRatingParams>,
+    Params(params): Params<SetRatingParams>,
     Format(format): Format,
 ) -> Result<ApiResponse<()>, AppError> {
     let user_id = &claims.sub;
@@ -268,7 +332,7 @@ pub async fn set_rating(
 pub async fn get_rating(
     claims: crate::middleware::auth_middleware::Claims,
     axum::extract::State(state): axum::extract::State<LibraryState>,
-    Query(params): Query<GetRatingParams>,
+    Params(params): Params<GetRatingParams>,
     Format(format): Format,
 ) -> Result<ApiResponse<RatingResponseWrapper>, AppError> {
     let user_id = &claims.sub;
@@ -295,13 +359,17 @@ pub async fn get_rating(
 pub async fn get_starred(
     claims: crate::middleware::auth_middleware::Claims,
     axum::extract::State(state): axum::extract::State<LibraryState>,
-    _params: Query<ScanParams>,
+    _params: Params<ScanParams>,
     Format(format): Format,
+    scope: FolderScope,
 ) -> Result<ApiResponse<StarredResponseWrapper>, AppError> {
     let user_id = &claims.sub;
 
     // 调用 Service 层 (并行查询三个表)
-    let starred_items = state.library_service.get_starred_items(user_id).await?;
+    let starred_items = state
+        .library_service
+        .get_starred_items(user_id, &scope)
+        .await?;
 
     let result = StarredResponseWrapper {
         starred: StarredResponse {
@@ -330,15 +398,16 @@ pub async fn get_starred(
 pub async fn get_starred2(
     claims: crate::middleware::auth_middleware::Claims,
     axum::extract::State(state): axum::extract::State<LibraryState>,
-    _params: Query<ScanParams>,
+    _params: Params<ScanParams>,
     Format(format): Format,
+    scope: FolderScope,
 ) -> Result<ApiResponse<Starred2ResponseWrapper>, AppError> {
     let user_id = &claims.sub;
 
     // 调用 Service 层 (并行查询三个表，返回详细信息)
     let starred_items = state
         .library_service
-        .get_starred_items_with_details(user_id)
+        .get_starred_items_with_details(user_id, &scope)
         .await?;
     // tracing::info!("starred_items: {:?}", starred_items);
 
@@ -369,13 +438,8 @@ pub fn routes(
     pool: Arc<sqlx::SqlitePool>,
     scan_service: Arc<ScanService>,
     library_service: Arc<LibraryService>,
+    scan_state: ScanState,
 ) -> Router {
-    let scan_state = ScanState {
-        scanning: Arc::new(Mutex::new(false)),
-        current: Arc::new(Mutex::new(0)),
-        count: Arc::new(Mutex::new(0)),
-    };
-
     let library_state = LibraryState {
         pool: pool.clone(),
         scan_service,
@@ -383,15 +447,23 @@ pub fn routes(
         scan_state,
     };
 
+    // 扫描控制仅管理员可访问
+    let admin_routes = Router::new()
+        .route("/rest/startScan", get(start_scan).post(start_scan))
+        .route_layer(axum::middleware::from_fn(admin_middleware));
+
     Router::new()
-        .route("/rest/getScanStatus", get(get_scan_status))
-        .route("/rest/startScan", get(start_scan))
-        .route("/rest/scrobble", get(scrobble))
-        .route("/rest/star", get(star))
-        .route("/rest/unstar", get(unstar))
-        .route("/rest/setRating", get(set_rating))
-        .route("/rest/getRating", get(get_rating))
-        .route("/rest/getStarred", get(get_starred))
-        .route("/rest/getStarred2", get(get_starred2))
+        .route(
+            "/rest/getScanStatus",
+            get(get_scan_status).post(get_scan_status),
+        )
+        .merge(admin_routes)
+        .route("/rest/scrobble", get(scrobble).post(scrobble))
+        .route("/rest/star", get(star).post(star))
+        .route("/rest/unstar", get(unstar).post(unstar))
+        .route("/rest/setRating", get(set_rating).post(set_rating))
+        .route("/rest/getRating", get(get_rating).post(get_rating))
+        .route("/rest/getStarred", get(get_starred).post(get_starred))
+        .route("/rest/getStarred2", get(get_starred2).post(get_starred2))
         .with_state(library_state)
 }
@@ -0,0 +1,73 @@
+//! 登录锁定管理端点处理器 (仅管理员)
+#![allow(dead_code)]
+
+use axum::{routing::get, Router};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::error::AppError;
+use crate::extractors::{Format, Params};
+use crate::middleware::admin_middleware;
+use crate::models::response::{LockoutInfo, LockoutList, LockoutsResponse};
+use crate::response::ApiResponse;
+use crate::services::{LockoutKey, LoginThrottleService};
+
+/// 解除锁定参数，均未指定时解除全部锁定
+#[derive(Debug, Deserialize)]
+pub struct ClearLockoutsParams {
+    pub username: Option<String>,
+    pub ip: Option<String>,
+}
+
+/// GET /rest/getLockouts - 获取当前被锁定的用户名与 IP
+pub async fn get_lockouts(
+    axum::extract::State(login_throttle): axum::extract::State<Arc<LoginThrottleService>>,
+    Format(format): Format,
+) -> Result<ApiResponse<LockoutsResponse>, AppError> {
+    let result = LockoutsResponse {
+        lockouts: LockoutList {
+            lockouts: login_throttle
+                .lockouts()
+                .into_iter()
+                .map(LockoutInfo::from)
+                .collect(),
+        },
+    };
+
+    Ok(ApiResponse::ok(Some(result), format))
+}
+
+/// GET /rest/clearLockouts - 解除锁定
+pub async fn clear_lockouts(
+    axum::extract::State(login_throttle): axum::extract::State<Arc<LoginThrottleService>>,
+    Params(params): Params<ClearLockoutsParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    if params.username.is_none() && params.ip.is_none() {
+        login_throttle.clear(None);
+        return Ok(ApiResponse::ok(None, format));
+    }
+
+    if let Some(username) = params.username {
+        login_throttle.clear(Some(&LockoutKey::User(username)));
+    }
+    if let Some(ip) = params.ip {
+        let ip = ip
+            .parse()
+            .map_err(|_| AppError::validation_error("Invalid IP address"))?;
+        login_throttle.clear(Some(&LockoutKey::Ip(ip)));
+    }
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+pub fn routes() -> Router<Arc<LoginThrottleService>> {
+    Router::new()
+        .route("/rest/getLockouts", get(get_lockouts).post(get_lockouts))
+        .route(
+            "/rest/clearLockouts",
+            get(clear_lockouts).post(clear_lockouts),
+        )
+        // 仅管理员可管理登录锁定
+        .route_layer(axum::middleware::from_fn(admin_middleware))
+}
@@ -1,10 +1,19 @@
 pub mod advanced;
+pub mod api_key;
 pub mod auth;
+pub mod bookmark;
 pub mod browsing;
+pub mod events;
+pub mod internet_radio;
+pub mod invite_code;
+pub mod jukebox;
 pub mod library;
+pub mod lockout;
 pub mod play_queue;
 pub mod playlist;
+pub mod podcast;
 pub mod search;
+pub mod share;
 pub mod stream;
 pub mod system;
 pub mod user;
@@ -1,14 +1,13 @@
 //! 播放队列端点处理器
 
 use axum::{routing::get, Router};
-use axum_extra::extract::Query;
 use serde::Deserialize;
 use std::sync::Arc;
 
-use crate::extractors::Format;
+use crate::extractors::{Format, Params};
 use crate::models::response::{PlayQueueResponse, PlayQueueWrapper, Song};
 use crate::response::ApiResponse;
-use crate::services::PlayQueueService;
+use crate::services::{FolderScope, PlayQueueService};
 use crate::{error::AppError, middleware::auth_middleware};
 
 /// 播放队列处理器状态
@@ -29,18 +28,22 @@ pub struct SavePlayQueueParams {
 /// GET /rest/getPlayQueue - 获取用户的播放队列
 pub async fn get_play_queue(
     claims: auth_middleware::Claims,
+    scope: FolderScope,
     axum::extract::State(state): axum::extract::State<PlayQueueState>,
     Format(format): Format,
 ) -> Result<ApiResponse<PlayQueueWrapper>, AppError> {
     let user_id = &claims.sub;
 
     // 调用 Service 层
-    let play_queue = state.play_queue_service.get_play_queue(user_id).await?;
+    let play_queue = state
+        .play_queue_service
+        .get_play_queue(user_id, &scope)
+        .await?;
 
     match play_queue {
         Some(queue) => {
             // 转换为响应格式
-            let songs: Vec<Song> = queue.songs.into_iter().map(|s| Song::from(s)).collect();
+            let songs: Vec<Song> = queue.songs.into_iter().map(Song::from).collect();
 
             let response = PlayQueueResponse {
                 current: queue.current_song_id,
@@ -83,7 +86,7 @@ pub async fn get_play_queue(
 pub async fn save_play_queue(
     claims: auth_middleware::Claims,
     axum::extract::State(state): axum::extract::State<PlayQueueState>,
-    Query(params): Query<SavePlayQueueParams>,
+    Params(params): Params<SavePlayQueueParams>,
     Format(format): Format,
 ) -> Result<ApiResponse<()>, AppError> {
     let user_id = &claims.sub;
@@ -112,7 +115,10 @@ pub async fn save_play_queue(
 /// 注册播放队列路由
 pub fn routes(state: PlayQueueState) -> Router {
     Router::new()
-        .route("/rest/getPlayQueue", get(get_play_queue))
+        .route(
+            "/rest/getPlayQueue",
+            get(get_play_queue).post(get_play_queue),
+        )
         .route(
             "/rest/savePlayQueue",
             get(save_play_queue).post(save_play_queue),
@@ -1,23 +1,22 @@
 //! 播放列表端点处理器
 
-use axum::{extract::Query, routing::get, Router};
+use axum::{routing::get, Router};
 use serde::Deserialize;
 use std::sync::Arc;
 
-use crate::extractors::Format;
+use crate::extractors::{Format, Params};
 use crate::models::dto::{CreatePlaylistRequest, UpdatePlaylistRequest};
 use crate::models::response::{
     PlaylistDetail, PlaylistDetailWrapper, PlaylistResponse, Playlists, Song,
 };
 use crate::response::ApiResponse;
-use crate::services::PlaylistService;
+use crate::services::{FolderScope, PlaylistService};
 use crate::{error::AppError, middleware::auth_middleware};
 
 /// 播放列表处理器状态
 #[derive(Clone)]
 pub struct PlaylistState {
     pub playlist_service: Arc<PlaylistService>,
-    pub pool: Arc<sqlx::SqlitePool>,
 }
 
 /// 通用播放列表参数
@@ -30,7 +29,7 @@ pub struct PlaylistParams {
 pub async fn get_playlists(
     claims: auth_middleware::Claims,
     axum::extract::State(state): axum::extract::State<PlaylistState>,
-    Query(_params): Query<PlaylistParams>,
+    Params(_params): Params<PlaylistParams>,
     Format(format): Format,
 ) -> Result<ApiResponse<Playlists>, AppError> {
     // 认证中间件获取当前用户 ID
@@ -62,8 +61,9 @@ pub async fn get_playlists(
 /// GET /rest/getPlaylist - 获取播放列表详情
 pub async fn get_playlist(
     claims: auth_middleware::Claims,
+    scope: FolderScope,
     axum::extract::State(state): axum::extract::State<PlaylistState>,
-    Query(params): Query<PlaylistParams>,
+    Params(params): Params<PlaylistParams>,
     Format(format): Format,
 ) -> Result<ApiResponse<PlaylistDetailWrapper>, AppError> {
     let playlist_id = params.id.ok_or_else(|| AppError::missing_parameter("id"))?;
@@ -71,7 +71,7 @@ pub async fn get_playlist(
     // 调用 Service 层
     let detail = state
         .playlist_service
-        .get_playlist_detail(&playlist_id)
+        .get_playlist_detail(&playlist_id, &scope)
         .await?;
 
     // 转换为响应格式
@@ -84,7 +84,7 @@ pub async fn get_playlist(
             song_count: detail.song_count,
             duration: detail.duration,
             entry: Song::from_detail_dtos(detail.songs),
-            allowed_user: vec![claims.username.clone()] // TODO 这里需要根据歌单是否公开来判断是否显示用户列表
+            allowed_user: vec![claims.username.clone()], // TODO 这里需要根据歌单是否公开来判断是否显示用户列表
         },
     };
 
@@ -95,19 +95,9 @@ pub async fn get_playlist(
 pub async fn create_playlist(
     claims: crate::middleware::auth_middleware::Claims,
     axum::extract::State(state): axum::extract::State<PlaylistState>,
-    Query(body): Query<CreatePlaylistRequest>,
+    Params(body): Params<CreatePlaylistRequest>,
     Format(format): Format,
 ) -> Result<ApiResponse<()>, AppError> {
-    // 检查播放列表权限
-    let permissions =
-        crate::middleware::auth_middleware::get_user_permissions(&state.pool, &claims.sub)
-            .await
-            .map_err(|_| AppError::access_denied("Failed to check permissions"))?;
-
-    if !permissions.can_manage_playlist() {
-        return Err(AppError::access_denied("Playlist permission required"));
-    }
-
     // 调用 Service 层 (带事务保护)
     state
         .playlist_service
@@ -121,19 +111,9 @@ pub async fn create_playlist(
 pub async fn update_playlist(
     claims: crate::middleware::auth_middleware::Claims,
     axum::extract::State(state): axum::extract::State<PlaylistState>,
-    axum_extra::extract::Query(body): axum_extra::extract::Query<UpdatePlaylistRequest>,
+    Params(body): Params<UpdatePlaylistRequest>,
     Format(format): Format,
 ) -> Result<ApiResponse<()>, AppError> {
-    // 检查播放列表权限
-    let permissions =
-        crate::middleware::auth_middleware::get_user_permissions(&state.pool, &claims.sub)
-            .await
-            .map_err(|_| AppError::access_denied("Failed to check permissions"))?;
-
-    if !permissions.can_manage_playlist() {
-        return Err(AppError::access_denied("Playlist permission required"));
-    }
-
     // 调用 Service 层 (带事务保护,包含权限检查)
     state
         .playlist_service
@@ -147,19 +127,9 @@ pub async fn update_playlist(
 pub async fn delete_playlist(
     claims: crate::middleware::auth_middleware::Claims,
     axum::extract::State(state): axum::extract::State<PlaylistState>,
-    Query(params): Query<PlaylistParams>,
+    Params(params): Params<PlaylistParams>,
     Format(format): Format,
 ) -> Result<ApiResponse<()>, AppError> {
-    // 检查播放列表权限
-    let permissions =
-        crate::middleware::auth_middleware::get_user_permissions(&state.pool, &claims.sub)
-            .await
-            .map_err(|_| AppError::access_denied("Failed to check permissions"))?;
-
-    if !permissions.can_manage_playlist() {
-        return Err(AppError::access_denied("Playlist permission required"));
-    }
-
     let playlist_id = params.id.ok_or_else(|| AppError::missing_parameter("id"))?;
 
     // 调用 Service 层 (包含权限检查)
@@ -175,20 +145,10 @@ pub async fn delete_playlist(
 pub async fn append_playlist(
     claims: crate::middleware::auth_middleware::Claims,
     axum::extract::State(state): axum::extract::State<PlaylistState>,
-    Query(params): Query<PlaylistParams>,
-    Query(body): Query<CreatePlaylistRequest>,
+    Params(params): Params<PlaylistParams>,
+    Params(body): Params<CreatePlaylistRequest>,
     Format(format): Format,
 ) -> Result<ApiResponse<()>, AppError> {
-    // 检查播放列表权限
-    let permissions =
-        crate::middleware::auth_middleware::get_user_permissions(&state.pool, &claims.sub)
-            .await
-            .map_err(|_| AppError::access_denied("Failed to check permissions"))?;
-
-    if !permissions.can_manage_playlist() {
-        return Err(AppError::access_denied("Playlist permission required"));
-    }
-
     let playlist_id = params.id.ok_or_else(|| AppError::missing_parameter("id"))?;
 
     // 如果有歌曲,追加到播放列表
@@ -205,10 +165,22 @@ pub async fn append_playlist(
 
 pub fn routes() -> Router<PlaylistState> {
     Router::new()
-        .route("/rest/getPlaylists", get(get_playlists))
-        .route("/rest/getPlaylist", get(get_playlist))
-        .route("/rest/createPlaylist", get(create_playlist))
-        .route("/rest/updatePlaylist", get(update_playlist))
-        .route("/rest/deletePlaylist", get(delete_playlist))
-        .route("/rest/appendPlaylist", get(append_playlist))
+        .route("/rest/getPlaylists", get(get_playlists).post(get_playlists))
+        .route("/rest/getPlaylist", get(get_playlist).post(get_playlist))
+        .route(
+            "/rest/createPlaylist",
+            get(create_playlist).post(create_playlist),
+        )
+        .route(
+            "/rest/updatePlaylist",
+            get(update_playlist).post(update_playlist),
+        )
+        .route(
+            "/rest/deletePlaylist",
+            get(delete_playlist).post(delete_playlist),
+        )
+        .route(
+            "/rest/appendPlaylist",
+            get(append_playlist).post(append_playlist),
+        )
 }
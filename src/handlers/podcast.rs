@@ -0,0 +1,188 @@
+//! 播客端点处理器
+//!
+//! 刷新、订阅、删除和下载需要 podcastRole (由角色中间件检查)。
+//! 已下载的单集通过 `/rest/stream?id={streamId}` 播放。
+#![allow(dead_code)]
+
+use axum::{routing::get, Router};
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::error::AppError;
+use crate::extractors::{Format, Params};
+use crate::models::response::{
+    NewestPodcastsResponse, PodcastChannelInfo, PodcastChannelList, PodcastEpisodeInfo,
+    PodcastEpisodeList, PodcastsResponse,
+};
+use crate::response::ApiResponse;
+use crate::services::PodcastService;
+
+/// 获取频道参数
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GetPodcastsParams {
+    /// 是否包含单集，默认为 true
+    pub include_episodes: Option<bool>,
+    pub id: Option<String>,
+}
+
+/// 获取最新单集参数
+#[derive(Debug, Deserialize)]
+pub struct GetNewestPodcastsParams {
+    pub count: Option<i64>,
+}
+
+/// 订阅频道参数
+#[derive(Debug, Deserialize)]
+pub struct CreatePodcastChannelParams {
+    pub url: Option<String>,
+}
+
+/// 频道或单集 ID 参数
+#[derive(Debug, Deserialize)]
+pub struct PodcastIdParams {
+    pub id: Option<String>,
+}
+
+/// GET /rest/getPodcasts - 获取订阅的频道
+pub async fn get_podcasts(
+    axum::extract::State(podcast_service): axum::extract::State<Arc<PodcastService>>,
+    Params(params): Params<GetPodcastsParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<PodcastsResponse>, AppError> {
+    let channels = podcast_service
+        .get_channels(
+            params.id.as_deref(),
+            params.include_episodes.unwrap_or(true),
+        )
+        .await?;
+
+    let result = PodcastsResponse {
+        podcasts: PodcastChannelList {
+            channels: channels.into_iter().map(PodcastChannelInfo::from).collect(),
+        },
+    };
+
+    Ok(ApiResponse::ok(Some(result), format))
+}
+
+/// GET /rest/getNewestPodcasts - 获取最新发布的单集
+pub async fn get_newest_podcasts(
+    axum::extract::State(podcast_service): axum::extract::State<Arc<PodcastService>>,
+    Params(params): Params<GetNewestPodcastsParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<NewestPodcastsResponse>, AppError> {
+    let episodes = podcast_service
+        .get_newest_episodes(params.count.unwrap_or(20))
+        .await?;
+
+    let result = NewestPodcastsResponse {
+        newest_podcasts: PodcastEpisodeList {
+            episodes: episodes.into_iter().map(PodcastEpisodeInfo::from).collect(),
+        },
+    };
+
+    Ok(ApiResponse::ok(Some(result), format))
+}
+
+/// GET /rest/refreshPodcasts - 在后台刷新所有频道
+pub async fn refresh_podcasts(
+    axum::extract::State(podcast_service): axum::extract::State<Arc<PodcastService>>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    tokio::spawn(async move {
+        if let Err(e) = podcast_service.refresh_all().await {
+            tracing::warn!("Failed to refresh podcasts: {}", e);
+        }
+    });
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+/// GET /rest/createPodcastChannel - 订阅频道，并在后台获取单集
+pub async fn create_podcast_channel(
+    axum::extract::State(podcast_service): axum::extract::State<Arc<PodcastService>>,
+    Params(params): Params<CreatePodcastChannelParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    let url = params
+        .url
+        .ok_or_else(|| AppError::missing_parameter("url"))?;
+
+    let channel = podcast_service.create_channel(&url).await?;
+    tokio::spawn(async move {
+        if let Err(e) = podcast_service.refresh_channel(&channel.id).await {
+            tracing::warn!("Failed to refresh podcast {}: {}", channel.url, e);
+        }
+    });
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+/// GET /rest/deletePodcastChannel - 删除频道及已下载的单集
+pub async fn delete_podcast_channel(
+    axum::extract::State(podcast_service): axum::extract::State<Arc<PodcastService>>,
+    Params(params): Params<PodcastIdParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    let id = params.id.ok_or_else(|| AppError::missing_parameter("id"))?;
+
+    podcast_service.delete_channel(&id).await?;
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+/// GET /rest/downloadPodcastEpisode - 将单集加入后台下载队列
+pub async fn download_podcast_episode(
+    axum::extract::State(podcast_service): axum::extract::State<Arc<PodcastService>>,
+    Params(params): Params<PodcastIdParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    let id = params.id.ok_or_else(|| AppError::missing_parameter("id"))?;
+
+    podcast_service.queue_download(&id).await?;
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+/// GET /rest/deletePodcastEpisode - 删除已下载的单集
+pub async fn delete_podcast_episode(
+    axum::extract::State(podcast_service): axum::extract::State<Arc<PodcastService>>,
+    Params(params): Params<PodcastIdParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    let id = params.id.ok_or_else(|| AppError::missing_parameter("id"))?;
+
+    podcast_service.delete_episode(&id).await?;
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+pub fn routes() -> Router<Arc<PodcastService>> {
+    Router::new()
+        .route("/rest/getPodcasts", get(get_podcasts).post(get_podcasts))
+        .route(
+            "/rest/getNewestPodcasts",
+            get(get_newest_podcasts).post(get_newest_podcasts),
+        )
+        .route(
+            "/rest/refreshPodcasts",
+            get(refresh_podcasts).post(refresh_podcasts),
+        )
+        .route(
+            "/rest/createPodcastChannel",
+            get(create_podcast_channel).post(create_podcast_channel),
+        )
+        .route(
+            "/rest/deletePodcastChannel",
+            get(delete_podcast_channel).post(delete_podcast_channel),
+        )
+        .route(
+            "/rest/downloadPodcastEpisode",
+            get(download_podcast_episode).post(download_podcast_episode),
+        )
+        .route(
+            "/rest/deletePodcastEpisode",
+            get(delete_podcast_episode).post(delete_podcast_episode),
+        )
+}
@@ -2,15 +2,15 @@
 #![allow(dead_code)]
 
 use crate::error::AppError;
-use crate::extractors::Format;
+use crate::extractors::{Format, Params};
 use crate::models::response::{
     SearchResult2, SearchResult2Response, SearchResult3, SearchResult3Response,
     SearchResultResponse,
 };
 use crate::response::ApiResponse;
 use crate::services::search_service::SearchParams;
-use crate::services::SearchService;
-use axum::{extract::Query, routing::get, Router};
+use crate::services::{FolderScope, SearchService};
+use axum::{routing::get, Router};
 use serde::Deserialize;
 use std::sync::Arc;
 
@@ -25,6 +25,7 @@ pub struct Search3Params {
     pub album_offset: Option<i32>,
     pub song_count: Option<i32>,
     pub song_offset: Option<i32>,
+    pub music_folder_id: Option<i32>,
 }
 
 /// 搜索参数 (search2)
@@ -38,14 +39,16 @@ pub struct Search2Params {
     pub album_offset: Option<i32>,
     pub song_count: Option<i32>,
     pub song_offset: Option<i32>,
+    pub music_folder_id: Option<i32>,
 }
 
 /// GET /rest/search3
 pub async fn search3(
     axum::extract::State(state): axum::extract::State<Arc<SearchService>>,
-    Query(params): Query<Search3Params>,
+    Params(params): Params<Search3Params>,
     Format(format): Format,
     claims: crate::middleware::auth_middleware::Claims,
+    scope: FolderScope,
 ) -> Result<ApiResponse<SearchResult3Response>, AppError> {
     let artist_count = params.artist_count.unwrap_or(20);
     let artist_offset = params.artist_offset.unwrap_or(0);
@@ -68,6 +71,7 @@ pub async fn search3(
         album_offset,
         song_count,
         song_offset,
+        scope: scope.narrow(params.music_folder_id),
     };
 
     let result = state.search_all(&claims.sub, params).await?;
@@ -86,9 +90,10 @@ pub async fn search3(
 /// GET /rest/search2
 pub async fn search2(
     axum::extract::State(state): axum::extract::State<Arc<SearchService>>,
-    Query(params): Query<Search2Params>,
+    Params(params): Params<Search2Params>,
     Format(format): Format,
     claims: crate::middleware::auth_middleware::Claims,
+    scope: FolderScope,
 ) -> Result<ApiResponse<SearchResult2Response>, AppError> {
     let artist_count = params.artist_count.unwrap_or(20);
     let artist_offset = params.artist_offset.unwrap_or(0);
@@ -105,6 +110,7 @@ pub async fn search2(
         album_offset,
         song_count,
         song_offset,
+        scope: scope.narrow(params.music_folder_id),
     };
 
     let result = state.search_all_simple(&claims.sub, params).await?;
@@ -128,7 +134,7 @@ pub async fn search(Format(format): Format) -> Result<ApiResponse<SearchResultRe
 
 pub fn routes() -> Router<Arc<SearchService>> {
     Router::new()
-        .route("/rest/search3", get(search3))
-        .route("/rest/search2", get(search2))
-        .route("/rest/search", get(search))
+        .route("/rest/search3", get(search3).post(search3))
+        .route("/rest/search2", get(search2).post(search2))
+        .route("/rest/search", get(search).post(search))
 }
@@ -0,0 +1,360 @@
+//! 分享端点处理器
+//!
+//! - `/rest/*Share*`: Subsonic 分享管理端点 (需要认证，创建与修改需要 shareRole)
+//! - `/share/{token}`: 公开分享页面与歌曲播放 (免登录，按客户端 IP 限流，歌曲流按客户端 IP 和分享单独限流)
+#![allow(dead_code)]
+
+use axum::{
+    extract::Path,
+    http::{header, HeaderMap},
+    response::{Html, Response},
+    routing::get,
+    Router,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use crate::error::AppError;
+use crate::extractors::{ClientIp, Format, Params, TrustedProxy};
+use crate::middleware::auth_middleware::Claims;
+use crate::models::response::common::html_escape;
+use crate::models::response::{ShareInfo, ShareList, SharesResponse};
+use crate::response::ApiResponse;
+use crate::services::{FolderScope, ShareDetail, ShareService};
+use crate::utils::rate_limiter::RateLimiter;
+use crate::utils::stream_utils;
+
+/// 分享处理器状态
+#[derive(Clone)]
+pub struct ShareState {
+    pub share_service: Arc<ShareService>,
+    /// 公开分享页面的限流器
+    pub rate_limiter: Arc<RateLimiter>,
+    /// 分享歌曲流的限流器，按客户端 IP 和分享计数，播放时的续传请求较多，上限更高
+    pub stream_rate_limiter: Arc<RateLimiter<(IpAddr, String)>>,
+    /// 分享链接的外部访问地址，为空时按请求的 Host 生成
+    pub base_url: Option<String>,
+}
+
+impl ShareState {
+    /// 分享链接的外部访问地址
+    ///
+    /// 只有对端是受信任的反向代理时才采信 `X-Forwarded-Host` / `X-Forwarded-Proto`
+    fn base_url(&self, headers: &HeaderMap, trusted_proxy: bool) -> String {
+        if let Some(base_url) = &self.base_url {
+            return base_url.clone();
+        }
+
+        let header_value = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let forwarded = |name: &str| header_value(name).filter(|_| trusted_proxy);
+        let host = forwarded("x-forwarded-host")
+            .or_else(|| header_value(header::HOST.as_str()))
+            .unwrap_or("localhost");
+        let scheme = forwarded("x-forwarded-proto").unwrap_or("http");
+
+        format!("{}://{}", scheme, host)
+    }
+}
+
+/// 创建分享参数
+#[derive(Debug, Deserialize)]
+pub struct CreateShareParams {
+    /// 歌曲、专辑或播放列表 ID (可重复)
+    #[serde(default)]
+    pub id: Vec<String>,
+    pub description: Option<String>,
+    /// 过期时间 (毫秒时间戳)
+    pub expires: Option<i64>,
+}
+
+/// 更新分享参数
+#[derive(Debug, Deserialize)]
+pub struct UpdateShareParams {
+    pub id: Option<String>,
+    pub description: Option<String>,
+    /// 过期时间 (毫秒时间戳)，0 表示永不过期
+    pub expires: Option<i64>,
+}
+
+/// 删除分享参数
+#[derive(Debug, Deserialize)]
+pub struct DeleteShareParams {
+    pub id: Option<String>,
+}
+
+/// 毫秒时间戳转换为过期时间
+fn parse_expires(millis: i64) -> Result<DateTime<Utc>, AppError> {
+    DateTime::from_timestamp_millis(millis)
+        .ok_or_else(|| AppError::validation_error("Invalid expires timestamp"))
+}
+
+/// GET /rest/createShare - 创建分享
+pub async fn create_share(
+    claims: Claims,
+    scope: FolderScope,
+    axum::extract::State(state): axum::extract::State<ShareState>,
+    TrustedProxy(trusted_proxy): TrustedProxy,
+    headers: HeaderMap,
+    Params(params): Params<CreateShareParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<SharesResponse>, AppError> {
+    let expires_at = params.expires.map(parse_expires).transpose()?;
+
+    let detail = state
+        .share_service
+        .create_share(
+            &claims.sub,
+            &params.id,
+            params.description,
+            expires_at,
+            &scope,
+        )
+        .await?;
+
+    let base_url = state.base_url(&headers, trusted_proxy);
+    let result = SharesResponse {
+        shares: ShareList {
+            shares: vec![ShareInfo::new(detail, &base_url)],
+        },
+    };
+
+    Ok(ApiResponse::ok(Some(result), format))
+}
+
+/// GET /rest/getShares - 获取当前用户的分享
+pub async fn get_shares(
+    claims: Claims,
+    axum::extract::State(state): axum::extract::State<ShareState>,
+    TrustedProxy(trusted_proxy): TrustedProxy,
+    headers: HeaderMap,
+    Format(format): Format,
+) -> Result<ApiResponse<SharesResponse>, AppError> {
+    let details = state.share_service.get_shares(&claims.sub).await?;
+
+    let base_url = state.base_url(&headers, trusted_proxy);
+    let result = SharesResponse {
+        shares: ShareList {
+            shares: details
+                .into_iter()
+                .map(|detail| ShareInfo::new(detail, &base_url))
+                .collect(),
+        },
+    };
+
+    Ok(ApiResponse::ok(Some(result), format))
+}
+
+/// GET /rest/updateShare - 更新分享的描述和过期时间
+pub async fn update_share(
+    claims: Claims,
+    axum::extract::State(state): axum::extract::State<ShareState>,
+    Params(params): Params<UpdateShareParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    let id = params.id.ok_or_else(|| AppError::missing_parameter("id"))?;
+    let expires_at = match params.expires {
+        Some(0) => Some(None),
+        Some(millis) => Some(Some(parse_expires(millis)?)),
+        None => None,
+    };
+
+    state
+        .share_service
+        .update_share(
+            &claims.sub,
+            claims.is_admin,
+            &id,
+            params.description,
+            expires_at,
+        )
+        .await?;
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+/// GET /rest/deleteShare - 删除分享
+pub async fn delete_share(
+    claims: Claims,
+    axum::extract::State(state): axum::extract::State<ShareState>,
+    Params(params): Params<DeleteShareParams>,
+    Format(format): Format,
+) -> Result<ApiResponse<()>, AppError> {
+    let id = params.id.ok_or_else(|| AppError::missing_parameter("id"))?;
+
+    state
+        .share_service
+        .delete_share(&claims.sub, claims.is_admin, &id)
+        .await?;
+
+    Ok(ApiResponse::ok(None, format))
+}
+
+/// 公开分享访问限流 (按客户端 IP)
+fn check_rate_limit<K: Eq + Hash>(
+    rate_limiter: &RateLimiter<K>,
+    key: Option<K>,
+) -> Result<(), AppError> {
+    let Some(key) = key else {
+        return Ok(());
+    };
+
+    rate_limiter.check(key).map_err(|retry_after| {
+        AppError::too_many_requests(&format!(
+            "Too many requests, try again in {} seconds",
+            retry_after
+        ))
+    })
+}
+
+/// GET /share/{token} - 公开分享页面
+pub async fn share_page(
+    axum::extract::State(state): axum::extract::State<ShareState>,
+    ClientIp(client_ip): ClientIp,
+    Path(token): Path<String>,
+) -> Result<Html<String>, AppError> {
+    check_rate_limit(&state.rate_limiter, client_ip)?;
+
+    let detail = state.share_service.visit(&token).await?;
+
+    Ok(Html(render_share_page(&token, &detail)))
+}
+
+/// GET /share/{token}/stream/{songId} - 播放分享中的歌曲 (支持 Range 请求)
+///
+/// 每个请求 (包括续传请求) 都计入该分享的歌曲流限流
+pub async fn share_stream(
+    axum::extract::State(state): axum::extract::State<ShareState>,
+    ClientIp(client_ip): ClientIp,
+    headers: HeaderMap,
+    Path((token, song_id)): Path<(String, String)>,
+) -> Result<Response, AppError> {
+    check_rate_limit(
+        &state.stream_rate_limiter,
+        client_ip.map(|ip| (ip, token.clone())),
+    )?;
+
+    let song = state
+        .share_service
+        .get_shared_song(&token, &song_id)
+        .await?;
+
+    let file_path = PathBuf::from(song.path.unwrap_or_default());
+    if !file_path.is_file() {
+        return Err(AppError::not_found("Audio file"));
+    }
+
+    let content_type = song
+        .content_type
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    stream_utils::serve_file(&file_path, &content_type, &headers, HeaderMap::new()).await
+}
+
+/// 生成公开分享页面
+fn render_share_page(token: &str, detail: &ShareDetail) -> String {
+    let title = html_escape(
+        detail
+            .share
+            .description
+            .as_deref()
+            .filter(|description| !description.is_empty())
+            .unwrap_or("Shared music"),
+    );
+
+    let mut items = String::new();
+    for song in &detail.songs {
+        items.push_str(&format!(
+            r#"<li><div>{} - {}</div><audio controls preload="none" src="{}/stream/{}"></audio></li>"#,
+            html_escape(&song.title),
+            html_escape(&song.artist),
+            html_escape(token),
+            html_escape(&song.id)
+        ));
+    }
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<meta name="robots" content="noindex">
+<title>{title}</title>
+</head>
+<body>
+<h1>{title}</h1>
+<p>Shared by {username}</p>
+<ol>{items}</ol>
+</body>
+</html>"#,
+        title = title,
+        username = html_escape(&detail.username),
+        items = items
+    )
+}
+
+/// Subsonic 分享管理端点 (需要认证)
+pub fn routes() -> Router<ShareState> {
+    Router::new()
+        .route("/rest/createShare", get(create_share).post(create_share))
+        .route("/rest/getShares", get(get_shares).post(get_shares))
+        .route("/rest/updateShare", get(update_share).post(update_share))
+        .route("/rest/deleteShare", get(delete_share).post(delete_share))
+}
+
+/// 公开分享端点 (免登录)
+pub fn public_routes() -> Router<ShareState> {
+    Router::new()
+        .route("/share/:token", get(share_page))
+        .route("/share/:token/stream/:song_id", get(share_stream))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ServiceContext;
+    use axum::extract::State;
+    use axum::http::HeaderValue;
+    use sqlx::SqlitePool;
+
+    #[tokio::test]
+    async fn test_stream_range_requests_are_limited() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        let state = ShareState {
+            share_service: Arc::new(ShareService::new(Arc::new(ServiceContext::new(pool)))),
+            rate_limiter: Arc::new(RateLimiter::new(1, 60)),
+            stream_rate_limiter: Arc::new(RateLimiter::new(2, 60)),
+            base_url: None,
+        };
+        let ip: IpAddr = "10.0.0.1".parse().unwrap();
+        let stream = |token: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::RANGE, HeaderValue::from_static("bytes=1-"));
+            share_stream(
+                State(state.clone()),
+                ClientIp(Some(ip)),
+                headers,
+                Path((token.to_string(), "s1".to_string())),
+            )
+        };
+        let limited = |result: Result<Response, AppError>| {
+            matches!(result, Err(AppError::TooManyRequests(_)))
+        };
+
+        // 续传请求同样计入限流
+        assert!(!limited(stream("t1").await));
+        assert!(!limited(stream("t1").await));
+        assert!(limited(stream("t1").await));
+        // 其他分享单独计数
+        assert!(!limited(stream("t2").await));
+    }
+}
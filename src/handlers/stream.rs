@@ -1,21 +1,31 @@
 //! 流媒体端点处理器
 #![allow(dead_code)]
 
-use axum::body::Body;
-use axum::{extract::Query, http::HeaderMap, response::IntoResponse, routing::get, Router};
-use serde::Deserialize;
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Extension, Router,
+};
+use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
-use tokio::fs::File;
-use tokio_util::io::ReaderStream;
 
 use crate::error::AppError;
-use crate::extractors::Format;
+use crate::extractors::{Format, Params, RequestParams};
 use crate::middleware::auth_middleware;
-use crate::models::response::{Lyrics, LyricsResponse};
+use crate::models::response::{Lyrics, LyricsResponse, ToXml};
 use crate::response::ApiResponse;
-use crate::services::ServiceContext;
-use crate::utils::{image_utils, MetaClient};
+use crate::services::transcode_service::{
+    SourceInfo, StreamDecision, TranscodeProfile, DEFAULT_BIT_RATE,
+};
+use crate::services::{
+    FolderScope, MusicFolderService, ServiceContext, SessionService, TranscodeService,
+};
+use crate::utils::transcode_cache::{self, TranscodeCache};
+use crate::utils::{hls_utils, image_utils, stream_utils, MetaClient};
+use tokio_util::io::ReaderStream;
 
 /// 流媒体参数
 #[derive(Debug, Deserialize)]
@@ -24,30 +34,64 @@ pub struct StreamParams {
     #[serde(rename = "maxBitRate")]
     pub max_bit_rate: Option<i32>,
     pub format: Option<String>,
-    #[serde(rename = "sampleRate")]
+    #[serde(rename = "timeOffset")]
     pub time_offset: Option<i32>,
-    #[serde(rename = "startTime")]
+    #[serde(rename = "estimateContentLength")]
     pub estimate_content_length: Option<bool>,
-    pub u: String,
-    pub t: Option<String>,
-    pub s: Option<String>,
-    pub p: Option<String>,
-    pub v: String,
-    pub c: String,
-    pub f: Option<String>,
 }
 
 /// 下载参数
 #[derive(Debug, Deserialize)]
 pub struct DownloadParams {
     pub id: String,
-    pub u: String,
-    pub t: Option<String>,
-    pub s: Option<String>,
-    pub p: Option<String>,
-    pub v: String,
-    pub c: String,
-    pub f: Option<String>,
+}
+
+/// HLS 播放列表参数
+#[derive(Debug, Deserialize)]
+pub struct HlsParams {
+    pub id: String,
+    /// 多次指定时生成多码率主播放列表
+    #[serde(rename = "bitRate", default)]
+    pub bit_rate: Vec<i32>,
+}
+
+/// HLS 分片参数
+#[derive(Debug, Deserialize)]
+pub struct HlsSegmentParams {
+    pub id: String,
+    pub index: usize,
+    #[serde(rename = "bitRate")]
+    pub bit_rate: Option<i32>,
+}
+
+/// HLS 分片转码输出 (MPEG-TS) 的 Content-Type
+const HLS_TRANSCODED_CONTENT_TYPE: &str = "video/mp2t";
+
+/// 可直接切分原始文件作为 HLS 分片的格式
+const HLS_PASSTHROUGH_SUFFIXES: &[&str] = &["mp3", "aac"];
+
+/// 转码缓存清理结果
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscodeCacheResponse {
+    pub transcode_cache: TranscodeCacheInfo,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscodeCacheInfo {
+    /// 清理的文件数
+    files: usize,
+    /// 清理的总字节数
+    size: u64,
+}
+
+impl ToXml for TranscodeCacheResponse {
+    fn to_xml_element(&self) -> String {
+        format!(
+            r#"<transcodeCache files="{}" size="{}"/>"#,
+            self.transcode_cache.files, self.transcode_cache.size
+        )
+    }
 }
 
 /// 获取封面参数
@@ -58,19 +102,59 @@ pub struct CoverArtParams {
 }
 
 /// GET /rest/stream - 流式播放音乐
+///
+/// 根据 `format` / `maxBitRate` 与用户码率上限决定是否实时转码，
+/// 不转码时返回原始文件并支持 Range 请求。
+/// `id` 也可以是已下载的播客单集或视频，视频始终返回原始文件。
 pub async fn stream(
+    claims: auth_middleware::Claims,
+    scope: FolderScope,
     axum::extract::State(state): axum::extract::State<StreamState>,
-    Query(params): Query<StreamParams>,
-) -> Result<impl IntoResponse, AppError> {
-    // 根据ID查询歌曲信息
-    let song = sqlx::query_as::<_, (String, Option<String>)>(
-        "SELECT file_path, content_type FROM songs WHERE id = ?",
-    )
+    headers: HeaderMap,
+    Params(params): Params<StreamParams>,
+) -> Result<Response, AppError> {
+    // 根据ID查询歌曲信息 (仅限用户可访问的音乐文件夹)
+    let mut song = sqlx::query_as::<_, (String, Option<String>, Option<i32>, String)>(&format!(
+        "SELECT file_path, content_type, bit_rate, updated_at FROM songs WHERE id = ? AND {}",
+        scope.sql_filter("music_folder_id")
+    ))
     .bind(&params.id)
     .fetch_optional(&state.ctx.pool)
     .await?;
 
-    let (file_path_str, content_type) = song.ok_or_else(|| AppError::not_found("Song"))?;
+    // 不是歌曲时查找已下载的播客单集
+    if song.is_none() {
+        song = sqlx::query_as(
+            "SELECT file_path, content_type, NULL, updated_at FROM podcast_episodes
+             WHERE id = ? AND status = 'completed' AND file_path IS NOT NULL",
+        )
+        .bind(&params.id)
+        .fetch_optional(&state.ctx.pool)
+        .await?;
+    }
+
+    // 也不是播客单集时查找视频，视频不转码，直接返回原始文件
+    if song.is_none() {
+        let video = sqlx::query_as::<_, (String, String)>(&format!(
+            "SELECT file_path, content_type FROM videos WHERE id = ? AND {}",
+            scope.sql_filter("music_folder_id")
+        ))
+        .bind(&params.id)
+        .fetch_optional(&state.ctx.pool)
+        .await?;
+
+        if let Some((file_path, content_type)) = video {
+            let file_path = PathBuf::from(file_path);
+            if !file_path.exists() {
+                return Err(AppError::not_found("Video file"));
+            }
+            return stream_utils::serve_file(&file_path, &content_type, &headers, HeaderMap::new())
+                .await;
+        }
+    }
+
+    let (file_path_str, content_type, bit_rate, updated_at) =
+        song.ok_or_else(|| AppError::not_found("Song"))?;
 
     let file_path = PathBuf::from(&file_path_str);
 
@@ -79,53 +163,310 @@ pub async fn stream(
         return Err(AppError::not_found("Audio file"));
     }
 
-    // 打开文件
-    let file = File::open(&file_path).await.map_err(AppError::IoError)?;
+    let user_bit_rate_limit = user_bit_rate_limit(&state, &claims.sub).await?;
+
+    let suffix = file_suffix(&file_path);
+    let decision = state.transcoder.decide(
+        params.format.as_deref(),
+        params.max_bit_rate,
+        user_bit_rate_limit,
+        SourceInfo {
+            suffix: &suffix,
+            bit_rate,
+        },
+    );
 
-    // 创建流
-    let stream = ReaderStream::new(file);
-    let body = Body::from_stream(stream);
+    match decision {
+        StreamDecision::Raw => {
+            let content_type = content_type.unwrap_or_else(|| "audio/mpeg".to_string());
 
-    // 设置响应头
-    let mut headers = HeaderMap::new();
-    let content_type = content_type.unwrap_or_else(|| "audio/mpeg".to_string());
-    headers.insert("Content-Type", content_type.parse().unwrap());
-    headers.insert("Accept-Ranges", "bytes".parse().unwrap());
-
-    // 如果估计内容长度，可以设置 Content-Length
-    if params.estimate_content_length.unwrap_or(false) {
-        if let Ok(metadata) = tokio::fs::metadata(&file_path).await {
-            headers.insert(
-                "Content-Length",
-                metadata.len().to_string().parse().unwrap(),
+            // 支持 Range 请求，便于客户端拖动进度
+            stream_utils::serve_file(&file_path, &content_type, &headers, HeaderMap::new()).await
+        }
+        StreamDecision::Transcode(profile) => {
+            let cache = &state.transcode_cache;
+            if !cache.is_enabled() {
+                tracing::info!(
+                    "Transcoding song {} to {} @ {}kbps",
+                    params.id,
+                    profile.format,
+                    profile.bit_rate
+                );
+                let process = state.transcoder.transcode(&profile, &file_path)?;
+                return Ok(transcoded_response(
+                    profile.content_type(),
+                    Body::from_stream(ReaderStream::new(process.stdout)),
+                ));
+            }
+
+            // 1. 检查缓存
+            let key = transcode_cache::cache_key(&params.id, &updated_at, &profile);
+            if let Some(path) = cache.lookup(&key).await {
+                return stream_utils::serve_file(
+                    &path,
+                    profile.content_type(),
+                    &headers,
+                    HeaderMap::new(),
+                )
+                .await;
+            }
+
+            // 2. 获取写入锁后双重检查，避免并发请求重复转码
+            let guard = cache.lock(&key).await;
+            if let Some(path) = cache.lookup(&key).await {
+                drop(guard);
+                return stream_utils::serve_file(
+                    &path,
+                    profile.content_type(),
+                    &headers,
+                    HeaderMap::new(),
+                )
+                .await;
+            }
+
+            // 3. 转码并同时写入缓存
+            tracing::info!(
+                "Transcoding song {} to {} @ {}kbps (caching)",
+                params.id,
+                profile.format,
+                profile.bit_rate
             );
+            let process = state.transcoder.transcode(&profile, &file_path)?;
+            let body = cache.clone().stream_and_store(key, guard, process);
+            Ok(transcoded_response(profile.content_type(), body))
         }
     }
+}
 
-    Ok((headers, body).into_response())
+/// 构建转码输出响应 (长度未知，不支持 Range)
+fn transcoded_response(content_type: &'static str, body: Body) -> Response {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(content_type));
+    headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("none"));
+    (StatusCode::OK, headers, body).into_response()
 }
 
-/// GET /rest/download - 下载音乐文件
-pub async fn download(
+/// 查询用户码率上限 (kbps)
+async fn user_bit_rate_limit(state: &StreamState, user_id: &str) -> Result<Option<i32>, AppError> {
+    let limit = sqlx::query_scalar::<_, Option<i32>>("SELECT max_bitrate FROM users WHERE id = ?")
+        .bind(user_id)
+        .fetch_optional(&state.ctx.pool)
+        .await?
+        .flatten();
+
+    Ok(limit)
+}
+
+/// 小写文件后缀
+fn file_suffix(path: &std::path::Path) -> String {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+/// GET /rest/hls.m3u8 - 获取 HLS 播放列表
+///
+/// 单个 (或未指定) `bitRate` 时返回媒体播放列表，分片按歌曲时长切分；
+/// 多个 `bitRate` 时返回多码率主播放列表。
+/// 播放列表中的 URL 使用只能访问该歌曲的短期令牌认证。
+pub async fn hls(
     claims: auth_middleware::Claims,
+    scope: FolderScope,
     axum::extract::State(state): axum::extract::State<StreamState>,
-    Query(params): Query<DownloadParams>,
-) -> Result<impl IntoResponse, AppError> {
-    // 检查下载权限
-    let permissions = auth_middleware::get_user_permissions(&state.ctx.pool, &claims.sub)
-        .await
-        .map_err(|_| AppError::access_denied("Failed to check permissions"))?;
+    Extension(session_service): Extension<Arc<SessionService>>,
+    raw_params: RequestParams,
+    Params(params): Params<HlsParams>,
+) -> Result<Response, AppError> {
+    let duration = sqlx::query_scalar::<_, i64>(&format!(
+        "SELECT duration FROM songs WHERE id = ? AND {}",
+        scope.sql_filter("music_folder_id")
+    ))
+    .bind(&params.id)
+    .fetch_optional(&state.ctx.pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Song"))?;
+
+    let user_bit_rate_limit = user_bit_rate_limit(&state, &claims.sub).await?;
+    let token = session_service.sign_stream_token(
+        &claims.sub,
+        &params.id,
+        duration.max(0) + hls_utils::TOKEN_GRACE_SECONDS,
+    )?;
+    let auth_query = hls_utils::auth_query(Some(raw_params.as_str()), &token);
+
+    // 码率受用户上限约束，去重后按从低到高排列
+    let mut bit_rates: Vec<u32> = params
+        .bit_rate
+        .iter()
+        .filter_map(|rate| TranscodeService::effective_bit_rate(Some(*rate), user_bit_rate_limit))
+        .collect();
+    bit_rates.sort_unstable();
+    bit_rates.dedup();
+
+    let playlist = if bit_rates.len() > 1 {
+        let variants: Vec<(u32, String)> = bit_rates
+            .iter()
+            .map(|bit_rate| {
+                let query = serde_urlencoded::to_string([
+                    ("id", params.id.clone()),
+                    ("bitRate", bit_rate.to_string()),
+                ])
+                .unwrap_or_default();
+                let url = hls_utils::append_query(format!("hls.m3u8?{}", query), &auth_query);
+                (*bit_rate, url)
+            })
+            .collect();
+
+        hls_utils::master_playlist(&variants)
+    } else {
+        let slices = hls_utils::segment_slices(duration, hls_utils::HLS_SEGMENT_SECONDS);
+        if slices.is_empty() {
+            return Err(AppError::not_found("Song duration"));
+        }
 
-    if !permissions.can_download() {
-        return Err(AppError::access_denied("Download permission required"));
+        hls_utils::media_playlist(&slices, |index| {
+            let mut query = vec![("id", params.id.clone()), ("index", index.to_string())];
+            if let Some(bit_rate) = bit_rates.first() {
+                query.push(("bitRate", bit_rate.to_string()));
+            }
+            let query = serde_urlencoded::to_string(query).unwrap_or_default();
+            hls_utils::append_query(format!("hlsSegment?{}", query), &auth_query)
+        })
+    };
+
+    Ok((
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(hls_utils::MPEGURL_CONTENT_TYPE),
+        )],
+        playlist,
+    )
+        .into_response())
+}
+
+/// GET /rest/hlsSegment - 获取 HLS 分片
+///
+/// 原始 mp3 / aac 文件且无需降码率时按字节比例切分原始文件，
+/// 否则按时间片段转码为 MPEG-TS。
+pub async fn hls_segment(
+    claims: auth_middleware::Claims,
+    scope: FolderScope,
+    axum::extract::State(state): axum::extract::State<StreamState>,
+    Params(params): Params<HlsSegmentParams>,
+) -> Result<Response, AppError> {
+    let song = sqlx::query_as::<_, (String, i64, Option<i32>)>(&format!(
+        "SELECT file_path, duration, bit_rate FROM songs WHERE id = ? AND {}",
+        scope.sql_filter("music_folder_id")
+    ))
+    .bind(&params.id)
+    .fetch_optional(&state.ctx.pool)
+    .await?;
+
+    let (file_path_str, duration, bit_rate) = song.ok_or_else(|| AppError::not_found("Song"))?;
+    let file_path = PathBuf::from(&file_path_str);
+    if !file_path.exists() {
+        return Err(AppError::not_found("Audio file"));
     }
 
-    // 根据ID查询歌曲信息
-    let song =
-        sqlx::query_as::<_, (String, String)>("SELECT file_path, title FROM songs WHERE id = ?")
-            .bind(&params.id)
-            .fetch_optional(&state.ctx.pool)
-            .await?;
+    let slices = hls_utils::segment_slices(duration, hls_utils::HLS_SEGMENT_SECONDS);
+    let slice = *slices
+        .get(params.index)
+        .ok_or_else(|| AppError::not_found("Segment"))?;
+
+    let user_bit_rate_limit = user_bit_rate_limit(&state, &claims.sub).await?;
+    let suffix = file_suffix(&file_path);
+    let source = SourceInfo {
+        suffix: &suffix,
+        bit_rate,
+    };
+
+    let transcode_bit_rate =
+        match state
+            .transcoder
+            .decide(None, params.bit_rate, user_bit_rate_limit, source)
+        {
+            StreamDecision::Transcode(TranscodeProfile { bit_rate, .. }) => Some(bit_rate),
+            StreamDecision::Raw if HLS_PASSTHROUGH_SUFFIXES.contains(&suffix.as_str()) => None,
+            StreamDecision::Raw => Some(
+                bit_rate
+                    .filter(|rate| *rate > 0)
+                    .map(|rate| (rate as u32).div_ceil(1000))
+                    .unwrap_or(DEFAULT_BIT_RATE)
+                    .min(DEFAULT_BIT_RATE),
+            ),
+        };
+
+    match transcode_bit_rate {
+        None => {
+            // 按时间比例换算字节区间
+            let file_len = tokio::fs::metadata(&file_path).await?.len();
+            let byte_at = |secs: f64| (file_len as f64 * secs / duration as f64) as u64;
+            let content_type = image_utils::get_content_type(&file_path);
+
+            stream_utils::serve_file_slice(
+                &file_path,
+                &content_type,
+                byte_at(slice.start),
+                byte_at(slice.start + slice.length),
+            )
+            .await
+        }
+        Some(bit_rate) => {
+            let process = state
+                .transcoder
+                .transcode_segment(&file_path, bit_rate, slice)?;
+            Ok(transcoded_response(
+                HLS_TRANSCODED_CONTENT_TYPE,
+                Body::from_stream(ReaderStream::new(process.stdout)),
+            ))
+        }
+    }
+}
+
+/// POST /rest/purgeTranscodeCache - 清空转码缓存 (仅管理员)
+pub async fn purge_transcode_cache(
+    claims: auth_middleware::Claims,
+    Format(format): Format,
+    axum::extract::State(state): axum::extract::State<StreamState>,
+) -> Result<ApiResponse<TranscodeCacheResponse>, AppError> {
+    if !claims.is_admin {
+        return Err(AppError::access_denied("Admin only"));
+    }
+
+    let stats = state.transcode_cache.purge().await?;
+    tracing::info!(
+        "Transcode cache purged by {}: {} files, {} bytes",
+        claims.username,
+        stats.files,
+        stats.size
+    );
+
+    let result = TranscodeCacheResponse {
+        transcode_cache: TranscodeCacheInfo {
+            files: stats.files,
+            size: stats.size,
+        },
+    };
+
+    Ok(ApiResponse::ok(Some(result), format))
+}
+
+/// GET /rest/download - 下载音乐文件 (需要 downloadRole，由角色中间件检查)
+pub async fn download(
+    scope: FolderScope,
+    axum::extract::State(state): axum::extract::State<StreamState>,
+    headers: HeaderMap,
+    Params(params): Params<DownloadParams>,
+) -> Result<Response, AppError> {
+    // 根据ID查询歌曲信息 (仅限用户可访问的音乐文件夹)
+    let song = sqlx::query_as::<_, (String, String)>(&format!(
+        "SELECT file_path, title FROM songs WHERE id = ? AND {}",
+        scope.sql_filter("music_folder_id")
+    ))
+    .bind(&params.id)
+    .fetch_optional(&state.ctx.pool)
+    .await?;
 
     let (file_path_str, title) = song.ok_or_else(|| AppError::not_found("Song"))?;
 
@@ -136,30 +477,30 @@ pub async fn download(
         return Err(AppError::not_found("Audio file"));
     }
 
-    // 打开文件
-    let file = File::open(&file_path).await.map_err(AppError::IoError)?;
-
-    // 创建流
-    let stream = ReaderStream::new(file);
-    let body = Body::from_stream(stream);
-
     // 设置响应头，触发浏览器下载
-    let mut headers = HeaderMap::new();
-    headers.insert("Content-Type", "application/octet-stream".parse().unwrap());
-    headers.insert(
+    let mut extra_headers = HeaderMap::new();
+    extra_headers.insert(
         "Content-Disposition",
         format!("attachment; filename=\"{}\"", title)
             .parse()
             .unwrap(),
     );
 
-    Ok((headers, body).into_response())
+    // 与 stream 共用同一套 Range 处理逻辑，支持断点续传
+    stream_utils::serve_file(
+        &file_path,
+        "application/octet-stream",
+        &headers,
+        extra_headers,
+    )
+    .await
 }
 
 /// GET /rest/getCoverArt - 获取封面图片
 pub async fn get_cover_art(
+    scope: FolderScope,
     axum::extract::State(state): axum::extract::State<StreamState>,
-    Query(params): Query<CoverArtParams>,
+    Params(params): Params<CoverArtParams>,
 ) -> Result<impl IntoResponse, AppError> {
     let cover_art_id = &params.id;
     tracing::info!("get cover art for: {}", cover_art_id);
@@ -170,6 +511,14 @@ pub async fn get_cover_art(
         return image_utils::serve_image_file(PathBuf::from("./web/default_cover.webp")).await;
     }
 
+    // 缓存与原图按封面 ID 存放，需先检查用户能否访问对应内容
+    if !MusicFolderService::new(state.ctx.clone())
+        .cover_art_allowed(&scope, cover_art_id)
+        .await?
+    {
+        return Err(AppError::not_found("Cover art"));
+    }
+
     let size = params.size.unwrap_or(300).clamp(50, 2000) as u32;
 
     // 1. 检查缓存
@@ -257,56 +606,51 @@ pub async fn get_cover_art(
 pub struct LyricsParams {
     pub artist: Option<String>,
     pub title: Option<String>,
-    pub u: String,
-    pub t: Option<String>,
-    pub s: Option<String>,
-    pub p: Option<String>,
-    pub v: String,
-    pub c: String,
-    pub f: Option<String>,
 }
 
 /// GET /rest/getLyrics - 获取歌词
 pub async fn get_lyrics(
     Format(format): Format,
+    scope: FolderScope,
     axum::extract::State(state): axum::extract::State<StreamState>,
-    Query(params): Query<LyricsParams>,
+    Params(params): Params<LyricsParams>,
 ) -> Result<ApiResponse<LyricsResponse>, AppError> {
     // 构建查询条件
+    let filter = scope.sql_filter("s.music_folder_id");
     let song = if let (Some(artist), Some(title)) = (params.artist.as_ref(), params.title.as_ref())
     {
         // 同时有艺术家和标题
-        sqlx::query_as::<_, (Option<String>, String, String)>(
+        sqlx::query_as::<_, (Option<String>, String, String)>(&format!(
             "SELECT s.lyrics, s.artist_id, s.title
              FROM songs s
              JOIN artists a ON s.artist_id = a.id
-             WHERE s.title LIKE ? AND a.name LIKE ?
-             LIMIT 1",
-        )
+             WHERE s.title LIKE ? AND a.name LIKE ? AND {filter}
+             LIMIT 1"
+        ))
         .bind(format!("%{}%", title))
         .bind(format!("%{}%", artist))
         .fetch_optional(&state.ctx.pool)
         .await?
     } else if let Some(title) = params.title.as_ref() {
         // 只有标题
-        sqlx::query_as::<_, (Option<String>, String, String)>(
-            "SELECT lyrics, artist_id, title
-             FROM songs
-             WHERE title LIKE ?
-             LIMIT 1",
-        )
+        sqlx::query_as::<_, (Option<String>, String, String)>(&format!(
+            "SELECT s.lyrics, s.artist_id, s.title
+             FROM songs s
+             WHERE s.title LIKE ? AND {filter}
+             LIMIT 1"
+        ))
         .bind(format!("%{}%", title))
         .fetch_optional(&state.ctx.pool)
         .await?
     } else if let Some(artist) = params.artist.as_ref() {
         // 只有艺术家
-        sqlx::query_as::<_, (Option<String>, String, String)>(
+        sqlx::query_as::<_, (Option<String>, String, String)>(&format!(
             "SELECT s.lyrics, s.artist_id, s.title
              FROM songs s
              JOIN artists a ON s.artist_id = a.id
-             WHERE a.name LIKE ? AND s.lyrics IS NOT NULL
-             LIMIT 1",
-        )
+             WHERE a.name LIKE ? AND s.lyrics IS NOT NULL AND {filter}
+             LIMIT 1"
+        ))
         .bind(format!("%{}%", artist))
         .fetch_optional(&state.ctx.pool)
         .await?
@@ -345,7 +689,7 @@ pub async fn get_lyrics(
 }
 
 /// GET /rest/getAvatar - 获取用户头像
-pub async fn get_avatar(_params: Query<DownloadParams>) -> Result<(HeaderMap, Vec<u8>), AppError> {
+pub async fn get_avatar(_params: Params<DownloadParams>) -> Result<(HeaderMap, Vec<u8>), AppError> {
     // 简化处理：返回默认头像或 404
     // 实际应用中应该从用户表查询头像路径
     Err(AppError::not_found("Avatar"))
@@ -354,18 +698,34 @@ pub async fn get_avatar(_params: Query<DownloadParams>) -> Result<(HeaderMap, Ve
 #[derive(Clone)]
 pub struct StreamState {
     ctx: Arc<ServiceContext>,
+    transcoder: Arc<TranscodeService>,
+    transcode_cache: Arc<TranscodeCache>,
 }
 impl StreamState {
-    /// 创建新的 BrowsingService
-    pub fn new(ctx: Arc<ServiceContext>) -> Self {
-        Self { ctx }
+    /// 创建新的 StreamState
+    pub fn new(
+        ctx: Arc<ServiceContext>,
+        transcoder: Arc<TranscodeService>,
+        transcode_cache: Arc<TranscodeCache>,
+    ) -> Self {
+        Self {
+            ctx,
+            transcoder,
+            transcode_cache,
+        }
     }
 }
 pub fn routes() -> Router<StreamState> {
     Router::new()
-        .route("/rest/stream", get(stream))
-        .route("/rest/download", get(download))
-        .route("/rest/getCoverArt", get(get_cover_art))
-        .route("/rest/getLyrics", get(get_lyrics))
-        .route("/rest/getAvatar", get(get_avatar))
+        .route("/rest/stream", get(stream).post(stream))
+        .route("/rest/download", get(download).post(download))
+        .route("/rest/getCoverArt", get(get_cover_art).post(get_cover_art))
+        .route("/rest/getLyrics", get(get_lyrics).post(get_lyrics))
+        .route("/rest/getAvatar", get(get_avatar).post(get_avatar))
+        .route("/rest/hls.m3u8", get(hls).post(hls))
+        .route("/rest/hlsSegment", get(hls_segment).post(hls_segment))
+        .route(
+            "/rest/purgeTranscodeCache",
+            get(purge_transcode_cache).post(purge_transcode_cache),
+        )
 }
@@ -22,6 +22,26 @@ pub async fn get_license(Format(format): Format) -> ApiResponse<LicenseResponse>
     ApiResponse::ok(Some(license), format)
 }
 
+// GET /rest/getOpenSubsonicExtensions
+pub async fn get_open_subsonic_extensions(
+    Format(format): Format,
+) -> ApiResponse<OpenSubsonicExtensionsResponse> {
+    let extensions = OpenSubsonicExtensionsResponse {
+        open_subsonic_extensions: vec![
+            OpenSubsonicExtension {
+                name: "apiKeyAuthentication".to_string(),
+                versions: vec![1],
+            },
+            OpenSubsonicExtension {
+                name: "formPost".to_string(),
+                versions: vec![1],
+            },
+        ],
+    };
+
+    ApiResponse::ok(Some(extensions), format)
+}
+
 #[derive(Debug, Clone, Serialize)]
 pub struct LicenseResponse {
     pub valid: bool,   // JSON: "valid" (干净!)
@@ -39,8 +59,42 @@ impl ToXml for LicenseResponse {
     }
 }
 
+/// OpenSubsonic 扩展
+#[derive(Debug, Clone, Serialize)]
+pub struct OpenSubsonicExtension {
+    pub name: String,
+    pub versions: Vec<i32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenSubsonicExtensionsResponse {
+    pub open_subsonic_extensions: Vec<OpenSubsonicExtension>,
+}
+
+impl ToXml for OpenSubsonicExtensionsResponse {
+    fn to_xml_element(&self) -> String {
+        let mut xml = String::new();
+        for extension in &self.open_subsonic_extensions {
+            xml.push_str(&format!(
+                "<openSubsonicExtensions name=\"{}\">",
+                extension.name
+            ));
+            for version in &extension.versions {
+                xml.push_str(&format!("<versions>{}</versions>", version));
+            }
+            xml.push_str("</openSubsonicExtensions>");
+        }
+        xml
+    }
+}
+
 pub fn routes() -> Router {
     Router::new()
-        .route("/rest/ping", get(ping))
-        .route("/rest/getLicense", get(get_license))
+        .route("/rest/ping", get(ping).post(ping))
+        .route("/rest/getLicense", get(get_license).post(get_license))
+        .route(
+            "/rest/getOpenSubsonicExtensions",
+            get(get_open_subsonic_extensions).post(get_open_subsonic_extensions),
+        )
 }
@@ -1,12 +1,13 @@
 //! 用户管理端点处理器
 #![allow(dead_code)]
 
-use axum::{extract::Query, routing::get, Router};
+use axum::{routing::get, Router};
 use serde::Deserialize;
 use std::sync::Arc;
 
 use crate::error::AppError;
-use crate::extractors::Format;
+use crate::extractors::{Format, Params};
+use crate::middleware::admin_middleware;
 use crate::middleware::auth_middleware::Claims;
 use crate::models::dto::{ChangePasswordRequest, CreateUserRequest, UpdateUserRequest};
 use crate::models::response::{UserResponse, UsersResponse};
@@ -22,7 +23,7 @@ pub struct UserParams {
 /// GET /rest/getUser - 获取用户信息
 pub async fn get_user(
     axum::extract::State(user_service): axum::extract::State<Arc<UserService>>,
-    Query(params): Query<UserParams>,
+    Params(params): Params<UserParams>,
     Format(format): Format,
 ) -> Result<ApiResponse<UserResponse>, AppError> {
     let username = params
@@ -32,7 +33,11 @@ pub async fn get_user(
 
     // 调用 Service 层
     let user = user_service.get_user(username).await?;
-    let user_response: UserResponse = user.into();
+    let folder = user_service.get_user_folder_ids(&user.id).await?;
+    let user_response = UserResponse {
+        folder,
+        ..user.into()
+    };
 
     Ok(ApiResponse::ok(Some(user_response), format))
 }
@@ -46,7 +51,14 @@ pub async fn get_users(
     // 调用 Service 层 (包含权限检查)
     let users = user_service.get_all_users(claims.is_admin).await?;
 
-    let user_responses: Vec<UserResponse> = users.into_iter().map(|u| u.into()).collect();
+    let mut user_responses = Vec::with_capacity(users.len());
+    for user in users {
+        let folder = user_service.get_user_folder_ids(&user.id).await?;
+        user_responses.push(UserResponse {
+            folder,
+            ..user.into()
+        });
+    }
 
     let result = UsersResponse {
         users: user_responses,
@@ -59,7 +71,7 @@ pub async fn get_users(
 pub async fn create_user(
     claims: Claims,
     axum::extract::State(user_service): axum::extract::State<Arc<UserService>>,
-    Query(body): Query<CreateUserRequest>,
+    Params(body): Params<CreateUserRequest>,
     Format(format): Format,
 ) -> Result<ApiResponse<()>, AppError> {
     // 调用 Service 层 (包含权限检查和用户存在性检查)
@@ -72,7 +84,7 @@ pub async fn create_user(
 pub async fn delete_user(
     claims: Claims,
     axum::extract::State(user_service): axum::extract::State<Arc<UserService>>,
-    Query(params): Query<UserParams>,
+    Params(params): Params<UserParams>,
     Format(format): Format,
 ) -> Result<ApiResponse<()>, AppError> {
     let username = params
@@ -80,7 +92,9 @@ pub async fn delete_user(
         .ok_or_else(|| AppError::missing_parameter("username"))?;
 
     // 调用 Service 层 (包含权限检查和自删除检查)
-    user_service.delete_user(claims.is_admin, &claims.username, &username).await?;
+    user_service
+        .delete_user(claims.is_admin, &claims.username, &username)
+        .await?;
 
     Ok(ApiResponse::ok(None, format))
 }
@@ -89,8 +103,8 @@ pub async fn delete_user(
 pub async fn update_user(
     claims: Claims,
     axum::extract::State(user_service): axum::extract::State<Arc<UserService>>,
-    Query(params): Query<UserParams>,
-    Query(body): Query<UpdateUserRequest>,
+    Params(params): Params<UserParams>,
+    Params(body): Params<UpdateUserRequest>,
     Format(format): Format,
 ) -> Result<ApiResponse<()>, AppError> {
     let username = params
@@ -109,23 +123,36 @@ pub async fn update_user(
 pub async fn change_password(
     claims: Claims,
     axum::extract::State(user_service): axum::extract::State<Arc<UserService>>,
-    Query(body): Query<ChangePasswordRequest>,
+    Params(body): Params<ChangePasswordRequest>,
     Format(format): Format,
 ) -> Result<ApiResponse<()>, AppError> {
     // 调用 Service 层 (包含权限检查)
     user_service
-        .change_password(claims.is_admin, &claims.username, &body.username, &body.password)
+        .change_password(
+            claims.is_admin,
+            &claims.username,
+            &body.username,
+            &body.password,
+        )
         .await?;
 
     Ok(ApiResponse::ok(None, format))
 }
 
 pub fn routes() -> Router<Arc<UserService>> {
+    // 用户管理端点仅管理员可访问
+    let admin_routes = Router::new()
+        .route("/rest/getUsers", get(get_users).post(get_users))
+        .route("/rest/createUser", get(create_user).post(create_user))
+        .route("/rest/deleteUser", get(delete_user).post(delete_user))
+        .route("/rest/updateUser", get(update_user).post(update_user))
+        .route_layer(axum::middleware::from_fn(admin_middleware));
+
     Router::new()
-        .route("/rest/getUser", get(get_user))
-        .route("/rest/getUsers", get(get_users))
-        .route("/rest/createUser", get(create_user))
-        .route("/rest/deleteUser", get(delete_user))
-        .route("/rest/updateUser", get(update_user))
-        .route("/rest/changePassword", get(change_password))
+        .route("/rest/getUser", get(get_user).post(get_user))
+        .merge(admin_routes)
+        .route(
+            "/rest/changePassword",
+            get(change_password).post(change_password),
+        )
 }
@@ -7,8 +7,12 @@ use axum::{middleware as axum_middleware, Router};
 use musicflow_server::utils::id_builder;
 use std::net::SocketAddr;
 use std::sync::Arc;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
 use tower_http::services::ServeDir;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use utils::password_cipher::PasswordCipher;
+use utils::rate_limiter::RateLimiter;
+use utils::transcode_cache::TranscodeCache;
 
 mod config;
 mod database;
@@ -21,11 +25,14 @@ mod response;
 mod services;
 mod utils;
 
-use config::AppConfig;
+use config::{AppConfig, LogFormat};
 use database::{get_db_pool, run_migrations, DbPool};
+use middleware::access_log::{AccessLogResponse, AccessLogSpan, REQUEST_ID_HEADER};
 use services::{
-    AuthService, LibraryService, PlayQueueService, PlaylistService, ScanService, SearchService,
-    ServiceContext, UserService,
+    ApiKeyService, AuthService, BookmarkService, EventService, InternetRadioService,
+    InviteCodeService, JukeboxService, LibraryService, LibraryWatcher, LoginThrottleService,
+    MusicFolderService, PlayQueueService, PlaylistService, PodcastService, ScanService,
+    SearchService, ServiceContext, SessionService, ShareService, TranscodeService, UserService,
 };
 
 #[tokio::main]
@@ -33,15 +40,23 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 1. 加载配置
     let config = AppConfig::from_env()?;
 
-    // 2. 初始化日志
+    // 2. 初始化日志 (文本或 JSON 格式)
+    let json_log = config.log_format == LogFormat::Json;
     tracing_subscriber::registry()
         .with(tracing_subscriber::EnvFilter::new(&config.rust_log))
-        .with(
+        .with((!json_log).then(|| {
             tracing_subscriber::fmt::layer()
                 .with_file(true) // 显示文件名
                 .with_line_number(true) // 显示行号
-                .with_target(true), // 显示模块路径(target)
-        )
+                .with_target(true) // 显示模块路径(target)
+        }))
+        .with(json_log.then(|| {
+            tracing_subscriber::fmt::layer()
+                .json()
+                .flatten_event(true) // 事件字段置于顶层
+                .with_current_span(true) // 包含请求 span (request_id、method、uri)
+                .with_span_list(false)
+        }))
         .init();
     tracing::debug!("Loaded config: {:?}", config);
 
@@ -64,40 +79,119 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     run_migrations(&pool).await?;
     tracing::info!("Migrations completed");
 
-    // 6. 创建默认管理员用户（如果不存在）
-    create_default_admin(&pool).await?;
+    // 6. 初始化密码加密，并将历史明文密码加密存储
+    let password_cipher = Arc::new(PasswordCipher::from_config(&config));
+    let upgraded = AuthService::new(pool.clone(), password_cipher.clone())
+        .upgrade_passwords()
+        .await?;
+    if upgraded > 0 {
+        tracing::info!(
+            "Re-encrypted {} user passwords with the current key",
+            upgraded
+        );
+    }
+
+    // 7. 同步音乐文件夹配置
+    let music_folders = MusicFolderService::new(Arc::new(ServiceContext::new(pool.clone())))
+        .sync_folders(&config.music_folders)
+        .await?;
+    for folder in &music_folders {
+        tracing::info!(
+            "Music folder {}: {} ({})",
+            folder.id,
+            folder.name,
+            folder.path
+        );
+    }
 
-    // 7. 构建应用路由
-    let app = build_app(pool, config.clone());
+    // 8. 创建默认管理员用户（如果不存在）
+    create_default_admin(&pool, &password_cipher).await?;
 
-    // 8. 启动服务器
+    // 9. 构建应用路由
+    let app = build_app(pool, config.clone(), password_cipher)?;
+
+    // 10. 启动服务器
     let addr = SocketAddr::from((config.host.parse::<std::net::IpAddr>()?, config.port));
 
     tracing::info!("Server listening on http://{}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await?;
 
     Ok(())
 }
 
 /// 构建应用路由
-fn build_app(pool: DbPool, config: AppConfig) -> Router {
+fn build_app(
+    pool: DbPool,
+    config: AppConfig,
+    password_cipher: Arc<PasswordCipher>,
+) -> Result<Router, std::io::Error> {
     // 创建服务上下文
     let service_ctx = Arc::new(ServiceContext::new(pool.clone()));
 
-    let auth_service = Arc::new(AuthService::new(pool.clone()));
-    let scan_service = Arc::new(ScanService::new(
-        pool.clone(),
-        config.music_library_path.clone(),
+    let auth_service = Arc::new(AuthService::new(pool.clone(), password_cipher.clone()));
+    let session_service = Arc::new(SessionService::new(
+        service_ctx.clone(),
+        &config.session_secret,
+        config.session_ttl_hours,
     ));
+    let api_key_service = Arc::new(ApiKeyService::new(service_ctx.clone()));
+    let invite_code_service = Arc::new(InviteCodeService::new(service_ctx.clone()));
+    let login_throttle = Arc::new(LoginThrottleService::from_config(&config));
+    let event_service = Arc::new(EventService::new(service_ctx.clone()));
+    let scan_service = Arc::new(ScanService::new(pool.clone()));
     let library_service = Arc::new(LibraryService::new(service_ctx.clone()));
+    let scan_state = handlers::library::ScanState::new(event_service.clone());
+    if config.watch_library {
+        let roots: Vec<_> = config
+            .music_folders
+            .iter()
+            .map(|f| f.path.clone())
+            .collect();
+        LibraryWatcher::new(
+            scan_service.clone(),
+            scan_state.clone(),
+            std::time::Duration::from_millis(config.watch_debounce_ms),
+        )
+        .start(&roots)
+        .map_err(std::io::Error::other)?;
+    }
+    spawn_now_playing_sweeper(library_service.clone(), config.now_playing_ttl_minutes);
     let user_service = Arc::new(UserService::new(service_ctx.clone(), auth_service.clone()));
     let playlist_service = Arc::new(PlaylistService::new(service_ctx.clone()));
     let browsing_service = Arc::new(BrowsingService::new(service_ctx.clone()));
+    let music_folder_service = Arc::new(MusicFolderService::new(service_ctx.clone()));
     let search_service = Arc::new(SearchService::new(service_ctx.clone()));
     let play_queue_service = Arc::new(PlayQueueService::new(service_ctx.clone()));
-    let stream_state = StreamState::new(service_ctx.clone());
+    let bookmark_service = Arc::new(BookmarkService::new(service_ctx.clone()));
+    let internet_radio_service = Arc::new(InternetRadioService::new(service_ctx.clone()));
+    let podcast_service = Arc::new(PodcastService::from_config(service_ctx.clone(), &config));
+    tokio::spawn(podcast_service.clone().run_download_worker());
+    spawn_podcast_refresher(
+        podcast_service.clone(),
+        config.podcast_refresh_interval_minutes,
+    );
+    let jukebox_service = Arc::new(JukeboxService::start(
+        service_ctx.clone(),
+        config.jukebox_sink.clone(),
+    )?);
+    let transcode_service = Arc::new(TranscodeService::from_config(&config));
+    let transcode_cache = Arc::new(TranscodeCache::open(
+        &config.transcode_cache_dir,
+        config.transcode_cache_max_size,
+    )?);
+    let stream_state = StreamState::new(service_ctx.clone(), transcode_service, transcode_cache);
+    let share_state = handlers::share::ShareState {
+        share_service: Arc::new(ShareService::new(service_ctx.clone())),
+        rate_limiter: Arc::new(RateLimiter::new(config.share_rate_limit, 60)),
+        stream_rate_limiter: Arc::new(RateLimiter::new(config.share_stream_rate_limit, 60)),
+        base_url: config.share_base_url.clone(),
+    };
 
     // 创建共享状态
     let _auth_state = auth_service.clone();
@@ -106,22 +200,38 @@ fn build_app(pool: DbPool, config: AppConfig) -> Router {
 
     // 构建各个模块的路由
     let system_routes = handlers::system::routes();
-    let auth_routes = handlers::auth::routes().with_state(auth_service);
-    let browsing_routes = handlers::browsing::routes(pool.clone(), browsing_service);
+    let auth_state = handlers::auth::AuthState {
+        auth_service,
+        session_service: session_service.clone(),
+        invite_code_service: invite_code_service.clone(),
+        login_throttle: login_throttle.clone(),
+        registration_mode: config.registration_mode,
+    };
+    let auth_routes = handlers::auth::routes().with_state(auth_state);
+    let browsing_routes =
+        handlers::browsing::routes(pool.clone(), browsing_service, music_folder_service);
     let search_routes = handlers::search::routes().with_state(search_service.clone());
     let stream_routes = handlers::stream::routes().with_state(stream_state);
-    let playlist_state = handlers::playlist::PlaylistState {
-        playlist_service,
-        pool: pool.clone(),
-    };
+    let playlist_state = handlers::playlist::PlaylistState { playlist_service };
     let playlist_routes = handlers::playlist::routes().with_state(playlist_state);
     let user_routes = handlers::user::routes().with_state(user_service);
-    let library_routes = handlers::library::routes(pool.clone(), scan_service, library_service);
+    let api_key_routes = handlers::api_key::routes().with_state(api_key_service);
+    let invite_code_routes = handlers::invite_code::routes().with_state(invite_code_service);
+    let lockout_routes = handlers::lockout::routes().with_state(login_throttle.clone());
+    let library_routes =
+        handlers::library::routes(pool.clone(), scan_service, library_service, scan_state);
     let advanced_routes = handlers::advanced::routes().with_state(pool.clone());
-    let play_queue_state = handlers::play_queue::PlayQueueState {
-        play_queue_service,
-    };
+    let internet_radio_routes =
+        handlers::internet_radio::routes().with_state(internet_radio_service);
+    let podcast_routes = handlers::podcast::routes().with_state(podcast_service);
+    let jukebox_routes = handlers::jukebox::routes().with_state(jukebox_service);
+    let event_routes = handlers::events::routes().with_state(event_service.clone());
+    let play_queue_state = handlers::play_queue::PlayQueueState { play_queue_service };
     let play_queue_routes = handlers::play_queue::routes(play_queue_state);
+    let bookmark_state = handlers::bookmark::BookmarkState { bookmark_service };
+    let bookmark_routes = handlers::bookmark::routes(bookmark_state);
+    let share_routes = handlers::share::routes().with_state(share_state.clone());
+    let public_share_routes = handlers::share::public_routes().with_state(share_state);
 
     // 需要认证的 API 路由
     let protected_routes = Router::new()
@@ -130,22 +240,41 @@ fn build_app(pool: DbPool, config: AppConfig) -> Router {
         .merge(stream_routes)
         .merge(playlist_routes)
         .merge(play_queue_routes)
+        .merge(bookmark_routes)
         .merge(user_routes)
+        .merge(api_key_routes)
+        .merge(invite_code_routes)
+        .merge(lockout_routes)
         .merge(library_routes)
         .merge(advanced_routes)
+        .merge(internet_radio_routes)
+        .merge(podcast_routes)
+        .merge(jukebox_routes)
+        .merge(event_routes)
+        .merge(share_routes)
+        // 角色权限中间件（在认证之后按端点检查用户角色）
+        .layer(axum_middleware::from_fn(middleware::role_middleware))
         // 认证中间件（仅保护需要认证的端点）
         .layer(axum_middleware::from_fn(middleware::auth_middleware));
 
     // 合并所有路由
-    Router::new()
+    Ok(Router::new()
         // 系统端点（公开访问）
         .merge(system_routes)
         // 认证端点（公开访问）
         .merge(auth_routes)
+        // 公开分享页面（免登录，按 IP 限流）
+        .merge(public_share_routes)
         // 需要认证的端点
         .merge(protected_routes)
         // 静态文件服务（web 管理面板，公开访问）
         .nest_service("/", ServeDir::new("web"))
+        // 按请求格式输出 Subsonic 错误响应
+        .layer(axum_middleware::from_fn(
+            middleware::subsonic_error_middleware,
+        ))
+        // 合并查询字符串与表单请求体参数
+        .layer(axum_middleware::from_fn(middleware::params_middleware))
         // CORS 配置
         .layer(
             tower_http::cors::CorsLayer::new()
@@ -153,15 +282,68 @@ fn build_app(pool: DbPool, config: AppConfig) -> Router {
                 .allow_methods(tower_http::cors::Any)
                 .allow_headers(tower_http::cors::Any),
         )
-        // 日志中间件
-        .layer(tower_http::trace::TraceLayer::new_for_http())
-        // 将配置和数据库连接池添加到请求扩展中（供中间件使用）
+        // 将请求 ID 写入响应头
+        .layer(PropagateRequestIdLayer::new(REQUEST_ID_HEADER))
+        // 日志中间件（请求 span 包含请求 ID 与脱敏后的 URI）
+        .layer(
+            tower_http::trace::TraceLayer::new_for_http()
+                .make_span_with(AccessLogSpan)
+                .on_response(AccessLogResponse),
+        )
+        // 为没有 X-Request-Id 的请求生成请求 ID
+        .layer(SetRequestIdLayer::new(REQUEST_ID_HEADER, MakeRequestUuid))
+        // 将配置、数据库连接池、密码加密、会话、登录限流和事件组件添加到请求扩展中
+        .layer(axum::Extension(event_service))
+        .layer(axum::Extension(login_throttle))
+        .layer(axum::Extension(session_service))
         .layer(axum::Extension(config))
-        .layer(axum::Extension(pool))
+        .layer(axum::Extension(password_cipher))
+        .layer(axum::Extension(pool)))
+}
+
+/// 定期清理过期的正在播放记录
+fn spawn_now_playing_sweeper(library_service: Arc<LibraryService>, ttl_minutes: i64) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            match library_service
+                .purge_expired_now_playing(ttl_minutes * 60)
+                .await
+            {
+                Ok(0) => {}
+                Ok(count) => tracing::debug!("Purged {} expired now playing entries", count),
+                Err(e) => tracing::warn!("Failed to purge now playing entries: {}", e),
+            }
+        }
+    });
+}
+
+/// 定期刷新所有播客频道，间隔为 0 时不自动刷新
+fn spawn_podcast_refresher(podcast_service: Arc<PodcastService>, interval_minutes: u64) {
+    if interval_minutes == 0 {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut interval =
+            tokio::time::interval(std::time::Duration::from_secs(interval_minutes * 60));
+        loop {
+            interval.tick().await;
+            match podcast_service.refresh_all().await {
+                Ok(0) => {}
+                Ok(count) => tracing::debug!("Refreshed {} podcast channels", count),
+                Err(e) => tracing::warn!("Failed to refresh podcasts: {}", e),
+            }
+        }
+    });
 }
 
 /// 创建默认管理员用户
-async fn create_default_admin(pool: &DbPool) -> Result<(), anyhow::Error> {
+async fn create_default_admin(
+    pool: &DbPool,
+    password_cipher: &PasswordCipher,
+) -> Result<(), anyhow::Error> {
     // 检查 users 表是否存在
     let table_exists: bool = sqlx::query_scalar(
         "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type='table' AND name='users')",
@@ -189,7 +371,7 @@ async fn create_default_admin(pool: &DbPool) -> Result<(), anyhow::Error> {
         )
         .bind(id)
         .bind("admin")
-        .bind("admin")
+        .bind(password_cipher.encrypt("admin")?)
         .bind("admin@localhost")
         .bind(true)
         .execute(pool)
@@ -0,0 +1,91 @@
+//! 访问日志
+//!
+//! 为 `TraceLayer` 提供请求 span 与响应日志:
+//! - span 中记录请求 ID (`X-Request-Id`)、方法和脱敏后的 URI
+//! - 请求完成时以 info 级别记录状态码与耗时
+
+use axum::http::{HeaderName, Request, Response};
+use std::time::Duration;
+use tower_http::trace::{MakeSpan, OnResponse};
+use tracing::Span;
+
+use crate::utils::log_redact::redact_uri;
+
+/// 请求 ID 请求头
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+/// 为每个请求创建 span
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AccessLogSpan;
+
+impl<B> MakeSpan<B> for AccessLogSpan {
+    fn make_span(&mut self, request: &Request<B>) -> Span {
+        let request_id = request
+            .headers()
+            .get(&REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("-");
+
+        tracing::info_span!(
+            "request",
+            request_id = %request_id,
+            method = %request.method(),
+            uri = %redact_uri(request.uri()),
+        )
+    }
+}
+
+/// 请求完成时记录访问日志
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AccessLogResponse;
+
+impl<B> OnResponse<B> for AccessLogResponse {
+    fn on_response(self, response: &Response<B>, latency: Duration, _span: &Span) {
+        tracing::info!(
+            status = response.status().as_u16(),
+            latency_ms = latency.as_millis() as u64,
+            "request completed"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::get, Router};
+    use tower::ServiceExt;
+    use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+    use tower_http::trace::TraceLayer;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/rest/ping", get(|| async { "pong" }))
+            .layer(PropagateRequestIdLayer::new(REQUEST_ID_HEADER))
+            .layer(
+                TraceLayer::new_for_http()
+                    .make_span_with(AccessLogSpan)
+                    .on_response(AccessLogResponse),
+            )
+            .layer(SetRequestIdLayer::new(REQUEST_ID_HEADER, MakeRequestUuid))
+    }
+
+    #[tokio::test]
+    async fn test_request_id_generated_and_propagated() {
+        let req = Request::builder()
+            .uri("/rest/ping?u=alice&p=secret")
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(req).await.unwrap();
+        let request_id = response.headers().get(&REQUEST_ID_HEADER).unwrap();
+        assert!(!request_id.is_empty());
+
+        // 客户端提供的请求 ID 原样返回
+        let req = Request::builder()
+            .uri("/rest/ping")
+            .header(&REQUEST_ID_HEADER, "abc-123")
+            .body(Body::empty())
+            .unwrap();
+        let response = app().oneshot(req).await.unwrap();
+        assert_eq!(response.headers()[&REQUEST_ID_HEADER], "abc-123");
+    }
+}
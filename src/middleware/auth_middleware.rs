@@ -3,15 +3,28 @@
 
 use axum::{
     async_trait,
-    extract::FromRequestParts,
+    extract::{ConnectInfo, FromRequestParts},
     http::{request::Parts, StatusCode},
     response::{IntoResponse, Response},
-    Json,
 };
 use serde::{Deserialize, Serialize};
 use sqlx::SqlitePool;
 
+use crate::config::{AppConfig, ReverseProxyConfig};
+use crate::error::AppError;
+use crate::extractors::RequestParams;
 use crate::models::entities::User;
+use crate::models::response::API_VERSION;
+use crate::services::{
+    ApiKeyService, AuthService, LockoutKey, LoginThrottleService, ServiceContext, SessionService,
+};
+use crate::utils::hls_utils;
+use crate::utils::ip_cidr::{self, forwarded_client_ip};
+use crate::utils::password_cipher::PasswordCipher;
+use crate::utils::session_token::bearer_token;
+
+/// 用户名或密码错误时的提示信息
+const WRONG_CREDENTIALS: &str = "Wrong username or password";
 
 /// 标准错误响应结构
 #[derive(Debug, Serialize)]
@@ -38,53 +51,180 @@ impl<S> FromRequestParts<S> for Claims
 where
     S: Send + Sync,
 {
-    type Rejection = (StatusCode, String);
+    type Rejection = AppError;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
         // 从请求扩展中获取 Claims（由认证中间件设置）
-        parts.extensions.get::<Claims>().cloned().ok_or((
-            StatusCode::UNAUTHORIZED,
-            "未找到认证信息，请提供有效的认证参数".to_string(),
-        ))
+        parts
+            .extensions
+            .get::<Claims>()
+            .cloned()
+            .ok_or_else(|| AppError::auth_failed("Authentication required"))
+    }
+}
+
+/// 检查客户端协议版本 (v 参数)
+///
+/// 主版本号不同时协议不兼容: 客户端更低返回错误码 20，服务端更低返回错误码 30
+fn check_client_version(version: &str) -> Result<(), AppError> {
+    let major = |v: &str| {
+        v.split('.')
+            .next()
+            .and_then(|m| m.trim().parse::<u32>().ok())
+    };
+
+    let (Some(client_major), Some(server_major)) = (major(version), major(API_VERSION)) else {
+        return Ok(());
+    };
+
+    if client_major < server_major {
+        return Err(AppError::IncompatibleClient(format!(
+            "Incompatible Subsonic REST protocol version {}, client must upgrade",
+            version
+        )));
     }
+    if client_major > server_major {
+        return Err(AppError::IncompatibleServer(format!(
+            "Incompatible Subsonic REST protocol version {}, server must upgrade",
+            version
+        )));
+    }
+
+    Ok(())
 }
 
-/// 尝试使用 Subsonic 查询参数进行认证
-async fn try_subsonic_auth(query: &str, pool: &SqlitePool) -> Option<User> {
-    // 解析查询参数
+/// 获取 TCP 连接的对端地址
+pub fn peer_ip(extensions: &axum::http::Extensions) -> Option<std::net::IpAddr> {
+    extensions
+        .get::<ConnectInfo<std::net::SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+}
+
+/// 获取客户端 IP
+///
+/// 对端是受信任的反向代理时按 `X-Forwarded-For` 识别真实客户端，否则为对端地址
+pub fn client_ip(
+    headers: &axum::http::HeaderMap,
+    extensions: &axum::http::Extensions,
+) -> Option<std::net::IpAddr> {
+    let peer = peer_ip(extensions)?;
+    let Some(config) = extensions.get::<AppConfig>() else {
+        return Some(peer);
+    };
+    let forwarded_for = headers
+        .get("x-forwarded-for")
+        .and_then(|value| value.to_str().ok());
+
+    Some(forwarded_client_ip(
+        peer,
+        forwarded_for,
+        &config.trusted_proxies,
+    ))
+}
+
+/// 对端是否为受信任的反向代理
+///
+/// 只有受信任的代理设置的 `X-Forwarded-*` 请求头才可采信
+pub fn from_trusted_proxy(extensions: &axum::http::Extensions) -> bool {
+    let (Some(peer), Some(config)) = (peer_ip(extensions), extensions.get::<AppConfig>()) else {
+        return false;
+    };
+    ip_cidr::is_trusted(&peer, &config.trusted_proxies)
+}
+
+/// 登录限流的记录键: 用户名 (u 参数) 与客户端 IP
+fn lockout_keys(query: &str, client_ip: Option<std::net::IpAddr>) -> Vec<LockoutKey> {
+    let params: std::collections::HashMap<String, String> =
+        serde_urlencoded::from_str(query).unwrap_or_default();
+
+    params
+        .get("u")
+        .map(|username| LockoutKey::User(username.clone()))
+        .into_iter()
+        .chain(client_ip.map(LockoutKey::Ip))
+        .collect()
+}
+
+/// 登录锁定期间返回的错误
+pub fn lockout_error(retry_after_seconds: i64) -> AppError {
+    AppError::auth_failed(&format!(
+        "Too many failed login attempts, try again in {} seconds",
+        retry_after_seconds
+    ))
+}
+
+/// 是否为凭据错误 (计入登录失败次数)
+pub fn is_credential_failure(error: &AppError) -> bool {
+    matches!(error, AppError::AuthFailed(_) | AppError::InvalidApiKey(_))
+}
+
+/// 尝试使用 Subsonic 请求参数进行认证
+async fn try_subsonic_auth(
+    query: &str,
+    pool: &SqlitePool,
+    cipher: &PasswordCipher,
+) -> Result<User, AppError> {
+    // 解析请求参数
     let params: std::collections::HashMap<String, String> =
-        serde_urlencoded::from_str(query).ok()?;
+        serde_urlencoded::from_str(query).unwrap_or_default();
+
+    if let Some(version) = params.get("v") {
+        check_client_version(version)?;
+    }
+
+    // OpenSubsonic API 密钥认证 (apiKey 参数，不能与 u 同时出现)
+    if let Some(api_key) = params.get("apiKey") {
+        if params.contains_key("u") {
+            tracing::info!("try subsonic auth failed: apiKey conflicts with u");
+            return Err(AppError::ConflictingAuth(
+                "apiKey cannot be combined with u".to_string(),
+            ));
+        }
+        return authenticate_with_api_key(api_key, pool).await;
+    }
 
     // 提取用户名
-    let username = params.get("u")?;
+    let username = params
+        .get("u")
+        .ok_or_else(|| AppError::missing_parameter("u"))?;
 
     // 尝试密码认证 (p 参数)
     if let Some(password) = params.get("p") {
-        tracing::debug!(
-            "try subsonic auth with password username: {} password: {}",
-            username,
-            password
-        );
-        return authenticate_with_password(username, password, pool)
-            .await
-            .ok();
+        tracing::debug!("try subsonic auth with password username: {}", username);
+        // 支持 p=enc:<hex> 形式的编码密码
+        let password = crate::utils::decode_subsonic_password(password);
+        return authenticate_with_password(username, &password, pool, cipher).await;
     }
 
     // 尝试 token + salt 认证 (t + s 参数)
     if let (Some(token), Some(salt)) = (params.get("t"), params.get("s")) {
-        tracing::debug!(
-            "try subsonic auth with token username: {} token: {} salt: {}",
-            username,
-            token,
-            salt
-        );
-        return authenticate_subsonic(username, token, salt, pool)
-            .await
-            .ok();
+        tracing::debug!("try subsonic auth with token username: {}", username);
+        return authenticate_subsonic(username, token, salt, pool, cipher).await;
     }
 
-    tracing::info!("try subsonic auth failed with query: {}", query);
-    None
+    tracing::info!(
+        "try subsonic auth failed with query: {}",
+        crate::utils::log_redact::redact_query(query)
+    );
+    Err(AppError::missing_parameter("p or t/s"))
+}
+
+/// 通过 API 密钥认证
+async fn authenticate_with_api_key(api_key: &str, pool: &SqlitePool) -> Result<User, AppError> {
+    let service = ApiKeyService::new(std::sync::Arc::new(ServiceContext::new(pool.clone())));
+    service
+        .authenticate(api_key)
+        .await?
+        .ok_or_else(|| AppError::InvalidApiKey("Invalid API key".to_string()))
+}
+
+/// 按用户名查询用户，不存在时视为用户名或密码错误
+async fn find_user(username: &str, pool: &SqlitePool) -> Result<User, AppError> {
+    sqlx::query_as::<_, User>("SELECT * FROM users WHERE username = ?")
+        .bind(username)
+        .fetch_optional(pool)
+        .await?
+        .ok_or_else(|| AppError::auth_failed(WRONG_CREDENTIALS))
 }
 
 /// 通过密码直接认证
@@ -92,17 +232,13 @@ async fn authenticate_with_password(
     username: &str,
     password: &str,
     pool: &SqlitePool,
-) -> Result<User, ()> {
-    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE username = ?")
-        .bind(username)
-        .fetch_optional(pool)
-        .await
-        .map_err(|_| ())?
-        .ok_or(())?;
+    cipher: &PasswordCipher,
+) -> Result<User, AppError> {
+    let user = find_user(username, pool).await?;
 
-    // 验证密码(直接比较明文)
-    if password != user.password {
-        return Err(());
+    // 验证密码(解密后比较)
+    if !cipher.verify(password, &user.password) {
+        return Err(AppError::auth_failed(WRONG_CREDENTIALS));
     }
 
     Ok(user)
@@ -114,103 +250,229 @@ async fn authenticate_subsonic(
     token: &str,
     salt: &str,
     pool: &SqlitePool,
-) -> Result<User, ()> {
-    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE username = ?")
-        .bind(username)
-        .fetch_optional(pool)
-        .await
-        .map_err(|_| ())?
-        .ok_or(())?;
+    cipher: &PasswordCipher,
+) -> Result<User, AppError> {
+    let user = find_user(username, pool).await?;
 
-    // 获取用户的密码
-    let password = &user.password;
+    // 获取用户的明文密码，无法解密时该用户不支持 token 认证
+    let password = cipher.decrypt(&user.password).map_err(|e| {
+        tracing::error!("failed to decrypt password for user {}: {}", username, e);
+        AppError::token_not_supported("Token authentication not supported for this user")
+    })?;
 
     // 计算预期的 token: MD5(password + salt)
-    let expected_token = crate::utils::generate_subsonic_token(password, salt);
+    let expected_token = crate::utils::generate_subsonic_token(&password, salt);
 
     // 验证 token(不区分大小写)
     if !expected_token.eq_ignore_ascii_case(token) {
-        tracing::error!(
-            "subsonic token auth failed with username: {} expected_token: {} token: {}",
-            username,
-            expected_token,
-            token
-        );
-        return Err(());
+        tracing::info!("subsonic token auth failed with username: {}", username);
+        return Err(AppError::auth_failed(WRONG_CREDENTIALS));
     }
 
-    tracing::debug!(
-        "subsonic token auth success with user: {:?} token: {}",
-        user,
-        token
-    );
+    tracing::debug!("subsonic token auth success with username: {}", username);
     Ok(user)
 }
 
+/// 由用户构造仅在本次请求内有效的 Claims (逐请求校验的凭据)
+fn request_claims(user: User) -> Claims {
+    let now = chrono::Utc::now().timestamp() as usize;
+    Claims {
+        sub: user.id,
+        username: user.username,
+        is_admin: user.is_admin,
+        exp: now,
+        iat: now,
+    }
+}
+
+/// 读取受信任反向代理转发的用户名
+///
+/// 未启用反向代理认证或请求不带用户名请求头时返回 `None`；
+/// 请求头来自不受信任的地址时记录警告并忽略，继续走常规认证
+fn proxy_username(
+    headers: &axum::http::HeaderMap,
+    proxy: &ReverseProxyConfig,
+    client_ip: Option<std::net::IpAddr>,
+) -> Option<String> {
+    let value = headers.get(proxy.user_header.as_str())?;
+
+    let trusted = client_ip.is_some_and(|ip| ip_cidr::is_trusted(&ip, &proxy.trusted_proxies));
+    if !trusted {
+        tracing::warn!(
+            "ignoring {} header from untrusted address {:?}",
+            proxy.user_header,
+            client_ip
+        );
+        return None;
+    }
+
+    value
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|username| !username.is_empty())
+        .map(str::to_string)
+}
+
+/// 免认证的 Subsonic 端点 (完整路径匹配)
+const PUBLIC_ENDPOINTS: &[&str] = &[
+    "/rest/ping",
+    "/rest/getLicense",
+    "/rest/getOpenSubsonicExtensions",
+];
+
+/// 接受流媒体令牌 (`hlsToken`) 认证的端点
+const STREAM_TOKEN_ENDPOINTS: &[&str] = &["/rest/hls.m3u8", "/rest/hlsSegment"];
+
+/// 免认证的路径前缀 (需以 `/` 结尾，避免 `/shareXxx` 之类的路径被放行)
+const PUBLIC_PREFIXES: &[&str] = &["/api/auth/", "/share/"];
+
+/// 检查路径是否允许公开访问
+///
+/// 含 `..` 等非规范片段的路径一律需要认证，防止借公开前缀绕过
+pub fn is_public_path(path: &str) -> bool {
+    if path
+        .split('/')
+        .any(|segment| segment == ".." || segment == "." || segment.contains('%'))
+    {
+        return false;
+    }
+
+    PUBLIC_ENDPOINTS.contains(&path)
+        || PUBLIC_PREFIXES
+            .iter()
+            .any(|prefix| path.starts_with(prefix) && path.len() > prefix.len())
+}
+
 /// 认证中间件函数
 pub async fn auth_middleware(
     mut req: axum::http::Request<axum::body::Body>,
     next: axum::middleware::Next,
 ) -> Response {
-    // 检查路径是否需要认证
-    let path = req.uri().path();
-
     // 允许公开访问的端点
-    if path.starts_with("/rest/ping")
-        || path.starts_with("/rest/getLicense")
-        || path.starts_with("/api/auth")
-    {
+    if is_public_path(req.uri().path()) {
         return next.run(req).await;
     }
 
-    // 从扩展中获取数据库连接池
-    let pool = match req
+    // 从扩展中获取数据库连接池和密码加密组件
+    let pool = req
         .extensions()
         .get::<std::sync::Arc<SqlitePool>>()
-        .cloned()
-    {
-        Some(pool) => pool,
-        None => {
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: "InternalServerError".to_string(),
-                    message: "服务器配置错误".to_string(),
-                    details: Some("数据库连接池未初始化".to_string()),
-                }),
-            )
-                .into_response();
-        }
+        .cloned();
+    let cipher = req
+        .extensions()
+        .get::<std::sync::Arc<PasswordCipher>>()
+        .cloned();
+    let throttle = req
+        .extensions()
+        .get::<std::sync::Arc<LoginThrottleService>>()
+        .cloned();
+    let (Some(pool), Some(cipher), Some(throttle)) = (pool, cipher, throttle) else {
+        return AppError::ConfigError("Authentication is not initialized".to_string())
+            .into_response();
     };
 
-    // 尝试 Subsonic 查询参数认证
-    if let Some(query) = req.uri().query() {
-        // tracing::info!("try subsonic auth with query");
-        if let Some(user) = try_subsonic_auth(query, &pool).await {
-            // 创建 Claims 并添加到请求扩展中
-            let claims = Claims {
-                sub: user.id.clone(),
-                username: user.username.clone(),
-                is_admin: user.is_admin,
-                exp: (chrono::Utc::now() + chrono::Duration::hours(24)).timestamp() as usize,
-                iat: chrono::Utc::now().timestamp() as usize,
+    // 受信任反向代理转发的用户名 (例如 Remote-User)，用户不存在时自动创建
+    let proxy = req
+        .extensions()
+        .get::<AppConfig>()
+        .and_then(|config| config.reverse_proxy.clone());
+    if let Some(proxy) = proxy {
+        if let Some(username) = proxy_username(req.headers(), &proxy, peer_ip(req.extensions())) {
+            let auth_service = AuthService::new(pool.as_ref().clone(), cipher.clone());
+            return match auth_service
+                .provision_user(&username, &proxy.default_roles)
+                .await
+            {
+                Ok(user) => {
+                    req.extensions_mut().insert(request_claims(user));
+                    next.run(req).await
+                }
+                Err(e) => e.into_response(),
+            };
+        }
+    }
+
+    // 优先使用会话令牌认证 (Authorization: Bearer)
+    if let Some(token) = bearer_token(req.headers()) {
+        let Some(session_service) = req
+            .extensions()
+            .get::<std::sync::Arc<SessionService>>()
+            .cloned()
+        else {
+            return AppError::ConfigError("Authentication is not initialized".to_string())
+                .into_response();
+        };
+
+        return match session_service.authenticate(token).await {
+            Ok((user, token_claims)) => {
+                let claims = Claims {
+                    sub: user.id,
+                    username: user.username,
+                    is_admin: user.is_admin,
+                    exp: token_claims.exp as usize,
+                    iat: token_claims.iat as usize,
+                };
+
+                req.extensions_mut().insert(claims);
+                next.run(req).await
+            }
+            Err(e) => e.into_response(),
+        };
+    }
+
+    // 尝试 Subsonic 请求参数认证 (查询字符串或表单请求体)
+    let params = RequestParams::from_request(req.extensions(), req.uri());
+
+    // HLS 播放列表中的 URL 使用短期流媒体令牌认证 (只能访问签发时的歌曲)
+    if STREAM_TOKEN_ENDPOINTS.contains(&req.uri().path()) {
+        let query: std::collections::HashMap<String, String> =
+            serde_urlencoded::from_str(params.as_str()).unwrap_or_default();
+        if let Some(token) = query.get(hls_utils::TOKEN_QUERY_KEY) {
+            let Some(session_service) = req
+                .extensions()
+                .get::<std::sync::Arc<SessionService>>()
+                .cloned()
+            else {
+                return AppError::ConfigError("Authentication is not initialized".to_string())
+                    .into_response();
             };
 
-            req.extensions_mut().insert(claims);
-            return next.run(req).await;
+            return match session_service
+                .authenticate_stream_token(token, query.get("id").map(String::as_str))
+                .await
+            {
+                Ok(user) => {
+                    req.extensions_mut().insert(request_claims(user));
+                    next.run(req).await
+                }
+                Err(e) => e.into_response(),
+            };
         }
     }
 
-    // 认证失败
-    (
-        StatusCode::UNAUTHORIZED,
-        Json(ErrorResponse {
-            error: "Unauthorized".to_string(),
-            message: "认证失败".to_string(),
-            details: Some("请提供有效的认证参数 (u+p 或 u+t+s)".to_string()),
-        }),
-    )
-        .into_response()
+    // 用户名或客户端 IP 处于锁定状态时直接拒绝
+    let keys = lockout_keys(params.as_str(), client_ip(req.headers(), req.extensions()));
+    if let Some(retry_after) = throttle.check(&keys) {
+        return lockout_error(retry_after).into_response();
+    }
+
+    match try_subsonic_auth(params.as_str(), &pool, &cipher).await {
+        Ok(user) => {
+            throttle.record_success(&user.username);
+
+            // Subsonic 凭据逐请求校验，Claims 仅在本次请求内有效
+            req.extensions_mut().insert(request_claims(user));
+            next.run(req).await
+        }
+        // 认证失败
+        Err(e) => {
+            if is_credential_failure(&e) {
+                throttle.record_failure(&keys);
+            }
+            e.into_response()
+        }
+    }
 }
 
 /// 管理员权限检查中间件
@@ -219,31 +481,12 @@ pub async fn admin_middleware(
     next: axum::middleware::Next,
 ) -> Response {
     // 从请求扩展中获取 claims
-    let claims = match req.extensions().get::<Claims>() {
-        Some(claims) => claims,
-        None => {
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(ErrorResponse {
-                    error: "Unauthorized".to_string(),
-                    message: "认证失败".to_string(),
-                    details: Some("未找到认证信息，请先登录".to_string()),
-                }),
-            )
-                .into_response();
-        }
+    let Some(claims) = req.extensions().get::<Claims>() else {
+        return AppError::auth_failed("Authentication required").into_response();
     };
 
     if !claims.is_admin {
-        return (
-            StatusCode::FORBIDDEN,
-            Json(ErrorResponse {
-                error: "Forbidden".to_string(),
-                message: "权限不足".to_string(),
-                details: Some("需要管理员权限才能访问此资源".to_string()),
-            }),
-        )
-            .into_response();
+        return AppError::access_denied("Admin privileges required").into_response();
     }
 
     next.run(req).await
@@ -264,6 +507,7 @@ pub async fn get_user_permissions(
             podcast_role,
             share_role,
             video_conversion_role,
+            jukebox_role,
             scrobbling_enabled
          FROM users WHERE id = ?",
     )
@@ -287,6 +531,7 @@ pub struct UserPermissions {
     pub podcast_role: bool,
     pub share_role: bool,
     pub video_conversion_role: bool,
+    pub jukebox_role: bool,
     pub scrobbling_enabled: bool,
 }
 
@@ -331,8 +576,226 @@ impl UserPermissions {
         self.video_conversion_role
     }
 
+    /// 检查点唱机权限
+    pub fn can_use_jukebox(&self) -> bool {
+        self.jukebox_role
+    }
+
     /// 检查Scrobble权限
     pub fn can_scrobble(&self) -> bool {
         self.scrobbling_enabled
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DefaultUserRoles;
+    use crate::middleware::subsonic_error_middleware;
+    use crate::utils::ip_cidr::IpCidr;
+    use axum::{
+        body::Body, extract::Extension, http::Request, middleware::from_fn, routing::get, Router,
+    };
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+        sqlx::query(
+            "CREATE TABLE users (
+                id TEXT PRIMARY KEY,
+                username TEXT NOT NULL UNIQUE,
+                password TEXT NOT NULL,
+                email TEXT NOT NULL UNIQUE,
+                is_admin BOOLEAN NOT NULL DEFAULT 0,
+                max_bitrate INTEGER NOT NULL DEFAULT 320,
+                download_role BOOLEAN NOT NULL DEFAULT 1,
+                upload_role BOOLEAN NOT NULL DEFAULT 0,
+                playlist_role BOOLEAN NOT NULL DEFAULT 1,
+                cover_art_role BOOLEAN NOT NULL DEFAULT 1,
+                comment_role BOOLEAN NOT NULL DEFAULT 0,
+                podcast_role BOOLEAN NOT NULL DEFAULT 0,
+                share_role BOOLEAN NOT NULL DEFAULT 1,
+                video_conversion_role BOOLEAN NOT NULL DEFAULT 0,
+                jukebox_role BOOLEAN NOT NULL DEFAULT 0,
+                scrobbling_enabled BOOLEAN NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    /// 只信任 10.0.0.0/8 的反向代理，客户端地址为 `client`
+    fn app(pool: SqlitePool, client: &str) -> Router {
+        let mut config = AppConfig::test_config();
+        config.trusted_proxies = vec![IpCidr::parse("10.0.0.0/8").unwrap()];
+        config.reverse_proxy = Some(ReverseProxyConfig {
+            user_header: "Remote-User".to_string(),
+            trusted_proxies: vec![IpCidr::parse("10.0.0.0/8").unwrap()],
+            default_roles: DefaultUserRoles::parse("download").unwrap(),
+        });
+        let client = SocketAddr::new(client.parse().unwrap(), 50000);
+
+        Router::new()
+            .route(
+                "/rest/whoami",
+                get(|claims: Claims| async move { claims.username }),
+            )
+            .layer(from_fn(auth_middleware))
+            .layer(from_fn(subsonic_error_middleware))
+            .layer(Extension(ConnectInfo(client)))
+            .layer(Extension(config))
+            .layer(Extension(Arc::new(LoginThrottleService::new(5, 30, 3600))))
+            .layer(Extension(Arc::new(PasswordCipher::new("secret", &[]))))
+            .layer(Extension(Arc::new(pool)))
+    }
+
+    /// 携带 Remote-User 请求头访问，返回响应体
+    async fn call(pool: &SqlitePool, client: &str, user: &str) -> String {
+        let req = Request::builder()
+            .uri("/rest/whoami?f=json")
+            .header("remote-user", user)
+            .body(Body::empty())
+            .unwrap();
+        let response = app(pool.clone(), client).oneshot(req).await.unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    async fn user_count(pool: &SqlitePool) -> i64 {
+        sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_trusted_proxy_provisions_user() {
+        let pool = setup_test_db().await;
+
+        assert_eq!(call(&pool, "10.1.2.3", "carol").await, "carol");
+        assert_eq!(call(&pool, "10.1.2.3", "carol").await, "carol");
+        assert_eq!(user_count(&pool).await, 1);
+
+        // 自动创建的用户使用配置的默认角色
+        let permissions = get_user_permissions(&pool, &find_user("carol", &pool).await.unwrap().id)
+            .await
+            .unwrap();
+        assert!(permissions.can_download());
+        assert!(!permissions.can_manage_playlist() && !permissions.can_scrobble());
+    }
+
+    #[tokio::test]
+    async fn test_untrusted_proxy_header_ignored() {
+        let pool = setup_test_db().await;
+
+        // 不受信任的地址伪造请求头: 忽略并按常规认证处理 (缺少凭据)
+        for client in ["192.168.1.10", "11.0.0.1", "::ffff:192.168.1.10"] {
+            let body = call(&pool, client, "admin").await;
+            let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+            assert_eq!(json["subsonic-response"]["status"], "failed", "{}", client);
+            assert_eq!(json["subsonic-response"]["error"]["code"], 10, "{}", client);
+        }
+        assert_eq!(user_count(&pool).await, 0);
+
+        // 映射为 IPv6 的受信任地址仍可认证
+        assert_eq!(call(&pool, "::ffff:10.0.0.1", "dave").await, "dave");
+    }
+
+    /// 经由代理 10.0.0.1 使用密码认证，成功时返回用户名，失败时返回错误码
+    async fn login_via_proxy(
+        app: &Router,
+        forwarded_for: &str,
+        user: &str,
+        password: &str,
+    ) -> String {
+        let req = Request::builder()
+            .uri(format!("/rest/whoami?f=json&u={}&p={}", user, password))
+            .header("x-forwarded-for", forwarded_for)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(req).await.unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        match serde_json::from_slice::<serde_json::Value>(&bytes) {
+            Ok(json) => json["subsonic-response"]["error"]["code"].to_string(),
+            Err(_) => String::from_utf8(bytes.to_vec()).unwrap(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lockout_uses_forwarded_client_ip() {
+        let pool = setup_test_db().await;
+        sqlx::query(
+            "INSERT INTO users (id, username, password, email) VALUES ('u1', 'alice', ?, 'a@x')",
+        )
+        .bind(
+            PasswordCipher::new("secret", &[])
+                .encrypt("sesame")
+                .unwrap(),
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let app = app(pool.clone(), "10.0.0.1");
+
+        // 代理后的某个客户端连续失败，该客户端 IP 被锁定
+        for _ in 0..5 {
+            assert_eq!(login_via_proxy(&app, "1.2.3.4", "mallory", "x").await, "40");
+        }
+        assert_eq!(
+            login_via_proxy(&app, "1.2.3.4", "alice", "sesame").await,
+            "40"
+        );
+
+        // 同一代理后的其他客户端不受影响
+        assert_eq!(
+            login_via_proxy(&app, "5.6.7.8", "alice", "sesame").await,
+            "alice"
+        );
+    }
+
+    #[test]
+    fn test_is_public_path() {
+        assert!(is_public_path("/rest/ping"));
+        assert!(is_public_path("/api/auth/login"));
+        assert!(is_public_path("/share/abc123"));
+        assert!(is_public_path("/share/abc123/stream/s1"));
+
+        // 仅放行完整路径或带 / 的前缀
+        assert!(!is_public_path("/rest/pingx"));
+        assert!(!is_public_path("/rest/getUsers"));
+        assert!(!is_public_path("/share"));
+        assert!(!is_public_path("/share/"));
+        assert!(!is_public_path("/shares/abc"));
+        assert!(!is_public_path("/api/authx/login"));
+        // 不允许借公开前缀访问其他路径
+        assert!(!is_public_path("/share/../rest/getUsers"));
+        assert!(!is_public_path("/share/%2e%2e/rest/getUsers"));
+    }
+
+    #[test]
+    fn test_check_client_version() {
+        assert!(check_client_version("1.16.1").is_ok());
+        assert!(check_client_version("1.8.0").is_ok());
+        assert!(check_client_version("unknown").is_ok());
+        assert!(matches!(
+            check_client_version("0.9"),
+            Err(AppError::IncompatibleClient(_))
+        ));
+        assert!(matches!(
+            check_client_version("2.0.0"),
+            Err(AppError::IncompatibleServer(_))
+        ));
+    }
+}
@@ -0,0 +1,111 @@
+//! Subsonic 错误格式中间件
+//!
+//! Subsonic 客户端期望失败时仍返回 HTTP 200，并按 `f` 参数返回
+//! `<subsonic-response status="failed"><error code=".." message=".."/></subsonic-response>`
+//! 或对应的 JSON。该中间件把 `/rest/*` 的 [`AppError`](crate::error::AppError)
+//! 响应改写为请求格式的 Subsonic 错误响应。
+
+use axum::{
+    body::Body,
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::SubsonicErrorInfo;
+use crate::extractors::{detect_format, RequestParams};
+use crate::models::response::ResponseFormat;
+use crate::response::ApiResponse;
+
+/// 错误格式中间件函数
+pub async fn subsonic_error_middleware(req: Request<Body>, next: Next) -> Response {
+    if !req.uri().path().starts_with("/rest/") {
+        return next.run(req).await;
+    }
+
+    let params = RequestParams::from_request(req.extensions(), req.uri());
+    let format = detect_format(&params, req.headers());
+
+    let response = next.run(req).await;
+    render_subsonic_error(response, format)
+}
+
+/// 把 [`AppError`](crate::error::AppError) 响应改写为指定格式的 Subsonic 错误响应，
+/// 其他响应原样返回
+///
+/// 在错误格式中间件之外提前返回错误的中间件也通过此函数渲染错误
+pub fn render_subsonic_error(response: Response, format: ResponseFormat) -> Response {
+    match response.extensions().get::<SubsonicErrorInfo>().cloned() {
+        Some(error) => ApiResponse::<()>::error(error.code, error.message, format).into_response(),
+        None => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::AppError;
+    use axum::{http::StatusCode, routing::get, Router};
+    use tower::ServiceExt;
+
+    async fn not_found() -> Result<String, AppError> {
+        Err(AppError::not_found("Song"))
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/rest/getSong", get(not_found))
+            .route("/api/song", get(not_found))
+            .layer(axum::middleware::from_fn(subsonic_error_middleware))
+    }
+
+    async fn body_string(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_error_as_json() {
+        let req = Request::builder()
+            .uri("/rest/getSong?id=1&f=json")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let json: serde_json::Value = serde_json::from_str(&body_string(response).await).unwrap();
+        let container = &json["subsonic-response"];
+        assert_eq!(container["status"], "failed");
+        assert_eq!(container["error"]["code"], 70);
+        assert_eq!(container["error"]["message"], "Song not found");
+    }
+
+    #[tokio::test]
+    async fn test_error_as_xml() {
+        let req = Request::builder()
+            .uri("/rest/getSong?id=1")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = body_string(response).await;
+        assert!(body.contains(r#"status="failed""#));
+        assert!(body.contains(r#"<error code="70" message="Song not found"/>"#));
+    }
+
+    #[tokio::test]
+    async fn test_non_subsonic_path_untouched() {
+        let req = Request::builder()
+            .uri("/api/song")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -1,5 +1,12 @@
 //! 中间件模块
 
+pub mod access_log;
 pub mod auth_middleware;
+pub mod error_middleware;
+pub mod params_middleware;
+pub mod role_middleware;
 
-pub use auth_middleware::auth_middleware;
+pub use auth_middleware::{admin_middleware, auth_middleware};
+pub use error_middleware::subsonic_error_middleware;
+pub use params_middleware::params_middleware;
+pub use role_middleware::role_middleware;
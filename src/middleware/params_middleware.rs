@@ -0,0 +1,147 @@
+//! 请求参数中间件
+//!
+//! 读取 `/rest/*` 的表单 POST 请求体，与查询字符串合并后放入请求扩展，
+//! 再把请求体原样放回，供认证中间件和 [`Params`](crate::extractors::Params) 提取器使用。
+#![allow(dead_code)]
+
+use axum::{
+    body::Body,
+    http::{header, Method, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use super::error_middleware::render_subsonic_error;
+use crate::error::AppError;
+use crate::extractors::{detect_format, RequestParams};
+
+/// 表单请求体大小上限 (1 MB)
+const MAX_FORM_BODY_SIZE: usize = 1024 * 1024;
+
+/// 表单 Content-Type
+const FORM_CONTENT_TYPE: &str = "application/x-www-form-urlencoded";
+
+/// 是否为需要合并参数的表单 POST 请求
+fn is_form_post(req: &Request<Body>) -> bool {
+    req.method() == Method::POST
+        && req.uri().path().starts_with("/rest/")
+        && req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with(FORM_CONTENT_TYPE))
+}
+
+/// 请求参数中间件函数
+pub async fn params_middleware(mut req: Request<Body>, next: Next) -> Response {
+    if !is_form_post(&req) {
+        let params = RequestParams::merge(req.uri().query(), "");
+        req.extensions_mut().insert(params);
+        return next.run(req).await;
+    }
+
+    let (mut parts, body) = req.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_FORM_BODY_SIZE).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            // 本中间件在错误格式中间件之外运行，直接按查询字符串中的 f 参数渲染错误
+            let format =
+                detect_format(&RequestParams::merge(parts.uri.query(), ""), &parts.headers);
+            let error = AppError::validation_error(&format!(
+                "Request body must not exceed {} bytes",
+                MAX_FORM_BODY_SIZE
+            ));
+            return render_subsonic_error(error.into_response(), format);
+        }
+    };
+
+    let form = String::from_utf8_lossy(&bytes);
+    parts
+        .extensions
+        .insert(RequestParams::merge(parts.uri.query(), form.trim()));
+
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Router};
+    use tower::ServiceExt;
+
+    async fn echo_params(params: RequestParams) -> String {
+        params.0
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/rest/echo", post(echo_params).get(echo_params))
+            .layer(axum::middleware::from_fn(params_middleware))
+    }
+
+    async fn body_string(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_form_post_merged() {
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/rest/echo?f=json")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from("u=alice&id=1&id=2"))
+            .unwrap();
+
+        let response = app().oneshot(req).await.unwrap();
+        assert_eq!(body_string(response).await, "f=json&u=alice&id=1&id=2");
+    }
+
+    #[tokio::test]
+    async fn test_get_uses_query() {
+        let req = Request::builder()
+            .uri("/rest/echo?u=alice")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app().oneshot(req).await.unwrap();
+        assert_eq!(body_string(response).await, "u=alice");
+    }
+
+    #[tokio::test]
+    async fn test_form_body_too_large() {
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/rest/echo?f=json")
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(format!(
+                "u=alice&id={}",
+                "1".repeat(MAX_FORM_BODY_SIZE)
+            )))
+            .unwrap();
+
+        let response = app().oneshot(req).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+        let json: serde_json::Value = serde_json::from_str(&body_string(response).await).unwrap();
+        let container = &json["subsonic-response"];
+        assert_eq!(container["status"], "failed");
+        assert_eq!(container["error"]["code"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_non_form_body_ignored() {
+        let req = Request::builder()
+            .method(Method::POST)
+            .uri("/rest/echo?u=alice")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from("{\"id\":1}"))
+            .unwrap();
+
+        let response = app().oneshot(req).await.unwrap();
+        assert_eq!(body_string(response).await, "u=alice");
+    }
+}
@@ -0,0 +1,303 @@
+//! 角色权限中间件
+//!
+//! 按端点声明所需的 Subsonic 角色 (downloadRole、playlistRole 等)，
+//! 用户缺少对应角色时返回错误码 50。用户权限在同一请求内只查询一次，
+//! 并缓存在请求扩展中供后续处理器使用。
+#![allow(dead_code)]
+
+use axum::{
+    http::Extensions,
+    response::{IntoResponse, Response},
+};
+use sqlx::SqlitePool;
+use std::sync::Arc;
+
+use crate::error::AppError;
+use crate::middleware::auth_middleware::{get_user_permissions, Claims, UserPermissions};
+
+/// Subsonic 用户角色
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Download,
+    Upload,
+    Playlist,
+    CoverArt,
+    Comment,
+    Podcast,
+    Share,
+    VideoConversion,
+    Jukebox,
+    Scrobbling,
+}
+
+impl Role {
+    /// 所有角色
+    pub const ALL: [Role; 10] = [
+        Role::Download,
+        Role::Upload,
+        Role::Playlist,
+        Role::CoverArt,
+        Role::Comment,
+        Role::Podcast,
+        Role::Share,
+        Role::VideoConversion,
+        Role::Jukebox,
+        Role::Scrobbling,
+    ];
+
+    /// 检查用户是否拥有该角色
+    pub fn is_granted(&self, permissions: &UserPermissions) -> bool {
+        match self {
+            Role::Download => permissions.can_download(),
+            Role::Upload => permissions.can_upload(),
+            Role::Playlist => permissions.can_manage_playlist(),
+            Role::CoverArt => permissions.can_access_cover_art(),
+            Role::Comment => permissions.can_comment(),
+            Role::Podcast => permissions.can_access_podcast(),
+            Role::Share => permissions.can_share(),
+            Role::VideoConversion => permissions.can_convert_video(),
+            Role::Jukebox => permissions.can_use_jukebox(),
+            Role::Scrobbling => permissions.can_scrobble(),
+        }
+    }
+
+    /// 缺少角色时的提示信息
+    fn denied_message(&self) -> &'static str {
+        match self {
+            Role::Download => "Download permission required",
+            Role::Upload => "Upload permission required",
+            Role::Playlist => "Playlist permission required",
+            Role::CoverArt => "Cover art permission required",
+            Role::Comment => "Comment permission required",
+            Role::Podcast => "Podcast permission required",
+            Role::Share => "Share permission required",
+            Role::VideoConversion => "Video conversion permission required",
+            Role::Jukebox => "Jukebox permission required",
+            Role::Scrobbling => "Scrobbling permission required",
+        }
+    }
+}
+
+/// 端点所需角色
+const ROUTE_ROLES: &[(&str, Role)] = &[
+    ("/rest/download", Role::Download),
+    ("/rest/createPlaylist", Role::Playlist),
+    ("/rest/updatePlaylist", Role::Playlist),
+    ("/rest/deletePlaylist", Role::Playlist),
+    ("/rest/appendPlaylist", Role::Playlist),
+    ("/rest/getCoverArt", Role::CoverArt),
+    ("/rest/addChatMessage", Role::Comment),
+    ("/rest/scrobble", Role::Scrobbling),
+    ("/rest/createShare", Role::Share),
+    ("/rest/updateShare", Role::Share),
+    ("/rest/deleteShare", Role::Share),
+    ("/rest/refreshPodcasts", Role::Podcast),
+    ("/rest/createPodcastChannel", Role::Podcast),
+    ("/rest/deletePodcastChannel", Role::Podcast),
+    ("/rest/downloadPodcastEpisode", Role::Podcast),
+    ("/rest/deletePodcastEpisode", Role::Podcast),
+    ("/rest/jukeboxControl", Role::Jukebox),
+];
+
+/// 获取端点所需角色
+pub fn required_role(path: &str) -> Option<Role> {
+    ROUTE_ROLES
+        .iter()
+        .find(|(route, _)| *route == path)
+        .map(|(_, role)| *role)
+}
+
+/// 获取当前请求用户的权限 (同一请求内缓存)
+pub async fn cached_permissions(
+    extensions: &mut Extensions,
+    pool: &SqlitePool,
+    user_id: &str,
+) -> Result<UserPermissions, AppError> {
+    if let Some(permissions) = extensions.get::<UserPermissions>() {
+        return Ok(permissions.clone());
+    }
+
+    let permissions = get_user_permissions(pool, user_id)
+        .await
+        .map_err(|_| AppError::access_denied("Failed to check permissions"))?;
+    extensions.insert(permissions.clone());
+
+    Ok(permissions)
+}
+
+/// 角色权限检查中间件 (需在认证中间件之后执行)
+pub async fn role_middleware(
+    mut req: axum::http::Request<axum::body::Body>,
+    next: axum::middleware::Next,
+) -> Response {
+    let Some(role) = required_role(req.uri().path()) else {
+        return next.run(req).await;
+    };
+
+    let Some(claims) = req.extensions().get::<Claims>().cloned() else {
+        return AppError::auth_failed("Authentication required").into_response();
+    };
+    let Some(pool) = req.extensions().get::<Arc<SqlitePool>>().cloned() else {
+        return AppError::ConfigError("Authentication is not initialized".to_string())
+            .into_response();
+    };
+
+    match cached_permissions(req.extensions_mut(), &pool, &claims.sub).await {
+        Ok(permissions) if role.is_granted(&permissions) => next.run(req).await,
+        Ok(_) => AppError::access_denied(role.denied_message()).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::subsonic_error_middleware;
+    use axum::{
+        body::Body, extract::Extension, http::Request, middleware::from_fn, routing::get, Router,
+    };
+    use tower::ServiceExt;
+
+    /// 角色对应的用户表字段
+    fn role_column(role: Role) -> &'static str {
+        match role {
+            Role::Download => "download_role",
+            Role::Upload => "upload_role",
+            Role::Playlist => "playlist_role",
+            Role::CoverArt => "cover_art_role",
+            Role::Comment => "comment_role",
+            Role::Podcast => "podcast_role",
+            Role::Share => "share_role",
+            Role::VideoConversion => "video_conversion_role",
+            Role::Jukebox => "jukebox_role",
+            Role::Scrobbling => "scrobbling_enabled",
+        }
+    }
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+        sqlx::query(
+            "CREATE TABLE users (
+                id TEXT PRIMARY KEY,
+                download_role BOOLEAN NOT NULL DEFAULT 1,
+                upload_role BOOLEAN NOT NULL DEFAULT 1,
+                playlist_role BOOLEAN NOT NULL DEFAULT 1,
+                cover_art_role BOOLEAN NOT NULL DEFAULT 1,
+                comment_role BOOLEAN NOT NULL DEFAULT 1,
+                podcast_role BOOLEAN NOT NULL DEFAULT 1,
+                share_role BOOLEAN NOT NULL DEFAULT 1,
+                video_conversion_role BOOLEAN NOT NULL DEFAULT 1,
+                jukebox_role BOOLEAN NOT NULL DEFAULT 1,
+                scrobbling_enabled BOOLEAN NOT NULL DEFAULT 1
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query("INSERT INTO users (id) VALUES ('u1')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        pool
+    }
+
+    /// 模拟认证后的请求: 注入 Claims，所有端点返回权限是否已缓存
+    fn app(pool: SqlitePool) -> Router {
+        let mut router = Router::new().route("/rest/ping", get(cached));
+        for (route, _) in ROUTE_ROLES {
+            router = router.route(route, get(cached));
+        }
+
+        router
+            .layer(from_fn(role_middleware))
+            .layer(from_fn(
+                |mut req: Request<Body>, next: axum::middleware::Next| async move {
+                    req.extensions_mut().insert(Claims {
+                        sub: "u1".to_string(),
+                        username: "alice".to_string(),
+                        is_admin: false,
+                        exp: 0,
+                        iat: 0,
+                    });
+                    next.run(req).await
+                },
+            ))
+            .layer(from_fn(subsonic_error_middleware))
+            .layer(Extension(Arc::new(pool)))
+    }
+
+    async fn cached(permissions: Option<Extension<UserPermissions>>) -> &'static str {
+        if permissions.is_some() {
+            "cached"
+        } else {
+            "ok"
+        }
+    }
+
+    /// 请求端点，返回响应体
+    async fn call(pool: &SqlitePool, path: &str) -> String {
+        let req = Request::builder()
+            .uri(format!("{}?f=json", path))
+            .body(Body::empty())
+            .unwrap();
+        let response = app(pool.clone()).oneshot(req).await.unwrap();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_role_matrix() {
+        let pool = setup_test_db().await;
+
+        for role in Role::ALL {
+            // 仅撤销当前角色
+            sqlx::query(&format!(
+                "UPDATE users SET {} = 0 WHERE id = 'u1'",
+                role_column(role)
+            ))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+            for (route, route_role) in ROUTE_ROLES {
+                let body = call(&pool, route).await;
+                if *route_role == role {
+                    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+                    assert_eq!(
+                        json["subsonic-response"]["error"]["code"], 50,
+                        "{} should require {:?}",
+                        route, role
+                    );
+                } else {
+                    assert_eq!(body, "cached", "{} should not require {:?}", route, role);
+                }
+            }
+
+            sqlx::query(&format!(
+                "UPDATE users SET {} = 1 WHERE id = 'u1'",
+                role_column(role)
+            ))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unrestricted_route() {
+        let pool = setup_test_db().await;
+        sqlx::query("UPDATE users SET download_role = 0, playlist_role = 0")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // 无需角色的端点不查询权限
+        assert_eq!(call(&pool, "/rest/ping").await, "ok");
+        assert_eq!(required_role("/rest/getPlaylists"), None);
+    }
+}
@@ -19,6 +19,7 @@ pub struct UserDto {
     pub podcast_role: bool,
     pub share_role: bool,
     pub video_conversion_role: bool,
+    pub jukebox_role: bool,
     pub scrobbling_enabled: bool,
 }
 
@@ -29,6 +30,11 @@ pub struct CreateUserRequest {
     pub password: String,
     pub email: String,
     pub is_admin: Option<bool>,
+    /// 邀请码 (仅自助注册且 `REGISTRATION_MODE=invite` 时使用)
+    pub invite_code: Option<String>,
+    /// 可访问的音乐文件夹 (可重复)，为空时可访问所有文件夹
+    #[serde(rename = "musicFolderId", default)]
+    pub music_folder_id: Vec<i64>,
 }
 
 /// 登录请求
@@ -51,7 +57,11 @@ pub struct UpdateUserRequest {
     pub podcast_role: Option<bool>,
     pub share_role: Option<bool>,
     pub video_conversion_role: Option<bool>,
+    pub jukebox_role: Option<bool>,
     pub scrobbling_enabled: Option<bool>,
+    /// 可访问的音乐文件夹 (可重复)，为空时保持不变
+    #[serde(rename = "musicFolderId", default)]
+    pub music_folder_id: Vec<i64>,
 }
 
 /// 修改密码请求
@@ -19,6 +19,8 @@ pub struct Album {
     pub song_count: i32,
     pub duration: i32,
     pub play_count: i32,
+    /// 所属音乐文件夹
+    pub music_folder_id: Option<i64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -43,6 +45,7 @@ impl Album {
             song_count: 0,
             duration: 0,
             play_count: 0,
+            music_folder_id: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
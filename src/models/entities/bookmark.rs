@@ -0,0 +1,18 @@
+//! 书签数据库实体
+#![allow(dead_code)]
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 书签实体 (完整数据库表结构)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Bookmark {
+    pub user_id: String,
+    pub song_id: String,
+    /// 播放位置（毫秒）
+    pub position: i64,
+    pub comment: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub changed_at: DateTime<Utc>,
+}
@@ -3,21 +3,37 @@
 #![allow(unused_imports)]
 
 pub mod album;
+pub mod api_key;
 pub mod artist;
+pub mod bookmark;
+pub mod internet_radio_station;
+pub mod invite_code;
+pub mod music_folder;
 pub mod play_queue;
 pub mod playlist;
+pub mod podcast;
 pub mod rating;
 pub mod scrobble;
+pub mod share;
 pub mod song;
 pub mod starred;
 pub mod user;
+pub mod video;
 
 pub use album::Album;
+pub use api_key::ApiKey;
 pub use artist::Artist;
+pub use bookmark::Bookmark;
+pub use internet_radio_station::InternetRadioStation;
+pub use invite_code::InviteCode;
+pub use music_folder::MusicFolder;
 pub use play_queue::{PlayQueue, PlayQueueSong};
 pub use playlist::Playlist;
+pub use podcast::{PodcastChannel, PodcastEpisode};
 pub use rating::Rating;
 pub use scrobble::Scrobble;
+pub use share::Share;
 pub use song::Song;
 pub use starred::Starred;
 pub use user::User;
+pub use video::Video;
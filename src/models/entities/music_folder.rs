@@ -0,0 +1,17 @@
+//! 音乐文件夹数据库实体
+#![allow(dead_code)]
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 音乐文件夹实体 - 对应 music_folders 表的完整结构
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct MusicFolder {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+    /// 已从配置中移除 (内容保留，等待管理员清除)
+    pub missing: bool,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,42 @@
+//! 播客数据库实体
+#![allow(dead_code)]
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 播客频道实体 (完整数据库表结构)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PodcastChannel {
+    pub id: String,
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+    /// new / completed / error
+    pub status: String,
+    pub error_message: Option<String>,
+    pub last_refreshed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// 播客单集实体 (完整数据库表结构)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PodcastEpisode {
+    pub id: String,
+    pub channel_id: String,
+    pub guid: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub stream_url: String,
+    pub content_type: Option<String>,
+    pub file_size: Option<i64>,
+    pub duration: Option<i64>,
+    pub publish_date: Option<DateTime<Utc>>,
+    /// new / downloading / completed / error / deleted
+    pub status: String,
+    pub file_path: Option<String>,
+    pub error_message: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
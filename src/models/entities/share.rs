@@ -0,0 +1,26 @@
+//! 分享数据库实体
+#![allow(dead_code)]
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 分享实体 (完整数据库表结构)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Share {
+    pub id: String,
+    pub token: String,
+    pub user_id: String,
+    pub description: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_visited: Option<DateTime<Utc>>,
+    pub visit_count: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Share {
+    /// 分享是否已过期
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        matches!(self.expires_at, Some(expires_at) if expires_at <= now)
+    }
+}
@@ -24,6 +24,8 @@ pub struct Song {
     pub file_size: Option<i64>,
     pub lyrics: Option<String>,
     pub play_count: i32,
+    /// 所属音乐文件夹
+    pub music_folder_id: Option<i64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -61,6 +63,7 @@ impl Song {
             file_size,
             lyrics,
             play_count: 0,
+            music_folder_id: None,
             created_at: Utc::now(),
             updated_at: Utc::now(),
         }
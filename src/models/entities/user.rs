@@ -12,7 +12,7 @@ use crate::utils::id_builder;
 pub struct User {
     pub id: String,
     pub username: String,
-    pub password: String, // 明文密码,用于 MD5 token 验证
+    pub password: String, // 加密存储的密码 (AES-GCM),解密后用于 MD5 token 验证
     pub email: String,
     pub is_admin: bool,
     pub max_bitrate: i32,
@@ -24,6 +24,7 @@ pub struct User {
     pub podcast_role: bool,
     pub share_role: bool,
     pub video_conversion_role: bool,
+    pub jukebox_role: bool,
     pub scrobbling_enabled: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
@@ -47,6 +48,7 @@ impl User {
             podcast_role: false,
             share_role: true,
             video_conversion_role: false,
+            jukebox_role: false,
             scrobbling_enabled: true,
             created_at: Utc::now(),
             updated_at: Utc::now(),
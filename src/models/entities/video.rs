@@ -0,0 +1,25 @@
+//! 视频数据库实体
+#![allow(dead_code)]
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 视频实体 (完整数据库表结构)
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Video {
+    pub id: String,
+    pub title: String,
+    pub file_path: String,
+    pub content_type: String,
+    pub file_size: Option<i64>,
+    /// 时长 (秒)
+    pub duration: Option<i32>,
+    /// 平均码率 (kbps)
+    pub bit_rate: Option<i32>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub music_folder_id: Option<i64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
@@ -0,0 +1,92 @@
+//! API 密钥响应模型 (OpenSubsonic apiKey 认证)
+#![allow(dead_code)]
+
+use super::common::html_escape;
+use super::ToXml;
+use crate::models::entities::ApiKey;
+use serde::{Deserialize, Serialize};
+
+/// API 密钥信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyInfo {
+    pub id: String,
+    pub name: String,
+    pub prefix: String,
+    pub created: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<String>,
+    /// 明文密钥，仅在创建时返回一次
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+}
+
+impl From<ApiKey> for ApiKeyInfo {
+    fn from(api_key: ApiKey) -> Self {
+        Self {
+            id: api_key.id,
+            name: api_key.name,
+            prefix: api_key.key_prefix,
+            created: api_key.created_at.to_rfc3339(),
+            last_used: api_key.last_used_at.map(|t| t.to_rfc3339()),
+            key: None,
+        }
+    }
+}
+
+impl ToXml for ApiKeyInfo {
+    fn to_xml_element(&self) -> String {
+        let mut xml = format!(
+            r#"<apiKey id="{}" name="{}" prefix="{}" created="{}""#,
+            self.id,
+            html_escape(&self.name),
+            self.prefix,
+            self.created
+        );
+        if let Some(last_used) = &self.last_used {
+            xml.push_str(&format!(r#" lastUsed="{}""#, last_used));
+        }
+        if let Some(key) = &self.key {
+            xml.push_str(&format!(r#" key="{}""#, key));
+        }
+        xml.push_str("/>");
+        xml
+    }
+}
+
+/// 单个 API 密钥响应 (createApiKey)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyResponse {
+    pub api_key: ApiKeyInfo,
+}
+
+impl ToXml for ApiKeyResponse {
+    fn to_xml_element(&self) -> String {
+        self.api_key.to_xml_element()
+    }
+}
+
+/// API 密钥列表响应 (getApiKeys)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeysResponse {
+    pub api_keys: ApiKeyList,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyList {
+    #[serde(rename = "apiKey")]
+    pub api_keys: Vec<ApiKeyInfo>,
+}
+
+impl ToXml for ApiKeysResponse {
+    fn to_xml_element(&self) -> String {
+        let mut xml = String::from("<apiKeys>");
+        for api_key in &self.api_keys.api_keys {
+            xml.push_str(&api_key.to_xml_element());
+        }
+        xml.push_str("</apiKeys>");
+        xml
+    }
+}
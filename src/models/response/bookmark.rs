@@ -0,0 +1,76 @@
+//! 书签响应模型 (Subsonic API 格式)
+#![allow(dead_code)]
+
+use super::common::html_escape;
+use super::{Song, ToXml};
+use crate::services::BookmarkDetail;
+use serde::{Deserialize, Serialize};
+
+/// 书签信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookmarkInfo {
+    pub position: i64,
+    pub username: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    pub created: String,
+    pub changed: String,
+    pub entry: Song,
+}
+
+impl From<BookmarkDetail> for BookmarkInfo {
+    fn from(detail: BookmarkDetail) -> Self {
+        Self {
+            position: detail.bookmark.position,
+            username: detail.username,
+            comment: detail.bookmark.comment,
+            created: detail.bookmark.created_at.to_rfc3339(),
+            changed: detail.bookmark.changed_at.to_rfc3339(),
+            entry: detail.song.into(),
+        }
+    }
+}
+
+impl ToXml for BookmarkInfo {
+    fn to_xml_element(&self) -> String {
+        let mut xml = format!(
+            r#"<bookmark position="{}" username="{}" created="{}" changed="{}""#,
+            self.position,
+            html_escape(&self.username),
+            self.created,
+            self.changed
+        );
+        if let Some(comment) = &self.comment {
+            xml.push_str(&format!(r#" comment="{}""#, html_escape(comment)));
+        }
+        xml.push_str(&format!(
+            "><entry{}/></bookmark>",
+            self.entry.xml_attributes()
+        ));
+        xml
+    }
+}
+
+/// 书签列表响应 (getBookmarks)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookmarksResponse {
+    pub bookmarks: BookmarkList,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookmarkList {
+    #[serde(rename = "bookmark")]
+    pub bookmarks: Vec<BookmarkInfo>,
+}
+
+impl ToXml for BookmarksResponse {
+    fn to_xml_element(&self) -> String {
+        let mut xml = String::from("<bookmarks>");
+        for bookmark in &self.bookmarks.bookmarks {
+            xml.push_str(&bookmark.to_xml_element());
+        }
+        xml.push_str("</bookmarks>");
+        xml
+    }
+}
@@ -3,6 +3,9 @@
 
 use serde::{Deserialize, Serialize};
 
+use super::Song;
+use crate::models::entities;
+
 /// XML 序列化 trait
 ///
 /// 用于将响应数据手动序列化为 XML 元素
@@ -19,6 +22,12 @@ impl ToXml for () {
     }
 }
 
+/// 支持的 Subsonic API 版本
+pub const API_VERSION: &str = "1.16.1";
+
+/// OpenSubsonic 服务端类型标识
+pub const SERVER_TYPE: &str = "musicflow";
+
 /// Subsonic 响应容器
 ///
 /// 支持 JSON 和 XML 两种格式:
@@ -40,6 +49,18 @@ pub struct ResponseContainer<T> {
     /// API 版本号
     pub version: String,
 
+    /// 服务端类型 (OpenSubsonic)
+    #[serde(rename = "type")]
+    pub server_type: String,
+
+    /// 服务端版本号 (OpenSubsonic)
+    #[serde(rename = "serverVersion")]
+    pub server_version: String,
+
+    /// 是否支持 OpenSubsonic 扩展
+    #[serde(rename = "openSubsonic")]
+    pub open_subsonic: bool,
+
     /// 错误信息 (仅在 status="failed" 时存在)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<SubsonicError>,
@@ -188,14 +209,17 @@ pub struct NowPlaying {
     pub entries: Vec<NowPlayingEntry>,
 }
 
-/// 正在播放条目
+/// 正在播放条目 (歌曲信息 + 播放者信息)
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct NowPlayingEntry {
-    pub id: String,
-    pub title: String,
-    pub artist: String,
+    #[serde(flatten)]
+    pub song: Song,
     pub username: String,
     pub minutes_ago: i32,
+    pub player_id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub player_name: Option<String>,
 }
 
 /// 视频
@@ -207,10 +231,47 @@ pub struct Videos {
 
 /// 视频详情
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Video {
     pub id: String,
     pub title: String,
+    pub is_dir: bool,
+    pub is_video: bool,
     pub content_type: String,
+    pub suffix: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bit_rate: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<i32>,
+    pub created: String,
+}
+
+impl From<entities::Video> for Video {
+    fn from(video: entities::Video) -> Self {
+        Self {
+            suffix: std::path::Path::new(&video.file_path)
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .unwrap_or_default(),
+            id: video.id,
+            title: video.title,
+            is_dir: false,
+            is_video: true,
+            content_type: video.content_type,
+            size: video.file_size,
+            duration: video.duration,
+            bit_rate: video.bit_rate,
+            width: video.width,
+            height: video.height,
+            created: video.created_at.to_rfc3339(),
+        }
+    }
 }
 
 /// 视频信息
@@ -218,20 +279,15 @@ pub struct Video {
 pub struct VideoInfo {
     pub id: String,
     pub title: String,
-    pub captions: Option<Captions>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub captions: Vec<Captions>,
 }
 
-/// 字幕
+/// 字幕，`id` 为 getCaptions 的 `captionId` 参数
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Captions {
     pub id: String,
-    pub label: String,
-}
-
-/// HLS 响应
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Hls {
-    pub url: String,
+    pub name: String,
 }
 
 // ============================================================================
@@ -255,14 +311,18 @@ impl ToXml for NowPlaying {
 /// NowPlayingEntry ToXml 实现
 impl ToXml for NowPlayingEntry {
     fn to_xml_element(&self) -> String {
-        format!(
-            r#"<entry id="{}" title="{}" artist="{}" username="{}" minutesAgo="{}"/>"#,
-            html_escape(&self.id),
-            html_escape(&self.title),
-            html_escape(&self.artist),
+        let mut xml = format!(
+            r#"<entry{} username="{}" minutesAgo="{}" playerId="{}""#,
+            self.song.xml_attributes(),
             html_escape(&self.username),
-            self.minutes_ago
-        )
+            self.minutes_ago,
+            self.player_id
+        );
+        if let Some(player_name) = &self.player_name {
+            xml.push_str(&format!(r#" playerName="{}""#, html_escape(player_name)));
+        }
+        xml.push_str("/>");
+        xml
     }
 }
 
@@ -309,12 +369,31 @@ impl ToXml for Videos {
 /// Video ToXml 实现
 impl ToXml for Video {
     fn to_xml_element(&self) -> String {
-        format!(
-            r#"<video id="{}" title="{}" contentType="{}"/>"#,
+        let mut xml = format!(
+            r#"<video id="{}" title="{}" isDir="false" isVideo="true" contentType="{}" suffix="{}" created="{}""#,
             html_escape(&self.id),
             html_escape(&self.title),
-            html_escape(&self.content_type)
-        )
+            html_escape(&self.content_type),
+            html_escape(&self.suffix),
+            self.created
+        );
+        if let Some(size) = self.size {
+            xml.push_str(&format!(r#" size="{}""#, size));
+        }
+        if let Some(duration) = self.duration {
+            xml.push_str(&format!(r#" duration="{}""#, duration));
+        }
+        if let Some(bit_rate) = self.bit_rate {
+            xml.push_str(&format!(r#" bitRate="{}""#, bit_rate));
+        }
+        if let Some(width) = self.width {
+            xml.push_str(&format!(r#" width="{}""#, width));
+        }
+        if let Some(height) = self.height {
+            xml.push_str(&format!(r#" height="{}""#, height));
+        }
+        xml.push_str("/>");
+        xml
     }
 }
 
@@ -327,12 +406,14 @@ impl ToXml for VideoInfo {
             html_escape(&self.title)
         );
 
-        if let Some(captions) = &self.captions {
+        if self.captions.is_empty() {
+            xml.push_str("/>");
+        } else {
             xml.push('>');
-            xml.push_str(&captions.to_xml_element());
+            for captions in &self.captions {
+                xml.push_str(&captions.to_xml_element());
+            }
             xml.push_str("</videoInfo>");
-        } else {
-            xml.push_str("/>");
         }
 
         xml
@@ -343,20 +424,13 @@ impl ToXml for VideoInfo {
 impl ToXml for Captions {
     fn to_xml_element(&self) -> String {
         format!(
-            r#"<captions id="{}" label="{}"/>"#,
+            r#"<captions id="{}" name="{}"/>"#,
             html_escape(&self.id),
-            html_escape(&self.label)
+            html_escape(&self.name)
         )
     }
 }
 
-/// Hls ToXml 实现
-impl ToXml for Hls {
-    fn to_xml_element(&self) -> String {
-        format!(r#"<hls url="{}"/>"#, html_escape(&self.url))
-    }
-}
-
 // 通用响应类型
 pub type StatusResponse = SubsonicResponse<()>;
 
@@ -366,7 +440,10 @@ impl<T> SubsonicResponse<T> {
         Self {
             response: ResponseContainer {
                 status: "ok".to_string(),
-                version: "1.16.1".to_string(),
+                version: API_VERSION.to_string(),
+                server_type: SERVER_TYPE.to_string(),
+                server_version: env!("CARGO_PKG_VERSION").to_string(),
+                open_subsonic: true,
                 error: None,
                 data,
             },
@@ -378,7 +455,10 @@ impl<T> SubsonicResponse<T> {
         Self {
             response: ResponseContainer {
                 status: "ok".to_string(),
-                version: "1.16.1".to_string(),
+                version: API_VERSION.to_string(),
+                server_type: SERVER_TYPE.to_string(),
+                server_version: env!("CARGO_PKG_VERSION").to_string(),
+                open_subsonic: true,
                 error: None,
                 data,
             },
@@ -390,7 +470,10 @@ impl<T> SubsonicResponse<T> {
         Self {
             response: ResponseContainer {
                 status: "failed".to_string(),
-                version: "1.16.1".to_string(),
+                version: API_VERSION.to_string(),
+                server_type: SERVER_TYPE.to_string(),
+                server_version: env!("CARGO_PKG_VERSION").to_string(),
+                open_subsonic: true,
                 error: Some(SubsonicError { code, message }),
                 data: None,
             },
@@ -402,7 +485,10 @@ impl<T> SubsonicResponse<T> {
         Self {
             response: ResponseContainer {
                 status: "failed".to_string(),
-                version: "1.16.1".to_string(),
+                version: API_VERSION.to_string(),
+                server_type: SERVER_TYPE.to_string(),
+                server_version: env!("CARGO_PKG_VERSION").to_string(),
+                open_subsonic: true,
                 error: Some(SubsonicError { code, message }),
                 data: None,
             },
@@ -425,8 +511,9 @@ impl<T: ToXml> SubsonicResponse<T> {
         let mut xml = String::from("<subsonic-response xmlns=\"http://subsonic.org/restapi\"");
         xml.push_str(&format!(" status=\"{}\"", response.status));
         xml.push_str(&format!(" version=\"{}\"", response.version));
-        xml.push_str(" serverVersion=\"1.0.0\"");
-        // xml.push_str(" openSubsonic=true");
+        xml.push_str(&format!(" type=\"{}\"", response.server_type));
+        xml.push_str(&format!(" serverVersion=\"{}\"", response.server_version));
+        xml.push_str(&format!(" openSubsonic=\"{}\"", response.open_subsonic));
 
         // 如果有 data,添加子元素
         if let Some(ref data) = response.data {
@@ -458,7 +545,7 @@ impl<T: ToXml> SubsonicResponse<T> {
 }
 
 /// HTML/XML 转义辅助函数
-fn html_escape(s: &str) -> String {
+pub(crate) fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
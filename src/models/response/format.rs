@@ -38,7 +38,7 @@ mod tests {
         assert_eq!(ResponseFormat::parse_str("JSON"), ResponseFormat::Json);
         assert_eq!(ResponseFormat::parse_str("xml"), ResponseFormat::Xml);
         assert_eq!(ResponseFormat::parse_str("XML"), ResponseFormat::Xml);
-        assert_eq!(ResponseFormat::parse_str("unknown"), ResponseFormat::Json);
+        assert_eq!(ResponseFormat::parse_str("unknown"), ResponseFormat::Xml);
     }
 
     #[test]
@@ -0,0 +1,69 @@
+//! 网络电台响应模型 (Subsonic API 格式)
+#![allow(dead_code)]
+
+use super::common::html_escape;
+use super::ToXml;
+use crate::models::entities::InternetRadioStation;
+use serde::{Deserialize, Serialize};
+
+/// 网络电台信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InternetRadioStationInfo {
+    pub id: String,
+    pub name: String,
+    pub stream_url: String,
+    #[serde(rename = "homePageUrl", skip_serializing_if = "Option::is_none")]
+    pub home_page_url: Option<String>,
+}
+
+impl From<InternetRadioStation> for InternetRadioStationInfo {
+    fn from(station: InternetRadioStation) -> Self {
+        Self {
+            id: station.id,
+            name: station.name,
+            stream_url: station.stream_url,
+            home_page_url: station.homepage_url,
+        }
+    }
+}
+
+impl ToXml for InternetRadioStationInfo {
+    fn to_xml_element(&self) -> String {
+        let mut xml = format!(
+            r#"<internetRadioStation id="{}" name="{}" streamUrl="{}""#,
+            self.id,
+            html_escape(&self.name),
+            html_escape(&self.stream_url)
+        );
+        if let Some(home_page_url) = &self.home_page_url {
+            xml.push_str(&format!(r#" homePageUrl="{}""#, html_escape(home_page_url)));
+        }
+        xml.push_str("/>");
+        xml
+    }
+}
+
+/// 网络电台列表响应 (getInternetRadioStations / importInternetRadioStations)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InternetRadioStationsResponse {
+    pub internet_radio_stations: InternetRadioStationList,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InternetRadioStationList {
+    #[serde(rename = "internetRadioStation")]
+    pub stations: Vec<InternetRadioStationInfo>,
+}
+
+impl ToXml for InternetRadioStationsResponse {
+    fn to_xml_element(&self) -> String {
+        let mut xml = String::from("<internetRadioStations>");
+        for station in &self.internet_radio_stations.stations {
+            xml.push_str(&station.to_xml_element());
+        }
+        xml.push_str("</internetRadioStations>");
+        xml
+    }
+}
@@ -0,0 +1,87 @@
+//! 邀请码响应模型
+#![allow(dead_code)]
+
+use super::ToXml;
+use crate::models::entities::InviteCode;
+use serde::{Deserialize, Serialize};
+
+/// 邀请码信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteCodeInfo {
+    pub id: String,
+    pub code: String,
+    pub created: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires: Option<String>,
+    pub used: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub used_at: Option<String>,
+}
+
+impl From<InviteCode> for InviteCodeInfo {
+    fn from(invite: InviteCode) -> Self {
+        Self {
+            id: invite.id,
+            code: invite.code,
+            created: invite.created_at.to_rfc3339(),
+            expires: invite.expires_at.map(|t| t.to_rfc3339()),
+            used: invite.used_at.is_some(),
+            used_at: invite.used_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+impl ToXml for InviteCodeInfo {
+    fn to_xml_element(&self) -> String {
+        let mut xml = format!(
+            r#"<inviteCode id="{}" code="{}" created="{}" used="{}""#,
+            self.id, self.code, self.created, self.used
+        );
+        if let Some(expires) = &self.expires {
+            xml.push_str(&format!(r#" expires="{}""#, expires));
+        }
+        if let Some(used_at) = &self.used_at {
+            xml.push_str(&format!(r#" usedAt="{}""#, used_at));
+        }
+        xml.push_str("/>");
+        xml
+    }
+}
+
+/// 单个邀请码响应 (createInviteCode)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteCodeResponse {
+    pub invite_code: InviteCodeInfo,
+}
+
+impl ToXml for InviteCodeResponse {
+    fn to_xml_element(&self) -> String {
+        self.invite_code.to_xml_element()
+    }
+}
+
+/// 邀请码列表响应 (getInviteCodes)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteCodesResponse {
+    pub invite_codes: InviteCodeList,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InviteCodeList {
+    #[serde(rename = "inviteCode")]
+    pub invite_codes: Vec<InviteCodeInfo>,
+}
+
+impl ToXml for InviteCodesResponse {
+    fn to_xml_element(&self) -> String {
+        let mut xml = String::from("<inviteCodes>");
+        for invite in &self.invite_codes.invite_codes {
+            xml.push_str(&invite.to_xml_element());
+        }
+        xml.push_str("</inviteCodes>");
+        xml
+    }
+}
@@ -0,0 +1,85 @@
+//! 点唱机响应模型 (Subsonic API 格式)
+#![allow(dead_code)]
+
+use super::{Song, ToXml};
+use crate::services::{JukeboxPlaylist, JukeboxStatus};
+use serde::{Deserialize, Serialize};
+
+/// 点唱机状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JukeboxStatusInfo {
+    pub current_index: i32,
+    pub playing: bool,
+    pub gain: f32,
+    pub position: u32,
+}
+
+impl From<JukeboxStatus> for JukeboxStatusInfo {
+    fn from(status: JukeboxStatus) -> Self {
+        Self {
+            current_index: status.current_index,
+            playing: status.playing,
+            gain: status.gain,
+            position: status.position,
+        }
+    }
+}
+
+impl JukeboxStatusInfo {
+    fn xml_attributes(&self) -> String {
+        format!(
+            r#" currentIndex="{}" playing="{}" gain="{}" position="{}""#,
+            self.current_index, self.playing, self.gain, self.position
+        )
+    }
+}
+
+/// 点唱机状态响应 (jukeboxControl 除 get 外的操作)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JukeboxStatusResponse {
+    pub jukebox_status: JukeboxStatusInfo,
+}
+
+impl ToXml for JukeboxStatusResponse {
+    fn to_xml_element(&self) -> String {
+        format!("<jukeboxStatus{}/>", self.jukebox_status.xml_attributes())
+    }
+}
+
+/// 点唱机播放列表
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JukeboxPlaylistInfo {
+    #[serde(flatten)]
+    pub status: JukeboxStatusInfo,
+    pub entry: Vec<Song>,
+}
+
+impl From<JukeboxPlaylist> for JukeboxPlaylistInfo {
+    fn from(playlist: JukeboxPlaylist) -> Self {
+        Self {
+            status: playlist.status.into(),
+            entry: Song::from_complex_dtos(playlist.songs),
+        }
+    }
+}
+
+/// 点唱机播放列表响应 (jukeboxControl?action=get)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JukeboxPlaylistResponse {
+    pub jukebox_playlist: JukeboxPlaylistInfo,
+}
+
+impl ToXml for JukeboxPlaylistResponse {
+    fn to_xml_element(&self) -> String {
+        let playlist = &self.jukebox_playlist;
+        let mut xml = format!("<jukeboxPlaylist{}>", playlist.status.xml_attributes());
+        for song in &playlist.entry {
+            xml.push_str(&format!("<entry{}/>", song.xml_attributes()));
+        }
+        xml.push_str("</jukeboxPlaylist>");
+        xml
+    }
+}
@@ -0,0 +1,70 @@
+//! 登录锁定响应模型
+#![allow(dead_code)]
+
+use super::common::html_escape;
+use super::ToXml;
+use crate::services::login_throttle_service::{Lockout, LockoutKey};
+use serde::{Deserialize, Serialize};
+
+/// 锁定信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LockoutInfo {
+    /// 锁定类型: user 或 ip
+    #[serde(rename = "type")]
+    pub lockout_type: String,
+    pub key: String,
+    pub failures: u32,
+    pub locked_until: String,
+}
+
+impl From<Lockout> for LockoutInfo {
+    fn from(lockout: Lockout) -> Self {
+        let lockout_type = match lockout.key {
+            LockoutKey::User(_) => "user",
+            LockoutKey::Ip(_) => "ip",
+        };
+
+        Self {
+            lockout_type: lockout_type.to_string(),
+            key: lockout.key.to_string(),
+            failures: lockout.failures,
+            locked_until: lockout.locked_until.to_rfc3339(),
+        }
+    }
+}
+
+impl ToXml for LockoutInfo {
+    fn to_xml_element(&self) -> String {
+        format!(
+            r#"<lockout type="{}" key="{}" failures="{}" lockedUntil="{}"/>"#,
+            self.lockout_type,
+            html_escape(&self.key),
+            self.failures,
+            self.locked_until
+        )
+    }
+}
+
+/// 锁定列表响应 (getLockouts)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockoutsResponse {
+    pub lockouts: LockoutList,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockoutList {
+    #[serde(rename = "lockout")]
+    pub lockouts: Vec<LockoutInfo>,
+}
+
+impl ToXml for LockoutsResponse {
+    fn to_xml_element(&self) -> String {
+        let mut xml = String::from("<lockouts>");
+        for lockout in &self.lockouts.lockouts {
+            xml.push_str(&lockout.to_xml_element());
+        }
+        xml.push_str("</lockouts>");
+        xml
+    }
+}
@@ -2,28 +2,46 @@
 //! 所有响应结构都使用 Subsonic API 格式,不实现 FromRow
 
 pub mod album;
+pub mod api_key;
 pub mod artist;
+pub mod bookmark;
 pub mod common;
 pub mod format;
 pub mod genre;
+pub mod internet_radio;
+pub mod invite_code;
+pub mod jukebox;
+pub mod lockout;
+pub mod music_folder;
 pub mod play_queue;
 pub mod playlist;
+pub mod podcast;
 pub mod rating;
 pub mod search;
+pub mod share;
 pub mod song;
 pub mod starred;
 pub mod user;
 
 // 导出常用类型
 pub use album::*;
+pub use api_key::*;
 pub use artist::*;
+pub use bookmark::*;
 pub use common::*;
 pub use format::ResponseFormat;
 pub use genre::*;
+pub use internet_radio::*;
+pub use invite_code::*;
+pub use jukebox::*;
+pub use lockout::*;
+pub use music_folder::*;
 pub use play_queue::*;
 pub use playlist::*;
+pub use podcast::*;
 pub use rating::*;
 pub use search::*;
+pub use share::*;
 pub use song::*;
 pub use starred::*;
 pub use user::*;
@@ -0,0 +1,66 @@
+//! 音乐文件夹响应结构
+#![allow(dead_code)]
+
+use super::common::html_escape;
+use super::ToXml;
+use crate::models::entities;
+use serde::{Deserialize, Serialize};
+
+/// 单个音乐文件夹
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MusicFolder {
+    pub id: i64,
+    pub name: String,
+}
+
+impl From<entities::MusicFolder> for MusicFolder {
+    fn from(folder: entities::MusicFolder) -> Self {
+        Self {
+            id: folder.id,
+            name: folder.name,
+        }
+    }
+}
+
+/// 音乐文件夹列表响应
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MusicFoldersResponse {
+    #[serde(rename = "musicFolders")]
+    pub music_folders: MusicFolders,
+}
+
+/// 音乐文件夹列表
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MusicFolders {
+    #[serde(rename = "musicFolder")]
+    pub music_folder: Vec<MusicFolder>,
+}
+
+// ========== XML 序列化实现 ==========
+
+impl ToXml for MusicFolder {
+    fn to_xml_element(&self) -> String {
+        format!(
+            r#"<musicFolder id="{}" name="{}"/>"#,
+            self.id,
+            html_escape(&self.name)
+        )
+    }
+}
+
+impl ToXml for MusicFoldersResponse {
+    fn to_xml_element(&self) -> String {
+        self.music_folders.to_xml_element()
+    }
+}
+
+impl ToXml for MusicFolders {
+    fn to_xml_element(&self) -> String {
+        let mut xml = String::from("<musicFolders>");
+        for folder in &self.music_folder {
+            xml.push_str(&folder.to_xml_element());
+        }
+        xml.push_str("</musicFolders>");
+        xml
+    }
+}
@@ -30,7 +30,7 @@ impl ToXml for PlayQueueResponse {
         xml.push_str(&format!(" username=\"{}\"", self.username));
         xml.push_str(&format!(" changed=\"{}\"", self.changed));
         xml.push_str(&format!(" changedBy=\"{}\"", self.changed_by));
-        xml.push_str(">");
+        xml.push('>');
 
         for song in &self.entries {
             xml.push_str(&song.to_xml_element());
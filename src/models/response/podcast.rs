@@ -0,0 +1,211 @@
+//! 播客响应模型 (Subsonic API 格式)
+#![allow(dead_code)]
+
+use super::common::html_escape;
+use super::ToXml;
+use crate::models::entities::{PodcastChannel, PodcastEpisode};
+use crate::services::PodcastChannelDetail;
+use serde::{Deserialize, Serialize};
+
+/// 播客单集
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodcastEpisodeInfo {
+    pub id: String,
+    /// 已下载的单集可通过 `/rest/stream?id={streamId}` 播放
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_id: Option<String>,
+    pub channel_id: String,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publish_date: Option<String>,
+    pub status: String,
+    pub is_dir: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration: Option<i64>,
+}
+
+impl From<PodcastEpisode> for PodcastEpisodeInfo {
+    fn from(episode: PodcastEpisode) -> Self {
+        Self {
+            stream_id: (episode.status == "completed").then(|| episode.id.clone()),
+            id: episode.id,
+            channel_id: episode.channel_id,
+            title: episode.title,
+            description: episode.description,
+            publish_date: episode.publish_date.map(|t| t.to_rfc3339()),
+            status: episode.status,
+            is_dir: false,
+            size: episode.file_size,
+            content_type: episode.content_type,
+            duration: episode.duration,
+        }
+    }
+}
+
+impl ToXml for PodcastEpisodeInfo {
+    fn to_xml_element(&self) -> String {
+        let mut xml = format!(
+            r#"<episode id="{}" channelId="{}" title="{}" status="{}" isDir="false""#,
+            self.id,
+            self.channel_id,
+            html_escape(&self.title),
+            self.status
+        );
+        if let Some(stream_id) = &self.stream_id {
+            xml.push_str(&format!(r#" streamId="{}""#, stream_id));
+        }
+        if let Some(description) = &self.description {
+            xml.push_str(&format!(r#" description="{}""#, html_escape(description)));
+        }
+        if let Some(publish_date) = &self.publish_date {
+            xml.push_str(&format!(r#" publishDate="{}""#, publish_date));
+        }
+        if let Some(size) = self.size {
+            xml.push_str(&format!(r#" size="{}""#, size));
+        }
+        if let Some(content_type) = &self.content_type {
+            xml.push_str(&format!(r#" contentType="{}""#, html_escape(content_type)));
+        }
+        if let Some(duration) = self.duration {
+            xml.push_str(&format!(r#" duration="{}""#, duration));
+        }
+        xml.push_str("/>");
+        xml
+    }
+}
+
+/// 播客频道
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodcastChannelInfo {
+    pub id: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_image_url: Option<String>,
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub episode: Vec<PodcastEpisodeInfo>,
+}
+
+impl From<PodcastChannel> for PodcastChannelInfo {
+    fn from(channel: PodcastChannel) -> Self {
+        Self {
+            id: channel.id,
+            url: channel.url,
+            title: channel.title,
+            description: channel.description,
+            original_image_url: channel.image_url,
+            status: channel.status,
+            error_message: channel.error_message,
+            episode: vec![],
+        }
+    }
+}
+
+impl From<PodcastChannelDetail> for PodcastChannelInfo {
+    fn from(detail: PodcastChannelDetail) -> Self {
+        Self {
+            episode: detail
+                .episodes
+                .into_iter()
+                .map(PodcastEpisodeInfo::from)
+                .collect(),
+            ..detail.channel.into()
+        }
+    }
+}
+
+impl ToXml for PodcastChannelInfo {
+    fn to_xml_element(&self) -> String {
+        let mut xml = format!(
+            r#"<channel id="{}" url="{}" status="{}""#,
+            self.id,
+            html_escape(&self.url),
+            self.status
+        );
+        if let Some(title) = &self.title {
+            xml.push_str(&format!(r#" title="{}""#, html_escape(title)));
+        }
+        if let Some(description) = &self.description {
+            xml.push_str(&format!(r#" description="{}""#, html_escape(description)));
+        }
+        if let Some(image_url) = &self.original_image_url {
+            xml.push_str(&format!(
+                r#" originalImageUrl="{}""#,
+                html_escape(image_url)
+            ));
+        }
+        if let Some(error_message) = &self.error_message {
+            xml.push_str(&format!(
+                r#" errorMessage="{}""#,
+                html_escape(error_message)
+            ));
+        }
+        xml.push('>');
+        for episode in &self.episode {
+            xml.push_str(&episode.to_xml_element());
+        }
+        xml.push_str("</channel>");
+        xml
+    }
+}
+
+/// 播客频道列表响应 (getPodcasts)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodcastsResponse {
+    pub podcasts: PodcastChannelList,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodcastChannelList {
+    #[serde(rename = "channel")]
+    pub channels: Vec<PodcastChannelInfo>,
+}
+
+impl ToXml for PodcastsResponse {
+    fn to_xml_element(&self) -> String {
+        let mut xml = String::from("<podcasts>");
+        for channel in &self.podcasts.channels {
+            xml.push_str(&channel.to_xml_element());
+        }
+        xml.push_str("</podcasts>");
+        xml
+    }
+}
+
+/// 最新单集响应 (getNewestPodcasts)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewestPodcastsResponse {
+    pub newest_podcasts: PodcastEpisodeList,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PodcastEpisodeList {
+    #[serde(rename = "episode")]
+    pub episodes: Vec<PodcastEpisodeInfo>,
+}
+
+impl ToXml for NewestPodcastsResponse {
+    fn to_xml_element(&self) -> String {
+        let mut xml = String::from("<newestPodcasts>");
+        for episode in &self.newest_podcasts.episodes {
+            xml.push_str(&episode.to_xml_element());
+        }
+        xml.push_str("</newestPodcasts>");
+        xml
+    }
+}
@@ -0,0 +1,93 @@
+//! 分享响应模型
+#![allow(dead_code)]
+
+use super::common::html_escape;
+use super::{Song, ToXml};
+use crate::services::ShareDetail;
+use serde::{Deserialize, Serialize};
+
+/// 分享信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareInfo {
+    pub id: String,
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub username: String,
+    pub created: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_visited: Option<String>,
+    pub visit_count: i64,
+    pub entry: Vec<Song>,
+}
+
+impl ShareInfo {
+    /// 由分享详情生成，`base_url` 为分享链接的外部访问地址
+    pub fn new(detail: ShareDetail, base_url: &str) -> Self {
+        Self {
+            id: detail.share.id,
+            url: format!("{}/share/{}", base_url, detail.share.token),
+            description: detail.share.description,
+            username: detail.username,
+            created: detail.share.created_at.to_rfc3339(),
+            expires: detail.share.expires_at.map(|t| t.to_rfc3339()),
+            last_visited: detail.share.last_visited.map(|t| t.to_rfc3339()),
+            visit_count: detail.share.visit_count,
+            entry: Song::from_detail_dtos(detail.songs),
+        }
+    }
+}
+
+impl ToXml for ShareInfo {
+    fn to_xml_element(&self) -> String {
+        let mut xml = format!(
+            r#"<share id="{}" url="{}" username="{}" created="{}" visitCount="{}""#,
+            self.id,
+            html_escape(&self.url),
+            html_escape(&self.username),
+            self.created,
+            self.visit_count
+        );
+        if let Some(description) = &self.description {
+            xml.push_str(&format!(r#" description="{}""#, html_escape(description)));
+        }
+        if let Some(expires) = &self.expires {
+            xml.push_str(&format!(r#" expires="{}""#, expires));
+        }
+        if let Some(last_visited) = &self.last_visited {
+            xml.push_str(&format!(r#" lastVisited="{}""#, last_visited));
+        }
+        xml.push('>');
+        for song in &self.entry {
+            xml.push_str(&format!("<entry{}/>", song.xml_attributes()));
+        }
+        xml.push_str("</share>");
+        xml
+    }
+}
+
+/// 分享列表响应 (getShares / createShare)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharesResponse {
+    pub shares: ShareList,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShareList {
+    #[serde(rename = "share")]
+    pub shares: Vec<ShareInfo>,
+}
+
+impl ToXml for SharesResponse {
+    fn to_xml_element(&self) -> String {
+        let mut xml = String::from("<shares>");
+        for share in &self.shares.shares {
+            xml.push_str(&share.to_xml_element());
+        }
+        xml.push_str("</shares>");
+        xml
+    }
+}
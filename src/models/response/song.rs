@@ -193,10 +193,11 @@ pub struct SongResponse {
 
 // ========== XML 序列化实现 ==========
 
-impl ToXml for Song {
-    fn to_xml_element(&self) -> String {
+impl Song {
+    /// 生成歌曲的 XML 属性 (不含元素名)，供 song / entry 等元素复用
+    pub fn xml_attributes(&self) -> String {
         let mut xml = format!(
-            r#"<song id="{}" title="{}" artist="{}" album="{}" duration="{}" contentType="{}""#,
+            r#" id="{}" title="{}" artist="{}" album="{}" duration="{}" contentType="{}""#,
             self.id, self.title, self.artist, self.album, self.duration, self.content_type
         );
         if let Some(genre) = &self.genre {
@@ -238,14 +239,16 @@ impl ToXml for Song {
         if let Some(value) = &self.starred {
             xml.push_str(&format!(r#" starred="{}""#, value));
         }
-        if let Some(value) = &self.suffix {
-            xml.push_str(&format!(r#" suffix="{}""#, value));
-        }
-        xml.push_str("/>");
         xml
     }
 }
 
+impl ToXml for Song {
+    fn to_xml_element(&self) -> String {
+        format!("<song{}/>", self.xml_attributes())
+    }
+}
+
 impl ToXml for RandomSongsResponse {
     fn to_xml_element(&self) -> String {
         self.random_songs.to_xml_element()
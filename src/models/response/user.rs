@@ -30,6 +30,10 @@ pub struct UserResponse {
     pub share_role: bool,
     #[serde(rename = "videoConversionRole")]
     pub video_conversion_role: bool,
+    #[serde(rename = "jukeboxRole")]
+    pub jukebox_role: bool,
+    /// 可访问的音乐文件夹 ID
+    pub folder: Vec<i64>,
 }
 
 /// 用户列表响应
@@ -55,6 +59,8 @@ impl From<UserDto> for UserResponse {
             podcast_role: dto.podcast_role,
             share_role: dto.share_role,
             video_conversion_role: dto.video_conversion_role,
+            jukebox_role: dto.jukebox_role,
+            folder: Vec::new(),
         }
     }
 }
@@ -76,6 +82,8 @@ impl From<User> for UserResponse {
             podcast_role: user.podcast_role,
             share_role: user.share_role,
             video_conversion_role: user.video_conversion_role,
+            jukebox_role: user.jukebox_role,
+            folder: Vec::new(),
         }
     }
 }
@@ -84,8 +92,13 @@ impl From<User> for UserResponse {
 
 impl ToXml for UserResponse {
     fn to_xml_element(&self) -> String {
+        let folders: String = self
+            .folder
+            .iter()
+            .map(|id| format!("<folder>{}</folder>", id))
+            .collect();
         format!(
-            r#"<user username="{}" email="{}" adminRole="{}" scrobblingEnabled="{}" maxBitRate="{}" downloadRole="{}" uploadRole="{}" playlistRole="{}" coverArtRole="{}" commentRole="{}" podcastRole="{}" shareRole="{}" videoConversionRole="{}"/>"#,
+            r#"<user username="{}" email="{}" adminRole="{}" scrobblingEnabled="{}" maxBitRate="{}" downloadRole="{}" uploadRole="{}" playlistRole="{}" coverArtRole="{}" commentRole="{}" podcastRole="{}" shareRole="{}" videoConversionRole="{}" jukeboxRole="{}">{}</user>"#,
             self.username,
             self.email,
             self.admin,
@@ -98,7 +111,9 @@ impl ToXml for UserResponse {
             self.comment_role,
             self.podcast_role,
             self.share_role,
-            self.video_conversion_role
+            self.video_conversion_role,
+            self.jukebox_role,
+            folders
         )
     }
 }
@@ -15,8 +15,12 @@ use crate::models::response::{ResponseFormat, SubsonicResponse, ToXml};
 /// # 使用示例
 ///
 /// ```rust
-/// use crate::response::ApiResponse;
-/// use crate::extractors::Format;
+/// use musicflow_server::response::ApiResponse;
+/// use musicflow_server::extractors::Format;
+/// # struct Data;
+/// # fn get_data() -> Data {
+/// #     Data
+/// # }
 ///
 /// async fn handler(Format(format): Format) -> ApiResponse<Data> {
 ///     let data = get_data();
@@ -44,7 +48,7 @@ impl<T> ApiResponse<T> {
     }
 
     /// 便捷构造函数 - 错误响应
-    pub fn _error(code: i32, message: String, format: ResponseFormat) -> Self {
+    pub fn error(code: i32, message: String, format: ResponseFormat) -> Self {
         let response = match format {
             ResponseFormat::Json => SubsonicResponse::failed(code, message),
             ResponseFormat::Xml => SubsonicResponse::failed_xml(code, message),
@@ -0,0 +1,237 @@
+//! API 密钥管理服务
+//!
+//! 负责 OpenSubsonic apiKey 认证相关的业务逻辑:
+//! - 创建密钥（明文仅在创建时返回一次，数据库只保存哈希）
+//! - 列出、吊销用户的密钥
+//! - 通过密钥认证用户并记录最后使用时间
+
+use crate::error::AppError;
+use crate::models::entities::{ApiKey, User};
+use crate::services::ServiceContext;
+use crate::utils::{generate_api_key, hash_api_key, id_builder};
+use chrono::Utc;
+use std::sync::Arc;
+
+/// 密钥前缀长度 (用于在列表中识别密钥)
+const KEY_PREFIX_LEN: usize = 8;
+
+/// API 密钥管理服务
+pub struct ApiKeyService {
+    ctx: Arc<ServiceContext>,
+}
+
+impl ApiKeyService {
+    /// 创建新的 ApiKeyService
+    pub fn new(ctx: Arc<ServiceContext>) -> Self {
+        Self { ctx }
+    }
+
+    /// 为用户创建 API 密钥
+    ///
+    /// 返回 (密钥实体, 明文密钥)
+    pub async fn create_api_key(
+        &self,
+        user_id: &str,
+        name: &str,
+    ) -> Result<(ApiKey, String), AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::validation_error("API key name cannot be empty"));
+        }
+
+        let key = generate_api_key();
+        let api_key = ApiKey {
+            id: id_builder::generate_id(),
+            user_id: user_id.to_string(),
+            name: name.to_string(),
+            key_hash: hash_api_key(&key),
+            key_prefix: key[..KEY_PREFIX_LEN].to_string(),
+            created_at: Utc::now(),
+            last_used_at: None,
+        };
+
+        sqlx::query(
+            "INSERT INTO api_keys (id, user_id, name, key_hash, key_prefix, created_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&api_key.id)
+        .bind(&api_key.user_id)
+        .bind(&api_key.name)
+        .bind(&api_key.key_hash)
+        .bind(&api_key.key_prefix)
+        .bind(api_key.created_at)
+        .execute(&self.ctx.pool)
+        .await?;
+
+        Ok((api_key, key))
+    }
+
+    /// 获取用户的所有 API 密钥
+    pub async fn get_api_keys(&self, user_id: &str) -> Result<Vec<ApiKey>, AppError> {
+        let api_keys = sqlx::query_as::<_, ApiKey>(
+            "SELECT * FROM api_keys WHERE user_id = ? ORDER BY created_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.ctx.pool)
+        .await?;
+
+        Ok(api_keys)
+    }
+
+    /// 吊销 API 密钥（仅所有者或管理员）
+    pub async fn delete_api_key(
+        &self,
+        key_id: &str,
+        user_id: &str,
+        is_admin: bool,
+    ) -> Result<(), AppError> {
+        let owner: Option<String> = sqlx::query_scalar("SELECT user_id FROM api_keys WHERE id = ?")
+            .bind(key_id)
+            .fetch_optional(&self.ctx.pool)
+            .await?;
+
+        let owner = owner.ok_or_else(|| AppError::not_found("API key"))?;
+        if owner != user_id && !is_admin {
+            return Err(AppError::access_denied(
+                "Cannot delete another user's API key",
+            ));
+        }
+
+        sqlx::query("DELETE FROM api_keys WHERE id = ?")
+            .bind(key_id)
+            .execute(&self.ctx.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 通过 API 密钥认证用户，成功时更新最后使用时间
+    pub async fn authenticate(&self, key: &str) -> Result<Option<User>, AppError> {
+        let key_hash = hash_api_key(key);
+
+        let user = sqlx::query_as::<_, User>(
+            "SELECT u.* FROM users u
+             JOIN api_keys k ON k.user_id = u.id
+             WHERE k.key_hash = ?",
+        )
+        .bind(&key_hash)
+        .fetch_optional(&self.ctx.pool)
+        .await?;
+
+        if user.is_some() {
+            sqlx::query("UPDATE api_keys SET last_used_at = ? WHERE key_hash = ?")
+                .bind(Utc::now())
+                .bind(&key_hash)
+                .execute(&self.ctx.pool)
+                .await?;
+        }
+
+        Ok(user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::SqlitePool;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+        sqlx::query(
+            "CREATE TABLE users (
+                id TEXT PRIMARY KEY,
+                username TEXT NOT NULL,
+                password TEXT NOT NULL,
+                email TEXT NOT NULL,
+                is_admin BOOLEAN NOT NULL DEFAULT 0,
+                max_bitrate INTEGER NOT NULL DEFAULT 320,
+                download_role BOOLEAN NOT NULL DEFAULT 1,
+                upload_role BOOLEAN NOT NULL DEFAULT 0,
+                playlist_role BOOLEAN NOT NULL DEFAULT 1,
+                cover_art_role BOOLEAN NOT NULL DEFAULT 1,
+                comment_role BOOLEAN NOT NULL DEFAULT 0,
+                podcast_role BOOLEAN NOT NULL DEFAULT 0,
+                share_role BOOLEAN NOT NULL DEFAULT 1,
+                video_conversion_role BOOLEAN NOT NULL DEFAULT 0,
+                jukebox_role BOOLEAN NOT NULL DEFAULT 0,
+                scrobbling_enabled BOOLEAN NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "CREATE TABLE api_keys (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                key_hash TEXT NOT NULL UNIQUE,
+                key_prefix TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                last_used_at TEXT
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        for (id, username) in [("u1", "alice"), ("u2", "bob")] {
+            sqlx::query("INSERT INTO users (id, username, password, email) VALUES (?, ?, 'x', '')")
+                .bind(id)
+                .bind(username)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_create_and_authenticate() {
+        let pool = setup_test_db().await;
+        let service = ApiKeyService::new(Arc::new(ServiceContext::new(pool)));
+
+        let (api_key, key) = service.create_api_key("u1", "phone").await.unwrap();
+        assert_eq!(api_key.key_prefix, &key[..KEY_PREFIX_LEN]);
+        assert_ne!(api_key.key_hash, key);
+
+        let user = service.authenticate(&key).await.unwrap().unwrap();
+        assert_eq!(user.username, "alice");
+        assert!(service.authenticate("invalid").await.unwrap().is_none());
+
+        let api_keys = service.get_api_keys("u1").await.unwrap();
+        assert_eq!(api_keys.len(), 1);
+        assert!(api_keys[0].last_used_at.is_some());
+
+        assert!(service.create_api_key("u1", "  ").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_delete_api_key() {
+        let pool = setup_test_db().await;
+        let service = ApiKeyService::new(Arc::new(ServiceContext::new(pool)));
+
+        let (api_key, key) = service.create_api_key("u1", "phone").await.unwrap();
+
+        // 其他普通用户不能吊销
+        assert!(service
+            .delete_api_key(&api_key.id, "u2", false)
+            .await
+            .is_err());
+
+        service
+            .delete_api_key(&api_key.id, "u1", false)
+            .await
+            .unwrap();
+        assert!(service.authenticate(&key).await.unwrap().is_none());
+        assert!(service
+            .delete_api_key(&api_key.id, "u1", false)
+            .await
+            .is_err());
+    }
+}
@@ -1,10 +1,14 @@
 //! 认证服务
 
+use crate::config::DefaultUserRoles;
 use crate::error::AppError;
 use crate::models::dto::{CreateUserRequest, LoginRequest};
 use crate::models::entities::User;
-use crate::utils::{generate_salt, generate_subsonic_token, id_builder};
+use crate::services::session_service::{SessionService, SessionToken};
+use crate::utils::password_cipher::PasswordCipher;
+use crate::utils::{generate_api_key, generate_salt, generate_subsonic_token, id_builder};
 use sqlx::SqlitePool;
+use std::sync::Arc;
 
 /// 带令牌的用户响应
 #[derive(Debug, serde::Serialize)]
@@ -22,19 +26,59 @@ pub struct UserWithToken {
     pub podcast_role: bool,
     pub share_role: bool,
     pub video_conversion_role: bool,
+    pub jukebox_role: bool,
+    /// 会话令牌，通过 `Authorization: Bearer` 访问接口
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    /// 令牌过期时间 (Unix 时间戳，秒)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+impl UserWithToken {
+    /// 附加会话令牌
+    pub fn with_session(mut self, session: SessionToken) -> Self {
+        self.token = Some(session.token);
+        self.expires_at = Some(session.expires_at);
+        self
+    }
+}
+
+impl From<User> for UserWithToken {
+    fn from(user: User) -> Self {
+        Self {
+            username: user.username,
+            email: user.email,
+            admin: user.is_admin,
+            scrobbling_enabled: user.scrobbling_enabled,
+            max_bit_rate: user.max_bitrate,
+            download_role: user.download_role,
+            upload_role: user.upload_role,
+            playlist_role: user.playlist_role,
+            cover_art_role: user.cover_art_role,
+            comment_role: user.comment_role,
+            podcast_role: user.podcast_role,
+            share_role: user.share_role,
+            video_conversion_role: user.video_conversion_role,
+            jukebox_role: user.jukebox_role,
+            token: None,
+            expires_at: None,
+        }
+    }
 }
 
 pub struct AuthService {
     pool: SqlitePool,
+    cipher: Arc<PasswordCipher>,
 }
 
 impl AuthService {
-    pub fn new(pool: SqlitePool) -> Self {
-        Self { pool }
+    pub fn new(pool: SqlitePool, cipher: Arc<PasswordCipher>) -> Self {
+        Self { pool, cipher }
     }
 
     /// 用户注册
-    pub async fn register(&self, req: CreateUserRequest) -> Result<UserWithToken, AppError> {
+    pub async fn register(&self, req: CreateUserRequest) -> Result<User, AppError> {
         // 检查用户名是否已存在
         let existing =
             sqlx::query_as::<_, User>("SELECT * FROM users WHERE username = ? OR email = ?")
@@ -52,37 +96,29 @@ impl AuthService {
         // 生成用户ID
         let user_id = id_builder::generate_id();
 
-        // 创建用户(直接存储明文密码)
+        // 创建用户(密码加密存储)
+        let password = self.cipher.encrypt(&req.password)?;
         sqlx::query(
             "INSERT INTO users (id, username, password, email, is_admin) VALUES (?, ?, ?, ?, ?)",
         )
         .bind(&user_id)
         .bind(&req.username)
-        .bind(&req.password)
+        .bind(&password)
         .bind(&req.email)
         .bind(req.is_admin.unwrap_or(false))
         .execute(&self.pool)
         .await?;
 
-        Ok(UserWithToken {
-            username: req.username,
-            email: req.email,
-            admin: req.is_admin.unwrap_or(false),
-            scrobbling_enabled: true,
-            max_bit_rate: 320,
-            download_role: true,
-            upload_role: false,
-            playlist_role: true,
-            cover_art_role: true,
-            comment_role: false,
-            podcast_role: false,
-            share_role: true,
-            video_conversion_role: false,
-        })
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = ?")
+            .bind(&user_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(user)
     }
 
     /// 用户登录
-    pub async fn login(&self, req: LoginRequest) -> Result<UserWithToken, AppError> {
+    pub async fn login(&self, req: LoginRequest) -> Result<User, AppError> {
         // 查询用户
         let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE username = ?")
             .bind(&req.username)
@@ -91,36 +127,295 @@ impl AuthService {
 
         let user = user.ok_or_else(|| AppError::auth_failed("Invalid username or password"))?;
 
-        // 验证密码(直接比较明文)
-        if req.password != user.password {
+        // 验证密码(解密后比较)
+        if !self.cipher.verify(&req.password, &user.password) {
             return Err(AppError::auth_failed("Invalid username or password"));
         }
 
-        Ok(UserWithToken {
-            username: user.username,
-            email: user.email,
-            admin: user.is_admin,
-            scrobbling_enabled: user.scrobbling_enabled,
-            max_bit_rate: user.max_bitrate,
-            download_role: user.download_role,
-            upload_role: user.upload_role,
-            playlist_role: user.playlist_role,
-            cover_art_role: user.cover_art_role,
-            comment_role: user.comment_role,
-            podcast_role: user.podcast_role,
-            share_role: user.share_role,
-            video_conversion_role: user.video_conversion_role,
-        })
+        Ok(user)
+    }
+
+    /// 查找反向代理认证的用户，不存在时自动创建
+    ///
+    /// 自动创建的用户使用随机密码 (只能通过反向代理登录，直到管理员重置密码)
+    pub async fn provision_user(
+        &self,
+        username: &str,
+        roles: &DefaultUserRoles,
+    ) -> Result<User, AppError> {
+        let username = username.trim();
+        if username.is_empty() {
+            return Err(AppError::auth_failed("Empty proxy user"));
+        }
+
+        let password = self.cipher.encrypt(&generate_api_key())?;
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO users (
+                id, username, password, email, is_admin,
+                download_role, upload_role, playlist_role, cover_art_role, comment_role,
+                podcast_role, share_role, video_conversion_role, jukebox_role, scrobbling_enabled
+             ) VALUES (?, ?, ?, ?, 0, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(id_builder::generate_id())
+        .bind(username)
+        .bind(&password)
+        .bind(format!("{}@localhost", username))
+        .bind(roles.download)
+        .bind(roles.upload)
+        .bind(roles.playlist)
+        .bind(roles.cover_art)
+        .bind(roles.comment)
+        .bind(roles.podcast)
+        .bind(roles.share)
+        .bind(roles.video_conversion)
+        .bind(roles.jukebox)
+        .bind(roles.scrobbling)
+        .execute(&self.pool)
+        .await?;
+
+        if result.rows_affected() > 0 {
+            tracing::info!("provisioned user {} from reverse proxy", username);
+        }
+
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE username = ?")
+            .bind(username)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::auth_failed("Failed to provision proxy user"))?;
+
+        Ok(user)
     }
 
     /// 修改密码
+    ///
+    /// 同时吊销该用户的所有会话令牌
     pub async fn change_password(&self, user_id: &str, new_password: &str) -> Result<(), AppError> {
+        let password = self.cipher.encrypt(new_password)?;
+        let mut tx = self.pool.begin().await?;
         sqlx::query("UPDATE users SET password = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?")
-            .bind(new_password)
+            .bind(&password)
             .bind(user_id)
-            .execute(&self.pool)
+            .execute(&mut *tx)
             .await?;
+        SessionService::revoke_user_sessions(&mut *tx, user_id).await?;
+        tx.commit().await?;
 
         Ok(())
     }
+
+    /// 将历史明文密码和旧密钥加密的密码重新加密为当前密钥
+    ///
+    /// 启动时执行，已是当前密钥加密的记录不受影响，返回更新的用户数。
+    /// 无法解密的记录 (例如加密密钥已被移除) 会被跳过，这些用户需要重置密码。
+    pub async fn upgrade_passwords(&self) -> Result<u64, AppError> {
+        let rows: Vec<(String, String)> = sqlx::query_as("SELECT id, password FROM users")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut upgraded = 0;
+        for (user_id, stored) in rows {
+            if !self.cipher.needs_upgrade(&stored) {
+                continue;
+            }
+
+            let password = if PasswordCipher::is_encrypted(&stored) {
+                match self.cipher.decrypt(&stored) {
+                    Ok(password) => password,
+                    Err(e) => {
+                        tracing::warn!("无法重新加密用户 {} 的密码，已跳过: {}", user_id, e);
+                        continue;
+                    }
+                }
+            } else {
+                stored
+            };
+            sqlx::query("UPDATE users SET password = ? WHERE id = ?")
+                .bind(self.cipher.encrypt(&password)?)
+                .bind(&user_id)
+                .execute(&self.pool)
+                .await?;
+            upgraded += 1;
+        }
+
+        Ok(upgraded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+        sqlx::query(
+            "CREATE TABLE users (
+                id TEXT PRIMARY KEY,
+                username TEXT NOT NULL UNIQUE,
+                password TEXT NOT NULL,
+                email TEXT,
+                is_admin BOOLEAN DEFAULT 0,
+                max_bitrate INTEGER DEFAULT 320,
+                download_role INTEGER DEFAULT 1,
+                upload_role INTEGER DEFAULT 0,
+                playlist_role INTEGER DEFAULT 1,
+                cover_art_role INTEGER DEFAULT 1,
+                comment_role INTEGER DEFAULT 0,
+                podcast_role INTEGER DEFAULT 0,
+                share_role INTEGER DEFAULT 1,
+                video_conversion_role INTEGER DEFAULT 0,
+                jukebox_role BOOLEAN NOT NULL DEFAULT 0,
+                scrobbling_enabled INTEGER DEFAULT 1,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                updated_at TEXT DEFAULT CURRENT_TIMESTAMP
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "CREATE TABLE sessions (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                expires_at INTEGER NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    async fn stored_password(pool: &SqlitePool, user_id: &str) -> String {
+        sqlx::query_scalar("SELECT password FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    fn login_request(password: &str) -> LoginRequest {
+        LoginRequest {
+            username: "alice".to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_legacy_plaintext() {
+        let pool = setup_test_db().await;
+        sqlx::query("INSERT INTO users (id, username, password) VALUES ('u1', 'alice', 'sesame')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let cipher = Arc::new(PasswordCipher::new("secret", &[]));
+        let service = AuthService::new(pool.clone(), cipher.clone());
+
+        // 升级前明文密码不能登录
+        assert!(service.login(login_request("sesame")).await.is_err());
+
+        assert_eq!(service.upgrade_passwords().await.unwrap(), 1);
+        let stored = stored_password(&pool, "u1").await;
+        assert_ne!(stored, "sesame");
+        assert!(!cipher.needs_upgrade(&stored));
+        assert!(service.login(login_request("sesame")).await.is_ok());
+        assert!(service.login(login_request("wrong")).await.is_err());
+
+        // 重复执行不再更新
+        assert_eq!(service.upgrade_passwords().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_rotated_key() {
+        let pool = setup_test_db().await;
+        let old_cipher = PasswordCipher::new("old-secret", &[]);
+        sqlx::query("INSERT INTO users (id, username, password) VALUES ('u1', 'alice', ?)")
+            .bind(old_cipher.encrypt("sesame").unwrap())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let cipher = Arc::new(PasswordCipher::new(
+            "new-secret",
+            &["old-secret".to_string()],
+        ));
+        let service = AuthService::new(pool.clone(), cipher);
+        assert_eq!(service.upgrade_passwords().await.unwrap(), 1);
+
+        // 升级后不再依赖旧密钥
+        let service = AuthService::new(
+            pool.clone(),
+            Arc::new(PasswordCipher::new("new-secret", &[])),
+        );
+        assert!(service.login(login_request("sesame")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_upgrade_skips_undecryptable() {
+        let pool = setup_test_db().await;
+        let removed_key = PasswordCipher::new("removed-secret", &[]);
+        sqlx::query(
+            "INSERT INTO users (id, username, password) VALUES
+             ('u1', 'alice', ?), ('u2', 'bob', 'sesame')",
+        )
+        .bind(removed_key.encrypt("sesame").unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let service = AuthService::new(pool.clone(), Arc::new(PasswordCipher::new("secret", &[])));
+        assert_eq!(service.upgrade_passwords().await.unwrap(), 1);
+
+        // 无法解密的记录保持不变，其余用户照常升级
+        let stored = stored_password(&pool, "u1").await;
+        assert!(removed_key.verify("sesame", &stored));
+        assert_ne!(stored_password(&pool, "u2").await, "sesame");
+    }
+
+    #[tokio::test]
+    async fn test_change_password_revokes_sessions() {
+        let pool = setup_test_db().await;
+        sqlx::query("INSERT INTO users (id, username, password) VALUES ('u1', 'alice', 'sesame')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO sessions (id, user_id, expires_at) VALUES ('s1', 'u1', 0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let service = AuthService::new(pool.clone(), Arc::new(PasswordCipher::new("secret", &[])));
+        service.change_password("u1", "open").await.unwrap();
+
+        assert!(service.login(login_request("open")).await.is_ok());
+        let sessions: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sessions")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(sessions, 0);
+    }
+
+    #[tokio::test]
+    async fn test_provision_user() {
+        let pool = setup_test_db().await;
+        let service = AuthService::new(pool.clone(), Arc::new(PasswordCipher::new("secret", &[])));
+        let roles = DefaultUserRoles::parse("download,scrobbling").unwrap();
+
+        let user = service.provision_user("bob", &roles).await.unwrap();
+        assert_eq!(user.username, "bob");
+        assert!(!user.is_admin);
+        assert!(user.download_role && user.scrobbling_enabled);
+        assert!(!user.playlist_role && !user.share_role);
+
+        // 已存在的用户直接返回，不修改角色
+        let other_roles = DefaultUserRoles::default();
+        let again = service.provision_user("bob", &other_roles).await.unwrap();
+        assert_eq!(again.id, user.id);
+        assert!(!again.playlist_role);
+
+        assert!(service.provision_user(" ", &roles).await.is_err());
+    }
 }
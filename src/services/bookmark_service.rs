@@ -0,0 +1,341 @@
+//! 书签管理服务
+//!
+//! 负责处理书签相关的业务逻辑:
+//! - 保存歌曲的续播位置（有声书、长混音等）
+//! - 获取用户的所有书签
+//! - 删除书签
+//! - 每个用户每首歌曲只有一个书签
+
+use crate::error::AppError;
+use crate::models::dto::{ComplexSongDto, SongDetailDto};
+use crate::models::entities::Bookmark;
+use crate::services::{FolderScope, ServiceContext, SongService};
+use crate::utils::sql_utils;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 书签详细信息（包含歌曲信息）
+#[derive(Debug)]
+pub struct BookmarkDetail {
+    pub bookmark: Bookmark,
+    pub username: String,
+    pub song: ComplexSongDto,
+}
+
+/// 书签管理服务
+pub struct BookmarkService {
+    ctx: Arc<ServiceContext>,
+}
+
+impl BookmarkService {
+    /// 创建新的 BookmarkService
+    pub fn new(ctx: Arc<ServiceContext>) -> Self {
+        Self { ctx }
+    }
+
+    /// 创建或更新书签
+    ///
+    /// 已存在的书签保留创建时间，只更新位置、备注和修改时间
+    pub async fn create_bookmark(
+        &self,
+        user_id: &str,
+        song_id: &str,
+        position: i64,
+        comment: Option<String>,
+        scope: &FolderScope,
+    ) -> Result<(), AppError> {
+        if position < 0 {
+            return Err(AppError::validation_error("Position must not be negative"));
+        }
+
+        // 只能为可访问的歌曲创建书签
+        let exists: Option<String> = sqlx::query_scalar(&format!(
+            "SELECT id FROM songs WHERE id = ? AND {}",
+            scope.sql_filter("music_folder_id")
+        ))
+        .bind(song_id)
+        .fetch_optional(&self.ctx.pool)
+        .await?;
+        if exists.is_none() {
+            return Err(AppError::not_found("Song"));
+        }
+
+        let now = Utc::now();
+        sqlx::query(
+            r#"
+            INSERT INTO bookmarks (user_id, song_id, position, comment, created_at, changed_at)
+            VALUES (?, ?, ?, ?, ?, ?)
+            ON CONFLICT (user_id, song_id) DO UPDATE SET
+                position = excluded.position,
+                comment = excluded.comment,
+                changed_at = excluded.changed_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(song_id)
+        .bind(position)
+        .bind(&comment)
+        .bind(now)
+        .bind(now)
+        .execute(&self.ctx.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 获取用户的所有书签（按修改时间倒序）
+    ///
+    /// 不在用户可访问音乐文件夹中的歌曲不返回
+    pub async fn get_bookmarks(
+        &self,
+        user_id: &str,
+        scope: &FolderScope,
+    ) -> Result<Vec<BookmarkDetail>, AppError> {
+        let bookmarks = sqlx::query_as::<_, Bookmark>(
+            "SELECT * FROM bookmarks WHERE user_id = ? ORDER BY changed_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.ctx.pool)
+        .await?;
+
+        if bookmarks.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // 查询用户名
+        let username: String = sqlx::query_scalar("SELECT username FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_one(&self.ctx.pool)
+            .await?;
+
+        // 查询书签对应的歌曲
+        let songs = sqlx::query_as::<_, SongDetailDto>(&format!(
+            r#"{}
+            JOIN bookmarks as b ON b.song_id = s.id
+            WHERE b.user_id = ? AND {}
+            "#,
+            sql_utils::detail_sql(),
+            scope.sql_filter("s.music_folder_id")
+        ))
+        .bind(user_id)
+        .fetch_all(&self.ctx.pool)
+        .await?;
+
+        // 使用 SongService 丰富歌曲信息
+        let song_service = SongService::new(self.ctx.clone());
+        let mut songs: HashMap<String, ComplexSongDto> = song_service
+            .enrich_songs(user_id, songs)
+            .await?
+            .into_iter()
+            .map(|song| (song.song.id.clone(), song))
+            .collect();
+
+        let details = bookmarks
+            .into_iter()
+            .filter_map(|bookmark| {
+                let song = songs.remove(&bookmark.song_id)?;
+                Some(BookmarkDetail {
+                    bookmark,
+                    username: username.clone(),
+                    song,
+                })
+            })
+            .collect();
+
+        Ok(details)
+    }
+
+    /// 删除书签
+    pub async fn delete_bookmark(&self, user_id: &str, song_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM bookmarks WHERE user_id = ? AND song_id = ?")
+            .bind(user_id)
+            .bind(song_id)
+            .execute(&self.ctx.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Bookmark"));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::SqlitePool;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+        for sql in [
+            "CREATE TABLE users (id TEXT PRIMARY KEY, username TEXT NOT NULL)",
+            "CREATE TABLE artists (id TEXT PRIMARY KEY, name TEXT NOT NULL)",
+            "CREATE TABLE albums (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                artist_id TEXT NOT NULL,
+                cover_art_path TEXT,
+                music_folder_id INTEGER
+            )",
+            "CREATE TABLE songs (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                artist_id TEXT NOT NULL,
+                album_id TEXT NOT NULL,
+                duration INTEGER DEFAULT 0,
+                bit_rate INTEGER,
+                track_number INTEGER,
+                disc_number INTEGER,
+                year INTEGER,
+                genre TEXT,
+                file_path TEXT,
+                file_size INTEGER,
+                content_type TEXT,
+                play_count INTEGER DEFAULT 0,
+                music_folder_id INTEGER
+            )",
+            "CREATE TABLE ratings (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                song_id TEXT NOT NULL,
+                rating INTEGER NOT NULL
+            )",
+            "CREATE TABLE starred (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                artist_id TEXT,
+                album_id TEXT,
+                song_id TEXT
+            )",
+            "CREATE TABLE bookmarks (
+                user_id TEXT NOT NULL,
+                song_id TEXT NOT NULL,
+                position INTEGER NOT NULL DEFAULT 0,
+                comment TEXT,
+                created_at TEXT NOT NULL,
+                changed_at TEXT NOT NULL,
+                PRIMARY KEY (user_id, song_id)
+            )",
+            "INSERT INTO users (id, username) VALUES ('u1', 'alice'), ('u2', 'bob')",
+            "INSERT INTO artists (id, name) VALUES ('ar1', 'Artist')",
+            "INSERT INTO albums (id, name, artist_id, music_folder_id) VALUES
+                ('al1', 'Album 1', 'ar1', 1), ('al2', 'Album 2', 'ar1', 2)",
+            "INSERT INTO songs (id, title, artist_id, album_id, file_path, music_folder_id) VALUES
+                ('s1', 'Song 1', 'ar1', 'al1', '/music/1.mp3', 1),
+                ('s2', 'Song 2', 'ar1', 'al2', '/music/2.mp3', 2)",
+            "INSERT INTO ratings (id, user_id, song_id, rating) VALUES ('r1', 'u1', 's1', 4)",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+
+        pool
+    }
+
+    fn create_service(pool: SqlitePool) -> BookmarkService {
+        BookmarkService::new(Arc::new(ServiceContext::new(pool)))
+    }
+
+    #[tokio::test]
+    async fn test_create_and_update_bookmark() {
+        let service = create_service(setup_test_db().await);
+
+        service
+            .create_bookmark(
+                "u1",
+                "s1",
+                1000,
+                Some("chapter 1".to_string()),
+                &FolderScope::All,
+            )
+            .await
+            .unwrap();
+        let created_at = service
+            .get_bookmarks("u1", &FolderScope::All)
+            .await
+            .unwrap()[0]
+            .bookmark
+            .created_at;
+
+        // 再次保存同一首歌曲时更新书签
+        service
+            .create_bookmark("u1", "s1", 5000, None, &FolderScope::All)
+            .await
+            .unwrap();
+
+        let bookmarks = service
+            .get_bookmarks("u1", &FolderScope::All)
+            .await
+            .unwrap();
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].username, "alice");
+        assert_eq!(bookmarks[0].bookmark.position, 5000);
+        assert_eq!(bookmarks[0].bookmark.comment, None);
+        assert_eq!(bookmarks[0].bookmark.created_at, created_at);
+        assert_eq!(bookmarks[0].song.song.title, "Song 1");
+        assert_eq!(bookmarks[0].song.user_rating, Some(4));
+
+        // 其他用户的书签互不影响
+        assert!(service
+            .get_bookmarks("u2", &FolderScope::All)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bookmark_folder_scope() {
+        let service = create_service(setup_test_db().await);
+        let scope = FolderScope::Folders(vec![1]);
+
+        // 无权访问的歌曲不能创建书签
+        let result = service.create_bookmark("u1", "s2", 0, None, &scope).await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+
+        // 文件夹权限收回后，已有书签不再返回
+        service
+            .create_bookmark("u1", "s1", 0, None, &scope)
+            .await
+            .unwrap();
+        service
+            .create_bookmark("u1", "s2", 0, None, &FolderScope::All)
+            .await
+            .unwrap();
+        let bookmarks = service.get_bookmarks("u1", &scope).await.unwrap();
+        assert_eq!(bookmarks.len(), 1);
+        assert_eq!(bookmarks[0].bookmark.song_id, "s1");
+    }
+
+    #[tokio::test]
+    async fn test_delete_bookmark() {
+        let service = create_service(setup_test_db().await);
+
+        service
+            .create_bookmark("u1", "s1", 1000, None, &FolderScope::All)
+            .await
+            .unwrap();
+
+        // 不能删除其他用户的书签
+        let result = service.delete_bookmark("u2", "s1").await;
+        assert!(matches!(result, Err(AppError::NotFound(_))));
+
+        service.delete_bookmark("u1", "s1").await.unwrap();
+        assert!(service
+            .get_bookmarks("u1", &FolderScope::All)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_bookmark_invalid_position() {
+        let service = create_service(setup_test_db().await);
+
+        let result = service
+            .create_bookmark("u1", "s1", -1, None, &FolderScope::All)
+            .await;
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+    }
+}
@@ -11,7 +11,7 @@ use crate::error::AppError;
 use crate::models::dto::{
     AlbumDetailDto, ArtistDetailDto, ArtistDto, ComplexSongDto, SongDetailDto,
 };
-use crate::services::{ServiceContext, SongService};
+use crate::services::{FolderScope, ServiceContext, SongService};
 use crate::utils::sql_utils;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -105,6 +105,7 @@ impl BrowsingService {
     /// # 参数
     ///
     /// * `id` - 要检测的 ID
+    /// * `scope` - 用户可访问的音乐文件夹范围，范围外的 ID 视为不存在
     ///
     /// # 返回
     ///
@@ -113,13 +114,16 @@ impl BrowsingService {
     /// # 优化
     ///
     /// 使用单次查询同时检测三个表，避免多次数据库往返
-    async fn detect_id_type(&self, id: &str) -> Result<IdType, AppError> {
-        let result = sqlx::query_as::<_, (Option<i32>, Option<i32>, Option<i32>)>(
+    async fn detect_id_type(&self, id: &str, scope: &FolderScope) -> Result<IdType, AppError> {
+        let result = sqlx::query_as::<_, (Option<i32>, Option<i32>, Option<i32>)>(&format!(
             "SELECT
-                (SELECT 1 FROM songs WHERE id = ?) as is_song,
-                (SELECT 1 FROM albums WHERE id = ?) as is_album,
-                (SELECT 1 FROM artists WHERE id = ?) as is_artist"
-        )
+                (SELECT 1 FROM songs WHERE id = ? AND {}) as is_song,
+                (SELECT 1 FROM albums WHERE id = ? AND {}) as is_album,
+                (SELECT 1 FROM artists WHERE id = ? AND {}) as is_artist",
+            scope.sql_filter("songs.music_folder_id"),
+            scope.sql_filter("albums.music_folder_id"),
+            scope.artist_filter("artists.id")
+        ))
         .bind(id)
         .bind(id)
         .bind(id)
@@ -134,7 +138,6 @@ impl BrowsingService {
         }
     }
 
-
     /// 获取专辑列表 (统一查询接口)
     ///
     /// # 参数
@@ -142,6 +145,7 @@ impl BrowsingService {
     /// * `list_type` - 列表类型 (random/newest/highest等)
     /// * `size` - 返回数量
     /// * `offset` - 偏移量
+    /// * `scope` - 用户可访问的音乐文件夹范围
     ///
     /// # 优化
     ///
@@ -151,6 +155,7 @@ impl BrowsingService {
         list_type: AlbumListType,
         size: i32,
         offset: i32,
+        scope: &FolderScope,
     ) -> Result<Vec<AlbumDetailDto>, AppError> {
         // 构建基础查询
         let base_query = "SELECT a.id, a.name, ar.name as artist, a.artist_id, a.year, a.genre,
@@ -158,18 +163,19 @@ impl BrowsingService {
              FROM albums a
              JOIN artists ar ON a.artist_id = ar.id";
 
-        // 添加 WHERE 子句 (如果需要)
-        let where_clause = list_type.where_clause();
+        // 添加 WHERE 子句 (音乐文件夹过滤 + 类型相关条件)
+        let mut conditions = vec![scope.sql_filter("a.music_folder_id")];
+        if let Some(where_cond) = list_type.where_clause() {
+            conditions.push(where_cond.to_string());
+        }
         let order_by = list_type.order_by_clause();
 
-        let query = if let Some(where_cond) = where_clause {
-            format!(
-                "{} WHERE {} ORDER BY {} LIMIT ? OFFSET ?",
-                base_query, where_cond, order_by
-            )
-        } else {
-            format!("{} ORDER BY {} LIMIT ? OFFSET ?", base_query, order_by)
-        };
+        let query = format!(
+            "{} WHERE {} ORDER BY {} LIMIT ? OFFSET ?",
+            base_query,
+            conditions.join(" AND "),
+            order_by
+        );
 
         let albums = sqlx::query_as::<_, AlbumDetailDto>(&query)
             .bind(size)
@@ -188,6 +194,7 @@ impl BrowsingService {
     /// * `genre` - 可选的流派过滤
     /// * `from_year` - 可选的起始年份
     /// * `to_year` - 可选的结束年份
+    /// * `scope` - 用户可访问的音乐文件夹范围
     pub async fn get_random_songs(
         &self,
         user_id: &str,
@@ -195,8 +202,9 @@ impl BrowsingService {
         genre: Option<&str>,
         from_year: Option<i32>,
         to_year: Option<i32>,
+        scope: &FolderScope,
     ) -> Result<Vec<ComplexSongDto>, AppError> {
-        let mut conditions: Vec<String> = vec!["1=1".to_string()];
+        let mut conditions: Vec<String> = vec![scope.sql_filter("s.music_folder_id")];
 
         if let Some(g) = genre {
             conditions.push(format!("al.genre = '{}'", g));
@@ -221,7 +229,9 @@ impl BrowsingService {
             .fetch_all(&self.ctx.pool)
             .await?;
 
-        let complex_songs = SongService::new(self.ctx.clone()).enrich_songs(user_id, songs).await?;
+        let complex_songs = SongService::new(self.ctx.clone())
+            .enrich_songs(user_id, songs)
+            .await?;
 
         Ok(complex_songs)
     }
@@ -232,16 +242,19 @@ impl BrowsingService {
     ///
     /// * `artist_name` - 艺术家名称
     /// * `count` - 返回数量
+    /// * `scope` - 用户可访问的音乐文件夹范围
     pub async fn get_top_songs(
         &self,
         artist_name: &str,
         count: i32,
+        scope: &FolderScope,
     ) -> Result<Vec<SongDetailDto>, AppError> {
         let songs = sqlx::query_as::<_, SongDetailDto>(&format!(
-            "{} WHERE ar.name = ?
+            "{} WHERE ar.name = ? AND {}
              ORDER BY s.play_count DESC
              LIMIT ?",
-            sql_utils::detail_sql()
+            sql_utils::detail_sql(),
+            scope.sql_filter("s.music_folder_id")
         ))
         .bind(artist_name)
         .bind(count)
@@ -258,17 +271,20 @@ impl BrowsingService {
     /// * `genre` - 流派名称
     /// * `count` - 返回数量
     /// * `offset` - 偏移量
+    /// * `scope` - 用户可访问的音乐文件夹范围
     pub async fn get_songs_by_genre(
         &self,
         genre: &str,
         count: i32,
         offset: i32,
+        scope: &FolderScope,
     ) -> Result<Vec<SongDetailDto>, AppError> {
         let songs = sqlx::query_as::<_, SongDetailDto>(&format!(
-            "{} WHERE s.genre = ?
+            "{} WHERE s.genre = ? AND {}
              ORDER BY ar.name ASC, al.name ASC
              LIMIT ? OFFSET ?",
-            sql_utils::detail_sql()
+            sql_utils::detail_sql(),
+            scope.sql_filter("s.music_folder_id")
         ))
         .bind(genre)
         .bind(count)
@@ -279,15 +295,16 @@ impl BrowsingService {
         Ok(songs)
     }
 
-    /// 获取所有流派
-    pub async fn get_genres(&self) -> Result<Vec<GenreInfo>, AppError> {
-        let genres = sqlx::query_as::<_, GenreInfo>(
+    /// 获取所有流派 (仅统计范围内的歌曲)
+    pub async fn get_genres(&self, scope: &FolderScope) -> Result<Vec<GenreInfo>, AppError> {
+        let genres = sqlx::query_as::<_, GenreInfo>(&format!(
             "SELECT genre as name, COUNT(*) as song_count, COUNT(DISTINCT album_id) as album_count
              FROM songs
-             WHERE genre IS NOT NULL
+             WHERE genre IS NOT NULL AND {}
              GROUP BY genre
              ORDER BY genre ASC",
-        )
+            scope.sql_filter("music_folder_id")
+        ))
         .fetch_all(&self.ctx.pool)
         .await?;
 
@@ -296,14 +313,25 @@ impl BrowsingService {
 
     /// 获取所有艺术家 (索引格式)
     ///
+    /// # 参数
+    ///
+    /// * `scope` - 用户可访问的音乐文件夹范围，仅返回在范围内有专辑的艺术家
+    ///
     /// # 返回
     ///
     /// 按字母分组的艺术家列表
-    pub async fn get_artist_indexes(&self) -> Result<Vec<ArtistDto>, AppError> {
-        let artists =
-            sqlx::query_as::<_, ArtistDto>("SELECT id, name FROM artists ORDER BY name ASC")
-                .fetch_all(&self.ctx.pool)
-                .await?;
+    pub async fn get_artist_indexes(
+        &self,
+        scope: &FolderScope,
+    ) -> Result<Vec<ArtistDto>, AppError> {
+        let artists = sqlx::query_as::<_, ArtistDto>(&format!(
+            "SELECT id, name FROM artists ar
+             WHERE {}
+             ORDER BY name ASC",
+            scope.artist_filter("ar.id")
+        ))
+        .fetch_all(&self.ctx.pool)
+        .await?;
 
         Ok(artists)
     }
@@ -313,28 +341,32 @@ impl BrowsingService {
     /// # 参数
     ///
     /// * `artist_id` - 艺术家 ID
+    /// * `scope` - 用户可访问的音乐文件夹范围
     pub async fn get_artist(
         &self,
         artist_id: &str,
+        scope: &FolderScope,
     ) -> Result<(ArtistDetailDto, Vec<AlbumDetailDto>), AppError> {
         // 获取艺术家信息
-        let artist = sqlx::query_as::<_, ArtistDetailDto>(
-            "SELECT id, name, cover_art_path FROM artists WHERE id = ?",
-        )
+        let artist = sqlx::query_as::<_, ArtistDetailDto>(&format!(
+            "SELECT id, name, cover_art_path FROM artists WHERE id = ? AND {}",
+            scope.artist_filter("artists.id")
+        ))
         .bind(artist_id)
         .fetch_optional(&self.ctx.pool)
         .await?
         .ok_or_else(|| AppError::not_found("Artist"))?;
 
         // 获取艺术家的专辑
-        let albums = sqlx::query_as::<_, AlbumDetailDto>(
+        let albums = sqlx::query_as::<_, AlbumDetailDto>(&format!(
             "SELECT a.id, a.name, ar.name as artist, a.artist_id, a.year, a.genre,
                     a.cover_art_path, a.song_count, a.duration, a.play_count
              FROM albums a
              JOIN artists ar ON a.artist_id = ar.id
-             WHERE a.artist_id = ?
+             WHERE a.artist_id = ? AND {}
              ORDER BY a.year DESC, a.name ASC",
-        )
+            scope.sql_filter("a.music_folder_id")
+        ))
         .bind(artist_id)
         .fetch_all(&self.ctx.pool)
         .await?;
@@ -347,18 +379,21 @@ impl BrowsingService {
     /// # 参数
     ///
     /// * `album_id` - 专辑 ID
+    /// * `scope` - 用户可访问的音乐文件夹范围
     pub async fn get_album(
         &self,
         album_id: &str,
+        scope: &FolderScope,
     ) -> Result<(AlbumDetailDto, Vec<SongDetailDto>), AppError> {
         // 获取专辑信息
-        let album = sqlx::query_as::<_, AlbumDetailDto>(
+        let album = sqlx::query_as::<_, AlbumDetailDto>(&format!(
             "SELECT a.id, a.name, ar.name as artist, a.artist_id, a.year, a.genre,
                     a.cover_art_path, a.song_count, a.duration, a.play_count
              FROM albums a
              JOIN artists ar ON a.artist_id = ar.id
-             WHERE a.id = ?",
-        )
+             WHERE a.id = ? AND {}",
+            scope.sql_filter("a.music_folder_id")
+        ))
         .bind(album_id)
         .fetch_optional(&self.ctx.pool)
         .await?
@@ -367,9 +402,10 @@ impl BrowsingService {
         // 获取专辑的歌曲
         let songs = sqlx::query_as::<_, SongDetailDto>(&format!(
             "{}
-             WHERE s.album_id = ?
+             WHERE s.album_id = ? AND {}
              ORDER BY s.disc_number ASC, s.track_number ASC",
-            sql_utils::detail_sql()
+            sql_utils::detail_sql(),
+            scope.sql_filter("s.music_folder_id")
         ))
         .bind(album_id)
         .fetch_all(&self.ctx.pool)
@@ -383,10 +419,18 @@ impl BrowsingService {
     /// # 参数
     ///
     /// * `song_id` - 歌曲 ID
-    pub async fn get_song(&self, user_id: &str, song_id: &str) -> Result<ComplexSongDto, AppError> {
+    /// * `scope` - 用户可访问的音乐文件夹范围
+    pub async fn get_song(
+        &self,
+        user_id: &str,
+        song_id: &str,
+        scope: &FolderScope,
+    ) -> Result<ComplexSongDto, AppError> {
         // 使用 SongService 获取完整歌曲信息
         let song_service = SongService::new(self.ctx.clone());
-        let complex_song = song_service.get_complex_song(user_id, song_id).await?;
+        let complex_song = song_service
+            .get_complex_song(user_id, song_id, scope)
+            .await?;
 
         Ok(complex_song)
     }
@@ -397,6 +441,7 @@ impl BrowsingService {
     ///
     /// * `id` - 歌曲/专辑/艺术家 ID
     /// * `count` - 返回数量
+    /// * `scope` - 用户可访问的音乐文件夹范围
     ///
     /// # 功能
     ///
@@ -412,20 +457,21 @@ impl BrowsingService {
         &self,
         id: &str,
         count: i32,
+        scope: &FolderScope,
     ) -> Result<Vec<SongDetailDto>, AppError> {
         // 检测 ID 类型
-        let id_type = self.detect_id_type(id).await?;
+        let id_type = self.detect_id_type(id, scope).await?;
 
         // 根据类型调用不同的逻辑
         match id_type {
-            IdType::Song(song_id) => {
-                self.get_similar_songs_by_song(&song_id, count).await
-            }
+            IdType::Song(song_id) => self.get_similar_songs_by_song(&song_id, count, scope).await,
             IdType::Album(album_id) => {
-                self.get_similar_songs_by_album(&album_id, count).await
+                self.get_similar_songs_by_album(&album_id, count, scope)
+                    .await
             }
             IdType::Artist(artist_id) => {
-                self.get_similar_songs_by_artist(&artist_id, count).await
+                self.get_similar_songs_by_artist(&artist_id, count, scope)
+                    .await
             }
         }
     }
@@ -450,6 +496,7 @@ impl BrowsingService {
         &self,
         song_id: &str,
         count: i32,
+        scope: &FolderScope,
     ) -> Result<Vec<SongDetailDto>, AppError> {
         // 首先获取目标歌曲的信息
         let target_song = sqlx::query_as::<_, SongDetailDto>(&format!(
@@ -464,7 +511,7 @@ impl BrowsingService {
         // 构建相似度查询
         let query = format!(
             "{}
-             WHERE s.id != ?
+             WHERE s.id != ? AND {}
              ORDER BY (
                 -- 同艺术家得分
                 CASE WHEN s.artist_id = ? THEN 5 ELSE 0 END +
@@ -476,15 +523,16 @@ impl BrowsingService {
                 CASE WHEN s.album_id = ? THEN 3 ELSE 0 END
              ) DESC, RANDOM()
              LIMIT ?",
-            sql_utils::detail_sql()
+            sql_utils::detail_sql(),
+            scope.sql_filter("s.music_folder_id")
         );
 
         let songs = sqlx::query_as::<_, SongDetailDto>(&query)
             .bind(song_id) // 排除目标歌曲
             .bind(&target_song.artist_id) // 同艺术家判断
             .bind(&target_song.genre) // 同流派判断
-            .bind(&target_song.year) // 年代判断 (第一个)
-            .bind(&target_song.year) // 年代判断 (第二个)
+            .bind(target_song.year) // 年代判断 (第一个)
+            .bind(target_song.year) // 年代判断 (第二个)
             .bind(&target_song.album_id) // 同专辑判断
             .bind(count)
             .fetch_all(&self.ctx.pool)
@@ -509,6 +557,7 @@ impl BrowsingService {
         &self,
         album_id: &str,
         count: i32,
+        scope: &FolderScope,
     ) -> Result<Vec<SongDetailDto>, AppError> {
         // 获取专辑信息
         let album = sqlx::query_as::<_, AlbumDetailDto>(
@@ -516,7 +565,7 @@ impl BrowsingService {
                     a.cover_art_path, a.song_count, a.duration, a.play_count
              FROM albums a
              JOIN artists ar ON a.artist_id = ar.id
-             WHERE a.id = ?"
+             WHERE a.id = ?",
         )
         .bind(album_id)
         .fetch_optional(&self.ctx.pool)
@@ -533,7 +582,7 @@ impl BrowsingService {
             "SELECT * FROM (
                 -- 该专辑的歌曲
                 {}
-                WHERE s.album_id = ?
+                WHERE s.album_id = ? AND {filter}
                 ORDER BY s.track_number ASC
                 LIMIT ?
             )
@@ -541,7 +590,7 @@ impl BrowsingService {
             SELECT * FROM (
                 -- 同艺术家其他专辑的歌曲
                 {}
-                WHERE s.artist_id = ? AND s.album_id != ?
+                WHERE s.artist_id = ? AND s.album_id != ? AND {filter}
                 ORDER BY s.play_count DESC, RANDOM()
                 LIMIT ?
             )
@@ -549,7 +598,7 @@ impl BrowsingService {
             SELECT * FROM (
                 -- 同流派/年代的歌曲
                 {}
-                WHERE s.album_id != ?
+                WHERE s.album_id != ? AND {filter}
                 AND (al.genre = ? OR (al.year IS NOT NULL AND ? IS NOT NULL AND abs(al.year - ?) <= 2))
                 ORDER BY s.play_count DESC, RANDOM()
                 LIMIT ?
@@ -557,7 +606,8 @@ impl BrowsingService {
             LIMIT ?",
             sql_utils::detail_sql(),
             sql_utils::detail_sql(),
-            sql_utils::detail_sql()
+            sql_utils::detail_sql(),
+            filter = scope.sql_filter("s.music_folder_id")
         );
 
         let songs = sqlx::query_as::<_, SongDetailDto>(&query)
@@ -568,8 +618,8 @@ impl BrowsingService {
             .bind(artist_songs_count)
             .bind(album_id)
             .bind(&album.genre)
-            .bind(&album.year)
-            .bind(&album.year)
+            .bind(album.year)
+            .bind(album.year)
             .bind(genre_songs_count)
             .bind(count)
             .fetch_all(&self.ctx.pool)
@@ -593,21 +643,22 @@ impl BrowsingService {
         &self,
         artist_id: &str,
         count: i32,
+        scope: &FolderScope,
     ) -> Result<Vec<SongDetailDto>, AppError> {
         // 验证艺术家是否存在
-        let _artist_exists = sqlx::query_scalar::<_, i32>(
-            "SELECT 1 FROM artists WHERE id = ? LIMIT 1"
-        )
-        .bind(artist_id)
-        .fetch_optional(&self.ctx.pool)
-        .await?
-        .ok_or_else(|| AppError::not_found("Artist"))?;
+        let _artist_exists =
+            sqlx::query_scalar::<_, i32>("SELECT 1 FROM artists WHERE id = ? LIMIT 1")
+                .bind(artist_id)
+                .fetch_optional(&self.ctx.pool)
+                .await?
+                .ok_or_else(|| AppError::not_found("Artist"))?;
 
         // 获取该艺术家的主要流派
-        let main_genre = sqlx::query_scalar::<_, Option<String>>(
-            "SELECT genre FROM albums WHERE artist_id = ? AND genre IS NOT NULL
-             GROUP BY genre ORDER BY COUNT(*) DESC LIMIT 1"
-        )
+        let main_genre = sqlx::query_scalar::<_, Option<String>>(&format!(
+            "SELECT genre FROM albums WHERE artist_id = ? AND genre IS NOT NULL AND {}
+             GROUP BY genre ORDER BY COUNT(*) DESC LIMIT 1",
+            scope.sql_filter("music_folder_id")
+        ))
         .bind(artist_id)
         .fetch_optional(&self.ctx.pool)
         .await?
@@ -622,7 +673,7 @@ impl BrowsingService {
             "SELECT * FROM (
                 -- 该艺术家的热门歌曲
                 {}
-                WHERE s.artist_id = ?
+                WHERE s.artist_id = ? AND {filter}
                 ORDER BY s.play_count DESC, RANDOM()
                 LIMIT ?
             )
@@ -630,13 +681,14 @@ impl BrowsingService {
             SELECT * FROM (
                 -- 同流派其他艺术家的歌曲
                 {}
-                WHERE s.artist_id != ? AND al.genre = ?
+                WHERE s.artist_id != ? AND al.genre = ? AND {filter}
                 ORDER BY s.play_count DESC, RANDOM()
                 LIMIT ?
             )
             LIMIT ?",
             sql_utils::detail_sql(),
-            sql_utils::detail_sql()
+            sql_utils::detail_sql(),
+            filter = scope.sql_filter("s.music_folder_id")
         );
 
         let songs = sqlx::query_as::<_, SongDetailDto>(&query)
@@ -684,6 +736,7 @@ mod tests {
                 song_count INTEGER DEFAULT 0,
                 duration INTEGER DEFAULT 0,
                 play_count INTEGER DEFAULT 0,
+                music_folder_id INTEGER,
                 created_at TEXT DEFAULT CURRENT_TIMESTAMP,
                 updated_at TEXT DEFAULT CURRENT_TIMESTAMP
             )",
@@ -707,7 +760,8 @@ mod tests {
                 file_path TEXT,
                 file_size INTEGER,
                 content_type TEXT,
-                play_count INTEGER DEFAULT 0
+                play_count INTEGER DEFAULT 0,
+                music_folder_id INTEGER
             )",
         )
         .execute(&pool)
@@ -748,32 +802,32 @@ mod tests {
             .unwrap();
 
         sqlx::query(
-            "INSERT INTO albums (id, name, artist_id, year, genre, song_count, duration, play_count, created_at)
-             VALUES ('album1', 'Test Album', 'artist1', 2020, 'Rock', 2, 300, 100, '2020-01-01 00:00:00')",
+            "INSERT INTO albums (id, name, artist_id, year, genre, song_count, duration, play_count, music_folder_id, created_at)
+             VALUES ('album1', 'Test Album', 'artist1', 2020, 'Rock', 2, 300, 100, 1, '2020-01-01 00:00:00')",
         )
         .execute(&pool)
         .await
         .unwrap();
 
         sqlx::query(
-            "INSERT INTO albums (id, name, artist_id, year, genre, song_count, duration, play_count, created_at)
-             VALUES ('album2', 'Another Album', 'artist1', 2021, 'Pop', 1, 200, 50, '2021-01-01 00:00:00')",
+            "INSERT INTO albums (id, name, artist_id, year, genre, song_count, duration, play_count, music_folder_id, created_at)
+             VALUES ('album2', 'Another Album', 'artist1', 2021, 'Pop', 1, 200, 50, 2, '2021-01-01 00:00:00')",
         )
         .execute(&pool)
         .await
         .unwrap();
 
         sqlx::query(
-            "INSERT INTO songs (id, title, artist_id, album_id, duration, track_number, genre, play_count)
-             VALUES ('song1', 'Song 1', 'artist1', 'album1', 180, 1, 'Rock', 50)",
+            "INSERT INTO songs (id, title, artist_id, album_id, duration, track_number, genre, play_count, music_folder_id)
+             VALUES ('song1', 'Song 1', 'artist1', 'album1', 180, 1, 'Rock', 50, 1)",
         )
         .execute(&pool)
         .await
         .unwrap();
 
         sqlx::query(
-            "INSERT INTO songs (id, title, artist_id, album_id, duration, track_number, genre, play_count)
-             VALUES ('song2', 'Song 2', 'artist1', 'album1', 120, 2, 'Rock', 30)",
+            "INSERT INTO songs (id, title, artist_id, album_id, duration, track_number, genre, play_count, music_folder_id)
+             VALUES ('song2', 'Song 2', 'artist1', 'album1', 120, 2, 'Rock', 30, 1)",
         )
         .execute(&pool)
         .await
@@ -793,7 +847,7 @@ mod tests {
         let service = create_service(pool);
 
         let albums = service
-            .get_album_list(AlbumListType::Newest, 10, 0)
+            .get_album_list(AlbumListType::Newest, 10, 0, &FolderScope::All)
             .await
             .unwrap();
 
@@ -808,7 +862,7 @@ mod tests {
         let service = create_service(pool);
 
         let albums = service
-            .get_album_list(AlbumListType::Highest, 10, 0)
+            .get_album_list(AlbumListType::Highest, 10, 0, &FolderScope::All)
             .await
             .unwrap();
 
@@ -824,24 +878,108 @@ mod tests {
         let service = create_service(pool);
 
         let albums = service
-            .get_album_list(AlbumListType::Newest, 1, 0)
+            .get_album_list(AlbumListType::Newest, 1, 0, &FolderScope::All)
             .await
             .unwrap();
         assert_eq!(albums.len(), 1);
 
         let albums = service
-            .get_album_list(AlbumListType::Newest, 1, 1)
+            .get_album_list(AlbumListType::Newest, 1, 1, &FolderScope::All)
             .await
             .unwrap();
         assert_eq!(albums.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_music_folder_filter() {
+        let pool = setup_test_db().await;
+        let service = create_service(pool);
+
+        let albums = service
+            .get_album_list(AlbumListType::Newest, 10, 0, &FolderScope::Folders(vec![2]))
+            .await
+            .unwrap();
+        assert_eq!(albums.len(), 1);
+        assert_eq!(albums[0].name, "Another Album");
+
+        assert_eq!(
+            service
+                .get_artist_indexes(&FolderScope::All)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            service
+                .get_artist_indexes(&FolderScope::Folders(vec![1]))
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert!(service
+            .get_artist_indexes(&FolderScope::Folders(vec![3]))
+            .await
+            .unwrap()
+            .is_empty());
+
+        let songs = service
+            .get_songs_by_genre("Rock", 10, 0, &FolderScope::Folders(vec![1]))
+            .await
+            .unwrap();
+        assert_eq!(songs.len(), 2);
+        let songs = service
+            .get_songs_by_genre("Rock", 10, 0, &FolderScope::Folders(vec![2]))
+            .await
+            .unwrap();
+        assert!(songs.is_empty());
+
+        let songs = service
+            .get_random_songs("1", 10, None, None, None, &FolderScope::Folders(vec![2]))
+            .await
+            .unwrap();
+        assert!(songs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_folder_scope_by_id() {
+        let pool = setup_test_db().await;
+        let service = create_service(pool);
+        let folder1 = FolderScope::Folders(vec![1]);
+        let folder2 = FolderScope::Folders(vec![2]);
+
+        // 范围外的内容按不存在处理
+        assert!(service.get_album("album1", &folder1).await.is_ok());
+        assert!(service.get_album("album1", &folder2).await.is_err());
+        assert!(service.get_song("1", "song1", &folder2).await.is_err());
+        assert!(service.detect_id_type("song1", &folder2).await.is_err());
+
+        // 艺术家只返回范围内的专辑
+        let (_, albums) = service.get_artist("artist1", &folder2).await.unwrap();
+        assert_eq!(albums.len(), 1);
+        assert_eq!(albums[0].name, "Another Album");
+        assert!(service
+            .get_artist("artist1", &FolderScope::Folders(vec![]))
+            .await
+            .is_err());
+
+        let songs = service
+            .get_top_songs("Test Artist", 10, &folder2)
+            .await
+            .unwrap();
+        assert!(songs.is_empty());
+    }
+
     #[tokio::test]
     async fn test_get_top_songs() {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let songs = service.get_top_songs("Test Artist", 10).await.unwrap();
+        let songs = service
+            .get_top_songs("Test Artist", 10, &FolderScope::All)
+            .await
+            .unwrap();
 
         assert_eq!(songs.len(), 2);
         // 播放次数高的在前面
@@ -854,7 +992,10 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let (artist, albums) = service.get_artist("artist1").await.unwrap();
+        let (artist, albums) = service
+            .get_artist("artist1", &FolderScope::All)
+            .await
+            .unwrap();
 
         assert_eq!(artist.name, "Test Artist");
         assert_eq!(albums.len(), 2);
@@ -865,7 +1006,10 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let (album, songs) = service.get_album("album1").await.unwrap();
+        let (album, songs) = service
+            .get_album("album1", &FolderScope::All)
+            .await
+            .unwrap();
 
         assert_eq!(album.name, "Test Album");
         assert_eq!(songs.len(), 2);
@@ -876,7 +1020,10 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let song = service.get_song("1", "song1").await.unwrap();
+        let song = service
+            .get_song("1", "song1", &FolderScope::All)
+            .await
+            .unwrap();
 
         assert_eq!(song.song.title, "Song 1");
         assert_eq!(song.song.artist, "Test Artist");
@@ -887,11 +1034,11 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let genres = service.get_genres().await.unwrap();
+        let genres = service.get_genres(&FolderScope::All).await.unwrap();
 
         println!("genres: {:?}", genres);
 
-        assert!(genres.len() >= 1);
+        assert!(!genres.is_empty());
         // 应该有 Rock 流派
         // assert!(genres.iter().any(|g| g.name == "Rock"));
     }
@@ -901,7 +1048,10 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let id_type = service.detect_id_type("song1").await.unwrap();
+        let id_type = service
+            .detect_id_type("song1", &FolderScope::All)
+            .await
+            .unwrap();
         assert!(matches!(id_type, IdType::Song(_)));
     }
 
@@ -910,7 +1060,10 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let id_type = service.detect_id_type("album1").await.unwrap();
+        let id_type = service
+            .detect_id_type("album1", &FolderScope::All)
+            .await
+            .unwrap();
         assert!(matches!(id_type, IdType::Album(_)));
     }
 
@@ -919,7 +1072,10 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let id_type = service.detect_id_type("artist1").await.unwrap();
+        let id_type = service
+            .detect_id_type("artist1", &FolderScope::All)
+            .await
+            .unwrap();
         assert!(matches!(id_type, IdType::Artist(_)));
     }
 
@@ -928,7 +1084,9 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let result = service.detect_id_type("nonexistent").await;
+        let result = service
+            .detect_id_type("nonexistent", &FolderScope::All)
+            .await;
         assert!(result.is_err());
     }
 
@@ -937,9 +1095,12 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let songs = service.get_similar_songs("song1", 10).await.unwrap();
+        let songs = service
+            .get_similar_songs("song1", 10, &FolderScope::All)
+            .await
+            .unwrap();
         // 应该至少有一些相似歌曲 (同专辑的 song2)
-        assert!(songs.len() > 0);
+        assert!(!songs.is_empty());
     }
 
     #[tokio::test]
@@ -947,9 +1108,12 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let songs = service.get_similar_songs("album1", 10).await.unwrap();
+        let songs = service
+            .get_similar_songs("album1", 10, &FolderScope::All)
+            .await
+            .unwrap();
         // 应该包含该专辑的歌曲
-        assert!(songs.len() > 0);
+        assert!(!songs.is_empty());
         // 应该包含专辑的歌曲
         assert!(songs.iter().any(|s| s.album_id == "album1"));
     }
@@ -959,9 +1123,12 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let songs = service.get_similar_songs("artist1", 10).await.unwrap();
+        let songs = service
+            .get_similar_songs("artist1", 10, &FolderScope::All)
+            .await
+            .unwrap();
         // 应该包含该艺术家的歌曲
-        assert!(songs.len() > 0);
+        assert!(!songs.is_empty());
         // 所有歌曲应该来自该艺术家或相似流派
         assert!(songs.iter().any(|s| s.artist_id == "artist1"));
     }
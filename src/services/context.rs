@@ -22,11 +22,19 @@ impl ServiceContext {
     /// # 示例
     ///
     /// ```rust
+    /// # use futures::FutureExt;
+    /// # use musicflow_server::{services::ServiceContext, AppError};
+    /// # struct Service {
+    /// #     ctx: ServiceContext,
+    /// # }
+    /// # async fn run(service: Service) -> Result<(), AppError> {
     /// service.ctx.transaction(|tx| async move {
     ///     sqlx::query("INSERT INTO ...").execute(&mut **tx).await?;
     ///     sqlx::query("UPDATE ...").execute(&mut **tx).await?;
     ///     Ok(())
     /// }.boxed()).await?;
+    /// # Ok(())
+    /// # }
     /// ```
     ///
     /// # 特性
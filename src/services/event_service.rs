@@ -0,0 +1,283 @@
+//! 服务器事件服务
+//!
+//! 通过广播通道向 WebSocket 客户端推送实时事件:
+//! - 新的聊天消息
+//! - 正在播放变化
+//! - 扫描进度
+//! - 扫描完成后的媒体库变化
+//!
+//! 每个事件带有递增的 ID，最近的事件保留在内存中，
+//! 客户端重连时可以从上次收到的事件 ID 之后补发。
+
+use crate::error::AppError;
+use crate::services::{FolderScope, ServiceContext};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// 保留用于补发的事件数，同时也是广播通道的容量
+const HISTORY_CAPACITY: usize = 1000;
+
+/// 服务器事件
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ServerEvent {
+    /// 新的聊天消息
+    #[serde(rename_all = "camelCase")]
+    ChatMessage {
+        username: String,
+        message: String,
+        /// 发送时间 (与 getChatMessages 的 time 相同)
+        time: i64,
+    },
+    /// 用户开始播放歌曲
+    #[serde(rename_all = "camelCase")]
+    NowPlaying {
+        username: String,
+        song_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        player_name: Option<String>,
+    },
+    /// 扫描进度
+    #[serde(rename_all = "camelCase")]
+    ScanProgress {
+        scanning: bool,
+        /// 已处理的文件数
+        current: usize,
+        /// 需要处理的文件数
+        count: usize,
+    },
+    /// 扫描完成，媒体库内容有变化
+    #[serde(rename_all = "camelCase")]
+    LibraryChanged {
+        songs: usize,
+        videos: usize,
+        deleted: usize,
+    },
+    /// 无法补发错过的事件 (已超出保留范围或服务器已重启)，客户端需要重新获取状态
+    Resync,
+}
+
+/// 带 ID 的事件
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventEnvelope {
+    pub id: u64,
+    #[serde(flatten)]
+    pub event: ServerEvent,
+}
+
+/// 订阅结果
+pub struct Subscription {
+    /// 需要先发送给客户端的事件 (补发或 `Resync`)
+    pub replay: Vec<Arc<EventEnvelope>>,
+    pub receiver: broadcast::Receiver<Arc<EventEnvelope>>,
+}
+
+/// 事件历史，发布和订阅在同一把锁下进行，保证补发和实时事件不重复、不遗漏
+struct History {
+    next_id: u64,
+    events: VecDeque<Arc<EventEnvelope>>,
+}
+
+/// 服务器事件服务
+pub struct EventService {
+    ctx: Arc<ServiceContext>,
+    sender: broadcast::Sender<Arc<EventEnvelope>>,
+    history: Mutex<History>,
+}
+
+impl EventService {
+    /// 创建新的 EventService
+    pub fn new(ctx: Arc<ServiceContext>) -> Self {
+        let (sender, _) = broadcast::channel(HISTORY_CAPACITY);
+        Self {
+            ctx,
+            sender,
+            history: Mutex::new(History {
+                next_id: 1,
+                events: VecDeque::with_capacity(HISTORY_CAPACITY),
+            }),
+        }
+    }
+
+    /// 发布事件，没有订阅者时只记录到历史中
+    pub fn publish(&self, event: ServerEvent) {
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let envelope = Arc::new(EventEnvelope {
+            id: history.next_id,
+            event,
+        });
+        history.next_id += 1;
+        if history.events.len() == HISTORY_CAPACITY {
+            history.events.pop_front();
+        }
+        history.events.push_back(envelope.clone());
+
+        let _ = self.sender.send(envelope);
+    }
+
+    /// 订阅事件
+    ///
+    /// `last_event_id` 为客户端上次收到的事件 ID，补发其后的事件；
+    /// 错过的事件已不在历史中时补发一个 `Resync` 事件。
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> Subscription {
+        let history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let receiver = self.sender.subscribe();
+
+        let replay = match last_event_id {
+            None => vec![],
+            Some(last_id) => {
+                let latest_id = history.next_id - 1;
+                let oldest_id = history.events.front().map_or(history.next_id, |e| e.id);
+                if last_id > latest_id || last_id + 1 < oldest_id {
+                    vec![resync_envelope(latest_id)]
+                } else {
+                    history
+                        .events
+                        .iter()
+                        .filter(|e| e.id > last_id)
+                        .cloned()
+                        .collect()
+                }
+            }
+        };
+
+        Subscription { replay, receiver }
+    }
+
+    /// 订阅者落后太多 (广播通道溢出) 时发送的 `Resync` 事件
+    pub fn resync(&self) -> Arc<EventEnvelope> {
+        let history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        resync_envelope(history.next_id - 1)
+    }
+
+    /// 事件对用户是否可见: 正在播放的歌曲需要在用户可访问的音乐文件夹中
+    pub async fn is_visible(
+        &self,
+        event: &ServerEvent,
+        scope: &FolderScope,
+    ) -> Result<bool, AppError> {
+        match (event, scope) {
+            (ServerEvent::NowPlaying { song_id, .. }, FolderScope::Folders(_)) => {
+                let exists: Option<i64> = sqlx::query_scalar(&format!(
+                    "SELECT 1 FROM songs WHERE id = ? AND {}",
+                    scope.sql_filter("music_folder_id")
+                ))
+                .bind(song_id)
+                .fetch_optional(&self.ctx.pool)
+                .await?;
+                Ok(exists.is_some())
+            }
+            _ => Ok(true),
+        }
+    }
+}
+
+/// `Resync` 事件使用最新的事件 ID，客户端之后以此 ID 重连
+fn resync_envelope(latest_id: u64) -> Arc<EventEnvelope> {
+    Arc::new(EventEnvelope {
+        id: latest_id,
+        event: ServerEvent::Resync,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::SqlitePool;
+
+    async fn setup_service() -> EventService {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query("CREATE TABLE songs (id TEXT PRIMARY KEY, music_folder_id INTEGER)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO songs (id, music_folder_id) VALUES ('s1', 1), ('s2', 2)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        EventService::new(Arc::new(ServiceContext::new(pool)))
+    }
+
+    fn chat(message: &str) -> ServerEvent {
+        ServerEvent::ChatMessage {
+            username: "admin".to_string(),
+            message: message.to_string(),
+            time: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_publish_and_replay() {
+        let service = setup_service().await;
+
+        let mut live = service.subscribe(None);
+        assert!(live.replay.is_empty());
+
+        service.publish(chat("one"));
+        service.publish(chat("two"));
+        service.publish(chat("three"));
+
+        let received = live.receiver.recv().await.unwrap();
+        assert_eq!(received.id, 1);
+        assert_eq!(received.event, chat("one"));
+
+        // 从事件 1 之后补发
+        let reconnect = service.subscribe(Some(1));
+        let ids: Vec<u64> = reconnect.replay.iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+
+        // 已是最新
+        assert!(service.subscribe(Some(3)).replay.is_empty());
+
+        // 服务器重启后 (ID 大于最新 ID) 需要重新同步
+        let replay = service.subscribe(Some(42)).replay;
+        assert_eq!(replay.len(), 1);
+        assert_eq!(replay[0].event, ServerEvent::Resync);
+        assert_eq!(replay[0].id, 3);
+    }
+
+    #[tokio::test]
+    async fn test_replay_beyond_history() {
+        let service = setup_service().await;
+        for i in 0..HISTORY_CAPACITY + 5 {
+            service.publish(chat(&i.to_string()));
+        }
+
+        // 事件 1..=5 已被丢弃
+        let replay = service.subscribe(Some(3)).replay;
+        assert_eq!(replay.len(), 1);
+        assert_eq!(replay[0].event, ServerEvent::Resync);
+
+        let replay = service.subscribe(Some(5)).replay;
+        assert_eq!(replay.len(), HISTORY_CAPACITY);
+        assert_eq!(replay[0].id, 6);
+    }
+
+    #[tokio::test]
+    async fn test_event_json_and_visibility() {
+        let service = setup_service().await;
+
+        let envelope = EventEnvelope {
+            id: 7,
+            event: ServerEvent::NowPlaying {
+                username: "admin".to_string(),
+                song_id: "s2".to_string(),
+                player_name: None,
+            },
+        };
+        assert_eq!(
+            serde_json::to_string(&envelope).unwrap(),
+            r#"{"id":7,"type":"nowPlaying","username":"admin","songId":"s2"}"#
+        );
+
+        let scope = FolderScope::Folders(vec![1]);
+        assert!(!service.is_visible(&envelope.event, &scope).await.unwrap());
+        assert!(service
+            .is_visible(&envelope.event, &FolderScope::All)
+            .await
+            .unwrap());
+        assert!(service.is_visible(&chat("hi"), &scope).await.unwrap());
+    }
+}
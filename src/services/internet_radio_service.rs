@@ -0,0 +1,345 @@
+//! 网络电台服务
+//!
+//! 负责网络电台相关的业务逻辑:
+//! - 查询电台列表
+//! - 管理员创建、修改、删除电台
+//! - 以 M3U/PLS 播放列表导入导出电台
+
+use crate::error::AppError;
+use crate::models::entities::InternetRadioStation;
+use crate::services::ServiceContext;
+use crate::utils::id_builder;
+use crate::utils::radio_playlist::{self, RadioEntry, RadioPlaylistFormat};
+use chrono::Utc;
+use futures::FutureExt;
+use std::sync::Arc;
+
+/// 网络电台服务
+pub struct InternetRadioService {
+    ctx: Arc<ServiceContext>,
+}
+
+impl InternetRadioService {
+    /// 创建新的 InternetRadioService
+    pub fn new(ctx: Arc<ServiceContext>) -> Self {
+        Self { ctx }
+    }
+
+    /// 获取所有电台 (按名称排序)
+    pub async fn get_stations(&self) -> Result<Vec<InternetRadioStation>, AppError> {
+        let stations = sqlx::query_as::<_, InternetRadioStation>(
+            "SELECT * FROM internet_radio_stations ORDER BY name COLLATE NOCASE",
+        )
+        .fetch_all(&self.ctx.pool)
+        .await?;
+
+        Ok(stations)
+    }
+
+    /// 创建电台
+    pub async fn create_station(
+        &self,
+        name: &str,
+        stream_url: &str,
+        homepage_url: Option<&str>,
+    ) -> Result<InternetRadioStation, AppError> {
+        let station = new_station(name, stream_url, homepage_url)?;
+
+        sqlx::query(
+            "INSERT INTO internet_radio_stations (id, name, stream_url, homepage_url, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&station.id)
+        .bind(&station.name)
+        .bind(&station.stream_url)
+        .bind(&station.homepage_url)
+        .bind(station.created_at)
+        .bind(station.updated_at)
+        .execute(&self.ctx.pool)
+        .await?;
+
+        Ok(station)
+    }
+
+    /// 修改电台
+    pub async fn update_station(
+        &self,
+        id: &str,
+        name: &str,
+        stream_url: &str,
+        homepage_url: Option<&str>,
+    ) -> Result<(), AppError> {
+        let name = validate_name(name)?;
+        let stream_url = validate_url(stream_url, "streamUrl")?;
+        let homepage_url = validate_homepage_url(homepage_url)?;
+
+        let result = sqlx::query(
+            "UPDATE internet_radio_stations
+             SET name = ?, stream_url = ?, homepage_url = ?, updated_at = ?
+             WHERE id = ?",
+        )
+        .bind(name)
+        .bind(stream_url)
+        .bind(homepage_url)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(&self.ctx.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Internet radio station"));
+        }
+
+        Ok(())
+    }
+
+    /// 删除电台
+    pub async fn delete_station(&self, id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM internet_radio_stations WHERE id = ?")
+            .bind(id)
+            .execute(&self.ctx.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Internet radio station"));
+        }
+
+        Ok(())
+    }
+
+    /// 从 M3U/PLS 播放列表导入电台
+    ///
+    /// 所有条目校验通过后在同一事务中写入，已存在相同流地址的条目跳过
+    pub async fn import_stations(
+        &self,
+        content: &str,
+    ) -> Result<Vec<InternetRadioStation>, AppError> {
+        let entries = radio_playlist::parse(content);
+        if entries.is_empty() {
+            return Err(AppError::validation_error(
+                "No internet radio stations found in playlist",
+            ));
+        }
+
+        let mut existing: Vec<String> =
+            sqlx::query_scalar("SELECT stream_url FROM internet_radio_stations")
+                .fetch_all(&self.ctx.pool)
+                .await?;
+
+        let mut stations = Vec::new();
+        for entry in entries {
+            let station = new_station(&entry.name, &entry.stream_url, None)?;
+            if existing.contains(&station.stream_url) {
+                continue;
+            }
+            existing.push(station.stream_url.clone());
+            stations.push(station);
+        }
+
+        let inserted = stations.clone();
+        self.ctx
+            .transaction(|tx| {
+                async move {
+                    for station in &inserted {
+                        sqlx::query(
+                            "INSERT INTO internet_radio_stations (id, name, stream_url, homepage_url, created_at, updated_at)
+                             VALUES (?, ?, ?, ?, ?, ?)",
+                        )
+                        .bind(&station.id)
+                        .bind(&station.name)
+                        .bind(&station.stream_url)
+                        .bind(&station.homepage_url)
+                        .bind(station.created_at)
+                        .bind(station.updated_at)
+                        .execute(&mut **tx)
+                        .await?;
+                    }
+
+                    Ok(())
+                }
+                .boxed()
+            })
+            .await?;
+
+        Ok(stations)
+    }
+
+    /// 导出所有电台为 M3U/PLS 播放列表
+    pub async fn export_stations(&self, format: RadioPlaylistFormat) -> Result<String, AppError> {
+        let entries: Vec<RadioEntry> = self
+            .get_stations()
+            .await?
+            .into_iter()
+            .map(|station| RadioEntry {
+                name: station.name,
+                stream_url: station.stream_url,
+            })
+            .collect();
+
+        Ok(radio_playlist::write(format, &entries))
+    }
+}
+
+/// 校验参数并生成新电台
+fn new_station(
+    name: &str,
+    stream_url: &str,
+    homepage_url: Option<&str>,
+) -> Result<InternetRadioStation, AppError> {
+    let now = Utc::now();
+    Ok(InternetRadioStation {
+        id: id_builder::generate_id(),
+        name: validate_name(name)?.to_string(),
+        stream_url: validate_url(stream_url, "streamUrl")?.to_string(),
+        homepage_url: validate_homepage_url(homepage_url)?.map(str::to_string),
+        created_at: now,
+        updated_at: now,
+    })
+}
+
+fn validate_name(name: &str) -> Result<&str, AppError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(AppError::validation_error("Station name must not be empty"));
+    }
+    Ok(name)
+}
+
+/// 只允许 http/https 地址
+fn validate_url<'a>(url: &'a str, field: &str) -> Result<&'a str, AppError> {
+    let url = url.trim();
+    match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") => Ok(url),
+        _ => Err(AppError::validation_error(&format!(
+            "{} must be an http or https URL",
+            field
+        ))),
+    }
+}
+
+/// 主页地址可选，空字符串视为未设置
+fn validate_homepage_url(homepage_url: Option<&str>) -> Result<Option<&str>, AppError> {
+    homepage_url
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(|url| validate_url(url, "homepageUrl"))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::SqlitePool;
+
+    async fn create_service() -> InternetRadioService {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE internet_radio_stations (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                stream_url TEXT NOT NULL,
+                homepage_url TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        InternetRadioService::new(Arc::new(ServiceContext::new(pool)))
+    }
+
+    #[tokio::test]
+    async fn test_station_crud() {
+        let service = create_service().await;
+
+        let station = service
+            .create_station(" Jazz FM ", "http://jazz.example/stream", Some(""))
+            .await
+            .unwrap();
+        assert_eq!(station.name, "Jazz FM");
+        assert_eq!(station.homepage_url, None);
+
+        service
+            .update_station(
+                &station.id,
+                "Jazz 24",
+                "https://jazz.example/hq",
+                Some("https://jazz.example"),
+            )
+            .await
+            .unwrap();
+        let stations = service.get_stations().await.unwrap();
+        assert_eq!(stations.len(), 1);
+        assert_eq!(stations[0].name, "Jazz 24");
+        assert_eq!(stations[0].stream_url, "https://jazz.example/hq");
+        assert_eq!(
+            stations[0].homepage_url.as_deref(),
+            Some("https://jazz.example")
+        );
+
+        service.delete_station(&station.id).await.unwrap();
+        assert!(service.get_stations().await.unwrap().is_empty());
+        assert!(matches!(
+            service.delete_station(&station.id).await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_station_validation() {
+        let service = create_service().await;
+
+        for (name, stream_url, homepage_url) in [
+            ("", "http://a.example/", None),
+            ("A", "file:///etc/passwd", None),
+            ("A", "not a url", None),
+            ("A", "http://a.example/", Some("javascript:alert(1)")),
+        ] {
+            let result = service.create_station(name, stream_url, homepage_url).await;
+            assert!(matches!(result, Err(AppError::ValidationError(_))));
+        }
+        assert!(matches!(
+            service
+                .update_station("missing", "A", "http://a.example/", None)
+                .await,
+            Err(AppError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_import_and_export() {
+        let service = create_service().await;
+        service
+            .create_station("Jazz FM", "http://jazz.example/stream", None)
+            .await
+            .unwrap();
+
+        // 已存在的流地址和重复条目跳过
+        let imported = service
+            .import_stations(
+                "[playlist]\nFile1=http://jazz.example/stream\nFile2=http://rock.example/\nTitle2=Rock\nFile3=http://rock.example/\n",
+            )
+            .await
+            .unwrap();
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].name, "Rock");
+
+        // 包含无效地址时整体拒绝
+        let result = service
+            .import_stations("#EXTM3U\nhttp://pop.example/\nftp://bad.example/\n")
+            .await;
+        assert!(matches!(result, Err(AppError::ValidationError(_))));
+        assert_eq!(service.get_stations().await.unwrap().len(), 2);
+
+        let m3u = service
+            .export_stations(RadioPlaylistFormat::M3u)
+            .await
+            .unwrap();
+        assert_eq!(
+            m3u,
+            "#EXTM3U\n#EXTINF:-1,Jazz FM\nhttp://jazz.example/stream\n#EXTINF:-1,Rock\nhttp://rock.example/\n"
+        );
+    }
+}
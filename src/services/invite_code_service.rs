@@ -0,0 +1,224 @@
+//! 邀请码服务
+//!
+//! 负责邀请注册相关的业务逻辑:
+//! - 管理员签发、查询、吊销邀请码
+//! - 注册时占用邀请码 (每个邀请码只能使用一次)
+
+use crate::error::AppError;
+use crate::models::entities::InviteCode;
+use crate::services::ServiceContext;
+use crate::utils::id_builder;
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+
+/// 邀请码长度
+const INVITE_CODE_LEN: u32 = 12;
+
+/// 邀请码服务
+pub struct InviteCodeService {
+    ctx: Arc<ServiceContext>,
+}
+
+impl InviteCodeService {
+    /// 创建新的 InviteCodeService
+    pub fn new(ctx: Arc<ServiceContext>) -> Self {
+        Self { ctx }
+    }
+
+    /// 签发邀请码
+    ///
+    /// # 参数
+    ///
+    /// * `created_by` - 签发的管理员 ID
+    /// * `expires_in_hours` - 有效期 (小时)，为空表示永不过期
+    pub async fn create_invite_code(
+        &self,
+        created_by: &str,
+        expires_in_hours: Option<i64>,
+    ) -> Result<InviteCode, AppError> {
+        if matches!(expires_in_hours, Some(hours) if hours <= 0) {
+            return Err(AppError::validation_error(
+                "Invite code expiry must be positive",
+            ));
+        }
+
+        let now = Utc::now();
+        let invite = InviteCode {
+            id: id_builder::generate_id(),
+            code: id_builder::generate_id_by_len(INVITE_CODE_LEN),
+            created_by: Some(created_by.to_string()),
+            expires_at: expires_in_hours.map(|hours| now + Duration::hours(hours)),
+            used_by: None,
+            used_at: None,
+            created_at: now,
+        };
+
+        sqlx::query(
+            "INSERT INTO invite_codes (id, code, created_by, expires_at, created_at)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(&invite.id)
+        .bind(&invite.code)
+        .bind(&invite.created_by)
+        .bind(invite.expires_at)
+        .bind(invite.created_at)
+        .execute(&self.ctx.pool)
+        .await?;
+
+        Ok(invite)
+    }
+
+    /// 获取所有邀请码
+    pub async fn get_invite_codes(&self) -> Result<Vec<InviteCode>, AppError> {
+        let invites =
+            sqlx::query_as::<_, InviteCode>("SELECT * FROM invite_codes ORDER BY created_at DESC")
+                .fetch_all(&self.ctx.pool)
+                .await?;
+
+        Ok(invites)
+    }
+
+    /// 吊销邀请码
+    pub async fn delete_invite_code(&self, id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM invite_codes WHERE id = ?")
+            .bind(id)
+            .execute(&self.ctx.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Invite code"));
+        }
+
+        Ok(())
+    }
+
+    /// 占用邀请码
+    ///
+    /// 邀请码不存在、已使用或已过期时返回错误；注册失败时需调用 [`Self::release`] 归还
+    pub async fn claim(&self, code: &str) -> Result<(), AppError> {
+        let now = Utc::now();
+        let result = sqlx::query(
+            "UPDATE invite_codes SET used_at = ?
+             WHERE code = ? AND used_at IS NULL AND (expires_at IS NULL OR expires_at > ?)",
+        )
+        .bind(now)
+        .bind(code)
+        .bind(now)
+        .execute(&self.ctx.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::validation_error("Invalid or expired invite code"));
+        }
+
+        Ok(())
+    }
+
+    /// 记录使用邀请码注册的用户
+    pub async fn mark_used(&self, code: &str, user_id: &str) -> Result<(), AppError> {
+        sqlx::query("UPDATE invite_codes SET used_by = ? WHERE code = ?")
+            .bind(user_id)
+            .bind(code)
+            .execute(&self.ctx.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 归还已占用但未完成注册的邀请码
+    pub async fn release(&self, code: &str) -> Result<(), AppError> {
+        sqlx::query("UPDATE invite_codes SET used_at = NULL WHERE code = ? AND used_by IS NULL")
+            .bind(code)
+            .execute(&self.ctx.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::SqlitePool;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+        sqlx::query(
+            "CREATE TABLE invite_codes (
+                id TEXT PRIMARY KEY,
+                code TEXT NOT NULL UNIQUE,
+                created_by TEXT,
+                expires_at TEXT,
+                used_by TEXT,
+                used_at TEXT,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    fn create_service(pool: SqlitePool) -> InviteCodeService {
+        InviteCodeService::new(Arc::new(ServiceContext::new(pool)))
+    }
+
+    #[tokio::test]
+    async fn test_claim_invite_code_once() {
+        let service = create_service(setup_test_db().await);
+
+        let invite = service.create_invite_code("admin", Some(24)).await.unwrap();
+        assert_eq!(invite.code.len(), INVITE_CODE_LEN as usize);
+
+        service.claim(&invite.code).await.unwrap();
+        service.mark_used(&invite.code, "u1").await.unwrap();
+
+        // 已使用的邀请码不能再次使用，也不会被归还
+        assert!(service.claim(&invite.code).await.is_err());
+        service.release(&invite.code).await.unwrap();
+        assert!(service.claim(&invite.code).await.is_err());
+
+        let invites = service.get_invite_codes().await.unwrap();
+        assert_eq!(invites[0].used_by.as_deref(), Some("u1"));
+        assert!(invites[0].used_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_release_and_revoke() {
+        let service = create_service(setup_test_db().await);
+
+        let invite = service.create_invite_code("admin", None).await.unwrap();
+        service.claim(&invite.code).await.unwrap();
+
+        // 注册失败后归还，邀请码可再次使用
+        service.release(&invite.code).await.unwrap();
+        service.claim(&invite.code).await.unwrap();
+        service.release(&invite.code).await.unwrap();
+
+        service.delete_invite_code(&invite.id).await.unwrap();
+        assert!(service.claim(&invite.code).await.is_err());
+        assert!(service.delete_invite_code(&invite.id).await.is_err());
+
+        assert!(service.claim("unknown").await.is_err());
+        assert!(service.create_invite_code("admin", Some(0)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expired_invite_code() {
+        let pool = setup_test_db().await;
+        let service = create_service(pool.clone());
+
+        let invite = service.create_invite_code("admin", Some(1)).await.unwrap();
+        sqlx::query("UPDATE invite_codes SET expires_at = ? WHERE id = ?")
+            .bind(Utc::now() - Duration::hours(1))
+            .bind(&invite.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert!(service.claim(&invite.code).await.is_err());
+    }
+}
@@ -0,0 +1,773 @@
+//! 点唱机服务
+//!
+//! 点唱机在服务器上播放音乐 (jukeboxControl):
+//! - 播放器是运行在独立线程上的 actor，独占服务器端播放队列与音频输出
+//! - 服务通过命令通道与播放器通信，每个命令返回最新的播放状态
+//! - 使用 symphonia 解码，采样按音量缩放后写入配置的 `AudioSink`
+//!
+//! 点唱机为整个服务器共享，队列中的歌曲对所有拥有点唱机权限的用户可见。
+
+use crate::config::JukeboxSink;
+use crate::error::AppError;
+use crate::models::dto::ComplexSongDto;
+use crate::services::{FolderScope, ServiceContext, SongService};
+use crate::utils::audio_sink::{self, AudioSink, AudioSpec, NullSink};
+use anyhow::{anyhow, Context};
+use rand::seq::SliceRandom;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{Decoder, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+use symphonia::core::units::Time;
+use tokio::sync::mpsc::{self, error::TryRecvError};
+use tokio::sync::oneshot;
+
+/// 点唱机播放状态
+#[derive(Debug, Clone, PartialEq)]
+pub struct JukeboxStatus {
+    /// 当前歌曲在队列中的位置，队列为空时为 -1
+    pub current_index: i32,
+    pub playing: bool,
+    /// 音量 (0.0 - 1.0)
+    pub gain: f32,
+    /// 当前歌曲的播放位置 (秒)
+    pub position: u32,
+}
+
+/// 点唱机播放列表 (包含歌曲详情)
+#[derive(Debug)]
+pub struct JukeboxPlaylist {
+    pub status: JukeboxStatus,
+    pub songs: Vec<ComplexSongDto>,
+}
+
+/// 播放队列中的歌曲
+#[derive(Debug, Clone)]
+struct QueueEntry {
+    song_id: String,
+    file_path: PathBuf,
+}
+
+/// 播放器返回的状态快照
+#[derive(Debug)]
+struct PlayerState {
+    status: JukeboxStatus,
+    song_ids: Vec<String>,
+}
+
+/// 播放器命令
+#[derive(Debug)]
+enum Command {
+    Status,
+    Set(Vec<QueueEntry>),
+    Add(Vec<QueueEntry>),
+    Start,
+    Stop,
+    Skip { index: usize, offset: u32 },
+    Clear,
+    Remove(usize),
+    Shuffle,
+    SetGain(f32),
+}
+
+type Reply = oneshot::Sender<Result<PlayerState, AppError>>;
+
+/// 点唱机服务
+pub struct JukeboxService {
+    ctx: Arc<ServiceContext>,
+    commands: mpsc::UnboundedSender<(Command, Reply)>,
+}
+
+impl JukeboxService {
+    /// 创建点唱机服务并启动播放器线程
+    ///
+    /// 音频输出打开失败时记录错误并丢弃音频，播放队列仍可使用
+    pub fn start(ctx: Arc<ServiceContext>, sink: JukeboxSink) -> std::io::Result<Self> {
+        let (commands, receiver) = mpsc::unbounded_channel();
+
+        std::thread::Builder::new()
+            .name("jukebox".to_string())
+            .spawn(move || {
+                let output = audio_sink::open(&sink).unwrap_or_else(|e| {
+                    tracing::error!("Failed to open jukebox output {:?}: {:#}", sink, e);
+                    Box::new(NullSink::default())
+                });
+                Player::new(output).run(receiver);
+            })?;
+
+        Ok(Self { ctx, commands })
+    }
+
+    /// 获取播放状态
+    pub async fn status(&self) -> Result<JukeboxStatus, AppError> {
+        Ok(self.send(Command::Status).await?.status)
+    }
+
+    /// 获取播放队列
+    ///
+    /// 队列由所有用户共享，调用者无权访问的文件夹中的歌曲不会返回。
+    pub async fn playlist(
+        &self,
+        user_id: &str,
+        scope: &FolderScope,
+    ) -> Result<JukeboxPlaylist, AppError> {
+        let state = self.send(Command::Status).await?;
+
+        let songs = SongService::new(self.ctx.clone())
+            .get_complex_songs_by_ids(user_id, &state.song_ids, scope)
+            .await?;
+        let songs: HashMap<String, ComplexSongDto> = songs
+            .into_iter()
+            .map(|song| (song.song.id.clone(), song))
+            .collect();
+
+        // 队列可能包含重复的歌曲，按队列顺序组装
+        Ok(JukeboxPlaylist {
+            status: state.status,
+            songs: state
+                .song_ids
+                .iter()
+                .filter_map(|id| songs.get(id).cloned())
+                .collect(),
+        })
+    }
+
+    /// 替换播放队列
+    pub async fn set(
+        &self,
+        song_ids: &[String],
+        scope: &FolderScope,
+    ) -> Result<JukeboxStatus, AppError> {
+        let entries = self.resolve_entries(song_ids, scope).await?;
+        Ok(self.send(Command::Set(entries)).await?.status)
+    }
+
+    /// 添加歌曲到队列末尾
+    pub async fn add(
+        &self,
+        song_ids: &[String],
+        scope: &FolderScope,
+    ) -> Result<JukeboxStatus, AppError> {
+        let entries = self.resolve_entries(song_ids, scope).await?;
+        Ok(self.send(Command::Add(entries)).await?.status)
+    }
+
+    /// 开始 (或继续) 播放
+    pub async fn start_playback(&self) -> Result<JukeboxStatus, AppError> {
+        Ok(self.send(Command::Start).await?.status)
+    }
+
+    /// 暂停播放，保留播放位置
+    pub async fn stop_playback(&self) -> Result<JukeboxStatus, AppError> {
+        Ok(self.send(Command::Stop).await?.status)
+    }
+
+    /// 跳转到队列中的指定歌曲并开始播放
+    ///
+    /// # 参数
+    ///
+    /// * `index` - 歌曲在队列中的位置 (从 0 开始)
+    /// * `offset` - 从歌曲的第几秒开始播放
+    pub async fn skip(&self, index: i32, offset: i32) -> Result<JukeboxStatus, AppError> {
+        let index =
+            usize::try_from(index).map_err(|_| AppError::validation_error("Invalid index"))?;
+        let offset =
+            u32::try_from(offset).map_err(|_| AppError::validation_error("Invalid offset"))?;
+        Ok(self.send(Command::Skip { index, offset }).await?.status)
+    }
+
+    /// 清空播放队列
+    pub async fn clear(&self) -> Result<JukeboxStatus, AppError> {
+        Ok(self.send(Command::Clear).await?.status)
+    }
+
+    /// 移除队列中指定位置的歌曲
+    pub async fn remove(&self, index: i32) -> Result<JukeboxStatus, AppError> {
+        let index =
+            usize::try_from(index).map_err(|_| AppError::validation_error("Invalid index"))?;
+        Ok(self.send(Command::Remove(index)).await?.status)
+    }
+
+    /// 随机打乱播放队列，当前歌曲移到队列开头
+    pub async fn shuffle(&self) -> Result<JukeboxStatus, AppError> {
+        Ok(self.send(Command::Shuffle).await?.status)
+    }
+
+    /// 设置音量 (0.0 - 1.0)
+    pub async fn set_gain(&self, gain: f32) -> Result<JukeboxStatus, AppError> {
+        if !(0.0..=1.0).contains(&gain) {
+            return Err(AppError::validation_error(
+                "gain must be between 0.0 and 1.0",
+            ));
+        }
+        Ok(self.send(Command::SetGain(gain)).await?.status)
+    }
+
+    /// 发送命令并等待播放器返回状态
+    async fn send(&self, command: Command) -> Result<PlayerState, AppError> {
+        let (reply, response) = oneshot::channel();
+        self.commands
+            .send((command, reply))
+            .map_err(|_| AppError::server_busy("Jukebox player is not running"))?;
+        response
+            .await
+            .map_err(|_| AppError::server_busy("Jukebox player is not running"))?
+    }
+
+    /// 查询歌曲文件路径，保持请求顺序
+    ///
+    /// 任意歌曲不存在或不在用户可访问的音乐文件夹中时返回 NotFound
+    async fn resolve_entries(
+        &self,
+        song_ids: &[String],
+        scope: &FolderScope,
+    ) -> Result<Vec<QueueEntry>, AppError> {
+        if song_ids.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let placeholders = song_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let query = format!(
+            "SELECT id, file_path FROM songs WHERE id IN ({}) AND {}",
+            placeholders,
+            scope.sql_filter("music_folder_id")
+        );
+        let mut query_builder = sqlx::query_as::<_, (String, String)>(&query);
+        for song_id in song_ids {
+            query_builder = query_builder.bind(song_id);
+        }
+        let paths: HashMap<String, String> = query_builder
+            .fetch_all(&self.ctx.pool)
+            .await?
+            .into_iter()
+            .collect();
+
+        song_ids
+            .iter()
+            .map(|id| {
+                let file_path = paths.get(id).ok_or_else(|| AppError::not_found("Song"))?;
+                Ok(QueueEntry {
+                    song_id: id.clone(),
+                    file_path: PathBuf::from(file_path),
+                })
+            })
+            .collect()
+    }
+}
+
+/// 正在解码的歌曲
+struct Playback {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    sample_rate: u32,
+    /// 已解码的帧数 (含跳过的部分)，用于计算播放位置
+    frames: u64,
+}
+
+impl Playback {
+    /// 打开音频文件，并跳转到 `offset` 秒
+    fn open(path: &Path, offset: u32) -> anyhow::Result<Self> {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let source = MediaSourceStream::new(Box::new(file), Default::default());
+
+        let mut hint = Hint::new();
+        if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+            hint.with_extension(extension);
+        }
+        let probed = symphonia::default::get_probe().format(
+            &hint,
+            source,
+            &FormatOptions {
+                enable_gapless: true,
+                ..Default::default()
+            },
+            &MetadataOptions::default(),
+        )?;
+        let mut format = probed.format;
+
+        let track = format
+            .tracks()
+            .iter()
+            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+            .ok_or_else(|| anyhow!("No audio track in {}", path.display()))?;
+        let track_id = track.id;
+        let sample_rate = track
+            .codec_params
+            .sample_rate
+            .ok_or_else(|| anyhow!("Unknown sample rate in {}", path.display()))?;
+        let mut decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())?;
+
+        let mut frames = 0;
+        if offset > 0 {
+            format.seek(
+                SeekMode::Coarse,
+                SeekTo::Time {
+                    time: Time::from(offset),
+                    track_id: Some(track_id),
+                },
+            )?;
+            decoder.reset();
+            frames = offset as u64 * sample_rate as u64;
+        }
+
+        Ok(Self {
+            format,
+            decoder,
+            track_id,
+            sample_rate,
+            frames,
+        })
+    }
+
+    /// 播放位置 (秒)
+    fn position(&self) -> u32 {
+        (self.frames / self.sample_rate.max(1) as u64) as u32
+    }
+
+    /// 解码下一个数据包，歌曲结束时返回 `None`
+    fn next_chunk(&mut self, gain: f32) -> anyhow::Result<Option<(AudioSpec, Vec<f32>)>> {
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(SymphoniaError::IoError(e))
+                    if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    return Ok(None)
+                }
+                Err(e) => return Err(e.into()),
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // 跳过损坏的数据包
+                Err(SymphoniaError::DecodeError(e)) => {
+                    tracing::debug!("Skipping undecodable packet: {}", e);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            let spec = *decoded.spec();
+            let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+            buffer.copy_interleaved_ref(decoded);
+            let samples: Vec<f32> = buffer.samples().iter().map(|s| s * gain).collect();
+
+            let spec = AudioSpec {
+                sample_rate: spec.rate,
+                channels: spec.channels.count() as u16,
+            };
+            self.frames += (samples.len() / spec.channels.max(1) as usize) as u64;
+
+            return Ok(Some((spec, samples)));
+        }
+    }
+}
+
+/// 播放器 actor，在独立线程上运行
+struct Player {
+    output: Box<dyn AudioSink>,
+    queue: Vec<QueueEntry>,
+    current: Option<usize>,
+    playing: bool,
+    gain: f32,
+    /// 当前歌曲的解码状态，切换歌曲时重新打开
+    playback: Option<Playback>,
+    /// 下次打开歌曲时的起始位置 (秒)
+    start_offset: u32,
+}
+
+impl Player {
+    fn new(output: Box<dyn AudioSink>) -> Self {
+        Self {
+            output,
+            queue: Vec::new(),
+            current: None,
+            playing: false,
+            gain: 1.0,
+            playback: None,
+            start_offset: 0,
+        }
+    }
+
+    /// 处理命令并播放，服务释放命令通道后退出
+    fn run(mut self, mut commands: mpsc::UnboundedReceiver<(Command, Reply)>) {
+        loop {
+            // 播放时不阻塞等待命令
+            let next = if self.playing {
+                match commands.try_recv() {
+                    Ok(next) => Some(next),
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Disconnected) => break,
+                }
+            } else {
+                match commands.blocking_recv() {
+                    Some(next) => Some(next),
+                    None => break,
+                }
+            };
+
+            match next {
+                Some((command, reply)) => {
+                    let result = self.handle(command).map(|_| self.state());
+                    let _ = reply.send(result);
+                }
+                None => self.play_chunk(),
+            }
+        }
+
+        tracing::debug!("Jukebox player stopped");
+    }
+
+    fn handle(&mut self, command: Command) -> Result<(), AppError> {
+        match command {
+            Command::Status => {}
+            Command::Set(entries) => {
+                self.reset_track(0);
+                self.queue = entries;
+                self.current = (!self.queue.is_empty()).then_some(0);
+                self.playing &= self.current.is_some();
+            }
+            Command::Add(entries) => {
+                self.queue.extend(entries);
+                if self.current.is_none() && !self.queue.is_empty() {
+                    self.current = Some(0);
+                }
+            }
+            Command::Start => self.playing = self.current.is_some(),
+            Command::Stop => {
+                self.playing = false;
+                self.output.clear();
+            }
+            Command::Skip { index, offset } => {
+                if index >= self.queue.len() {
+                    return Err(AppError::validation_error("Invalid index"));
+                }
+                self.reset_track(offset);
+                self.current = Some(index);
+                self.playing = true;
+            }
+            Command::Clear => {
+                self.reset_track(0);
+                self.queue.clear();
+                self.current = None;
+                self.playing = false;
+            }
+            Command::Remove(index) => {
+                if index >= self.queue.len() {
+                    return Err(AppError::validation_error("Invalid index"));
+                }
+                self.queue.remove(index);
+                match self.current {
+                    Some(current) if index < current => self.current = Some(current - 1),
+                    // 移除当前歌曲时播放队列中的下一首
+                    Some(current) if index == current => {
+                        self.reset_track(0);
+                        if current >= self.queue.len() {
+                            self.finish();
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Command::Shuffle => {
+                let mut rng = rand::thread_rng();
+                match self.current {
+                    Some(current) => {
+                        let entry = self.queue.remove(current);
+                        self.queue.shuffle(&mut rng);
+                        self.queue.insert(0, entry);
+                        self.current = Some(0);
+                    }
+                    None => self.queue.shuffle(&mut rng),
+                }
+            }
+            Command::SetGain(gain) => self.gain = gain,
+        }
+
+        Ok(())
+    }
+
+    /// 解码并输出当前歌曲的下一段音频
+    fn play_chunk(&mut self) {
+        let Some(index) = self.current else {
+            self.playing = false;
+            return;
+        };
+
+        if self.playback.is_none() {
+            let entry = &self.queue[index];
+            match Playback::open(&entry.file_path, self.start_offset) {
+                Ok(playback) => {
+                    tracing::info!("Jukebox playing {}", entry.song_id);
+                    self.playback = Some(playback);
+                    self.start_offset = 0;
+                }
+                Err(e) => {
+                    tracing::warn!("Jukebox failed to play {}: {:#}", entry.song_id, e);
+                    self.advance();
+                    return;
+                }
+            }
+        }
+        let Some(playback) = self.playback.as_mut() else {
+            return;
+        };
+
+        let result = playback
+            .next_chunk(self.gain)
+            .and_then(|chunk| match chunk {
+                Some((spec, samples)) => self.output.write(spec, &samples).map(|_| true),
+                None => Ok(false),
+            });
+        match result {
+            Ok(true) => {}
+            Ok(false) => self.advance(),
+            Err(e) => {
+                tracing::warn!("Jukebox playback error: {:#}", e);
+                self.advance();
+            }
+        }
+    }
+
+    /// 播放队列中的下一首
+    fn advance(&mut self) {
+        self.reset_track(0);
+        match self.current {
+            Some(current) if current + 1 < self.queue.len() => self.current = Some(current + 1),
+            _ => self.finish(),
+        }
+    }
+
+    /// 队列播放完毕: 停止并回到第一首
+    fn finish(&mut self) {
+        self.playing = false;
+        self.current = (!self.queue.is_empty()).then_some(0);
+    }
+
+    /// 关闭当前歌曲，下次从 `offset` 秒开始播放
+    fn reset_track(&mut self, offset: u32) {
+        self.playback = None;
+        self.start_offset = offset;
+        self.output.clear();
+    }
+
+    fn state(&self) -> PlayerState {
+        let position = match &self.playback {
+            Some(playback) => playback.position(),
+            None => self.start_offset,
+        };
+
+        PlayerState {
+            status: JukeboxStatus {
+                current_index: self.current.map_or(-1, |i| i as i32),
+                playing: self.playing,
+                gain: self.gain,
+                position,
+            },
+            song_ids: self.queue.iter().map(|e| e.song_id.clone()).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::SqlitePool;
+    use std::time::Duration;
+
+    /// 测试用歌曲的时长 (秒)
+    const SONG_SECONDS: u32 = 2;
+    const SAMPLE_RATE: u32 = 8000;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("jukebox_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 生成单声道 WAV 测试文件
+    fn write_song(path: &Path) {
+        let mut sink = audio_sink::WavFileSink::create(path).unwrap();
+        let samples: Vec<f32> = (0..SAMPLE_RATE * SONG_SECONDS)
+            .map(|i| ((i as f32) * 0.05).sin() * 0.5)
+            .collect();
+        sink.write(
+            AudioSpec {
+                sample_rate: SAMPLE_RATE,
+                channels: 1,
+            },
+            &samples,
+        )
+        .unwrap();
+    }
+
+    /// 创建点唱机服务与测试歌曲，`wav_output` 为 false 时使用丢弃音频的输出
+    async fn setup(name: &str, wav_output: bool) -> (JukeboxService, PathBuf) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        crate::database::run_migrations(&pool).await.unwrap();
+        sqlx::query(
+            "INSERT INTO music_folders (id, name, path) VALUES (1, 'A', '/a'), (2, 'B', '/b')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "INSERT INTO artists (id, name) VALUES ('ar1', 'Artist');
+             INSERT INTO albums (id, artist_id, name, path) VALUES ('al1', 'ar1', 'Album', '/a')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let dir = temp_dir(name);
+        for (id, folder) in [("s1", 1), ("s2", 1), ("s3", 2)] {
+            let path = dir.join(format!("{}.wav", id));
+            write_song(&path);
+            sqlx::query(
+                "INSERT INTO songs (id, album_id, artist_id, title, duration, file_path, music_folder_id)
+                 VALUES (?, 'al1', 'ar1', ?, ?, ?, ?)",
+            )
+            .bind(id)
+            .bind(id)
+            .bind(SONG_SECONDS as i64)
+            .bind(path.to_string_lossy().to_string())
+            .bind(folder)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let sink = if wav_output {
+            JukeboxSink::WavFile(dir.join("output.wav"))
+        } else {
+            JukeboxSink::Null
+        };
+        let service = JukeboxService::start(Arc::new(ServiceContext::new(pool)), sink).unwrap();
+
+        (service, dir)
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_queue_control() {
+        let (service, dir) = setup("queue", false).await;
+
+        let status = service
+            .add(&ids(&["s1", "s2", "s3"]), &FolderScope::All)
+            .await
+            .unwrap();
+        assert_eq!(status.current_index, 0);
+        assert!(!status.playing);
+
+        // 范围外的歌曲无法添加
+        let scope = FolderScope::Folders(vec![1]);
+        assert!(matches!(
+            service.add(&ids(&["s3"]), &scope).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        // 播放列表只返回调用者可访问的歌曲
+        let playlist = service.playlist("u1", &scope).await.unwrap();
+        let song_ids: Vec<_> = playlist.songs.iter().map(|s| s.song.id.as_str()).collect();
+        assert_eq!(song_ids, ["s1", "s2"]);
+        assert_eq!(
+            service
+                .playlist("u1", &FolderScope::All)
+                .await
+                .unwrap()
+                .songs
+                .len(),
+            3
+        );
+
+        service.remove(0).await.unwrap();
+        assert!(service.remove(5).await.is_err());
+        assert!(service.set_gain(1.5).await.is_err());
+        assert_eq!(service.set_gain(0.5).await.unwrap().gain, 0.5);
+
+        // 当前歌曲移到队列开头
+        assert_eq!(service.shuffle().await.unwrap().current_index, 0);
+        let state = service.send(Command::Status).await.unwrap();
+        assert_eq!(state.song_ids.len(), 2);
+        assert_eq!(state.song_ids[0], "s2");
+
+        service.set(&ids(&["s1"]), &scope).await.unwrap();
+        assert_eq!(
+            service.send(Command::Status).await.unwrap().song_ids,
+            ids(&["s1"])
+        );
+
+        let status = service.clear().await.unwrap();
+        assert_eq!(status.current_index, -1);
+        assert!(!service.start_playback().await.unwrap().playing);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_skip_and_stop() {
+        let (service, dir) = setup("skip", false).await;
+        service
+            .set(&ids(&["s1", "s2"]), &FolderScope::All)
+            .await
+            .unwrap();
+        assert!(service.skip(2, 0).await.is_err());
+
+        // 丢弃音频的输出按实时速度播放
+        let status = service.skip(1, 1).await.unwrap();
+        assert!(status.playing);
+        assert_eq!(status.current_index, 1);
+        assert_eq!(status.position, 1);
+
+        let status = service.stop_playback().await.unwrap();
+        assert!(!status.playing);
+        assert_eq!(status.current_index, 1);
+        assert!(status.position >= 1);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_play_queue_to_output() {
+        let (service, dir) = setup("play", true).await;
+        service
+            .set(&ids(&["s1", "s2"]), &FolderScope::All)
+            .await
+            .unwrap();
+        assert!(service.start_playback().await.unwrap().playing);
+
+        // WAV 输出不限速，播放很快结束
+        let mut status = service.status().await.unwrap();
+        for _ in 0..100 {
+            if !status.playing {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            status = service.status().await.unwrap();
+        }
+        assert!(!status.playing);
+        assert_eq!(status.current_index, 0);
+
+        // 两首歌曲完整写入输出 (16 位单声道)
+        let size = std::fs::metadata(dir.join("output.wav")).unwrap().len();
+        assert_eq!(size, 44 + (SAMPLE_RATE * SONG_SECONDS * 2 * 2) as u64);
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
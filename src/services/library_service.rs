@@ -7,8 +7,10 @@
 //! - 评分
 
 use crate::error::AppError;
-use crate::models::dto::{AlbumDetailDto, AlbumDto, ArtistDto, ArtistStarredDto, SongDto, ComplexSongDto, SongDetailDto};
-use crate::services::{ServiceContext, SongService};
+use crate::models::dto::{
+    AlbumDetailDto, AlbumDto, ArtistDto, ArtistStarredDto, ComplexSongDto, SongDetailDto, SongDto,
+};
+use crate::services::{FolderScope, ServiceContext, SongService};
 use crate::utils::id_builder;
 use futures::FutureExt;
 use std::sync::Arc;
@@ -120,6 +122,66 @@ impl LibraryService {
             .await
     }
 
+    /// 更新正在播放 (scrobble submission=false)
+    ///
+    /// 每个用户的每个播放器只保留一条记录，重复上报时覆盖歌曲和开始时间。
+    ///
+    /// # 参数
+    ///
+    /// * `user_id` - 用户 ID
+    /// * `username` - 用户名
+    /// * `song_id` - 歌曲 ID
+    /// * `player_name` - 播放器名称 (客户端参数 c)
+    /// * `timestamp` - 开始播放时间戳
+    pub async fn update_now_playing(
+        &self,
+        user_id: &str,
+        username: &str,
+        song_id: &str,
+        player_name: &str,
+        timestamp: i64,
+    ) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO now_playing (id, user_id, username, song_id, player_name, started_at)
+             VALUES (?, ?, ?, ?, ?, ?)
+             ON CONFLICT(user_id, player_name) DO UPDATE SET
+                song_id = excluded.song_id,
+                username = excluded.username,
+                started_at = excluded.started_at,
+                updated_at = datetime('now')",
+        )
+        .bind(id_builder::generate_id())
+        .bind(user_id)
+        .bind(username)
+        .bind(song_id)
+        .bind(player_name)
+        .bind(timestamp)
+        .execute(&self.ctx.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// 清理过期的正在播放记录
+    ///
+    /// # 参数
+    ///
+    /// * `max_age_secs` - 记录最长保留秒数
+    ///
+    /// # 返回值
+    ///
+    /// 返回被清理的记录数
+    pub async fn purge_expired_now_playing(&self, max_age_secs: i64) -> Result<u64, AppError> {
+        let expire_before = chrono::Utc::now().timestamp() - max_age_secs;
+
+        let result = sqlx::query("DELETE FROM now_playing WHERE CAST(started_at AS INTEGER) < ?")
+            .bind(expire_before)
+            .execute(&self.ctx.pool)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
     /// 收藏项目
     ///
     /// # 参数
@@ -269,18 +331,23 @@ impl LibraryService {
     /// # 参数
     ///
     /// * `user_id` - 用户 ID
+    /// * `scope` - 用户可访问的音乐文件夹范围
     ///
     /// # 性能优化
     ///
     /// 使用 tokio::try_join! 并行查询三个表,提升性能
-    pub async fn get_starred_items(&self, user_id: &str) -> Result<StarredItems, AppError> {
+    pub async fn get_starred_items(
+        &self,
+        user_id: &str,
+        scope: &FolderScope,
+    ) -> Result<StarredItems, AppError> {
         let user_id = user_id.to_string();
 
         // 并行查询三个表
         let (artists, albums, songs) = tokio::try_join!(
-            self.get_starred_artists(&user_id),
-            self.get_starred_albums(&user_id),
-            self.get_starred_songs(&user_id),
+            self.get_starred_artists(&user_id, scope),
+            self.get_starred_albums(&user_id, scope),
+            self.get_starred_songs(&user_id, scope),
         )?;
 
         Ok(StarredItems {
@@ -295,18 +362,23 @@ impl LibraryService {
     /// # 参数
     ///
     /// * `user_id` - 用户 ID
+    /// * `scope` - 用户可访问的音乐文件夹范围
     ///
     /// # 性能优化
     ///
     /// 使用 tokio::try_join! 并行查询三个表,提升性能
-    pub async fn get_starred_items_with_details(&self, user_id: &str) -> Result<StarredItemsDetail, AppError> {
+    pub async fn get_starred_items_with_details(
+        &self,
+        user_id: &str,
+        scope: &FolderScope,
+    ) -> Result<StarredItemsDetail, AppError> {
         let user_id = user_id.to_string();
 
         // 并行查询三个表
         let (artists, albums, songs) = tokio::try_join!(
-            self.get_starred_artists_with_details(&user_id),
-            self.get_starred_albums_with_details(&user_id),
-            self.get_starred_songs_with_details(&user_id),  // 歌曲已包含 duration
+            self.get_starred_artists_with_details(&user_id, scope),
+            self.get_starred_albums_with_details(&user_id, scope),
+            self.get_starred_songs_with_details(&user_id, scope), // 歌曲已包含 duration
         )?;
 
         Ok(StarredItemsDetail {
@@ -317,13 +389,18 @@ impl LibraryService {
     }
 
     /// 获取收藏的艺术家 (私有方法)
-    async fn get_starred_artists(&self, user_id: &str) -> Result<Vec<ArtistDto>, AppError> {
-        let artists = sqlx::query_as::<_, ArtistDto>(
+    async fn get_starred_artists(
+        &self,
+        user_id: &str,
+        scope: &FolderScope,
+    ) -> Result<Vec<ArtistDto>, AppError> {
+        let artists = sqlx::query_as::<_, ArtistDto>(&format!(
             "SELECT a.id, a.name
              FROM starred s
              JOIN artists a ON s.artist_id = a.id
-             WHERE s.user_id = ? AND s.artist_id IS NOT NULL",
-        )
+             WHERE s.user_id = ? AND s.artist_id IS NOT NULL AND {}",
+            scope.artist_filter("a.id")
+        ))
         .bind(user_id)
         .fetch_all(&self.ctx.pool)
         .await?;
@@ -332,14 +409,19 @@ impl LibraryService {
     }
 
     /// 获取收藏的专辑 (私有方法)
-    async fn get_starred_albums(&self, user_id: &str) -> Result<Vec<AlbumDto>, AppError> {
-        let albums = sqlx::query_as::<_, AlbumDto>(
+    async fn get_starred_albums(
+        &self,
+        user_id: &str,
+        scope: &FolderScope,
+    ) -> Result<Vec<AlbumDto>, AppError> {
+        let albums = sqlx::query_as::<_, AlbumDto>(&format!(
             "SELECT a.id, a.name, ar.name as artist, a.year, a.song_count
              FROM starred s
              JOIN albums a ON s.album_id = a.id
              JOIN artists ar ON a.artist_id = ar.id
-             WHERE s.user_id = ? AND s.album_id IS NOT NULL",
-        )
+             WHERE s.user_id = ? AND s.album_id IS NOT NULL AND {}",
+            scope.sql_filter("a.music_folder_id")
+        ))
         .bind(user_id)
         .fetch_all(&self.ctx.pool)
         .await?;
@@ -348,15 +430,20 @@ impl LibraryService {
     }
 
     /// 获取收藏的歌曲 (私有方法)
-    async fn get_starred_songs(&self, user_id: &str) -> Result<Vec<SongDto>, AppError> {
-        let songs = sqlx::query_as::<_, SongDto>(
+    async fn get_starred_songs(
+        &self,
+        user_id: &str,
+        scope: &FolderScope,
+    ) -> Result<Vec<SongDto>, AppError> {
+        let songs = sqlx::query_as::<_, SongDto>(&format!(
             "SELECT s.id, s.title, ar.name as artist, al.name as album, s.duration, s.content_type, NULL as cover_art
              FROM starred st
              JOIN songs s ON st.song_id = s.id
              JOIN albums al ON s.album_id = al.id
              JOIN artists ar ON s.artist_id = ar.id
-             WHERE st.user_id = ? AND st.song_id IS NOT NULL",
-        )
+             WHERE st.user_id = ? AND st.song_id IS NOT NULL AND {}",
+            scope.sql_filter("s.music_folder_id")
+        ))
         .bind(user_id)
         .fetch_all(&self.ctx.pool)
         .await?;
@@ -365,14 +452,20 @@ impl LibraryService {
     }
 
     /// 获取收藏的艺术家（包含详细信息）- 用于 getStarred2
-    async fn get_starred_artists_with_details(&self, user_id: &str) -> Result<Vec<ArtistStarredDto>, AppError> {
-        let artists = sqlx::query_as::<_, ArtistStarredDto>(
+    async fn get_starred_artists_with_details(
+        &self,
+        user_id: &str,
+        scope: &FolderScope,
+    ) -> Result<Vec<ArtistStarredDto>, AppError> {
+        let artists = sqlx::query_as::<_, ArtistStarredDto>(&format!(
             "SELECT a.id, a.name, a.cover_art_path,
-                    (SELECT COUNT(*) FROM albums WHERE artist_id = a.id) as album_count
+                    (SELECT COUNT(*) FROM albums WHERE artist_id = a.id AND {}) as album_count
              FROM starred s
              JOIN artists a ON s.artist_id = a.id
-             WHERE s.user_id = ? AND s.artist_id IS NOT NULL",
-        )
+             WHERE s.user_id = ? AND s.artist_id IS NOT NULL AND {}",
+            scope.sql_filter("albums.music_folder_id"),
+            scope.artist_filter("a.id")
+        ))
         .bind(user_id)
         .fetch_all(&self.ctx.pool)
         .await?;
@@ -381,16 +474,21 @@ impl LibraryService {
     }
 
     /// 获取收藏的专辑（包含详细信息）- 用于 getStarred2
-    async fn get_starred_albums_with_details(&self, user_id: &str) -> Result<Vec<AlbumDetailDto>, AppError> {
-        let albums = sqlx::query_as::<_, AlbumDetailDto>(
+    async fn get_starred_albums_with_details(
+        &self,
+        user_id: &str,
+        scope: &FolderScope,
+    ) -> Result<Vec<AlbumDetailDto>, AppError> {
+        let albums = sqlx::query_as::<_, AlbumDetailDto>(&format!(
             "SELECT a.id, a.name, ar.name as artist, a.artist_id,
                     a.year, a.genre, a.cover_art_path,
                     a.song_count, a.duration, a.play_count
              FROM starred s
              JOIN albums a ON s.album_id = a.id
              JOIN artists ar ON a.artist_id = ar.id
-             WHERE s.user_id = ? AND s.album_id IS NOT NULL",
-        )
+             WHERE s.user_id = ? AND s.album_id IS NOT NULL AND {}",
+            scope.sql_filter("a.music_folder_id")
+        ))
         .bind(user_id)
         .fetch_all(&self.ctx.pool)
         .await?;
@@ -399,7 +497,11 @@ impl LibraryService {
     }
 
     /// 获取收藏的歌曲 (私有方法)
-    async fn get_starred_songs_with_details(&self, user_id: &str) -> Result<Vec<ComplexSongDto>, AppError> {
+    async fn get_starred_songs_with_details(
+        &self,
+        user_id: &str,
+        scope: &FolderScope,
+    ) -> Result<Vec<ComplexSongDto>, AppError> {
         let song_ids = sqlx::query_scalar::<_, String>(
             "SELECT song_id  FROM starred WHERE user_id = ? AND song_id IS NOT NULL",
         )
@@ -408,7 +510,9 @@ impl LibraryService {
         .await?;
 
         let song_service = SongService::new(self.ctx.clone());
-        let songs = song_service.get_complex_songs_by_ids(user_id, &song_ids).await?;
+        let songs = song_service
+            .get_complex_songs_by_ids(user_id, &song_ids, scope)
+            .await?;
 
         Ok(songs)
     }
@@ -446,9 +550,20 @@ mod tests {
         .await
         .unwrap();
 
+        sqlx::query(
+            "CREATE TABLE albums (
+                id TEXT PRIMARY KEY,
+                play_count INTEGER DEFAULT 0
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
         sqlx::query(
             "CREATE TABLE songs (
                 id TEXT PRIMARY KEY,
+                album_id TEXT,
                 title TEXT NOT NULL,
                 play_count INTEGER DEFAULT 0
             )",
@@ -499,6 +614,29 @@ mod tests {
         .await
         .unwrap();
 
+        sqlx::query(
+            "CREATE TABLE now_playing (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                username TEXT NOT NULL,
+                song_id TEXT NOT NULL,
+                started_at TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+                player_name TEXT NOT NULL DEFAULT ''
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "CREATE UNIQUE INDEX idx_now_playing_user_player ON now_playing(user_id, player_name)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
         // 插入测试数据
         sqlx::query("INSERT INTO users (id, username) VALUES (?, ?)")
             .bind("user1")
@@ -570,6 +708,66 @@ mod tests {
         assert_eq!(play_count, 0);
     }
 
+    #[tokio::test]
+    async fn test_update_now_playing() {
+        let pool = setup_test_db().await;
+        let service = create_service(pool.clone());
+        let now = chrono::Utc::now().timestamp();
+
+        sqlx::query("INSERT INTO songs (id, title, play_count) VALUES (?, ?, ?)")
+            .bind("song2")
+            .bind("Another Song")
+            .bind(0)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // 同一播放器重复上报时覆盖
+        service
+            .update_now_playing("user1", "testuser", "song1", "web", now - 10)
+            .await
+            .unwrap();
+        service
+            .update_now_playing("user1", "testuser", "song2", "web", now)
+            .await
+            .unwrap();
+        // 不同播放器各自保留
+        service
+            .update_now_playing("user1", "testuser", "song1", "mobile", now - 3600)
+            .await
+            .unwrap();
+
+        let rows = sqlx::query_as::<_, (String, String)>(
+            "SELECT player_name, song_id FROM now_playing ORDER BY player_name",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                ("mobile".to_string(), "song1".to_string()),
+                ("web".to_string(), "song2".to_string()),
+            ]
+        );
+
+        // 不记录 scrobble，也不更新播放次数
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM scrobbles")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+
+        // 清理超过 15 分钟的记录
+        let purged = service.purge_expired_now_playing(15 * 60).await.unwrap();
+        assert_eq!(purged, 1);
+        let remaining: Vec<String> = sqlx::query_scalar("SELECT player_name FROM now_playing")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, vec!["web".to_string()]);
+    }
+
     #[tokio::test]
     async fn test_star_and_unstar_song() {
         let pool = setup_test_db().await;
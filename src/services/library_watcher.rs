@@ -0,0 +1,202 @@
+//! 音乐库文件监听
+//!
+//! 监听音乐文件夹中文件的新增、修改、删除和移动 (inotify / FSEvents / ReadDirectoryChangesW)，
+//! 变化停止一段时间后 (去抖) 只对发生变化的路径增量扫描，无需遍历整个音乐库。
+
+use crate::handlers::library::ScanState;
+use crate::services::ScanService;
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// 音乐库文件监听
+pub struct LibraryWatcher {
+    scan_service: Arc<ScanService>,
+    scan_state: ScanState,
+    debounce: Duration,
+}
+
+impl LibraryWatcher {
+    /// 创建新的 LibraryWatcher
+    pub fn new(scan_service: Arc<ScanService>, scan_state: ScanState, debounce: Duration) -> Self {
+        Self {
+            scan_service,
+            scan_state,
+            debounce,
+        }
+    }
+
+    /// 开始监听指定的音乐文件夹，并在后台任务中处理变化
+    ///
+    /// 文件夹路径先规范化 (相对路径、符号链接)，与扫描时的匹配方式一致；
+    /// 无法监听的文件夹 (例如未挂载的磁盘) 只记录警告
+    pub fn start(self, roots: &[PathBuf]) -> notify::Result<()> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+                Ok(event) if is_content_change(&event.kind) => {
+                    for path in event.paths {
+                        let _ = tx.send(path);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!("文件监听出错: {}", e),
+            })?;
+
+        for root in roots {
+            let root = std::fs::canonicalize(root).unwrap_or_else(|_| root.clone());
+            match watcher.watch(&root, RecursiveMode::Recursive) {
+                Ok(()) => tracing::info!("开始监听音乐文件夹: {}", root.display()),
+                Err(e) => tracing::warn!("无法监听音乐文件夹 {}: {}", root.display(), e),
+            }
+        }
+
+        tokio::spawn(async move {
+            // 监听器在任务结束前一直保持运行
+            let _watcher = watcher;
+            self.run(rx).await;
+        });
+
+        Ok(())
+    }
+
+    /// 收集变化的路径，变化停止后扫描；已有扫描进行中或扫描失败时保留这些路径稍后重试
+    async fn run(self, mut rx: mpsc::UnboundedReceiver<PathBuf>) {
+        let mut pending = BTreeSet::new();
+
+        loop {
+            if !collect_changes(&mut rx, &mut pending, self.debounce).await {
+                return;
+            }
+
+            if !self.scan_state.begin().await {
+                tracing::debug!("扫描进行中，稍后处理 {} 个变化的路径", pending.len());
+                continue;
+            }
+
+            let paths: Vec<PathBuf> = std::mem::take(&mut pending).into_iter().collect();
+            tracing::info!("检测到 {} 个路径变化，开始增量扫描", paths.len());
+
+            match self
+                .scan_service
+                .scan_paths(paths.clone(), self.scan_state.clone())
+                .await
+            {
+                Ok(result) => {
+                    tracing::info!("增量扫描完成: {:?}", result);
+                    let changed = result.songs + result.videos + result.deleted > 0;
+                    self.scan_state.finish(changed.then_some(&result)).await;
+                }
+                Err(e) => {
+                    tracing::error!("增量扫描失败，稍后重试: {}", e);
+                    self.scan_state.finish(None).await;
+                    pending.extend(paths);
+                }
+            }
+        }
+    }
+}
+
+/// 等待变化的路径并合并到 `pending`，直到一段时间 (`debounce`) 内没有新的变化
+///
+/// `pending` 中已有路径时不等待新的变化，直接开始去抖；
+/// 监听已停止 (通道关闭) 时返回 `false`
+async fn collect_changes(
+    rx: &mut mpsc::UnboundedReceiver<PathBuf>,
+    pending: &mut BTreeSet<PathBuf>,
+    debounce: Duration,
+) -> bool {
+    if pending.is_empty() {
+        match rx.recv().await {
+            Some(path) => {
+                pending.insert(path);
+            }
+            None => return false,
+        }
+    }
+
+    loop {
+        match tokio::time::timeout(debounce, rx.recv()).await {
+            Ok(Some(path)) => {
+                pending.insert(path);
+            }
+            Ok(None) => return false,
+            Err(_) => return true,
+        }
+    }
+}
+
+/// 是否为文件内容或目录结构的变化 (忽略访问和权限、时间戳等元数据变化)
+fn is_content_change(kind: &EventKind) -> bool {
+    match kind {
+        EventKind::Create(_) | EventKind::Remove(_) => true,
+        EventKind::Modify(ModifyKind::Metadata(_)) => false,
+        EventKind::Modify(_) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, DataChange, MetadataKind, RenameMode};
+
+    #[test]
+    fn test_is_content_change() {
+        assert!(is_content_change(&EventKind::Create(CreateKind::File)));
+        assert!(is_content_change(&EventKind::Modify(ModifyKind::Data(
+            DataChange::Content
+        ))));
+        assert!(is_content_change(&EventKind::Modify(ModifyKind::Name(
+            RenameMode::Both
+        ))));
+        assert!(!is_content_change(&EventKind::Modify(
+            ModifyKind::Metadata(MetadataKind::Permissions)
+        )));
+        assert!(!is_content_change(&EventKind::Access(AccessKind::Read)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_collect_changes_debounce() {
+        let debounce = Duration::from_millis(500);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut pending = BTreeSet::new();
+
+        // 持续变化期间不结束去抖，重复的路径合并
+        let sender = tx.clone();
+        let producer = tokio::spawn(async move {
+            for path in ["/music/a.flac", "/music/b.flac", "/music/a.flac"] {
+                sender.send(PathBuf::from(path)).unwrap();
+                tokio::time::sleep(Duration::from_millis(400)).await;
+            }
+        });
+
+        let start = tokio::time::Instant::now();
+        assert!(collect_changes(&mut rx, &mut pending, debounce).await);
+        // 最后一次变化 (800ms) 之后再等待一个去抖周期
+        assert_eq!(start.elapsed(), Duration::from_millis(1300));
+        assert_eq!(
+            pending,
+            BTreeSet::from([
+                PathBuf::from("/music/a.flac"),
+                PathBuf::from("/music/b.flac")
+            ])
+        );
+        producer.await.unwrap();
+
+        // 保留的路径 (扫描失败或扫描进行中) 不等待新的变化，直接去抖后重试
+        let start = tokio::time::Instant::now();
+        assert!(collect_changes(&mut rx, &mut pending, debounce).await);
+        assert_eq!(start.elapsed(), debounce);
+        assert_eq!(pending.len(), 2);
+
+        // 监听停止
+        drop(tx);
+        pending.clear();
+        assert!(!collect_changes(&mut rx, &mut pending, debounce).await);
+    }
+}
@@ -0,0 +1,252 @@
+//! 登录限流服务
+//!
+//! 按用户名和客户端 IP 分别记录认证失败次数，防止暴力破解:
+//! - 连续失败达到阈值后临时锁定，锁定时长随失败次数指数增长 (有上限)
+//! - 认证成功后清除该用户名的失败记录
+//! - 锁定状态只保存在内存中，服务重启后清空
+
+use chrono::{DateTime, Duration, Utc};
+use std::fmt;
+use std::net::IpAddr;
+
+use crate::config::AppConfig;
+use crate::utils::rate_limiter::{KeyedRecords, TimedRecord};
+
+/// 失败记录的键
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LockoutKey {
+    User(String),
+    Ip(IpAddr),
+}
+
+impl fmt::Display for LockoutKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockoutKey::User(username) => write!(f, "{}", username),
+            LockoutKey::Ip(ip) => write!(f, "{}", ip),
+        }
+    }
+}
+
+/// 失败记录
+#[derive(Debug, Clone)]
+struct FailureRecord {
+    failures: u32,
+    last_failure: DateTime<Utc>,
+    locked_until: Option<DateTime<Utc>>,
+}
+
+impl TimedRecord for FailureRecord {
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.last_failure
+    }
+}
+
+/// 锁定信息
+#[derive(Debug, Clone)]
+pub struct Lockout {
+    pub key: LockoutKey,
+    pub failures: u32,
+    pub locked_until: DateTime<Utc>,
+}
+
+/// 登录限流服务
+pub struct LoginThrottleService {
+    /// 触发锁定的连续失败次数，0 表示不限流
+    max_attempts: u32,
+    /// 首次锁定时长
+    base_lockout: Duration,
+    /// 最长锁定时长
+    max_lockout: Duration,
+    /// 失败记录，长时间没有失败的记录会被清理
+    records: KeyedRecords<LockoutKey, FailureRecord>,
+}
+
+impl LoginThrottleService {
+    /// 创建新的 LoginThrottleService
+    pub fn new(max_attempts: u32, base_lockout_seconds: i64, max_lockout_seconds: i64) -> Self {
+        let max_lockout = Duration::seconds(max_lockout_seconds.max(base_lockout_seconds));
+        Self {
+            max_attempts,
+            base_lockout: Duration::seconds(base_lockout_seconds),
+            max_lockout,
+            records: KeyedRecords::new(max_lockout),
+        }
+    }
+
+    /// 从应用配置创建
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::new(
+            config.login_max_attempts,
+            config.login_lockout_seconds,
+            config.login_lockout_max_seconds,
+        )
+    }
+
+    /// 检查用户名或 IP 是否处于锁定状态，返回剩余锁定秒数
+    pub fn check(&self, keys: &[LockoutKey]) -> Option<i64> {
+        self.check_at(keys, Utc::now())
+    }
+
+    /// 记录一次认证失败
+    pub fn record_failure(&self, keys: &[LockoutKey]) {
+        self.record_failure_at(keys, Utc::now())
+    }
+
+    /// 认证成功，清除用户名的失败记录
+    ///
+    /// IP 的失败记录保留到过期，避免攻击者用自己的账号重置计数
+    pub fn record_success(&self, username: &str) {
+        self.records
+            .lock()
+            .remove(&LockoutKey::User(username.to_string()));
+    }
+
+    /// 获取当前处于锁定状态的记录
+    pub fn lockouts(&self) -> Vec<Lockout> {
+        self.lockouts_at(Utc::now())
+    }
+
+    /// 解除锁定并清除失败记录，未指定键时全部清除，返回清除的记录数
+    pub fn clear(&self, key: Option<&LockoutKey>) -> usize {
+        let mut records = self.records.lock();
+        match key {
+            Some(key) => records.remove(key).map_or(0, |_| 1),
+            None => {
+                let count = records.len();
+                records.clear();
+                count
+            }
+        }
+    }
+
+    fn check_at(&self, keys: &[LockoutKey], now: DateTime<Utc>) -> Option<i64> {
+        let records = self.records.lock();
+        keys.iter()
+            .filter_map(|key| records.get(key)?.locked_until)
+            .filter(|until| *until > now)
+            .map(|until| (until - now).num_seconds().max(1))
+            .max()
+    }
+
+    fn record_failure_at(&self, keys: &[LockoutKey], now: DateTime<Utc>) {
+        if self.max_attempts == 0 {
+            return;
+        }
+
+        let mut records = self.records.lock_pruned(now);
+        for key in keys {
+            let record = records.entry(key.clone()).or_insert(FailureRecord {
+                failures: 0,
+                last_failure: now,
+                locked_until: None,
+            });
+            record.failures += 1;
+            record.last_failure = now;
+
+            if record.failures >= self.max_attempts {
+                record.locked_until = Some(now + self.lockout_duration(record.failures));
+            }
+        }
+    }
+
+    fn lockouts_at(&self, now: DateTime<Utc>) -> Vec<Lockout> {
+        let records = self.records.lock();
+        let mut lockouts: Vec<Lockout> = records
+            .iter()
+            .filter_map(|(key, record)| {
+                let locked_until = record.locked_until.filter(|until| *until > now)?;
+                Some(Lockout {
+                    key: key.clone(),
+                    failures: record.failures,
+                    locked_until,
+                })
+            })
+            .collect();
+        lockouts.sort_by_key(|lockout| lockout.locked_until);
+        lockouts
+    }
+
+    /// 锁定时长: 达到阈值后每多失败一次翻倍
+    fn lockout_duration(&self, failures: u32) -> Duration {
+        let exponent = failures.saturating_sub(self.max_attempts).min(20);
+        let duration = self.base_lockout * 2i32.pow(exponent);
+        duration.min(self.max_lockout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> Vec<LockoutKey> {
+        vec![
+            LockoutKey::User("alice".to_string()),
+            LockoutKey::Ip("10.0.0.1".parse().unwrap()),
+        ]
+    }
+
+    #[test]
+    fn test_lockout_after_max_attempts() {
+        let service = LoginThrottleService::new(3, 30, 3600);
+        let now = Utc::now();
+
+        service.record_failure_at(&keys(), now);
+        service.record_failure_at(&keys(), now);
+        assert_eq!(service.check_at(&keys(), now), None);
+
+        service.record_failure_at(&keys(), now);
+        assert_eq!(service.check_at(&keys(), now), Some(30));
+        // 同一 IP 的其他用户名也被锁定
+        let other_user = [
+            LockoutKey::User("bob".to_string()),
+            LockoutKey::Ip("10.0.0.1".parse().unwrap()),
+        ];
+        assert_eq!(service.check_at(&other_user, now), Some(30));
+        assert_eq!(service.lockouts_at(now).len(), 2);
+
+        // 锁定到期后恢复
+        assert_eq!(service.check_at(&keys(), now + Duration::seconds(31)), None);
+    }
+
+    #[test]
+    fn test_exponential_backoff() {
+        let service = LoginThrottleService::new(2, 10, 60);
+
+        assert_eq!(service.lockout_duration(2), Duration::seconds(10));
+        assert_eq!(service.lockout_duration(3), Duration::seconds(20));
+        assert_eq!(service.lockout_duration(4), Duration::seconds(40));
+        assert_eq!(service.lockout_duration(5), Duration::seconds(60));
+        assert_eq!(service.lockout_duration(100), Duration::seconds(60));
+    }
+
+    #[test]
+    fn test_success_and_clear() {
+        let service = LoginThrottleService::new(1, 30, 3600);
+        let now = Utc::now();
+        let ip = LockoutKey::Ip("10.0.0.1".parse().unwrap());
+
+        service.record_failure_at(&keys(), now);
+        service.record_success("alice");
+        assert_eq!(service.check_at(&keys()[..1], now), None);
+        assert!(service.check_at(&keys()[1..], now).is_some());
+
+        assert_eq!(service.clear(Some(&ip)), 1);
+        assert_eq!(service.check_at(&keys(), now), None);
+
+        service.record_failure_at(&keys(), now);
+        assert_eq!(service.clear(None), 2);
+        assert!(service.lockouts_at(now).is_empty());
+    }
+
+    #[test]
+    fn test_disabled() {
+        let service = LoginThrottleService::new(0, 30, 3600);
+        let now = Utc::now();
+
+        for _ in 0..10 {
+            service.record_failure_at(&keys(), now);
+        }
+        assert_eq!(service.check_at(&keys(), now), None);
+    }
+}
@@ -1,24 +1,52 @@
 //! 业务逻辑服务模块
 #![allow(unused_imports)]
 
+pub mod api_key_service;
 pub mod auth_service;
+pub mod bookmark_service;
 pub mod browsing_service;
 pub mod context;
+pub mod event_service;
+pub mod internet_radio_service;
+pub mod invite_code_service;
+pub mod jukebox_service;
 pub mod library_service;
+pub mod library_watcher;
+pub mod login_throttle_service;
+pub mod music_folder_service;
 pub mod play_queue_service;
 pub mod playlist_service;
+pub mod podcast_service;
 pub mod scan_service;
 pub mod search_service;
+pub mod session_service;
+pub mod share_service;
 pub mod song_service;
+pub mod transcode_service;
 pub mod user_service;
+pub mod video_service;
 
+pub use api_key_service::ApiKeyService;
 pub use auth_service::{AuthService, UserWithToken};
+pub use bookmark_service::{BookmarkDetail, BookmarkService};
 pub use browsing_service::BrowsingService;
 pub use context::ServiceContext;
+pub use event_service::{EventEnvelope, EventService, ServerEvent, Subscription};
+pub use internet_radio_service::InternetRadioService;
+pub use invite_code_service::InviteCodeService;
+pub use jukebox_service::{JukeboxPlaylist, JukeboxService, JukeboxStatus};
 pub use library_service::{LibraryService, StarItemType};
+pub use library_watcher::LibraryWatcher;
+pub use login_throttle_service::{LockoutKey, LoginThrottleService};
+pub use music_folder_service::{FolderScope, MusicFolderService};
 pub use play_queue_service::PlayQueueService;
 pub use playlist_service::PlaylistService;
+pub use podcast_service::{PodcastChannelDetail, PodcastService};
 pub use scan_service::ScanService;
 pub use search_service::SearchService;
+pub use session_service::SessionService;
+pub use share_service::{ShareDetail, ShareService};
 pub use song_service::SongService;
+pub use transcode_service::TranscodeService;
 pub use user_service::UserService;
+pub use video_service::VideoService;
@@ -0,0 +1,738 @@
+//! 音乐文件夹服务
+//!
+//! 负责音乐库根目录相关的业务逻辑:
+//! - 启动时将配置的音乐文件夹同步到数据库
+//! - 按路径回填历史歌曲与专辑所属的文件夹
+//! - 查询音乐文件夹列表
+//! - 清除已从配置中移除的音乐文件夹
+//! - 管理用户可访问的音乐文件夹
+#![allow(dead_code)]
+
+use crate::config::MusicFolderConfig;
+use crate::error::AppError;
+use crate::models::entities::MusicFolder;
+use crate::services::ServiceContext;
+use std::path::MAIN_SEPARATOR;
+use std::sync::Arc;
+
+/// 用户可访问的音乐文件夹范围
+///
+/// 浏览、搜索、播放等查询通过 [`FolderScope::sql_filter`] 限定结果，
+/// 不在范围内的内容视为不存在
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FolderScope {
+    /// 不受限制 (管理员)
+    All,
+    /// 仅限指定的文件夹
+    Folders(Vec<i64>),
+}
+
+impl FolderScope {
+    /// 按请求的 musicFolderId 收窄范围，无权访问该文件夹时结果为空
+    pub fn narrow(&self, music_folder_id: Option<i32>) -> FolderScope {
+        let Some(folder_id) = music_folder_id.map(i64::from) else {
+            return self.clone();
+        };
+
+        if self.allows(Some(folder_id)) {
+            FolderScope::Folders(vec![folder_id])
+        } else {
+            FolderScope::Folders(vec![])
+        }
+    }
+
+    /// 检查文件夹是否在范围内 (不属于任何文件夹的内容仅管理员可见)
+    pub fn allows(&self, folder_id: Option<i64>) -> bool {
+        match self {
+            FolderScope::All => true,
+            FolderScope::Folders(ids) => folder_id.is_some_and(|id| ids.contains(&id)),
+        }
+    }
+
+    /// 生成按文件夹字段过滤的 SQL 条件，例如 `s.music_folder_id IN (1,2)`
+    pub fn sql_filter(&self, column: &str) -> String {
+        match self {
+            FolderScope::All => "1=1".to_string(),
+            FolderScope::Folders(ids) if ids.is_empty() => "0=1".to_string(),
+            FolderScope::Folders(ids) => format!(
+                "{} IN ({})",
+                column,
+                ids.iter()
+                    .map(|id| id.to_string())
+                    .collect::<Vec<_>>()
+                    .join(",")
+            ),
+        }
+    }
+
+    /// 生成艺术家的过滤条件: 在范围内有专辑的艺术家可见
+    pub fn artist_filter(&self, artist_id_column: &str) -> String {
+        match self {
+            FolderScope::All => "1=1".to_string(),
+            FolderScope::Folders(_) => format!(
+                "EXISTS (SELECT 1 FROM albums fa WHERE fa.artist_id = {} AND {})",
+                artist_id_column,
+                self.sql_filter("fa.music_folder_id")
+            ),
+        }
+    }
+}
+
+/// 音乐文件夹服务
+pub struct MusicFolderService {
+    ctx: Arc<ServiceContext>,
+}
+
+impl MusicFolderService {
+    /// 创建新的 MusicFolderService
+    pub fn new(ctx: Arc<ServiceContext>) -> Self {
+        Self { ctx }
+    }
+
+    /// 获取所有音乐文件夹
+    pub async fn get_music_folders(&self) -> Result<Vec<MusicFolder>, AppError> {
+        let folders =
+            sqlx::query_as::<_, MusicFolder>("SELECT * FROM music_folders ORDER BY id ASC")
+                .fetch_all(&self.ctx.pool)
+                .await?;
+
+        Ok(folders)
+    }
+
+    /// 获取用户可访问的音乐文件夹范围，管理员不受限制
+    pub async fn get_folder_scope(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+    ) -> Result<FolderScope, AppError> {
+        if is_admin {
+            return Ok(FolderScope::All);
+        }
+
+        Ok(FolderScope::Folders(
+            self.get_user_folder_ids(user_id).await?,
+        ))
+    }
+
+    /// 获取用户可访问的音乐文件夹 ID
+    pub async fn get_user_folder_ids(&self, user_id: &str) -> Result<Vec<i64>, AppError> {
+        let ids = sqlx::query_scalar::<_, i64>(
+            "SELECT umf.music_folder_id FROM user_music_folders umf
+             JOIN music_folders f ON f.id = umf.music_folder_id
+             WHERE umf.user_id = ? AND f.missing = 0
+             ORDER BY umf.music_folder_id ASC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.ctx.pool)
+        .await?;
+
+        Ok(ids)
+    }
+
+    /// 获取范围内的音乐文件夹 (不含已从配置中移除的文件夹)
+    pub async fn get_music_folders_in_scope(
+        &self,
+        scope: &FolderScope,
+    ) -> Result<Vec<MusicFolder>, AppError> {
+        let folders = sqlx::query_as::<_, MusicFolder>(&format!(
+            "SELECT * FROM music_folders WHERE missing = 0 AND {} ORDER BY id ASC",
+            scope.sql_filter("id")
+        ))
+        .fetch_all(&self.ctx.pool)
+        .await?;
+
+        Ok(folders)
+    }
+
+    /// 检查封面是否在范围内
+    ///
+    /// 封面 ID 为 `al-<专辑ID>`、`ar-<艺术家ID>` 或歌曲/专辑 ID，
+    /// 无法对应到音乐库内容的封面 (例如默认封面) 不受限制
+    pub async fn cover_art_allowed(
+        &self,
+        scope: &FolderScope,
+        cover_art_id: &str,
+    ) -> Result<bool, AppError> {
+        if *scope == FolderScope::All {
+            return Ok(true);
+        }
+
+        let (query, id) = if let Some(album_id) = cover_art_id.strip_prefix("al-") {
+            (
+                format!(
+                    "SELECT {} FROM albums WHERE id = ?",
+                    scope.sql_filter("music_folder_id")
+                ),
+                album_id,
+            )
+        } else if let Some(artist_id) = cover_art_id.strip_prefix("ar-") {
+            (
+                format!(
+                    "SELECT {} FROM artists WHERE id = ?",
+                    scope.artist_filter("artists.id")
+                ),
+                artist_id,
+            )
+        } else {
+            (
+                format!(
+                    "SELECT {} FROM songs WHERE id = ?1
+                     UNION ALL
+                     SELECT {} FROM albums WHERE id = ?1",
+                    scope.sql_filter("music_folder_id"),
+                    scope.sql_filter("music_folder_id")
+                ),
+                cover_art_id,
+            )
+        };
+
+        let allowed = sqlx::query_scalar::<_, bool>(&query)
+            .bind(id)
+            .fetch_optional(&self.ctx.pool)
+            .await?;
+
+        Ok(allowed.unwrap_or(true))
+    }
+
+    /// 检查音乐文件夹是否都存在
+    pub async fn validate_folder_ids(&self, folder_ids: &[i64]) -> Result<(), AppError> {
+        for folder_id in folder_ids {
+            let exists = sqlx::query_scalar::<_, bool>(
+                "SELECT EXISTS(SELECT 1 FROM music_folders WHERE id = ?)",
+            )
+            .bind(folder_id)
+            .fetch_one(&self.ctx.pool)
+            .await?;
+            if !exists {
+                return Err(AppError::not_found("Music folder"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 设置用户可访问的音乐文件夹 (替换原有设置)
+    pub async fn set_user_folders(
+        &self,
+        user_id: &str,
+        folder_ids: &[i64],
+    ) -> Result<(), AppError> {
+        self.validate_folder_ids(folder_ids).await?;
+
+        let mut tx = self.ctx.pool.begin().await?;
+        sqlx::query("DELETE FROM user_music_folders WHERE user_id = ?")
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?;
+        for folder_id in folder_ids {
+            sqlx::query(
+                "INSERT OR IGNORE INTO user_music_folders (user_id, music_folder_id) VALUES (?, ?)",
+            )
+            .bind(user_id)
+            .bind(folder_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 将配置的音乐文件夹同步到数据库
+    ///
+    /// - 新增的文件夹插入数据库，只授权给当前可访问全部文件夹的用户，
+    ///   其他用户需要管理员分配；已存在的文件夹 (按路径匹配) 更新名称
+    /// - 已从配置中移除的文件夹标记为缺失，其专辑、歌曲与用户授权保留，
+    ///   重新加入配置后恢复，只能由管理员通过 [`Self::purge_missing_folders`] 清除
+    /// - 尚未记录文件夹的历史歌曲与专辑按路径前缀回填
+    pub async fn sync_folders(
+        &self,
+        folders: &[MusicFolderConfig],
+    ) -> Result<Vec<MusicFolder>, AppError> {
+        let mut tx = self.ctx.pool.begin().await?;
+
+        let paths: Vec<String> = folders
+            .iter()
+            .map(|folder| folder.path.to_string_lossy().to_string())
+            .collect();
+
+        for (folder, path) in folders.iter().zip(&paths) {
+            let existing_id =
+                sqlx::query_scalar::<_, i64>("SELECT id FROM music_folders WHERE path = ?")
+                    .bind(path)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+
+            match existing_id {
+                Some(id) => {
+                    sqlx::query("UPDATE music_folders SET name = ?, missing = 0 WHERE id = ?")
+                        .bind(&folder.name)
+                        .bind(id)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+                None => {
+                    let id = sqlx::query("INSERT INTO music_folders (name, path) VALUES (?, ?)")
+                        .bind(&folder.name)
+                        .bind(path)
+                        .execute(&mut *tx)
+                        .await?
+                        .last_insert_rowid();
+
+                    // 受限用户的范围不因新增文件夹而扩大
+                    sqlx::query(
+                        "INSERT OR IGNORE INTO user_music_folders (user_id, music_folder_id)
+                         SELECT u.id, ?1 FROM users u
+                         WHERE NOT EXISTS (
+                             SELECT 1 FROM music_folders f
+                             WHERE f.id != ?1 AND f.missing = 0
+                               AND NOT EXISTS (
+                                   SELECT 1 FROM user_music_folders umf
+                                   WHERE umf.user_id = u.id AND umf.music_folder_id = f.id
+                               )
+                         )",
+                    )
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+        }
+
+        let existing = sqlx::query_as::<_, MusicFolder>("SELECT * FROM music_folders")
+            .fetch_all(&mut *tx)
+            .await?;
+
+        let mut synced = Vec::with_capacity(folders.len());
+        for folder in existing {
+            if paths.contains(&folder.path) {
+                synced.push(folder);
+                continue;
+            }
+
+            if !folder.missing {
+                tracing::warn!(
+                    "音乐文件夹已从配置中移除，标记为缺失: {} ({})",
+                    folder.name,
+                    folder.path
+                );
+                sqlx::query("UPDATE music_folders SET missing = 1 WHERE id = ?")
+                    .bind(folder.id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        // 回填历史歌曲 (按文件路径前缀匹配)
+        for folder in &synced {
+            let prefix = format!(
+                "{}{}",
+                folder.path.trim_end_matches(MAIN_SEPARATOR),
+                MAIN_SEPARATOR
+            );
+            sqlx::query(
+                "UPDATE songs SET music_folder_id = ?
+                 WHERE music_folder_id IS NULL AND substr(file_path, 1, length(?)) = ?",
+            )
+            .bind(folder.id)
+            .bind(&prefix)
+            .bind(&prefix)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        // 专辑跟随其歌曲所在的文件夹
+        sqlx::query(
+            "UPDATE albums SET music_folder_id = (
+                SELECT s.music_folder_id FROM songs s
+                WHERE s.album_id = albums.id AND s.music_folder_id IS NOT NULL
+                LIMIT 1
+             )
+             WHERE music_folder_id IS NULL",
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // 清理没有专辑的艺术家
+        sqlx::query("DELETE FROM artists WHERE id NOT IN (SELECT DISTINCT artist_id FROM albums)")
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        synced.sort_by_key(|folder| folder.id);
+        Ok(synced)
+    }
+
+    /// 清除已从配置中移除的音乐文件夹及其专辑和歌曲
+    ///
+    /// 指定 `folder_id` 时只清除该文件夹，文件夹不存在或仍在配置中时返回错误；
+    /// 未指定时清除全部缺失的文件夹。返回清除的文件夹数。
+    pub async fn purge_missing_folders(&self, folder_id: Option<i64>) -> Result<usize, AppError> {
+        let mut tx = self.ctx.pool.begin().await?;
+
+        let folders = match folder_id {
+            Some(id) => {
+                let folder =
+                    sqlx::query_as::<_, MusicFolder>("SELECT * FROM music_folders WHERE id = ?")
+                        .bind(id)
+                        .fetch_optional(&mut *tx)
+                        .await?
+                        .ok_or_else(|| AppError::not_found("Music folder"))?;
+                if !folder.missing {
+                    return Err(AppError::validation_error(
+                        "Music folder is still configured",
+                    ));
+                }
+                vec![folder]
+            }
+            None => {
+                sqlx::query_as::<_, MusicFolder>("SELECT * FROM music_folders WHERE missing = 1")
+                    .fetch_all(&mut *tx)
+                    .await?
+            }
+        };
+
+        for folder in &folders {
+            tracing::info!("清除音乐文件夹: {} ({})", folder.name, folder.path);
+            sqlx::query("DELETE FROM user_music_folders WHERE music_folder_id = ?")
+                .bind(folder.id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("DELETE FROM songs WHERE music_folder_id = ?")
+                .bind(folder.id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("DELETE FROM albums WHERE music_folder_id = ?")
+                .bind(folder.id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("DELETE FROM music_folders WHERE id = ?")
+                .bind(folder.id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        // 清理没有专辑的艺术家
+        sqlx::query("DELETE FROM artists WHERE id NOT IN (SELECT DISTINCT artist_id FROM albums)")
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(folders.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::SqlitePool;
+    use std::path::PathBuf;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+        sqlx::query(
+            "CREATE TABLE music_folders (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                name TEXT NOT NULL,
+                path TEXT NOT NULL UNIQUE,
+                missing BOOLEAN NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query("CREATE TABLE users (id TEXT PRIMARY KEY)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        sqlx::query(
+            "CREATE TABLE user_music_folders (
+                user_id TEXT NOT NULL,
+                music_folder_id INTEGER NOT NULL,
+                PRIMARY KEY (user_id, music_folder_id)
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query("CREATE TABLE artists (id TEXT PRIMARY KEY, name TEXT NOT NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        sqlx::query(
+            "CREATE TABLE albums (
+                id TEXT PRIMARY KEY,
+                artist_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                music_folder_id INTEGER
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "CREATE TABLE songs (
+                id TEXT PRIMARY KEY,
+                album_id TEXT NOT NULL,
+                artist_id TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                music_folder_id INTEGER
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // 历史数据: 尚未记录所属文件夹
+        sqlx::query("INSERT INTO artists (id, name) VALUES ('ar1', 'Bach'), ('ar2', 'Raffi')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO albums (id, artist_id, name) VALUES ('al1', 'ar1', 'Cello Suites'), ('al2', 'ar2', 'Singable Songs')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO songs (id, album_id, artist_id, file_path) VALUES
+                ('s1', 'al1', 'ar1', '/music/classical/bach/1.flac'),
+                ('s2', 'al2', 'ar2', '/music/kids/raffi/1.mp3')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    fn folder(name: &str, path: &str) -> MusicFolderConfig {
+        MusicFolderConfig {
+            name: name.to_string(),
+            path: PathBuf::from(path),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sync_folders_backfills_existing_songs() {
+        let pool = setup_test_db().await;
+        let service = MusicFolderService::new(Arc::new(ServiceContext::new(pool.clone())));
+
+        let folders = service
+            .sync_folders(&[
+                folder("Classical", "/music/classical"),
+                folder("Kids", "/music/kids/"),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(folders.len(), 2);
+
+        let song_folder: Option<i64> =
+            sqlx::query_scalar("SELECT music_folder_id FROM songs WHERE id = 's1'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(song_folder, Some(folders[0].id));
+
+        let album_folder: Option<i64> =
+            sqlx::query_scalar("SELECT music_folder_id FROM albums WHERE id = 'al2'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(album_folder, Some(folders[1].id));
+
+        // 再次同步时按路径更新名称，ID 保持不变
+        let renamed = service
+            .sync_folders(&[
+                folder("Classical Music", "/music/classical"),
+                folder("Kids", "/music/kids/"),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(renamed[0].id, folders[0].id);
+        assert_eq!(renamed[0].name, "Classical Music");
+    }
+
+    #[tokio::test]
+    async fn test_sync_folders_marks_dropped_folder_missing() {
+        let pool = setup_test_db().await;
+        sqlx::query("INSERT INTO users (id) VALUES ('kid')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let service = MusicFolderService::new(Arc::new(ServiceContext::new(pool.clone())));
+
+        let all = [
+            folder("Classical", "/music/classical"),
+            folder("Kids", "/music/kids"),
+        ];
+        let initial = service.sync_folders(&all).await.unwrap();
+        let kids_folder = initial[1].id;
+
+        let folders = service
+            .sync_folders(&[folder("Classical", "/music/classical")])
+            .await
+            .unwrap();
+        assert_eq!(folders.len(), 1);
+
+        // 缺失的文件夹不再可见，但内容与授权保留
+        let stored = service.get_music_folders().await.unwrap();
+        assert_eq!(stored.len(), 2);
+        assert!(stored[1].missing);
+        assert_eq!(
+            service
+                .get_music_folders_in_scope(&FolderScope::All)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+        assert_eq!(
+            service.get_user_folder_ids("kid").await.unwrap(),
+            vec![initial[0].id]
+        );
+        let songs: Vec<String> = sqlx::query_scalar("SELECT id FROM songs ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(songs, vec!["s1", "s2"]);
+
+        // 重新加入配置后恢复
+        service.sync_folders(&all).await.unwrap();
+        assert_eq!(
+            service.get_user_folder_ids("kid").await.unwrap(),
+            vec![initial[0].id, kids_folder]
+        );
+
+        // 仍在配置中的文件夹不能清除
+        service
+            .sync_folders(&[folder("Classical", "/music/classical")])
+            .await
+            .unwrap();
+        assert!(service
+            .purge_missing_folders(Some(initial[0].id))
+            .await
+            .is_err());
+        assert_eq!(service.purge_missing_folders(None).await.unwrap(), 1);
+        assert_eq!(service.get_music_folders().await.unwrap().len(), 1);
+
+        let songs: Vec<String> = sqlx::query_scalar("SELECT id FROM songs")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(songs, vec!["s1"]);
+
+        let artists: Vec<String> = sqlx::query_scalar("SELECT id FROM artists")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(artists, vec!["ar1"]);
+    }
+
+    #[test]
+    fn test_folder_scope_filter() {
+        assert_eq!(FolderScope::All.sql_filter("s.music_folder_id"), "1=1");
+        assert_eq!(FolderScope::All.artist_filter("ar.id"), "1=1");
+        assert_eq!(FolderScope::Folders(vec![]).sql_filter("id"), "0=1");
+        assert_eq!(
+            FolderScope::Folders(vec![1, 3]).sql_filter("s.music_folder_id"),
+            "s.music_folder_id IN (1,3)"
+        );
+
+        let scope = FolderScope::Folders(vec![1, 3]);
+        assert!(scope.allows(Some(3)));
+        assert!(!scope.allows(Some(2)));
+        assert!(!scope.allows(None));
+        assert!(FolderScope::All.allows(None));
+
+        // musicFolderId 只能收窄范围
+        assert_eq!(scope.narrow(None), scope);
+        assert_eq!(scope.narrow(Some(3)), FolderScope::Folders(vec![3]));
+        assert_eq!(scope.narrow(Some(2)), FolderScope::Folders(vec![]));
+        assert_eq!(
+            FolderScope::All.narrow(Some(2)),
+            FolderScope::Folders(vec![2])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_user_folders() {
+        let pool = setup_test_db().await;
+        sqlx::query("INSERT INTO users (id) VALUES ('adult'), ('kid')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let service = MusicFolderService::new(Arc::new(ServiceContext::new(pool.clone())));
+
+        // 新增的文件夹授权给所有现有用户
+        let folders = service
+            .sync_folders(&[
+                folder("Classical", "/music/classical"),
+                folder("Kids", "/music/kids"),
+            ])
+            .await
+            .unwrap();
+        let all_ids: Vec<i64> = folders.iter().map(|f| f.id).collect();
+        assert_eq!(service.get_user_folder_ids("kid").await.unwrap(), all_ids);
+
+        let kids_folder = folders[1].id;
+        service
+            .set_user_folders("kid", &[kids_folder])
+            .await
+            .unwrap();
+        let scope = service.get_folder_scope("kid", false).await.unwrap();
+        assert_eq!(scope, FolderScope::Folders(vec![kids_folder]));
+        assert_eq!(
+            service.get_music_folders_in_scope(&scope).await.unwrap()[0].name,
+            "Kids"
+        );
+        assert_eq!(
+            service.get_folder_scope("kid", true).await.unwrap(),
+            FolderScope::All
+        );
+
+        // 不存在的文件夹不能授权，原有设置保持不变
+        assert!(service.set_user_folders("kid", &[999]).await.is_err());
+        assert_eq!(
+            service.get_user_folder_ids("kid").await.unwrap(),
+            vec![kids_folder]
+        );
+
+        // 重新同步已有文件夹不会扩大受限用户的范围
+        service
+            .sync_folders(&[
+                folder("Classical", "/music/classical"),
+                folder("Kids", "/music/kids"),
+            ])
+            .await
+            .unwrap();
+        assert_eq!(
+            service.get_user_folder_ids("kid").await.unwrap(),
+            vec![kids_folder]
+        );
+
+        // 新增的文件夹只授权给可访问全部文件夹的用户
+        let folders = service
+            .sync_folders(&[
+                folder("Classical", "/music/classical"),
+                folder("Kids", "/music/kids"),
+                folder("Jazz", "/music/jazz"),
+            ])
+            .await
+            .unwrap();
+        let all_ids: Vec<i64> = folders.iter().map(|f| f.id).collect();
+        assert_eq!(service.get_user_folder_ids("adult").await.unwrap(), all_ids);
+        assert_eq!(
+            service.get_user_folder_ids("kid").await.unwrap(),
+            vec![kids_folder]
+        );
+    }
+}
@@ -8,7 +8,7 @@
 use crate::error::AppError;
 use crate::models::dto::SongDetailDto;
 use crate::models::entities::{PlayQueue, PlayQueueSong};
-use crate::services::ServiceContext;
+use crate::services::{FolderScope, ServiceContext};
 use crate::utils::{id_builder, sql_utils};
 use chrono::Utc;
 use futures::FutureExt;
@@ -37,17 +37,18 @@ impl PlayQueueService {
     }
 
     /// 获取用户的播放队列
+    ///
+    /// 不在用户可访问音乐文件夹中的歌曲不返回
     pub async fn get_play_queue(
         &self,
         user_id: &str,
+        scope: &FolderScope,
     ) -> Result<Option<PlayQueueDetail>, AppError> {
         // 查询播放队列主记录
-        let queue = sqlx::query_as::<_, PlayQueue>(
-            "SELECT * FROM play_queue WHERE user_id = ?",
-        )
-        .bind(user_id)
-        .fetch_optional(&self.ctx.pool)
-        .await?;
+        let queue = sqlx::query_as::<_, PlayQueue>("SELECT * FROM play_queue WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_optional(&self.ctx.pool)
+            .await?;
 
         let queue = match queue {
             Some(q) => q,
@@ -61,13 +62,15 @@ impl PlayQueueService {
             .await?;
 
         // 查询播放队列中的歌曲（按顺序）
-        let songs = sqlx::query_as::<_, SongDetailDto>(
-            &format!(r#"{}
+        let songs = sqlx::query_as::<_, SongDetailDto>(&format!(
+            r#"{}
             JOIN play_queue_songs as pqs ON pqs.song_id = s.id
-            WHERE pqs.play_queue_id = ?
+            WHERE pqs.play_queue_id = ? AND {}
             ORDER BY pqs.song_order ASC
-            "#, sql_utils::detail_sql()),
-        )
+            "#,
+            sql_utils::detail_sql(),
+            scope.sql_filter("s.music_folder_id")
+        ))
         .bind(&queue.id)
         .fetch_all(&self.ctx.pool)
         .await?;
@@ -172,7 +175,7 @@ impl PlayQueueService {
                         .bind(&pqs.id)
                         .bind(&pqs.play_queue_id)
                         .bind(&pqs.song_id)
-                        .bind(&pqs.song_order)
+                        .bind(pqs.song_order)
                         .execute(&mut **tx)
                         .await?;
                     }
@@ -7,8 +7,8 @@
 //! - 统计信息更新
 
 use crate::error::AppError;
-use crate::models::dto::{CreatePlaylistRequest, SongDto, UpdatePlaylistRequest, SongDetailDto};
-use crate::services::ServiceContext;
+use crate::models::dto::{CreatePlaylistRequest, SongDetailDto, SongDto, UpdatePlaylistRequest};
+use crate::services::{FolderScope, ServiceContext};
 use crate::utils::id_builder;
 use futures::FutureExt;
 use std::sync::Arc;
@@ -112,9 +112,11 @@ impl PlaylistService {
     /// # 参数
     ///
     /// * `playlist_id` - 播放列表 ID
+    /// * `scope` - 用户可访问的音乐文件夹，范围外的歌曲不返回
     pub async fn get_playlist_detail(
         &self,
         playlist_id: &str,
+        scope: &FolderScope,
     ) -> Result<PlaylistDetailInfo, AppError> {
         // 获取基本信息
         let (id, name, owner_id, is_public, song_count, duration) =
@@ -129,7 +131,7 @@ impl PlaylistService {
             .ok_or_else(|| AppError::not_found("Playlist"))?;
 
         // 获取歌曲列表
-        let songs = sqlx::query_as::<_, SongDetailDto>(
+        let songs = sqlx::query_as::<_, SongDetailDto>(&format!(
             "SELECT s.id, s.title, 
                     ar.name as artist, 
                     s.artist_id, 
@@ -142,9 +144,10 @@ impl PlaylistService {
                 JOIN songs s ON ps.song_id = s.id
                 JOIN albums al ON s.album_id = al.id
                 JOIN artists ar ON s.artist_id = ar.id
-                WHERE ps.playlist_id = ?
+                WHERE ps.playlist_id = ? AND {}
                 ORDER BY ps.position",
-        )
+            scope.sql_filter("s.music_folder_id")
+        ))
         .bind(playlist_id)
         .fetch_all(&self.ctx.pool)
         .await?;
@@ -0,0 +1,947 @@
+//! 播客服务
+//!
+//! 负责播客相关的业务逻辑:
+//! - 订阅、删除频道，刷新时解析 RSS 订阅源并新增单集
+//! - 后台下载队列: 单集状态置为 `downloading` 即排队，由下载任务逐个下载
+//! - 下载完成后按保留数量删除频道中最旧的已下载单集
+//! - 单集下载有总时长和大小上限，超出时单集标记为下载失败
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::models::entities::{PodcastChannel, PodcastEpisode};
+use crate::services::ServiceContext;
+use crate::utils::id_builder;
+use crate::utils::podcast_feed::{self, PodcastFeed};
+use anyhow::Context;
+use chrono::Utc;
+use futures::StreamExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Notify;
+
+/// 获取订阅源的超时时间
+const FEED_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 订阅源大小上限 (字节)
+const MAX_FEED_SIZE: u64 = 10 * 1024 * 1024;
+
+/// 播客频道详细信息（包含单集列表）
+#[derive(Debug)]
+pub struct PodcastChannelDetail {
+    pub channel: PodcastChannel,
+    pub episodes: Vec<PodcastEpisode>,
+}
+
+/// 播客服务
+pub struct PodcastService {
+    ctx: Arc<ServiceContext>,
+    client: reqwest::Client,
+    /// 单集下载目录，每个频道一个子目录
+    podcast_dir: PathBuf,
+    /// 每个频道保留的已下载单集数，0 表示不限制
+    retention: u32,
+    /// 刷新时自动下载的最新单集数
+    auto_download: u32,
+    /// 单集下载的总时长上限，`None` 表示不限制
+    download_timeout: Option<Duration>,
+    /// 单集文件大小上限 (字节)，`None` 表示不限制
+    max_episode_size: Option<u64>,
+    /// 订阅源大小上限 (字节)
+    max_feed_size: u64,
+    /// 唤醒下载任务
+    download_signal: Notify,
+}
+
+impl PodcastService {
+    /// 创建新的 PodcastService
+    pub fn new(
+        ctx: Arc<ServiceContext>,
+        podcast_dir: PathBuf,
+        retention: u32,
+        auto_download: u32,
+    ) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .user_agent(concat!("musicflow/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            ctx,
+            client,
+            podcast_dir,
+            retention,
+            auto_download,
+            download_timeout: None,
+            max_episode_size: None,
+            max_feed_size: MAX_FEED_SIZE,
+            download_signal: Notify::new(),
+        }
+    }
+
+    /// 设置单集下载的总时长和大小上限，0 表示不限制
+    pub fn with_download_limits(mut self, timeout: Duration, max_episode_size: u64) -> Self {
+        self.download_timeout = (!timeout.is_zero()).then_some(timeout);
+        self.max_episode_size = (max_episode_size > 0).then_some(max_episode_size);
+        self
+    }
+
+    /// 根据应用配置创建
+    pub fn from_config(ctx: Arc<ServiceContext>, config: &AppConfig) -> Self {
+        Self::new(
+            ctx,
+            config.podcast_dir.clone(),
+            config.podcast_retention,
+            config.podcast_auto_download,
+        )
+        .with_download_limits(
+            Duration::from_secs(config.podcast_download_timeout_minutes * 60),
+            config.podcast_max_episode_size_mb * 1024 * 1024,
+        )
+    }
+
+    /// 获取频道列表
+    ///
+    /// # 参数
+    ///
+    /// * `channel_id` - 只返回指定频道，不存在时返回 NotFound
+    /// * `include_episodes` - 是否包含单集列表
+    pub async fn get_channels(
+        &self,
+        channel_id: Option<&str>,
+        include_episodes: bool,
+    ) -> Result<Vec<PodcastChannelDetail>, AppError> {
+        let channels = match channel_id {
+            Some(id) => vec![self.get_channel(id).await?],
+            None => {
+                sqlx::query_as::<_, PodcastChannel>(
+                    "SELECT * FROM podcast_channels ORDER BY title COLLATE NOCASE, url",
+                )
+                .fetch_all(&self.ctx.pool)
+                .await?
+            }
+        };
+
+        let mut details = Vec::with_capacity(channels.len());
+        for channel in channels {
+            let episodes = if include_episodes {
+                sqlx::query_as::<_, PodcastEpisode>(
+                    "SELECT * FROM podcast_episodes WHERE channel_id = ?
+                     ORDER BY publish_date DESC, created_at DESC",
+                )
+                .bind(&channel.id)
+                .fetch_all(&self.ctx.pool)
+                .await?
+            } else {
+                vec![]
+            };
+            details.push(PodcastChannelDetail { channel, episodes });
+        }
+
+        Ok(details)
+    }
+
+    /// 获取所有频道中最新发布的单集 (不含已删除的单集)
+    pub async fn get_newest_episodes(&self, count: i64) -> Result<Vec<PodcastEpisode>, AppError> {
+        let episodes = sqlx::query_as::<_, PodcastEpisode>(
+            "SELECT * FROM podcast_episodes WHERE status != 'deleted'
+             ORDER BY publish_date DESC, created_at DESC LIMIT ?",
+        )
+        .bind(count.max(0))
+        .fetch_all(&self.ctx.pool)
+        .await?;
+
+        Ok(episodes)
+    }
+
+    /// 订阅频道 (需随后调用 `refresh_channel` 获取单集)
+    pub async fn create_channel(&self, url: &str) -> Result<PodcastChannel, AppError> {
+        let url = url.trim();
+        if !matches!(
+            reqwest::Url::parse(url)
+                .map(|parsed| parsed.scheme().to_string())
+                .as_deref(),
+            Ok("http" | "https")
+        ) {
+            return Err(AppError::validation_error(
+                "url must be an http or https URL",
+            ));
+        }
+
+        let exists: Option<String> =
+            sqlx::query_scalar("SELECT id FROM podcast_channels WHERE url = ?")
+                .bind(url)
+                .fetch_optional(&self.ctx.pool)
+                .await?;
+        if exists.is_some() {
+            return Err(AppError::validation_error("Podcast channel already exists"));
+        }
+
+        let id = id_builder::generate_id();
+        sqlx::query("INSERT INTO podcast_channels (id, url, created_at) VALUES (?, ?, ?)")
+            .bind(&id)
+            .bind(url)
+            .bind(Utc::now())
+            .execute(&self.ctx.pool)
+            .await?;
+
+        self.get_channel(&id).await
+    }
+
+    /// 刷新频道: 获取并解析订阅源，新增单集并按配置排队自动下载
+    ///
+    /// 获取或解析订阅源失败时记录在频道的错误信息中，不作为错误返回
+    pub async fn refresh_channel(&self, channel_id: &str) -> Result<(), AppError> {
+        let channel = self.get_channel(channel_id).await?;
+
+        let feed = match self.fetch_feed(&channel.url).await {
+            Ok(feed) => feed,
+            Err(e) => {
+                tracing::warn!("Failed to refresh podcast {}: {:#}", channel.url, e);
+                sqlx::query(
+                    "UPDATE podcast_channels
+                     SET status = 'error', error_message = ?, last_refreshed_at = ?
+                     WHERE id = ?",
+                )
+                .bind(format!("{:#}", e))
+                .bind(Utc::now())
+                .bind(channel_id)
+                .execute(&self.ctx.pool)
+                .await?;
+                return Ok(());
+            }
+        };
+
+        let now = Utc::now();
+        let mut tx = self.ctx.pool.begin().await?;
+        sqlx::query(
+            "UPDATE podcast_channels
+             SET title = ?, description = ?, image_url = ?, status = 'completed',
+                 error_message = NULL, last_refreshed_at = ?
+             WHERE id = ?",
+        )
+        .bind(Some(feed.title.as_str()).filter(|title| !title.is_empty()))
+        .bind(&feed.description)
+        .bind(&feed.image_url)
+        .bind(now)
+        .bind(channel_id)
+        .execute(&mut *tx)
+        .await?;
+
+        // 已存在的单集 (包括已删除的) 保持不变
+        for episode in &feed.episodes {
+            sqlx::query(
+                "INSERT OR IGNORE INTO podcast_episodes
+                 (id, channel_id, guid, title, description, stream_url, content_type,
+                  file_size, duration, publish_date, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(id_builder::generate_id())
+            .bind(channel_id)
+            .bind(&episode.guid)
+            .bind(&episode.title)
+            .bind(&episode.description)
+            .bind(&episode.enclosure_url)
+            .bind(&episode.content_type)
+            .bind(episode.size)
+            .bind(episode.duration)
+            .bind(episode.publish_date)
+            .bind(now)
+            .bind(now)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await?;
+
+        // 自动下载最新的单集 (手动删除过的不再下载)
+        if self.auto_download > 0 {
+            let newest: Vec<(String, String)> = sqlx::query_as(
+                "SELECT id, status FROM podcast_episodes WHERE channel_id = ?
+                 ORDER BY publish_date DESC, created_at DESC LIMIT ?",
+            )
+            .bind(channel_id)
+            .bind(self.auto_download)
+            .fetch_all(&self.ctx.pool)
+            .await?;
+
+            for (id, status) in newest {
+                if status == "new" {
+                    self.queue_download(&id).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// 刷新所有频道，返回刷新的频道数
+    pub async fn refresh_all(&self) -> Result<usize, AppError> {
+        let ids: Vec<String> = sqlx::query_scalar("SELECT id FROM podcast_channels")
+            .fetch_all(&self.ctx.pool)
+            .await?;
+
+        for id in &ids {
+            self.refresh_channel(id).await?;
+        }
+
+        Ok(ids.len())
+    }
+
+    /// 删除频道及其所有已下载的单集
+    pub async fn delete_channel(&self, channel_id: &str) -> Result<(), AppError> {
+        let result = sqlx::query("DELETE FROM podcast_channels WHERE id = ?")
+            .bind(channel_id)
+            .execute(&self.ctx.pool)
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::not_found("Podcast channel"));
+        }
+
+        match tokio::fs::remove_dir_all(self.channel_dir(channel_id)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                tracing::warn!("Failed to remove podcast directory {}: {}", channel_id, e)
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// 将单集加入下载队列，已下载或已在队列中时不做处理
+    pub async fn queue_download(&self, episode_id: &str) -> Result<(), AppError> {
+        let episode = self.get_episode(episode_id).await?;
+        if matches!(episode.status.as_str(), "completed" | "downloading") {
+            return Ok(());
+        }
+
+        sqlx::query(
+            "UPDATE podcast_episodes SET status = 'downloading', error_message = NULL, updated_at = ?
+             WHERE id = ?",
+        )
+        .bind(Utc::now())
+        .bind(episode_id)
+        .execute(&self.ctx.pool)
+        .await?;
+        self.download_signal.notify_one();
+
+        Ok(())
+    }
+
+    /// 删除已下载的单集文件，单集保留为已删除状态 (刷新时不会重新添加)
+    pub async fn delete_episode(&self, episode_id: &str) -> Result<(), AppError> {
+        let episode = self.get_episode(episode_id).await?;
+        self.mark_deleted(&episode.id, episode.file_path.as_deref())
+            .await
+    }
+
+    /// 下载任务: 逐个下载队列中的单集，队列为空时等待唤醒
+    ///
+    /// 启动时会继续下载上次未完成的单集
+    pub async fn run_download_worker(self: Arc<Self>) {
+        loop {
+            if let Err(e) = self.process_download_queue().await {
+                tracing::warn!("Podcast download queue failed: {}", e);
+            }
+            self.download_signal.notified().await;
+        }
+    }
+
+    /// 下载队列中的所有单集，返回成功下载的数量
+    pub async fn process_download_queue(&self) -> Result<usize, AppError> {
+        let mut downloaded = 0;
+        let mut failed: Vec<String> = Vec::new();
+
+        loop {
+            let episode = sqlx::query_as::<_, PodcastEpisode>(
+                "SELECT * FROM podcast_episodes WHERE status = 'downloading'
+                 ORDER BY updated_at, id",
+            )
+            .fetch_all(&self.ctx.pool)
+            .await?
+            .into_iter()
+            .find(|episode| !failed.contains(&episode.id));
+            let Some(episode) = episode else {
+                break;
+            };
+
+            match self.download(&episode).await {
+                Ok((path, size, content_type)) => {
+                    let result = sqlx::query(
+                        "UPDATE podcast_episodes
+                         SET status = 'completed', file_path = ?, file_size = ?,
+                             content_type = COALESCE(content_type, ?), updated_at = ?
+                         WHERE id = ? AND status = 'downloading'",
+                    )
+                    .bind(path.to_string_lossy().to_string())
+                    .bind(size)
+                    .bind(content_type)
+                    .bind(Utc::now())
+                    .bind(&episode.id)
+                    .execute(&self.ctx.pool)
+                    .await?;
+
+                    if result.rows_affected() == 0 {
+                        // 下载期间单集或频道已被删除
+                        let _ = tokio::fs::remove_file(&path).await;
+                        continue;
+                    }
+                    downloaded += 1;
+                    self.apply_retention(&episode.channel_id).await?;
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to download podcast episode {}: {:#}", episode.id, e);
+                    sqlx::query(
+                        "UPDATE podcast_episodes SET status = 'error', error_message = ?, updated_at = ?
+                         WHERE id = ? AND status = 'downloading'",
+                    )
+                    .bind(format!("{:#}", e))
+                    .bind(Utc::now())
+                    .bind(&episode.id)
+                    .execute(&self.ctx.pool)
+                    .await?;
+                    failed.push(episode.id);
+                }
+            }
+        }
+
+        Ok(downloaded)
+    }
+
+    async fn get_channel(&self, channel_id: &str) -> Result<PodcastChannel, AppError> {
+        sqlx::query_as::<_, PodcastChannel>("SELECT * FROM podcast_channels WHERE id = ?")
+            .bind(channel_id)
+            .fetch_optional(&self.ctx.pool)
+            .await?
+            .ok_or_else(|| AppError::not_found("Podcast channel"))
+    }
+
+    async fn get_episode(&self, episode_id: &str) -> Result<PodcastEpisode, AppError> {
+        sqlx::query_as::<_, PodcastEpisode>("SELECT * FROM podcast_episodes WHERE id = ?")
+            .bind(episode_id)
+            .fetch_optional(&self.ctx.pool)
+            .await?
+            .ok_or_else(|| AppError::not_found("Podcast episode"))
+    }
+
+    /// 获取并解析订阅源
+    ///
+    /// 边接收边检查大小，超过上限 (Content-Length 或实际接收的字节数) 时返回错误
+    async fn fetch_feed(&self, url: &str) -> anyhow::Result<PodcastFeed> {
+        let response = self
+            .client
+            .get(url)
+            .timeout(FEED_TIMEOUT)
+            .send()
+            .await
+            .context("Failed to fetch feed")?
+            .error_for_status()?;
+        if let Some(length) = response.content_length() {
+            if length > self.max_feed_size {
+                anyhow::bail!(
+                    "Feed is too large: {} bytes (limit {})",
+                    length,
+                    self.max_feed_size
+                );
+            }
+        }
+
+        let mut body = Vec::new();
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.context("Failed to fetch feed")?;
+            if (body.len() + chunk.len()) as u64 > self.max_feed_size {
+                anyhow::bail!("Feed is too large: exceeds {} bytes", self.max_feed_size);
+            }
+            body.extend_from_slice(&chunk);
+        }
+
+        podcast_feed::parse_feed(&String::from_utf8_lossy(&body))
+    }
+
+    /// 下载单集到频道目录，返回文件路径、大小和响应的 Content-Type
+    ///
+    /// 超过总时长或大小上限 (Content-Length 或实际接收的字节数) 时返回错误
+    async fn download(
+        &self,
+        episode: &PodcastEpisode,
+    ) -> anyhow::Result<(PathBuf, i64, Option<String>)> {
+        let mut request = self.client.get(&episode.stream_url);
+        if let Some(timeout) = self.download_timeout {
+            // 包括接收响应内容的时间
+            request = request.timeout(timeout);
+        }
+        let response = request
+            .send()
+            .await
+            .context("Failed to request episode")?
+            .error_for_status()?;
+        if let (Some(max_size), Some(length)) = (self.max_episode_size, response.content_length()) {
+            if length > max_size {
+                anyhow::bail!(
+                    "Episode is too large: {} bytes (limit {})",
+                    length,
+                    max_size
+                );
+            }
+        }
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.split(';').next().unwrap_or(value).trim().to_string())
+            .filter(|value| !value.is_empty());
+
+        let dir = self.channel_dir(&episode.channel_id);
+        tokio::fs::create_dir_all(&dir).await?;
+        let suffix = episode_suffix(
+            &episode.stream_url,
+            episode.content_type.as_deref().or(content_type.as_deref()),
+        );
+        let path = dir.join(format!("{}.{}", episode.id, suffix));
+        let part_path = dir.join(format!("{}.part", episode.id));
+
+        // 先写入临时文件，完成后再重命名，避免播放不完整的文件
+        let mut file = tokio::fs::File::create(&part_path).await?;
+        let mut size: i64 = 0;
+        let mut stream = response.bytes_stream();
+        let result: anyhow::Result<()> = async {
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk.context("Failed to download episode")?;
+                size += chunk.len() as i64;
+                if let Some(max_size) = self.max_episode_size {
+                    if size as u64 > max_size {
+                        anyhow::bail!("Episode is too large: exceeds {} bytes", max_size);
+                    }
+                }
+                file.write_all(&chunk).await?;
+            }
+            file.flush().await?;
+            Ok(())
+        }
+        .await;
+        drop(file);
+
+        if let Err(e) = result {
+            let _ = tokio::fs::remove_file(&part_path).await;
+            return Err(e);
+        }
+        tokio::fs::rename(&part_path, &path).await?;
+
+        Ok((path, size, content_type))
+    }
+
+    /// 删除超出保留数量的已下载单集 (按发布时间保留最新的)
+    async fn apply_retention(&self, channel_id: &str) -> Result<(), AppError> {
+        if self.retention == 0 {
+            return Ok(());
+        }
+
+        let expired: Vec<(String, Option<String>)> = sqlx::query_as(
+            "SELECT id, file_path FROM podcast_episodes
+             WHERE channel_id = ? AND status = 'completed'
+             ORDER BY publish_date DESC, created_at DESC
+             LIMIT -1 OFFSET ?",
+        )
+        .bind(channel_id)
+        .bind(self.retention)
+        .fetch_all(&self.ctx.pool)
+        .await?;
+
+        for (id, file_path) in expired {
+            tracing::info!("Removing podcast episode {} (retention)", id);
+            self.mark_deleted(&id, file_path.as_deref()).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn mark_deleted(
+        &self,
+        episode_id: &str,
+        file_path: Option<&str>,
+    ) -> Result<(), AppError> {
+        if let Some(file_path) = file_path {
+            match tokio::fs::remove_file(file_path).await {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+
+        sqlx::query(
+            "UPDATE podcast_episodes SET status = 'deleted', file_path = NULL, updated_at = ?
+             WHERE id = ?",
+        )
+        .bind(Utc::now())
+        .bind(episode_id)
+        .execute(&self.ctx.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    fn channel_dir(&self, channel_id: &str) -> PathBuf {
+        self.podcast_dir.join(channel_id)
+    }
+}
+
+/// 根据音频地址或 Content-Type 确定文件后缀
+fn episode_suffix(url: &str, content_type: Option<&str>) -> String {
+    let from_url = reqwest::Url::parse(url).ok().and_then(|url| {
+        Path::new(url.path())
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(str::to_lowercase)
+            .filter(|ext| ext.len() <= 5 && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+    });
+    if let Some(suffix) = from_url {
+        return suffix;
+    }
+
+    match content_type {
+        Some("audio/mp4" | "audio/x-m4a" | "audio/m4a") => "m4a",
+        Some("audio/aac") => "aac",
+        Some("audio/ogg") => "ogg",
+        Some("audio/opus") => "opus",
+        Some("audio/flac" | "audio/x-flac") => "flac",
+        Some("audio/wav" | "audio/x-wav") => "wav",
+        _ => "mp3",
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::get, Router};
+    use sqlx::SqlitePool;
+
+    const AUDIO: &[u8] = b"ID3 fake audio";
+
+    /// 本地订阅源: /feed.xml 包含 3 个单集，/missing.xml 返回 404
+    async fn spawn_feed_server() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+
+        let feed = format!(
+            r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Local Cast</title>
+    <item><title>Ep 1</title><guid>1</guid><pubDate>Mon, 01 Jan 2024 00:00:00 GMT</pubDate>
+      <enclosure url="{base}/audio/1.mp3" type="audio/mpeg"/></item>
+    <item><title>Ep 2</title><guid>2</guid><pubDate>Tue, 02 Jan 2024 00:00:00 GMT</pubDate>
+      <enclosure url="{base}/audio/2.mp3" type="audio/mpeg"/></item>
+    <item><title>Ep 3</title><guid>3</guid><pubDate>Wed, 03 Jan 2024 00:00:00 GMT</pubDate>
+      <enclosure url="{base}/missing.mp3" type="audio/mpeg"/></item>
+  </channel>
+</rss>"#
+        );
+
+        // 用于下载上限测试: 大小超出上限 (有或没有 Content-Length) 和响应过慢
+        let limits_feed = format!(
+            r#"<?xml version="1.0"?>
+<rss version="2.0">
+  <channel>
+    <title>Limits</title>
+    <item><title>Sized</title><guid>sized</guid>
+      <enclosure url="{base}/audio/sized.mp3" type="audio/mpeg"/></item>
+    <item><title>Chunked</title><guid>chunked</guid>
+      <enclosure url="{base}/chunked.mp3" type="audio/mpeg"/></item>
+    <item><title>Slow</title><guid>slow</guid>
+      <enclosure url="{base}/slow.mp3" type="audio/mpeg"/></item>
+  </channel>
+</rss>"#
+        );
+
+        let app = Router::new()
+            .route("/feed.xml", get(move || async move { feed }))
+            .route("/limits.xml", get(move || async move { limits_feed }))
+            .route("/audio/:name", get(|| async { AUDIO }))
+            .route(
+                "/chunked.xml",
+                get(|| async {
+                    axum::body::Body::from_stream(futures::stream::iter(
+                        [b"<?xml version=\"1.0\"?>".to_vec(), vec![b' '; 1024]]
+                            .into_iter()
+                            .map(Ok::<_, std::io::Error>),
+                    ))
+                }),
+            )
+            .route(
+                "/chunked.mp3",
+                get(|| async {
+                    axum::body::Body::from_stream(futures::stream::iter(
+                        AUDIO
+                            .chunks(4)
+                            .map(|chunk| Ok::<_, std::io::Error>(chunk.to_vec())),
+                    ))
+                }),
+            )
+            .route(
+                "/slow.mp3",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    AUDIO
+                }),
+            );
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        base
+    }
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+        for sql in [
+            "CREATE TABLE podcast_channels (
+                id TEXT PRIMARY KEY,
+                url TEXT NOT NULL UNIQUE,
+                title TEXT,
+                description TEXT,
+                image_url TEXT,
+                status TEXT NOT NULL DEFAULT 'new',
+                error_message TEXT,
+                last_refreshed_at TEXT,
+                created_at TEXT NOT NULL
+            )",
+            "CREATE TABLE podcast_episodes (
+                id TEXT PRIMARY KEY,
+                channel_id TEXT NOT NULL REFERENCES podcast_channels(id) ON DELETE CASCADE,
+                guid TEXT NOT NULL,
+                title TEXT NOT NULL,
+                description TEXT,
+                stream_url TEXT NOT NULL,
+                content_type TEXT,
+                file_size INTEGER,
+                duration INTEGER,
+                publish_date TEXT,
+                status TEXT NOT NULL DEFAULT 'new',
+                file_path TEXT,
+                error_message TEXT,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE (channel_id, guid)
+            )",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+
+        pool
+    }
+
+    fn create_service(
+        pool: SqlitePool,
+        dir: &Path,
+        retention: u32,
+        auto_download: u32,
+    ) -> PodcastService {
+        PodcastService::new(
+            Arc::new(ServiceContext::new(pool)),
+            dir.to_path_buf(),
+            retention,
+            auto_download,
+        )
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}_{}", name, id_builder::generate_id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// 按标题查找单集
+    async fn episode(service: &PodcastService, channel_id: &str, title: &str) -> PodcastEpisode {
+        service
+            .get_channels(Some(channel_id), true)
+            .await
+            .unwrap()
+            .remove(0)
+            .episodes
+            .into_iter()
+            .find(|episode| episode.title == title)
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_refresh_channel() {
+        let base = spawn_feed_server().await;
+        let dir = temp_dir("podcast_refresh");
+        let service = create_service(setup_test_db().await, &dir, 0, 0);
+
+        let channel = service
+            .create_channel(&format!("{}/feed.xml", base))
+            .await
+            .unwrap();
+        assert!(matches!(
+            service.create_channel(&format!("{}/feed.xml", base)).await,
+            Err(AppError::ValidationError(_))
+        ));
+
+        service.refresh_channel(&channel.id).await.unwrap();
+        // 重复刷新不会重复添加单集
+        service.refresh_channel(&channel.id).await.unwrap();
+
+        let detail = service
+            .get_channels(Some(&channel.id), true)
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(detail.channel.title.as_deref(), Some("Local Cast"));
+        assert_eq!(detail.channel.status, "completed");
+        let titles: Vec<&str> = detail.episodes.iter().map(|e| e.title.as_str()).collect();
+        assert_eq!(titles, ["Ep 3", "Ep 2", "Ep 1"]);
+        assert!(detail.episodes.iter().all(|e| e.status == "new"));
+
+        let newest = service.get_newest_episodes(2).await.unwrap();
+        assert_eq!(newest.len(), 2);
+        assert_eq!(newest[0].title, "Ep 3");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_refresh_channel_error() {
+        let base = spawn_feed_server().await;
+        let dir = temp_dir("podcast_error");
+        let service = create_service(setup_test_db().await, &dir, 0, 0);
+
+        let channel = service
+            .create_channel(&format!("{}/missing.xml", base))
+            .await
+            .unwrap();
+        service.refresh_channel(&channel.id).await.unwrap();
+
+        let detail = service
+            .get_channels(Some(&channel.id), false)
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(detail.channel.status, "error");
+        assert!(detail.channel.error_message.unwrap().contains("404"));
+
+        assert!(matches!(
+            service.create_channel("ftp://example.com/feed.xml").await,
+            Err(AppError::ValidationError(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_download_limits() {
+        let base = spawn_feed_server().await;
+        let dir = temp_dir("podcast_limits");
+        let service = create_service(setup_test_db().await, &dir, 0, 3)
+            .with_download_limits(Duration::from_millis(500), AUDIO.len() as u64 - 1);
+
+        let channel = service
+            .create_channel(&format!("{}/limits.xml", base))
+            .await
+            .unwrap();
+        service.refresh_channel(&channel.id).await.unwrap();
+
+        assert_eq!(service.process_download_queue().await.unwrap(), 0);
+        for title in ["Sized", "Chunked", "Slow"] {
+            let episode = episode(&service, &channel.id, title).await;
+            assert_eq!(episode.status, "error", "{}", title);
+            assert_eq!(episode.file_path, None);
+        }
+        let sized = episode(&service, &channel.id, "Sized").await;
+        assert!(sized.error_message.unwrap().contains("too large"));
+        let chunked = episode(&service, &channel.id, "Chunked").await;
+        assert!(chunked.error_message.unwrap().contains("too large"));
+
+        // 没有留下临时文件
+        let channel_dir = service.channel_dir(&channel.id);
+        assert_eq!(std::fs::read_dir(&channel_dir).unwrap().count(), 0);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_feed_size_limit() {
+        let base = spawn_feed_server().await;
+        let dir = temp_dir("podcast_feed_limit");
+        let mut service = create_service(setup_test_db().await, &dir, 0, 0);
+        service.max_feed_size = 512;
+
+        // 有 Content-Length 和分块传输的订阅源都受上限限制
+        for path in ["feed.xml", "chunked.xml"] {
+            let error = service
+                .fetch_feed(&format!("{}/{}", base, path))
+                .await
+                .unwrap_err();
+            assert!(error.to_string().contains("too large"), "{}", path);
+        }
+
+        service.max_feed_size = MAX_FEED_SIZE;
+        let feed = service
+            .fetch_feed(&format!("{}/feed.xml", base))
+            .await
+            .unwrap();
+        assert_eq!(feed.title, "Local Cast");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_download_and_retention() {
+        let base = spawn_feed_server().await;
+        let dir = temp_dir("podcast_download");
+        // 保留 1 个已下载单集，刷新时自动下载最新的 2 个单集
+        let service = create_service(setup_test_db().await, &dir, 1, 2);
+
+        let channel = service
+            .create_channel(&format!("{}/feed.xml", base))
+            .await
+            .unwrap();
+        service.refresh_channel(&channel.id).await.unwrap();
+
+        // Ep 3 下载失败，Ep 2 下载成功
+        assert_eq!(service.process_download_queue().await.unwrap(), 1);
+        let ep3 = episode(&service, &channel.id, "Ep 3").await;
+        assert_eq!(ep3.status, "error");
+        assert!(ep3.error_message.is_some());
+        let ep2 = episode(&service, &channel.id, "Ep 2").await;
+        assert_eq!(ep2.status, "completed");
+        assert_eq!(ep2.file_size, Some(AUDIO.len() as i64));
+        let ep2_path = PathBuf::from(ep2.file_path.unwrap());
+        assert_eq!(std::fs::read(&ep2_path).unwrap(), AUDIO);
+        assert_eq!(ep2_path.extension().unwrap(), "mp3");
+
+        // 下载更旧的 Ep 1 后超出保留数量，删除最旧的 Ep 1
+        let ep1 = episode(&service, &channel.id, "Ep 1").await;
+        service.queue_download(&ep1.id).await.unwrap();
+        assert_eq!(service.process_download_queue().await.unwrap(), 1);
+        let ep1 = episode(&service, &channel.id, "Ep 1").await;
+        assert_eq!(ep1.status, "deleted");
+        assert_eq!(ep1.file_path, None);
+        assert!(ep2_path.exists());
+
+        // 手动删除的单集刷新后保持删除状态
+        service.delete_episode(&ep2.id).await.unwrap();
+        assert!(!ep2_path.exists());
+        service.refresh_channel(&channel.id).await.unwrap();
+        assert_eq!(
+            episode(&service, &channel.id, "Ep 2").await.status,
+            "deleted"
+        );
+
+        service.delete_channel(&channel.id).await.unwrap();
+        assert!(!dir.join(&channel.id).exists());
+        assert!(service.get_channels(None, true).await.unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_episode_suffix() {
+        assert_eq!(episode_suffix("http://a/ep.M4A?x=1", None), "m4a");
+        assert_eq!(
+            episode_suffix("http://a/download", Some("audio/ogg")),
+            "ogg"
+        );
+        assert_eq!(episode_suffix("http://a/download", None), "mp3");
+    }
+}
@@ -3,22 +3,32 @@
 
 use crate::error::AppError;
 use crate::handlers::library::ScanState;
-use crate::models::entities::{Album, Artist, Song};
-use crate::utils::{get_image_format, image_utils, write_image_to_file, AudioMetadata};
+use crate::models::entities::{Album, Artist, MusicFolder, Song};
+use crate::utils::{
+    get_image_format, id_builder, image_utils, video_probe, write_image_to_file, AudioMetadata,
+};
 use sha2::{Digest, Sha256};
 use sqlx::{Execute, SqlitePool};
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use symphonia::core::formats::FormatOptions;
 use symphonia::core::io::MediaSourceStream;
 use symphonia::core::meta::{MetadataOptions, StandardTagKey};
 use symphonia::core::probe::Hint;
 use walkdir::WalkDir;
 
+/// 支持的音频格式
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "flac", "wav", "m4a", "aac", "ogg", "opus"];
+/// 并发解析数量
+const CONCURRENT_PARSE: usize = 8;
+/// 批量插入大小
+const BATCH_SIZE: usize = 100;
+
 /// 音乐库扫描服务
 pub struct ScanService {
     pool: SqlitePool,
-    library_path: PathBuf,
 }
 
 /// 扫描结果
@@ -27,48 +37,49 @@ pub struct ScanResult {
     pub artists: usize,
     pub albums: usize,
     pub songs: usize,
+    pub videos: usize,
     pub failed: usize,
     pub deleted: usize,
 }
 
 impl ScanService {
-    pub fn new(pool: SqlitePool, library_path: PathBuf) -> Self {
-        Self { pool, library_path }
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
     }
 
     /// 扫描音乐库 (优化版: 并发处理 + 批量插入 + 增量扫描)
+    ///
+    /// 依次扫描所有音乐文件夹，歌曲和专辑记录所属的文件夹。
+    /// 路径不存在的文件夹 (例如未挂载的磁盘) 会被跳过，其歌曲不会被清理。
     pub async fn scan_library(&self, scan_state: ScanState) -> Result<ScanResult, AppError> {
         use futures::stream::{self, StreamExt};
 
         let mut result = ScanResult::default();
 
-        if !self.library_path.exists() {
+        let (folders, skipped_folders) = self.load_music_folders().await?;
+
+        if folders.is_empty() {
             return Err(AppError::not_found("Music library path"));
         }
 
         let scan_start = std::time::Instant::now();
-        tracing::info!("开始扫描音乐库: {:?}", self.library_path);
 
-        // 步骤1: 收集所有音频文件路径
+        // 步骤1: 收集所有音频和视频文件路径
         let mut paths = vec![];
-        for entry in WalkDir::new(&self.library_path)
-            .into_iter()
-            .filter_map(|e| e.ok())
-            .filter(|e| e.file_type().is_file())
-        {
-            let ext = entry
-                .path()
-                .extension()
-                .and_then(|s| s.to_str())
-                .unwrap_or("")
-                .to_lowercase();
-
-            // 支持的音频格式
-            if matches!(
-                ext.as_str(),
-                "mp3" | "flac" | "wav" | "m4a" | "aac" | "ogg" | "opus"
-            ) {
-                paths.push(entry.into_path());
+        let mut video_paths = vec![];
+        for folder in &folders {
+            tracing::info!("开始扫描音乐文件夹: {} ({})", folder.name, folder.path);
+
+            for entry in WalkDir::new(&folder.path)
+                .into_iter()
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file())
+            {
+                if is_audio_file(entry.path()) {
+                    paths.push((entry.into_path(), folder.clone()));
+                } else if video_probe::is_video_file(entry.path()) {
+                    video_paths.push((entry.into_path(), folder.clone()));
+                }
             }
         }
 
@@ -76,6 +87,9 @@ impl ScanService {
 
         tracing::info!("发现 {} 个音频文件", total_files);
 
+        // 步骤1.2: 索引视频文件 (与歌曲相互独立)
+        result.videos = self.index_videos(video_paths, &skipped_folders).await?;
+
         // 步骤1.5: 增量扫描优化 - 查询数据库中已存在的文件及其更新时间
         let db_files = self.get_existing_files_info().await?;
         tracing::info!("数据库中已有 {} 个文件记录", db_files.len());
@@ -84,7 +98,7 @@ impl ScanService {
         let mut files_to_scan = Vec::new();
         let mut skipped = 0;
 
-        for path in paths {
+        for (path, folder) in paths {
             let path_str = path_to_string(&path);
 
             // 获取文件修改时间
@@ -115,7 +129,7 @@ impl ScanService {
             };
 
             if should_scan {
-                files_to_scan.push(path);
+                files_to_scan.push((path, folder));
             } else {
                 skipped += 1;
             }
@@ -132,12 +146,13 @@ impl ScanService {
         let mut count = scan_state.count.lock().await;
         *count = files_to_scan.len();
         drop(count);
+        scan_state.publish_progress().await;
 
         if files_to_scan.is_empty() {
             tracing::info!("所有文件都是最新的,无需扫描");
 
             // 仍然需要清理已删除的文件
-            let deleted = self.cleanup_deleted_files().await?;
+            let deleted = self.cleanup_deleted_files(&skipped_folders).await?;
             result.deleted = deleted;
 
             // 更新统计
@@ -161,11 +176,8 @@ impl ScanService {
         tracing::info!("开始并发处理 {} 个文件", files_to_scan.len());
 
         // 步骤2: 并发解析元数据 (CPU密集型任务)
-        const CONCURRENT_PARSE: usize = 8; // 并发解析数量
-        const BATCH_SIZE: usize = 100; // 批量插入大小
-
         let mut metadata_stream = stream::iter(files_to_scan.into_iter().enumerate())
-            .map(|(index, path)| {
+            .map(|(index, (path, folder))| {
                 async move {
                     let path_clone = path.clone();
                     // 在阻塞线程池中解析元数据(CPU密集型)
@@ -175,7 +187,7 @@ impl ScanService {
                     .await;
 
                     match result {
-                        Ok(Ok(metadata)) => Ok((index, path, metadata)),
+                        Ok(Ok(metadata)) => Ok((index, path, folder, metadata)),
                         Ok(Err(e)) => Err((index, path.clone(), e)),
                         Err(e) => Err((
                             index,
@@ -195,8 +207,8 @@ impl ScanService {
             processed += 1;
 
             match parse_result {
-                Ok((_index, path, metadata)) => {
-                    batch.push((path, metadata));
+                Ok((_index, path, folder, metadata)) => {
+                    batch.push((path, folder, metadata));
 
                     // 批量插入
                     if batch.len() >= BATCH_SIZE {
@@ -229,8 +241,9 @@ impl ScanService {
                 *current = processed;
                 drop(current); // unlock
 
-                // 每100个文件输出一次进度日志
+                // 每100个文件输出一次进度日志并推送进度事件
                 if processed % 100 == 0 || processed == total_files {
+                    scan_state.publish_progress().await;
                     let elapsed = scan_start.elapsed().as_secs_f64();
                     let speed = processed as f64 / elapsed;
                     tracing::info!(
@@ -256,7 +269,7 @@ impl ScanService {
         }
 
         // 步骤5: 清理已删除的文件
-        let deleted = self.cleanup_deleted_files().await?;
+        let deleted = self.cleanup_deleted_files(&skipped_folders).await?;
         result.deleted = deleted;
 
         // 步骤6: 更新艺术家和专辑计数
@@ -283,6 +296,154 @@ impl ScanService {
         Ok(result)
     }
 
+    /// 增量扫描发生变化的路径 (由文件监听触发)
+    ///
+    /// 存在的目录会被遍历，存在的音频和视频文件重新解析入库；
+    /// 不存在的路径视为已删除或移走，随后清理文件已不存在的歌曲和视频。
+    /// 不在任何音乐文件夹中的路径会被忽略。
+    pub async fn scan_paths(
+        &self,
+        paths: Vec<PathBuf>,
+        scan_state: ScanState,
+    ) -> Result<ScanResult, AppError> {
+        use futures::stream::{self, StreamExt};
+
+        let mut result = ScanResult::default();
+        let (folders, skipped_folders) = self.load_music_folders().await?;
+
+        // 监听到的路径是规范化的绝对路径，音乐文件夹可能配置为相对路径或经过符号链接
+        let roots: Vec<(Arc<MusicFolder>, PathBuf)> = folders
+            .iter()
+            .map(|folder| {
+                let root = std::fs::canonicalize(&folder.path)
+                    .unwrap_or_else(|_| PathBuf::from(&folder.path));
+                (folder.clone(), root)
+            })
+            .collect();
+
+        let mut audio_paths = vec![];
+        let mut video_paths = vec![];
+        let mut removed = false;
+        for path in paths {
+            let Some((folder, path)) = resolve_folder_path(&path, &roots) else {
+                continue;
+            };
+
+            if !path.exists() {
+                removed = true;
+                continue;
+            }
+
+            let files: Vec<PathBuf> = if path.is_dir() {
+                WalkDir::new(&path)
+                    .into_iter()
+                    .filter_map(|e| e.ok())
+                    .filter(|e| e.file_type().is_file())
+                    .map(|e| e.into_path())
+                    .collect()
+            } else {
+                vec![path]
+            };
+            for file in files {
+                if is_audio_file(&file) {
+                    audio_paths.push((file, folder.clone()));
+                } else if video_probe::is_video_file(&file) {
+                    video_paths.push((file, folder.clone()));
+                }
+            }
+        }
+
+        // 目录和其中的文件可能同时发生变化
+        audio_paths.sort_by(|a, b| a.0.cmp(&b.0));
+        audio_paths.dedup_by(|a, b| a.0 == b.0);
+        video_paths.sort_by(|a, b| a.0.cmp(&b.0));
+        video_paths.dedup_by(|a, b| a.0 == b.0);
+
+        tracing::info!(
+            "增量扫描变化路径: {} 个音频文件, {} 个视频文件",
+            audio_paths.len(),
+            video_paths.len()
+        );
+        *scan_state.count.lock().await = audio_paths.len();
+
+        if !video_paths.is_empty() || removed {
+            result.videos = self.index_videos(video_paths, &skipped_folders).await?;
+        }
+
+        let parsed: Vec<_> = stream::iter(audio_paths)
+            .map(|(path, folder)| async move {
+                let parse_path = path.clone();
+                let metadata = tokio::task::spawn_blocking(move || {
+                    image_utils::extract_audio_metadata_static(&parse_path)
+                })
+                .await;
+                (path, folder, metadata)
+            })
+            .buffer_unordered(CONCURRENT_PARSE)
+            .collect()
+            .await;
+        *scan_state.current.lock().await = parsed.len();
+
+        let mut batch = Vec::with_capacity(parsed.len());
+        for (path, folder, metadata) in parsed {
+            match metadata {
+                Ok(Ok(metadata)) => batch.push((path, folder, metadata)),
+                Ok(Err(e)) => {
+                    tracing::warn!("解析失败 {}: {}", path.display(), e);
+                    result.failed += 1;
+                }
+                Err(e) => {
+                    tracing::warn!("解析任务失败 {}: {}", path.display(), e);
+                    result.failed += 1;
+                }
+            }
+        }
+
+        for chunk in batch.chunks(BATCH_SIZE) {
+            match self.batch_save_to_database(chunk).await {
+                Ok(count) => result.songs += count,
+                Err(e) => {
+                    tracing::error!("批量插入失败: {}", e);
+                    result.failed += chunk.len();
+                }
+            }
+        }
+
+        if removed {
+            result.deleted = self.cleanup_deleted_files(&skipped_folders).await?;
+        }
+
+        Ok(result)
+    }
+
+    /// 获取所有存在的音乐文件夹，以及已从配置中移除或路径不存在 (例如未挂载的磁盘) 而需要跳过的文件夹 ID
+    async fn load_music_folders(&self) -> Result<(Vec<Arc<MusicFolder>>, HashSet<i64>), AppError> {
+        let folders = sqlx::query_as::<_, MusicFolder>("SELECT * FROM music_folders ORDER BY id")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut skipped_folders = HashSet::new();
+        let folders = folders
+            .into_iter()
+            .filter(|folder| {
+                // 已从配置中移除的文件夹保留原有内容，不再扫描
+                if folder.missing {
+                    skipped_folders.insert(folder.id);
+                    return false;
+                }
+                let exists = Path::new(&folder.path).is_dir();
+                if !exists {
+                    tracing::warn!("音乐文件夹不存在，跳过扫描: {}", folder.path);
+                    skipped_folders.insert(folder.id);
+                }
+                exists
+            })
+            .map(Arc::new)
+            .collect();
+
+        Ok((folders, skipped_folders))
+    }
+
     /// 获取数据库中已存在文件的路径和更新时间映射
     async fn get_existing_files_info(
         &self,
@@ -304,7 +465,7 @@ impl ScanService {
     /// 批量保存到数据库 (优化版: 减少数据库往返 + 封面异步处理)
     async fn batch_save_to_database(
         &self,
-        batch: &[(PathBuf, AudioMetadata)],
+        batch: &[(PathBuf, Arc<MusicFolder>, AudioMetadata)],
     ) -> Result<usize, AppError> {
         if batch.is_empty() {
             return Ok(0);
@@ -317,7 +478,7 @@ impl ScanService {
         let mut tx = self.pool.begin().await?;
         let mut success_count = 0;
 
-        for (path, metadata) in batch {
+        for (path, folder, metadata) in batch {
             let artist_name_fallback = self.extract_artist_from_path(path);
             let album_name_fallback = self.extract_album_from_path(path);
 
@@ -330,6 +491,7 @@ impl ScanService {
                 .save_to_database_tx_deferred_cover(
                     &mut tx,
                     &mut pending_covers,
+                    folder,
                     artist_name,
                     album_name,
                     title,
@@ -446,6 +608,7 @@ impl ScanService {
         &self,
         tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
         pending_covers: &mut Vec<(String, String, Box<[u8]>)>,
+        folder: &MusicFolder,
         artist_name: &str,
         album_name: &str,
         title: &str,
@@ -466,7 +629,7 @@ impl ScanService {
 
         // 插入或更新专辑(不处理封面)
         let album_id = self
-            .get_or_create_album_tx_no_cover(tx, &artist_id, album_name, year, genre)
+            .get_or_create_album_tx_no_cover(tx, folder, &artist_id, album_name, year, genre)
             .await?;
 
         // 如果有封面数据,添加到待处理列表
@@ -477,6 +640,7 @@ impl ScanService {
         // 插入或更新歌曲
         self.get_or_create_song_tx(
             tx,
+            folder.id,
             &album_id,
             &artist_id,
             title,
@@ -500,17 +664,20 @@ impl ScanService {
     async fn get_or_create_album_tx_no_cover(
         &self,
         tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        folder: &MusicFolder,
         artist_id: &str,
         name: &str,
         year: Option<i32>,
         genre: Option<&str>,
     ) -> Result<String, AppError> {
-        // 查找已存在的专辑
+        // 查找同一音乐文件夹中已存在的专辑
         let existing = sqlx::query_as::<_, (String, Option<i32>, Option<String>)>(
-            "SELECT id, year, genre FROM albums WHERE artist_id = ? AND name = ?",
+            "SELECT id, year, genre FROM albums
+             WHERE artist_id = ? AND name = ? AND music_folder_id = ?",
         )
         .bind(artist_id)
         .bind(name)
+        .bind(folder.id)
         .fetch_optional(&mut **tx)
         .await?;
 
@@ -537,7 +704,7 @@ impl ScanService {
         let album = Album::new(
             artist_id.to_string(),
             name.to_string(),
-            path_to_string(&self.library_path),
+            folder.path.clone(),
             year,
             genre.map(|s| s.to_string()),
             None,
@@ -545,8 +712,8 @@ impl ScanService {
 
         sqlx::query(
             "INSERT INTO albums (id, artist_id, name, year, genre, cover_art_path, cover_art_hash, path,
-             song_count, duration, play_count, created_at, updated_at)
-             VALUES (?, ?, ?, ?, ?, NULL, NULL, ?, 0, 0, 0, ?, ?)",
+             song_count, duration, play_count, music_folder_id, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, NULL, NULL, ?, 0, 0, 0, ?, ?, ?)",
         )
         .bind(&album.id)
         .bind(&album.artist_id)
@@ -554,6 +721,7 @@ impl ScanService {
         .bind(album.year)
         .bind(&album.genre)
         .bind(&album.path)
+        .bind(folder.id)
         .bind(album.created_at)
         .bind(album.updated_at)
         .execute(&mut **tx)
@@ -620,6 +788,7 @@ impl ScanService {
     async fn get_or_create_song_tx(
         &self,
         tx: &mut sqlx::Transaction<'_, sqlx::Sqlite>,
+        music_folder_id: i64,
         album_id: &str,
         artist_id: &str,
         title: &str,
@@ -659,7 +828,8 @@ impl ScanService {
             sqlx::query(
                 "UPDATE songs
                  SET title = ?, track_number = ?, disc_number = ?, duration = ?, bit_rate = ?,
-                     genre = ?, year = ?, content_type = ?, file_size = ?, lyrics = ?, updated_at = ?
+                     genre = ?, year = ?, content_type = ?, file_size = ?, lyrics = ?,
+                     music_folder_id = ?, updated_at = ?
                  WHERE file_path = ?",
             )
             .bind(&song.title)
@@ -672,6 +842,7 @@ impl ScanService {
             .bind(song.content_type.clone().unwrap_or_default())
             .bind(song.file_size.unwrap_or_default())
             .bind(song.lyrics.clone().unwrap_or_default())
+            .bind(music_folder_id)
             .bind(song.updated_at)
             .bind(path_to_string(path))
             .execute(&mut **tx)
@@ -682,8 +853,8 @@ impl ScanService {
             sqlx::query(
                 "INSERT INTO songs (id, album_id, artist_id, title, track_number, disc_number,
                  duration, bit_rate, genre, year, content_type, file_path, file_size, lyrics, play_count,
-                 created_at, updated_at)
-                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0, ?, ?)",
+                 music_folder_id, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, 0, ?, ?, ?)",
             )
             .bind(&song.id)
             .bind(&song.album_id)
@@ -699,6 +870,7 @@ impl ScanService {
             .bind(&song.file_path)
             .bind(song.file_size)
             .bind(&song.lyrics)
+            .bind(music_folder_id)
             .bind(song.created_at)
             .bind(song.updated_at)
             .execute(&mut **tx)
@@ -733,16 +905,27 @@ impl ScanService {
     }
 
     /// 清理数据库中文件已不存在的歌曲
-    async fn cleanup_deleted_files(&self) -> Result<usize, AppError> {
+    ///
+    /// `skipped_folders` 为本次未扫描的音乐文件夹，其歌曲保持不变
+    async fn cleanup_deleted_files(
+        &self,
+        skipped_folders: &HashSet<i64>,
+    ) -> Result<usize, AppError> {
         // 获取所有歌曲的文件路径
-        let all_songs = sqlx::query_as::<_, (String, String)>("SELECT id, file_path FROM songs")
-            .fetch_all(&self.pool)
-            .await?;
+        let all_songs = sqlx::query_as::<_, (String, String, Option<i64>)>(
+            "SELECT id, file_path, music_folder_id FROM songs",
+        )
+        .fetch_all(&self.pool)
+        .await?;
 
         let mut deleted_count = 0;
         let mut deleted_song_ids = Vec::new();
 
-        for (song_id, file_path) in all_songs {
+        for (song_id, file_path, music_folder_id) in all_songs {
+            if music_folder_id.is_some_and(|id| skipped_folders.contains(&id)) {
+                continue;
+            }
+
             let path = Path::new(&file_path);
             if !path.exists() {
                 tracing::debug!("发现已删除的文件: {}", file_path);
@@ -770,6 +953,129 @@ impl ScanService {
         Ok(deleted_count)
     }
 
+    /// 索引视频文件并清理文件已不存在的视频，返回新增或更新的视频数
+    ///
+    /// 与歌曲相同按文件修改时间增量扫描，只读取容器头部获取时长和画面尺寸。
+    /// 无法解析头部的文件仍然会被索引，只是缺少这些信息。
+    async fn index_videos(
+        &self,
+        paths: Vec<(PathBuf, Arc<MusicFolder>)>,
+        skipped_folders: &HashSet<i64>,
+    ) -> Result<usize, AppError> {
+        tracing::info!("发现 {} 个视频文件", paths.len());
+
+        let existing: HashMap<String, chrono::DateTime<chrono::Utc>> =
+            sqlx::query_as::<_, (String, chrono::DateTime<chrono::Utc>)>(
+                "SELECT file_path, updated_at FROM videos",
+            )
+            .fetch_all(&self.pool)
+            .await?
+            .into_iter()
+            .collect();
+
+        let mut indexed = 0;
+        for (path, folder) in paths {
+            let path_str = path_to_string(&path);
+
+            let file_mtime = std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .map(chrono::DateTime::<chrono::Utc>::from);
+            if let (Some(updated_at), Some(mtime)) = (existing.get(&path_str), file_mtime) {
+                if mtime <= *updated_at {
+                    continue;
+                }
+            }
+
+            let probe_path = path.clone();
+            let metadata =
+                match tokio::task::spawn_blocking(move || video_probe::probe(&probe_path)).await {
+                    Ok(Ok(metadata)) => metadata,
+                    Ok(Err(e)) => {
+                        tracing::warn!("视频信息解析失败 {}: {}", path.display(), e);
+                        video_probe::VideoMetadata {
+                            content_type: video_probe::content_type_for(&path).to_string(),
+                            file_size: std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+                            ..Default::default()
+                        }
+                    }
+                    Err(e) => {
+                        tracing::warn!("视频解析任务失败 {}: {}", path.display(), e);
+                        continue;
+                    }
+                };
+
+            let title = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_else(|| path_str.clone());
+            let now = chrono::Utc::now();
+
+            sqlx::query(
+                "INSERT INTO videos (id, title, file_path, content_type, file_size, duration,
+                 bit_rate, width, height, music_folder_id, created_at, updated_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                 ON CONFLICT (file_path) DO UPDATE SET
+                     title = excluded.title,
+                     content_type = excluded.content_type,
+                     file_size = excluded.file_size,
+                     duration = excluded.duration,
+                     bit_rate = excluded.bit_rate,
+                     width = excluded.width,
+                     height = excluded.height,
+                     music_folder_id = excluded.music_folder_id,
+                     updated_at = excluded.updated_at",
+            )
+            .bind(id_builder::generate_id())
+            .bind(&title)
+            .bind(&path_str)
+            .bind(&metadata.content_type)
+            .bind(metadata.file_size as i64)
+            .bind(metadata.duration_secs)
+            .bind(metadata.bit_rate)
+            .bind(metadata.width)
+            .bind(metadata.height)
+            .bind(folder.id)
+            .bind(now)
+            .bind(now)
+            .execute(&self.pool)
+            .await?;
+
+            indexed += 1;
+        }
+
+        // 清理文件已不存在的视频 (跳过未扫描的文件夹)
+        let all_videos = sqlx::query_as::<_, (String, String, Option<i64>)>(
+            "SELECT id, file_path, music_folder_id FROM videos",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut deleted = 0;
+        for (video_id, file_path, music_folder_id) in all_videos {
+            if music_folder_id.is_some_and(|id| skipped_folders.contains(&id)) {
+                continue;
+            }
+            if !Path::new(&file_path).exists() {
+                sqlx::query("DELETE FROM videos WHERE id = ?")
+                    .bind(&video_id)
+                    .execute(&self.pool)
+                    .await?;
+                deleted += 1;
+            }
+        }
+
+        if indexed > 0 || deleted > 0 {
+            tracing::info!(
+                "视频索引完成: 新增或更新 {} 个, 清理 {} 个",
+                indexed,
+                deleted
+            );
+        }
+
+        Ok(indexed)
+    }
+
     /// 清理没有歌曲的专辑
     async fn cleanup_empty_albums(&self) -> Result<usize, AppError> {
         let result = sqlx::query(
@@ -805,10 +1111,43 @@ impl ScanService {
     }
 }
 
+/// 是否为支持的音频文件
+pub fn is_audio_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
 fn path_to_string(path: &Path) -> String {
     path.to_string_lossy().to_string()
 }
 
+/// 找到路径所属的音乐文件夹 (嵌套的音乐文件夹以最内层为准)，
+/// 并改写为以文件夹配置路径开头的形式，与全量扫描记录的文件路径保持一致
+fn resolve_folder_path(
+    path: &Path,
+    roots: &[(Arc<MusicFolder>, PathBuf)],
+) -> Option<(Arc<MusicFolder>, PathBuf)> {
+    roots
+        .iter()
+        .filter_map(|(folder, root)| {
+            let relative = path
+                .strip_prefix(root)
+                .or_else(|_| path.strip_prefix(&folder.path))
+                .ok()?;
+            Some((folder, root, relative))
+        })
+        .max_by_key(|(_, root, _)| root.as_os_str().len())
+        .map(|(folder, _, relative)| {
+            let path = if relative.as_os_str().is_empty() {
+                PathBuf::from(&folder.path)
+            } else {
+                Path::new(&folder.path).join(relative)
+            };
+            (folder.clone(), path)
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -816,6 +1155,123 @@ mod tests {
     use super::*;
     use std::path::PathBuf;
 
+    /// 生成 0.1 秒的静音 WAV 文件
+    fn write_wav(path: &Path) {
+        let samples = vec![0u8; 8000 * 2 / 10];
+        let mut wav = Vec::new();
+        wav.extend_from_slice(b"RIFF");
+        wav.extend_from_slice(&(36 + samples.len() as u32).to_le_bytes());
+        wav.extend_from_slice(b"WAVEfmt ");
+        wav.extend_from_slice(&16u32.to_le_bytes());
+        wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        wav.extend_from_slice(&1u16.to_le_bytes()); // 单声道
+        wav.extend_from_slice(&8000u32.to_le_bytes());
+        wav.extend_from_slice(&16000u32.to_le_bytes());
+        wav.extend_from_slice(&2u16.to_le_bytes());
+        wav.extend_from_slice(&16u16.to_le_bytes());
+        wav.extend_from_slice(b"data");
+        wav.extend_from_slice(&(samples.len() as u32).to_le_bytes());
+        wav.extend_from_slice(&samples);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, wav).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_scan_paths() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        crate::database::run_migrations(&pool).await.unwrap();
+
+        let dir = std::env::temp_dir().join(format!("scan_paths_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        sqlx::query("INSERT INTO music_folders (name, path) VALUES ('Music', ?)")
+            .bind(path_to_string(&dir))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let service = ScanService::new(pool.clone());
+        let scan_state = ScanState::new(Arc::new(crate::services::EventService::new(Arc::new(
+            crate::services::ServiceContext::new(pool.clone()),
+        ))));
+        let song_count = || async {
+            sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM songs")
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+        };
+
+        // 新增的目录会被遍历，音乐文件夹以外的路径被忽略
+        let album = dir.join("Album").join("Artist");
+        write_wav(&album.join("one.wav"));
+        write_wav(&album.join("two.wav"));
+        std::fs::write(album.join("cover.txt"), "").unwrap();
+        let result = service
+            .scan_paths(
+                vec![
+                    dir.join("Album"),
+                    album.join("one.wav"),
+                    PathBuf::from("/elsewhere"),
+                ],
+                scan_state.clone(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result.songs, 2);
+        assert_eq!(result.deleted, 0);
+        assert_eq!(song_count().await, 2);
+        assert_eq!(*scan_state.count.lock().await, 2);
+
+        // 删除的文件从数据库中清理
+        std::fs::remove_file(album.join("two.wav")).unwrap();
+        let result = service
+            .scan_paths(vec![album.join("two.wav")], scan_state.clone())
+            .await
+            .unwrap();
+        assert_eq!(result.songs, 0);
+        assert_eq!(result.deleted, 1);
+        assert_eq!(song_count().await, 1);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_scan_paths_non_canonical_folder() {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        crate::database::run_migrations(&pool).await.unwrap();
+
+        let dir = std::env::temp_dir().join(format!("scan_paths_canonical_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        let dir = std::fs::canonicalize(&dir).unwrap();
+        // 配置的路径未规范化，监听到的路径为规范化路径
+        let configured = dir.join("sub").join("..");
+        sqlx::query("INSERT INTO music_folders (name, path) VALUES ('Music', ?)")
+            .bind(path_to_string(&configured))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let service = ScanService::new(pool.clone());
+        let scan_state = ScanState::new(Arc::new(crate::services::EventService::new(Arc::new(
+            crate::services::ServiceContext::new(pool.clone()),
+        ))));
+
+        write_wav(&dir.join("one.wav"));
+        let result = service
+            .scan_paths(vec![dir.join("one.wav")], scan_state)
+            .await
+            .unwrap();
+        assert_eq!(result.songs, 1);
+
+        // 记录的路径与全量扫描一致，以配置的路径开头
+        let file_path: String = sqlx::query_scalar("SELECT file_path FROM songs")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(file_path, path_to_string(&configured.join("one.wav")));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
     /// 手动测试入口:可以通过环境变量指定音频文件路径
     ///
     /// # 使用示例
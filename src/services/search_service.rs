@@ -8,7 +8,7 @@
 
 use crate::error::AppError;
 use crate::models::dto::{AlbumDetailDto, AlbumDto, ArtistDto, ComplexSongDto, SongDetailDto};
-use crate::services::{ServiceContext, SongService};
+use crate::services::{FolderScope, ServiceContext, SongService};
 use crate::utils::sql_utils;
 use std::sync::Arc;
 
@@ -38,6 +38,8 @@ pub struct SearchParams {
     pub album_offset: i32,
     pub song_count: i32,
     pub song_offset: i32,
+    /// 可访问的音乐文件夹范围 (已按 musicFolderId 收窄)
+    pub scope: FolderScope,
 }
 
 impl Default for SearchParams {
@@ -50,6 +52,7 @@ impl Default for SearchParams {
             album_offset: 0,
             song_count: 20,
             song_offset: 0,
+            scope: FolderScope::All,
         }
     }
 }
@@ -74,14 +77,25 @@ impl SearchService {
     /// # 性能优化
     ///
     /// 使用 tokio::try_join! 并行执行三个独立查询,提升性能
-    pub async fn search_all(&self, user_id: &str, params: SearchParams) -> Result<SearchResults, AppError> {
+    pub async fn search_all(
+        &self,
+        user_id: &str,
+        params: SearchParams,
+    ) -> Result<SearchResults, AppError> {
         let query = params.query.clone();
+        let folder = &params.scope;
 
         // 并行搜索三个表
         let (artists, albums, songs) = tokio::try_join!(
-            self.search_artists(&query, params.artist_count, params.artist_offset),
-            self.search_albums_detailed(&query, params.album_count, params.album_offset),
-            self.search_songs(user_id, &query, params.song_count, params.song_offset),
+            self.search_artists(&query, params.artist_count, params.artist_offset, folder),
+            self.search_albums_detailed(&query, params.album_count, params.album_offset, folder),
+            self.search_songs(
+                user_id,
+                &query,
+                params.song_count,
+                params.song_offset,
+                folder
+            ),
         )?;
 
         Ok(SearchResults {
@@ -106,12 +120,19 @@ impl SearchService {
         params: SearchParams,
     ) -> Result<SearchResults2, AppError> {
         let query = params.query.clone();
+        let folder = &params.scope;
 
         // 并行搜索三个表
         let (artists, albums, songs) = tokio::try_join!(
-            self.search_artists(&query, params.artist_count, params.artist_offset),
-            self.search_albums_simple(&query, params.album_count, params.album_offset),
-            self.search_songs(user_id, &query, params.song_count, params.song_offset),
+            self.search_artists(&query, params.artist_count, params.artist_offset, folder),
+            self.search_albums_simple(&query, params.album_count, params.album_offset, folder),
+            self.search_songs(
+                user_id,
+                &query,
+                params.song_count,
+                params.song_offset,
+                folder
+            ),
         )?;
 
         Ok(SearchResults2 {
@@ -128,18 +149,21 @@ impl SearchService {
     /// * `query` - 搜索关键词
     /// * `count` - 返回数量
     /// * `offset` - 偏移量
+    /// * `scope` - 用户可访问的音乐文件夹范围
     async fn search_artists(
         &self,
         query: &str,
         count: i32,
         offset: i32,
+        scope: &FolderScope,
     ) -> Result<Vec<ArtistDto>, AppError> {
-        let artists = sqlx::query_as::<_, ArtistDto>(
-            "SELECT id, name FROM artists
-             WHERE name LIKE ?
+        let artists = sqlx::query_as::<_, ArtistDto>(&format!(
+            "SELECT id, name FROM artists ar
+             WHERE name LIKE ? AND {}
              ORDER BY name
              LIMIT ? OFFSET ?",
-        )
+            scope.artist_filter("ar.id")
+        ))
         .bind(format!("%{}%", query))
         .bind(count)
         .bind(offset)
@@ -156,21 +180,25 @@ impl SearchService {
     /// * `query` - 搜索关键词
     /// * `count` - 返回数量
     /// * `offset` - 偏移量
+    /// * `scope` - 用户可访问的音乐文件夹范围
     async fn search_albums_detailed(
         &self,
         query: &str,
         count: i32,
         offset: i32,
+        scope: &FolderScope,
     ) -> Result<Vec<AlbumDetailDto>, AppError> {
-        let albums = sqlx::query_as::<_, AlbumDetailDto>(
+        let albums = sqlx::query_as::<_, AlbumDetailDto>(&format!(
             "SELECT a.id, a.name, ar.name as artist, a.artist_id, a.year, a.genre,
                     a.cover_art_path, a.song_count, a.duration, a.play_count
              FROM albums a
              JOIN artists ar ON a.artist_id = ar.id
-             WHERE a.name LIKE ? OR ar.name LIKE ?
+             WHERE (a.name LIKE ? OR ar.name LIKE ?)
+             AND {}
              ORDER BY a.name
              LIMIT ? OFFSET ?",
-        )
+            scope.sql_filter("a.music_folder_id")
+        ))
         .bind(format!("%{}%", query))
         .bind(format!("%{}%", query))
         .bind(count)
@@ -188,20 +216,24 @@ impl SearchService {
     /// * `query` - 搜索关键词
     /// * `count` - 返回数量
     /// * `offset` - 偏移量
+    /// * `scope` - 用户可访问的音乐文件夹范围
     async fn search_albums_simple(
         &self,
         query: &str,
         count: i32,
         offset: i32,
+        scope: &FolderScope,
     ) -> Result<Vec<AlbumDto>, AppError> {
-        let albums = sqlx::query_as::<_, AlbumDto>(
+        let albums = sqlx::query_as::<_, AlbumDto>(&format!(
             "SELECT a.id, a.name, ar.name as artist, a.year, a.song_count
              FROM albums a
              JOIN artists ar ON a.artist_id = ar.id
-             WHERE a.name LIKE ? OR ar.name LIKE ?
+             WHERE (a.name LIKE ? OR ar.name LIKE ?)
+             AND {}
              ORDER BY a.name
              LIMIT ? OFFSET ?",
-        )
+            scope.sql_filter("a.music_folder_id")
+        ))
         .bind(format!("%{}%", query))
         .bind(format!("%{}%", query))
         .bind(count)
@@ -219,18 +251,22 @@ impl SearchService {
     /// * `query` - 搜索关键词
     /// * `count` - 返回数量
     /// * `offset` - 偏移量
+    /// * `scope` - 用户可访问的音乐文件夹范围
     async fn search_songs(
         &self,
         user_id: &str,
         query: &str,
         count: i32,
         offset: i32,
+        scope: &FolderScope,
     ) -> Result<Vec<ComplexSongDto>, AppError> {
         let songs = sqlx::query_as::<_, SongDetailDto>(&format!(
-            "{} WHERE s.title LIKE ? OR al.name LIKE ? OR ar.name LIKE ?
+            "{} WHERE (s.title LIKE ? OR al.name LIKE ? OR ar.name LIKE ?)
+             AND {}
              ORDER BY s.title
              LIMIT ? OFFSET ?",
-            sql_utils::detail_sql()
+            sql_utils::detail_sql(),
+            scope.sql_filter("s.music_folder_id")
         ))
         .bind(format!("%{}%", query))
         .bind(format!("%{}%", query))
@@ -284,7 +320,8 @@ mod tests {
                 cover_art_path TEXT,
                 song_count INTEGER DEFAULT 0,
                 duration INTEGER DEFAULT 0,
-                play_count INTEGER DEFAULT 0
+                play_count INTEGER DEFAULT 0,
+                music_folder_id INTEGER
             )",
         )
         .execute(&pool)
@@ -306,7 +343,8 @@ mod tests {
                 file_path TEXT,
                 file_size INTEGER,
                 content_type TEXT,
-                play_count INTEGER DEFAULT 0
+                play_count INTEGER DEFAULT 0,
+                music_folder_id INTEGER
             )",
         )
         .execute(&pool)
@@ -348,18 +386,18 @@ mod tests {
             .unwrap();
 
         sqlx::query(
-            "INSERT INTO albums (id, name, artist_id, year, genre, song_count)
-             VALUES ('album1', 'Test Album', 'artist1', 2020, 'Rock', 2),
-                    ('album2', 'Another Album', 'artist2', 2021, 'Pop', 1)",
+            "INSERT INTO albums (id, name, artist_id, year, genre, song_count, music_folder_id)
+             VALUES ('album1', 'Test Album', 'artist1', 2020, 'Rock', 2, 1),
+                    ('album2', 'Another Album', 'artist2', 2021, 'Pop', 1, 2)",
         )
         .execute(&pool)
         .await
         .unwrap();
 
         sqlx::query(
-            "INSERT INTO songs (id, title, artist_id, album_id, duration, track_number, file_path)
-             VALUES ('song1', 'Test Song', 'artist1', 'album1', 180, 1, '/test/song1.mp3'),
-                    ('song2', 'Another Song', 'artist2', 'album2', 200, 1, '/test/song2.mp3')",
+            "INSERT INTO songs (id, title, artist_id, album_id, duration, track_number, file_path, music_folder_id)
+             VALUES ('song1', 'Test Song', 'artist1', 'album1', 180, 1, '/test/song1.mp3', 1),
+                    ('song2', 'Another Song', 'artist2', 'album2', 200, 1, '/test/song2.mp3', 2)",
         )
         .execute(&pool)
         .await
@@ -378,7 +416,10 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let artists = service.search_artists("Test", 10, 0).await.unwrap();
+        let artists = service
+            .search_artists("Test", 10, 0, &FolderScope::All)
+            .await
+            .unwrap();
         assert_eq!(artists.len(), 1);
         assert_eq!(artists[0].name, "Test Artist");
     }
@@ -388,7 +429,10 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let albums = service.search_albums_detailed("Test", 10, 0).await.unwrap();
+        let albums = service
+            .search_albums_detailed("Test", 10, 0, &FolderScope::All)
+            .await
+            .unwrap();
         assert_eq!(albums.len(), 1);
         assert_eq!(albums[0].name, "Test Album");
     }
@@ -398,7 +442,10 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let songs = service.search_songs("", "Test", 10, 0).await.unwrap();
+        let songs = service
+            .search_songs("", "Test", 10, 0, &FolderScope::All)
+            .await
+            .unwrap();
         assert_eq!(songs.len(), 1);
         assert_eq!(songs[0].song.title, "Test Song");
     }
@@ -437,17 +484,44 @@ mod tests {
         assert_eq!(results.songs.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_search_music_folder_filter() {
+        let pool = setup_test_db().await;
+        let service = create_service(pool);
+
+        let params = SearchParams {
+            query: "".to_string(),
+            scope: FolderScope::Folders(vec![2]),
+            ..Default::default()
+        };
+
+        let results = service.search_all("", params).await.unwrap();
+
+        assert_eq!(results.artists.len(), 1);
+        assert_eq!(results.artists[0].name, "Another Artist");
+        assert_eq!(results.albums.len(), 1);
+        assert_eq!(results.albums[0].name, "Another Album");
+        assert_eq!(results.songs.len(), 1);
+        assert_eq!(results.songs[0].song.title, "Another Song");
+    }
+
     #[tokio::test]
     async fn test_search_pagination() {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
         // 搜索第一页
-        let artists1 = service.search_artists("Artist", 1, 0).await.unwrap();
+        let artists1 = service
+            .search_artists("Artist", 1, 0, &FolderScope::All)
+            .await
+            .unwrap();
         assert_eq!(artists1.len(), 1);
 
         // 搜索第二页
-        let artists2 = service.search_artists("Artist", 1, 1).await.unwrap();
+        let artists2 = service
+            .search_artists("Artist", 1, 1, &FolderScope::All)
+            .await
+            .unwrap();
         assert_eq!(artists2.len(), 1);
 
         // 确保不同
@@ -0,0 +1,325 @@
+//! 会话管理服务
+//!
+//! 负责管理面板会话令牌相关的业务逻辑:
+//! - 登录后签发带有效期的会话令牌
+//! - 校验 Bearer 令牌 (签名 + 有效期 + 服务端会话记录)
+//! - 刷新令牌与退出登录 (服务端吊销)
+//! - 签发和校验 HLS 播放列表 URL 中的短期流媒体令牌
+#![allow(dead_code)]
+
+use crate::error::AppError;
+use crate::models::entities::User;
+use crate::services::ServiceContext;
+use crate::utils::id_builder;
+use crate::utils::session_token::{SessionTokenClaims, SessionTokenSigner, StreamTokenClaims};
+use chrono::{Duration, Utc};
+use sqlx::SqliteExecutor;
+use std::sync::Arc;
+
+/// 签发的会话令牌
+#[derive(Debug, Clone)]
+pub struct SessionToken {
+    pub token: String,
+    /// 过期时间 (Unix 时间戳，秒)
+    pub expires_at: i64,
+}
+
+/// 会话管理服务
+pub struct SessionService {
+    ctx: Arc<ServiceContext>,
+    signer: SessionTokenSigner,
+    /// 流媒体令牌使用单独的密钥，不能与会话令牌互相冒充
+    stream_signer: SessionTokenSigner,
+    ttl: Duration,
+}
+
+impl SessionService {
+    /// 创建新的 SessionService
+    ///
+    /// # 参数
+    ///
+    /// * `secret` - 令牌签名密钥
+    /// * `ttl_hours` - 令牌有效期 (小时)
+    pub fn new(ctx: Arc<ServiceContext>, secret: &str, ttl_hours: i64) -> Self {
+        Self {
+            ctx,
+            signer: SessionTokenSigner::new(secret),
+            stream_signer: SessionTokenSigner::new(&format!("stream:{}", secret)),
+            ttl: Duration::hours(ttl_hours),
+        }
+    }
+
+    /// 为用户创建会话并签发令牌
+    pub async fn create_session(&self, user_id: &str) -> Result<SessionToken, AppError> {
+        let now = Utc::now().timestamp();
+        let claims = SessionTokenClaims {
+            sid: id_builder::generate_id(),
+            sub: user_id.to_string(),
+            iat: now,
+            exp: now + self.ttl.num_seconds(),
+        };
+
+        // 顺带清理已过期的会话
+        sqlx::query("DELETE FROM sessions WHERE expires_at <= ?")
+            .bind(now)
+            .execute(&self.ctx.pool)
+            .await?;
+
+        sqlx::query("INSERT INTO sessions (id, user_id, expires_at) VALUES (?, ?, ?)")
+            .bind(&claims.sid)
+            .bind(&claims.sub)
+            .bind(claims.exp)
+            .execute(&self.ctx.pool)
+            .await?;
+
+        Ok(SessionToken {
+            token: self.signer.sign(&claims)?,
+            expires_at: claims.exp,
+        })
+    }
+
+    /// 校验令牌，返回令牌所属用户与载荷
+    ///
+    /// 会话已吊销 (退出登录、刷新、修改密码) 或用户已删除时认证失败
+    pub async fn authenticate(&self, token: &str) -> Result<(User, SessionTokenClaims), AppError> {
+        let claims: SessionTokenClaims = self.signer.verify(token, Utc::now().timestamp())?;
+
+        let user = sqlx::query_as::<_, User>(
+            "SELECT u.* FROM users u
+             JOIN sessions s ON s.user_id = u.id
+             WHERE s.id = ? AND s.user_id = ?",
+        )
+        .bind(&claims.sid)
+        .bind(&claims.sub)
+        .fetch_optional(&self.ctx.pool)
+        .await?
+        .ok_or_else(|| AppError::auth_failed("Session has been revoked"))?;
+
+        Ok((user, claims))
+    }
+
+    /// 刷新令牌: 吊销当前会话并签发新令牌
+    pub async fn refresh(&self, token: &str) -> Result<(User, SessionToken), AppError> {
+        let (user, claims) = self.authenticate(token).await?;
+        self.revoke_session(&claims.sid).await?;
+        let session = self.create_session(&user.id).await?;
+        Ok((user, session))
+    }
+
+    /// 退出登录: 吊销令牌对应的会话
+    pub async fn logout(&self, token: &str) -> Result<(), AppError> {
+        let (_, claims) = self.authenticate(token).await?;
+        self.revoke_session(&claims.sid).await
+    }
+
+    /// 吊销用户的所有会话
+    ///
+    /// 接受连接池或事务，修改密码时与密码更新在同一事务中执行
+    pub async fn revoke_user_sessions<'e, E>(executor: E, user_id: &str) -> Result<u64, AppError>
+    where
+        E: SqliteExecutor<'e>,
+    {
+        let result = sqlx::query("DELETE FROM sessions WHERE user_id = ?")
+            .bind(user_id)
+            .execute(executor)
+            .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// 签发只能访问指定歌曲的流媒体令牌
+    ///
+    /// # 参数
+    ///
+    /// * `ttl_secs` - 有效期 (秒)
+    pub fn sign_stream_token(
+        &self,
+        user_id: &str,
+        song_id: &str,
+        ttl_secs: i64,
+    ) -> Result<String, AppError> {
+        self.stream_signer.sign(&StreamTokenClaims {
+            sub: user_id.to_string(),
+            id: song_id.to_string(),
+            exp: Utc::now().timestamp() + ttl_secs,
+        })
+    }
+
+    /// 校验流媒体令牌，返回令牌所属用户
+    ///
+    /// 请求的歌曲与令牌不符或用户已删除时认证失败
+    pub async fn authenticate_stream_token(
+        &self,
+        token: &str,
+        song_id: Option<&str>,
+    ) -> Result<User, AppError> {
+        let claims: StreamTokenClaims = self.stream_signer.verify(token, Utc::now().timestamp())?;
+        if song_id != Some(claims.id.as_str()) {
+            return Err(AppError::auth_failed(
+                "Stream token is not valid for this song",
+            ));
+        }
+
+        sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = ?")
+            .bind(&claims.sub)
+            .fetch_optional(&self.ctx.pool)
+            .await?
+            .ok_or_else(|| AppError::auth_failed("Invalid stream token"))
+    }
+
+    async fn revoke_session(&self, session_id: &str) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM sessions WHERE id = ?")
+            .bind(session_id)
+            .execute(&self.ctx.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::SqlitePool;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+        sqlx::query(
+            "CREATE TABLE users (
+                id TEXT PRIMARY KEY,
+                username TEXT NOT NULL,
+                password TEXT NOT NULL,
+                email TEXT NOT NULL,
+                is_admin BOOLEAN NOT NULL DEFAULT 0,
+                max_bitrate INTEGER NOT NULL DEFAULT 320,
+                download_role BOOLEAN NOT NULL DEFAULT 1,
+                upload_role BOOLEAN NOT NULL DEFAULT 0,
+                playlist_role BOOLEAN NOT NULL DEFAULT 1,
+                cover_art_role BOOLEAN NOT NULL DEFAULT 1,
+                comment_role BOOLEAN NOT NULL DEFAULT 0,
+                podcast_role BOOLEAN NOT NULL DEFAULT 0,
+                share_role BOOLEAN NOT NULL DEFAULT 1,
+                video_conversion_role BOOLEAN NOT NULL DEFAULT 0,
+                jukebox_role BOOLEAN NOT NULL DEFAULT 0,
+                scrobbling_enabled BOOLEAN NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "CREATE TABLE sessions (
+                id TEXT PRIMARY KEY,
+                user_id TEXT NOT NULL,
+                expires_at INTEGER NOT NULL,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            "INSERT INTO users (id, username, password, email) VALUES ('u1', 'alice', 'x', '')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    fn create_service(pool: SqlitePool) -> SessionService {
+        SessionService::new(Arc::new(ServiceContext::new(pool)), "secret", 24)
+    }
+
+    #[tokio::test]
+    async fn test_create_and_authenticate() {
+        let service = create_service(setup_test_db().await);
+
+        let session = service.create_session("u1").await.unwrap();
+        let (user, claims) = service.authenticate(&session.token).await.unwrap();
+        assert_eq!(user.username, "alice");
+        assert_eq!(claims.exp, session.expires_at);
+        assert_eq!(claims.exp - claims.iat, 24 * 3600);
+
+        // 其他密钥签发的令牌无效
+        let other = SessionService::new(service.ctx.clone(), "other-secret", 24);
+        assert!(other.authenticate(&session.token).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_and_logout() {
+        let service = create_service(setup_test_db().await);
+
+        let session = service.create_session("u1").await.unwrap();
+        let (_, refreshed) = service.refresh(&session.token).await.unwrap();
+
+        // 刷新后旧令牌被吊销
+        assert!(service.authenticate(&session.token).await.is_err());
+        assert!(service.authenticate(&refreshed.token).await.is_ok());
+
+        service.logout(&refreshed.token).await.unwrap();
+        assert!(service.authenticate(&refreshed.token).await.is_err());
+        assert!(service.logout(&refreshed.token).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stream_token() {
+        let service = create_service(setup_test_db().await);
+
+        let token = service.sign_stream_token("u1", "song1", 60).unwrap();
+        let user = service
+            .authenticate_stream_token(&token, Some("song1"))
+            .await
+            .unwrap();
+        assert_eq!(user.id, "u1");
+
+        // 只能访问签发时的歌曲
+        assert!(service
+            .authenticate_stream_token(&token, Some("song2"))
+            .await
+            .is_err());
+        assert!(service
+            .authenticate_stream_token(&token, None)
+            .await
+            .is_err());
+
+        // 会话令牌与流媒体令牌不能互相冒充
+        let session = service.create_session("u1").await.unwrap();
+        assert!(service
+            .authenticate_stream_token(&session.token, Some("song1"))
+            .await
+            .is_err());
+        assert!(service.authenticate(&token).await.is_err());
+
+        // 已过期
+        let expired = service.sign_stream_token("u1", "song1", -1).unwrap();
+        assert!(service
+            .authenticate_stream_token(&expired, Some("song1"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_revoke_user_sessions() {
+        let pool = setup_test_db().await;
+        let service = create_service(pool.clone());
+
+        let first = service.create_session("u1").await.unwrap();
+        let second = service.create_session("u1").await.unwrap();
+
+        assert_eq!(
+            SessionService::revoke_user_sessions(&pool, "u1")
+                .await
+                .unwrap(),
+            2
+        );
+        assert!(service.authenticate(&first.token).await.is_err());
+        assert!(service.authenticate(&second.token).await.is_err());
+    }
+}
@@ -0,0 +1,621 @@
+//! 分享服务
+//!
+//! 负责分享相关的业务逻辑:
+//! - 创建、查询、更新、删除分享 (歌曲、专辑或播放列表)
+//! - 公开分享链接的访问 (有效期检查、访问统计)
+//! - 分享内容按创建者可访问的音乐文件夹展开为歌曲列表
+
+use crate::error::AppError;
+use crate::models::dto::SongDetailDto;
+use crate::models::entities::Share;
+use crate::services::{FolderScope, MusicFolderService, ServiceContext};
+use crate::utils::{id_builder, sql_utils};
+use chrono::{DateTime, Utc};
+use futures::FutureExt;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// 公开链接令牌长度
+const SHARE_TOKEN_LEN: u32 = 32;
+
+/// 分享内容类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareItemType {
+    Song,
+    Album,
+    Playlist,
+}
+
+impl ShareItemType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShareItemType::Song => "song",
+            ShareItemType::Album => "album",
+            ShareItemType::Playlist => "playlist",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "song" => Some(ShareItemType::Song),
+            "album" => Some(ShareItemType::Album),
+            "playlist" => Some(ShareItemType::Playlist),
+            _ => None,
+        }
+    }
+}
+
+/// 分享详情 (包含创建者用户名和展开后的歌曲列表)
+#[derive(Debug)]
+pub struct ShareDetail {
+    pub share: Share,
+    pub username: String,
+    pub songs: Vec<SongDetailDto>,
+}
+
+/// 分享服务
+pub struct ShareService {
+    ctx: Arc<ServiceContext>,
+}
+
+impl ShareService {
+    /// 创建新的 ShareService
+    pub fn new(ctx: Arc<ServiceContext>) -> Self {
+        Self { ctx }
+    }
+
+    /// 创建分享
+    ///
+    /// # 参数
+    ///
+    /// * `user_id` - 创建者 ID
+    /// * `ids` - 歌曲、专辑或播放列表 ID
+    /// * `description` - 分享描述
+    /// * `expires_at` - 过期时间，为空表示永不过期
+    /// * `scope` - 创建者可访问的音乐文件夹范围
+    pub async fn create_share(
+        &self,
+        user_id: &str,
+        ids: &[String],
+        description: Option<String>,
+        expires_at: Option<DateTime<Utc>>,
+        scope: &FolderScope,
+    ) -> Result<ShareDetail, AppError> {
+        if ids.is_empty() {
+            return Err(AppError::missing_parameter("id"));
+        }
+        if matches!(expires_at, Some(expires_at) if expires_at <= Utc::now()) {
+            return Err(AppError::validation_error(
+                "Share expiry must be in the future",
+            ));
+        }
+
+        let mut entries = Vec::with_capacity(ids.len());
+        for id in ids {
+            entries.push((self.detect_item_type(user_id, id, scope).await?, id.clone()));
+        }
+
+        let share_id = id_builder::generate_id();
+        let token = id_builder::generate_id_by_len(SHARE_TOKEN_LEN);
+        let user_id = user_id.to_string();
+        let new_share_id = share_id.clone();
+
+        self.ctx
+            .transaction(|tx| {
+                async move {
+                    sqlx::query(
+                        "INSERT INTO shares (id, token, user_id, description, expires_at, created_at)
+                         VALUES (?, ?, ?, ?, ?, ?)",
+                    )
+                    .bind(&new_share_id)
+                    .bind(&token)
+                    .bind(&user_id)
+                    .bind(&description)
+                    .bind(expires_at)
+                    .bind(Utc::now())
+                    .execute(&mut **tx)
+                    .await?;
+
+                    for (position, (item_type, item_id)) in entries.iter().enumerate() {
+                        sqlx::query(
+                            "INSERT INTO share_entries (share_id, position, item_type, item_id)
+                             VALUES (?, ?, ?, ?)",
+                        )
+                        .bind(&new_share_id)
+                        .bind(position as i32)
+                        .bind(item_type.as_str())
+                        .bind(item_id)
+                        .execute(&mut **tx)
+                        .await?;
+                    }
+
+                    Ok(())
+                }
+                .boxed()
+            })
+            .await?;
+
+        let share = self.get_share(&share_id).await?;
+        self.get_share_detail(share).await
+    }
+
+    /// 获取用户创建的所有分享
+    pub async fn get_shares(&self, user_id: &str) -> Result<Vec<ShareDetail>, AppError> {
+        let shares = sqlx::query_as::<_, Share>(
+            "SELECT * FROM shares WHERE user_id = ? ORDER BY created_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&self.ctx.pool)
+        .await?;
+
+        let mut details = Vec::with_capacity(shares.len());
+        for share in shares {
+            details.push(self.get_share_detail(share).await?);
+        }
+
+        Ok(details)
+    }
+
+    /// 更新分享的描述和过期时间
+    ///
+    /// # 参数
+    ///
+    /// * `description` - 新描述，为空时保持不变
+    /// * `expires_at` - 新过期时间，`Some(None)` 表示取消过期时间，为空时保持不变
+    ///
+    /// # 权限
+    ///
+    /// 只能更新自己的分享，管理员可更新任意分享
+    pub async fn update_share(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        share_id: &str,
+        description: Option<String>,
+        expires_at: Option<Option<DateTime<Utc>>>,
+    ) -> Result<(), AppError> {
+        let share = self.get_owned_share(user_id, is_admin, share_id).await?;
+
+        sqlx::query("UPDATE shares SET description = ?, expires_at = ? WHERE id = ?")
+            .bind(description.or(share.description))
+            .bind(expires_at.unwrap_or(share.expires_at))
+            .bind(share_id)
+            .execute(&self.ctx.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 删除分享
+    ///
+    /// # 权限
+    ///
+    /// 只能删除自己的分享，管理员可删除任意分享
+    pub async fn delete_share(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        share_id: &str,
+    ) -> Result<(), AppError> {
+        self.get_owned_share(user_id, is_admin, share_id).await?;
+
+        sqlx::query("DELETE FROM shares WHERE id = ?")
+            .bind(share_id)
+            .execute(&self.ctx.pool)
+            .await?;
+
+        Ok(())
+    }
+
+    /// 访问公开分享链接，记录访问次数
+    ///
+    /// 令牌不存在或分享已过期时返回 NotFound
+    pub async fn visit(&self, token: &str) -> Result<ShareDetail, AppError> {
+        let share = self.get_active_share(token).await?;
+
+        let now = Utc::now();
+        sqlx::query(
+            "UPDATE shares SET visit_count = visit_count + 1, last_visited = ? WHERE id = ?",
+        )
+        .bind(now)
+        .bind(&share.id)
+        .execute(&self.ctx.pool)
+        .await?;
+
+        let mut detail = self.get_share_detail(share).await?;
+        detail.share.visit_count += 1;
+        detail.share.last_visited = Some(now);
+        Ok(detail)
+    }
+
+    /// 获取公开分享中的歌曲 (用于免登录播放)
+    ///
+    /// 歌曲不属于该分享时返回 NotFound
+    pub async fn get_shared_song(
+        &self,
+        token: &str,
+        song_id: &str,
+    ) -> Result<SongDetailDto, AppError> {
+        let share = self.get_active_share(token).await?;
+
+        self.get_share_songs(&share)
+            .await?
+            .into_iter()
+            .find(|song| song.id == song_id)
+            .ok_or_else(|| AppError::not_found("Song"))
+    }
+
+    async fn get_share(&self, share_id: &str) -> Result<Share, AppError> {
+        sqlx::query_as::<_, Share>("SELECT * FROM shares WHERE id = ?")
+            .bind(share_id)
+            .fetch_optional(&self.ctx.pool)
+            .await?
+            .ok_or_else(|| AppError::not_found("Share"))
+    }
+
+    async fn get_owned_share(
+        &self,
+        user_id: &str,
+        is_admin: bool,
+        share_id: &str,
+    ) -> Result<Share, AppError> {
+        let share = self.get_share(share_id).await?;
+        if !is_admin && share.user_id != user_id {
+            return Err(AppError::access_denied("Not the owner of this share"));
+        }
+
+        Ok(share)
+    }
+
+    async fn get_active_share(&self, token: &str) -> Result<Share, AppError> {
+        sqlx::query_as::<_, Share>("SELECT * FROM shares WHERE token = ?")
+            .bind(token)
+            .fetch_optional(&self.ctx.pool)
+            .await?
+            .filter(|share| !share.is_expired(Utc::now()))
+            .ok_or_else(|| AppError::not_found("Share"))
+    }
+
+    async fn get_share_detail(&self, share: Share) -> Result<ShareDetail, AppError> {
+        let username: String = sqlx::query_scalar("SELECT username FROM users WHERE id = ?")
+            .bind(&share.user_id)
+            .fetch_one(&self.ctx.pool)
+            .await?;
+        let songs = self.get_share_songs(&share).await?;
+
+        Ok(ShareDetail {
+            share,
+            username,
+            songs,
+        })
+    }
+
+    /// 识别分享内容的类型，只能分享自己可访问的内容
+    async fn detect_item_type(
+        &self,
+        user_id: &str,
+        id: &str,
+        scope: &FolderScope,
+    ) -> Result<ShareItemType, AppError> {
+        let (is_song, is_album, is_playlist) =
+            sqlx::query_as::<_, (Option<i32>, Option<i32>, Option<i32>)>(&format!(
+                "SELECT
+                    (SELECT 1 FROM songs WHERE id = ?1 AND {}) as is_song,
+                    (SELECT 1 FROM albums WHERE id = ?1 AND {}) as is_album,
+                    (SELECT 1 FROM playlists WHERE id = ?1 AND (owner_id = ?2 OR is_public = 1)) as is_playlist",
+                scope.sql_filter("songs.music_folder_id"),
+                scope.sql_filter("albums.music_folder_id")
+            ))
+            .bind(id)
+            .bind(user_id)
+            .fetch_one(&self.ctx.pool)
+            .await?;
+
+        match (is_song, is_album, is_playlist) {
+            (Some(_), _, _) => Ok(ShareItemType::Song),
+            (_, Some(_), _) => Ok(ShareItemType::Album),
+            (_, _, Some(_)) => Ok(ShareItemType::Playlist),
+            _ => Err(AppError::not_found("Song/Album/Playlist")),
+        }
+    }
+
+    /// 将分享内容展开为歌曲列表 (去重，保持分享顺序)
+    ///
+    /// 只包含创建者当前可访问的音乐文件夹中的歌曲
+    async fn get_share_songs(&self, share: &Share) -> Result<Vec<SongDetailDto>, AppError> {
+        let is_admin: bool = sqlx::query_scalar("SELECT is_admin FROM users WHERE id = ?")
+            .bind(&share.user_id)
+            .fetch_one(&self.ctx.pool)
+            .await?;
+        let scope = MusicFolderService::new(self.ctx.clone())
+            .get_folder_scope(&share.user_id, is_admin)
+            .await?;
+        let filter = scope.sql_filter("s.music_folder_id");
+
+        let entries = sqlx::query_as::<_, (String, String)>(
+            "SELECT item_type, item_id FROM share_entries
+             WHERE share_id = ?
+             ORDER BY position ASC",
+        )
+        .bind(&share.id)
+        .fetch_all(&self.ctx.pool)
+        .await?;
+
+        let mut seen = HashSet::new();
+        let mut songs = Vec::new();
+        for (item_type, item_id) in entries {
+            let query = match ShareItemType::parse(&item_type) {
+                Some(ShareItemType::Song) => {
+                    format!("{} WHERE s.id = ? AND {filter}", sql_utils::detail_sql())
+                }
+                Some(ShareItemType::Album) => format!(
+                    "{} WHERE s.album_id = ? AND {filter}
+                     ORDER BY s.disc_number ASC, s.track_number ASC",
+                    sql_utils::detail_sql()
+                ),
+                Some(ShareItemType::Playlist) => format!(
+                    "{} JOIN playlist_songs ps ON ps.song_id = s.id
+                     WHERE ps.playlist_id = ? AND {filter}
+                     ORDER BY ps.position ASC",
+                    sql_utils::detail_sql()
+                ),
+                None => continue,
+            };
+
+            let entry_songs = sqlx::query_as::<_, SongDetailDto>(&query)
+                .bind(&item_id)
+                .fetch_all(&self.ctx.pool)
+                .await?;
+            songs.extend(
+                entry_songs
+                    .into_iter()
+                    .filter(|song| seen.insert(song.id.clone())),
+            );
+        }
+
+        Ok(songs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use sqlx::SqlitePool;
+
+    async fn setup_test_db() -> SqlitePool {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+
+        for sql in [
+            "CREATE TABLE users (
+                id TEXT PRIMARY KEY,
+                username TEXT NOT NULL,
+                is_admin BOOLEAN NOT NULL DEFAULT 0
+            )",
+            "CREATE TABLE music_folders (
+                id INTEGER PRIMARY KEY,
+                missing BOOLEAN NOT NULL DEFAULT 0
+            )",
+            "CREATE TABLE user_music_folders (
+                user_id TEXT NOT NULL,
+                music_folder_id INTEGER NOT NULL
+            )",
+            "CREATE TABLE artists (id TEXT PRIMARY KEY, name TEXT NOT NULL)",
+            "CREATE TABLE albums (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                artist_id TEXT NOT NULL,
+                cover_art_path TEXT,
+                music_folder_id INTEGER
+            )",
+            "CREATE TABLE songs (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                artist_id TEXT NOT NULL,
+                album_id TEXT NOT NULL,
+                duration INTEGER DEFAULT 0,
+                bit_rate INTEGER,
+                track_number INTEGER,
+                disc_number INTEGER,
+                year INTEGER,
+                genre TEXT,
+                file_path TEXT,
+                file_size INTEGER,
+                content_type TEXT,
+                play_count INTEGER DEFAULT 0,
+                music_folder_id INTEGER
+            )",
+            "CREATE TABLE playlists (
+                id TEXT PRIMARY KEY,
+                owner_id TEXT NOT NULL,
+                is_public BOOLEAN NOT NULL DEFAULT 0
+            )",
+            "CREATE TABLE playlist_songs (
+                playlist_id TEXT NOT NULL,
+                song_id TEXT NOT NULL,
+                position INTEGER NOT NULL
+            )",
+            "CREATE TABLE shares (
+                id TEXT PRIMARY KEY,
+                token TEXT NOT NULL UNIQUE,
+                user_id TEXT NOT NULL,
+                description TEXT,
+                expires_at TEXT,
+                last_visited TEXT,
+                visit_count INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+            "CREATE TABLE share_entries (
+                share_id TEXT NOT NULL REFERENCES shares(id) ON DELETE CASCADE,
+                position INTEGER NOT NULL,
+                item_type TEXT NOT NULL,
+                item_id TEXT NOT NULL
+            )",
+            // 测试数据: alice 只能访问文件夹 1
+            "INSERT INTO users (id, username, is_admin) VALUES
+                ('u1', 'alice', 0), ('u2', 'bob', 0), ('admin', 'admin', 1)",
+            "INSERT INTO music_folders (id) VALUES (1), (2)",
+            "INSERT INTO user_music_folders (user_id, music_folder_id) VALUES ('u1', 1)",
+            "INSERT INTO artists (id, name) VALUES ('ar1', 'Artist')",
+            "INSERT INTO albums (id, name, artist_id, music_folder_id) VALUES
+                ('al1', 'Album 1', 'ar1', 1), ('al2', 'Album 2', 'ar1', 2)",
+            "INSERT INTO songs (id, title, artist_id, album_id, track_number, file_path, music_folder_id) VALUES
+                ('s1', 'Song 1', 'ar1', 'al1', 1, '/music/1.mp3', 1),
+                ('s2', 'Song 2', 'ar1', 'al1', 2, '/music/2.mp3', 1),
+                ('s3', 'Song 3', 'ar1', 'al2', 1, '/music/3.mp3', 2)",
+            "INSERT INTO playlists (id, owner_id, is_public) VALUES ('p1', 'u2', 0), ('p2', 'u2', 1)",
+            "INSERT INTO playlist_songs (playlist_id, song_id, position) VALUES
+                ('p2', 's3', 0), ('p2', 's2', 1)",
+        ] {
+            sqlx::query(sql).execute(&pool).await.unwrap();
+        }
+
+        pool
+    }
+
+    fn create_service(pool: SqlitePool) -> ShareService {
+        ShareService::new(Arc::new(ServiceContext::new(pool)))
+    }
+
+    fn ids(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn alice_scope() -> FolderScope {
+        FolderScope::Folders(vec![1])
+    }
+
+    #[tokio::test]
+    async fn test_create_share_expands_entries() {
+        let service = create_service(setup_test_db().await);
+
+        let detail = service
+            .create_share(
+                "u1",
+                &ids(&["s2", "al1", "p2"]),
+                Some("mix".to_string()),
+                None,
+                &alice_scope(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(detail.username, "alice");
+        assert_eq!(detail.share.token.len(), SHARE_TOKEN_LEN as usize);
+        // 重复歌曲只保留一次，alice 无权访问的 s3 不包含在内
+        let song_ids: Vec<&str> = detail.songs.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(song_ids, vec!["s2", "s1"]);
+
+        let shares = service.get_shares("u1").await.unwrap();
+        assert_eq!(shares.len(), 1);
+        assert!(service.get_shares("u2").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_create_share_requires_access() {
+        let service = create_service(setup_test_db().await);
+
+        // 范围外的专辑、他人的私有播放列表和不存在的 ID 都不能分享
+        for id in ["al2", "s3", "p1", "missing"] {
+            assert!(service
+                .create_share("u1", &ids(&[id]), None, None, &alice_scope())
+                .await
+                .is_err());
+        }
+        assert!(service
+            .create_share("u1", &[], None, None, &alice_scope())
+            .await
+            .is_err());
+        assert!(service
+            .create_share(
+                "u1",
+                &ids(&["s1"]),
+                None,
+                Some(Utc::now() - Duration::hours(1)),
+                &alice_scope()
+            )
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_visit_and_expiry() {
+        let pool = setup_test_db().await;
+        let service = create_service(pool.clone());
+
+        let detail = service
+            .create_share("admin", &ids(&["al2"]), None, None, &FolderScope::All)
+            .await
+            .unwrap();
+        let token = detail.share.token.clone();
+
+        let visited = service.visit(&token).await.unwrap();
+        assert_eq!(visited.share.visit_count, 1);
+        assert_eq!(visited.songs.len(), 1);
+        service.visit(&token).await.unwrap();
+
+        let song = service.get_shared_song(&token, "s3").await.unwrap();
+        assert_eq!(song.path.as_deref(), Some("/music/3.mp3"));
+        assert!(service.get_shared_song(&token, "s1").await.is_err());
+
+        let shares = service.get_shares("admin").await.unwrap();
+        assert_eq!(shares[0].share.visit_count, 2);
+        assert!(shares[0].share.last_visited.is_some());
+
+        // 过期后链接失效
+        sqlx::query("UPDATE shares SET expires_at = ?")
+            .bind(Utc::now() - Duration::minutes(1))
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(service.visit(&token).await.is_err());
+        assert!(service.get_shared_song(&token, "s3").await.is_err());
+        assert!(service.visit("unknown").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_update_and_delete_ownership() {
+        let service = create_service(setup_test_db().await);
+
+        let detail = service
+            .create_share("u1", &ids(&["s1"]), None, None, &alice_scope())
+            .await
+            .unwrap();
+        let id = detail.share.id.clone();
+
+        // 其他用户不能修改或删除
+        assert!(service
+            .update_share("u2", false, &id, Some("x".to_string()), None)
+            .await
+            .is_err());
+        assert!(service.delete_share("u2", false, &id).await.is_err());
+
+        let expires_at = Utc::now() + Duration::days(1);
+        service
+            .update_share(
+                "u1",
+                false,
+                &id,
+                Some("new".to_string()),
+                Some(Some(expires_at)),
+            )
+            .await
+            .unwrap();
+        let share = &service.get_shares("u1").await.unwrap()[0].share;
+        assert_eq!(share.description.as_deref(), Some("new"));
+        assert!(share.expires_at.is_some());
+
+        // 只更新描述时保留过期时间，Some(None) 取消过期时间
+        service
+            .update_share("u1", false, &id, None, Some(None))
+            .await
+            .unwrap();
+        let share = &service.get_shares("u1").await.unwrap()[0].share;
+        assert_eq!(share.description.as_deref(), Some("new"));
+        assert!(share.expires_at.is_none());
+
+        // 管理员可删除任意分享
+        service.delete_share("admin", true, &id).await.unwrap();
+        assert!(service.get_shares("u1").await.unwrap().is_empty());
+    }
+}
@@ -7,7 +7,7 @@
 
 use crate::error::AppError;
 use crate::models::dto::{ComplexSongDto, SongDetailDto};
-use crate::services::ServiceContext;
+use crate::services::{FolderScope, ServiceContext};
 use crate::utils::{image_utils, sql_utils};
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
@@ -62,9 +62,10 @@ impl SongService {
         let complex_songs = songs
             .into_iter()
             .map(|song| {
-                let suffix = song.path.as_ref().map(|p| {
-                    image_utils::get_content_type(Path::new(p))
-                });
+                let suffix = song
+                    .path
+                    .as_ref()
+                    .map(|p| image_utils::get_content_type(Path::new(p)));
 
                 let user_rating = rating_map.get(&song.id).copied();
                 let starred = if starred_set.contains(&song.id) {
@@ -91,15 +92,18 @@ impl SongService {
     ///
     /// * `user_id` - 用户 ID
     /// * `song_id` - 歌曲 ID
+    /// * `scope` - 用户可访问的音乐文件夹范围
     pub async fn get_complex_song(
         &self,
         user_id: &str,
         song_id: &str,
+        scope: &FolderScope,
     ) -> Result<ComplexSongDto, AppError> {
         // 查询歌曲基础信息
         let song = sqlx::query_as::<_, SongDetailDto>(&format!(
-            "{} WHERE s.id = ?",
-            sql_utils::detail_sql()
+            "{} WHERE s.id = ? AND {}",
+            sql_utils::detail_sql(),
+            scope.sql_filter("s.music_folder_id")
         ))
         .bind(song_id)
         .fetch_optional(&self.ctx.pool)
@@ -112,17 +116,18 @@ impl SongService {
         Ok(complex_songs.remove(0))
     }
 
-
     /// 通过 song_ids 批量获取 ComplexSongDto
     ///
     /// # 参数
     ///
     /// * `user_id` - 用户 ID
     /// * `song_ids` - 歌曲 ID 列表
+    /// * `scope` - 用户可访问的音乐文件夹范围，范围外的歌曲被忽略
     pub async fn get_complex_songs_by_ids(
         &self,
         user_id: &str,
         song_ids: &[String],
+        scope: &FolderScope,
     ) -> Result<Vec<ComplexSongDto>, AppError> {
         if song_ids.is_empty() {
             return Ok(vec![]);
@@ -130,24 +135,26 @@ impl SongService {
 
         // 构建 IN 子句
         let placeholders = song_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-        let query = format!("{} WHERE s.id IN ({})", sql_utils::detail_sql(), placeholders);
+        let query = format!(
+            "{} WHERE s.id IN ({}) AND {}",
+            sql_utils::detail_sql(),
+            placeholders,
+            scope.sql_filter("s.music_folder_id")
+        );
 
         // 查询歌曲
-        
+
         let mut query_builder = sqlx::query_as::<_, SongDetailDto>(&query);
 
         for song_id in song_ids {
             query_builder = query_builder.bind(song_id);
         }
-        let songs = query_builder
-        .fetch_all(&self.ctx.pool)
-        .await?;
+        let songs = query_builder.fetch_all(&self.ctx.pool).await?;
 
         // 使用 enrich_songs 复用逻辑
         self.enrich_songs(user_id, songs).await
     }
 
-
     /// 批量查询 ratings（私有方法）
     ///
     /// # 参数
@@ -400,7 +407,10 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let complex_song = service.get_complex_song("user1", "song1").await.unwrap();
+        let complex_song = service
+            .get_complex_song("user1", "song1", &FolderScope::All)
+            .await
+            .unwrap();
 
         assert_eq!(complex_song.song.title, "Song 1");
         assert_eq!(complex_song.user_rating, Some(5));
@@ -413,7 +423,9 @@ mod tests {
         let pool = setup_test_db().await;
         let service = create_service(pool);
 
-        let result = service.get_complex_song("user1", "nonexistent").await;
+        let result = service
+            .get_complex_song("user1", "nonexistent", &FolderScope::All)
+            .await;
         assert!(result.is_err());
     }
 
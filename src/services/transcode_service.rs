@@ -0,0 +1,534 @@
+//! 转码服务
+//!
+//! 负责 /rest/stream 的实时转码:
+//! - 根据请求的 `format` / `maxBitRate` 与用户码率上限决定是否转码
+//! - 按配置的命令模板启动转码进程，将 stdout 作为响应流
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+
+use tokio::process::{ChildStdout, Command};
+use tokio::task::JoinHandle;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+
+/// 请求 `format=raw` 时直接返回原始文件
+pub const RAW_FORMAT: &str = "raw";
+
+/// 未限制码率时使用的默认转码码率 (kbps)
+pub const DEFAULT_BIT_RATE: u32 = 320;
+
+/// 转码参数
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TranscodeProfile {
+    /// 目标格式 (例如 mp3、opus)
+    pub format: String,
+    /// 目标码率 (kbps)
+    pub bit_rate: u32,
+}
+
+impl TranscodeProfile {
+    /// 目标格式对应的 Content-Type
+    pub fn content_type(&self) -> &'static str {
+        content_type_for_format(&self.format)
+    }
+}
+
+/// 流媒体处理方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamDecision {
+    /// 直接返回原始文件
+    Raw,
+    /// 按指定参数转码
+    Transcode(TranscodeProfile),
+}
+
+/// 时间片段 (秒)，用于 HLS 分片转码
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimeSlice {
+    pub start: f64,
+    pub length: f64,
+}
+
+/// 运行中的转码进程
+pub struct TranscodeProcess {
+    /// 转码输出
+    pub stdout: ChildStdout,
+    /// 进程退出后返回是否成功，用于判断输出是否完整
+    pub exit: JoinHandle<bool>,
+}
+
+/// 源文件信息
+#[derive(Debug, Clone, Copy)]
+pub struct SourceInfo<'a> {
+    /// 文件后缀 (小写)
+    pub suffix: &'a str,
+    /// 源文件码率 (bps，与 songs.bit_rate 一致)
+    pub bit_rate: Option<i32>,
+}
+
+/// 转码服务
+pub struct TranscodeService {
+    /// 目标格式 -> 命令模板
+    commands: HashMap<String, String>,
+    /// 仅限制码率时使用的默认格式
+    default_format: String,
+    /// HLS 分片转码命令模板
+    segment_command: Option<String>,
+}
+
+impl TranscodeService {
+    /// 创建新的 TranscodeService
+    pub fn new(commands: HashMap<String, String>, default_format: impl Into<String>) -> Self {
+        let commands = commands
+            .into_iter()
+            .map(|(format, command)| (format.to_lowercase(), command))
+            .collect();
+
+        Self {
+            commands,
+            default_format: default_format.into().to_lowercase(),
+            segment_command: None,
+        }
+    }
+
+    /// 设置 HLS 分片转码命令模板，空字符串表示禁用
+    pub fn with_segment_command(mut self, command: impl Into<String>) -> Self {
+        let command = command.into();
+        self.segment_command = (!command.trim().is_empty()).then_some(command);
+        self
+    }
+
+    /// 根据应用配置创建
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::new(
+            config.transcoders.clone(),
+            config.transcode_default_format.clone(),
+        )
+        .with_segment_command(config.hls_segment_transcoder.clone())
+    }
+
+    /// 是否支持 HLS 分片转码
+    pub fn supports_segments(&self) -> bool {
+        self.segment_command.is_some()
+    }
+
+    /// 是否支持转码到指定格式
+    pub fn supports(&self, format: &str) -> bool {
+        self.commands.contains_key(&format.to_lowercase())
+    }
+
+    /// 计算实际码率上限 (kbps)
+    ///
+    /// 取请求码率与用户码率上限的较小值，0 或负数表示不限制。
+    /// 返回 None 表示不限制码率。
+    pub fn effective_bit_rate(requested: Option<i32>, user_limit: Option<i32>) -> Option<u32> {
+        [requested, user_limit]
+            .into_iter()
+            .flatten()
+            .filter(|rate| *rate > 0)
+            .map(|rate| rate as u32)
+            .min()
+    }
+
+    /// 决定流媒体处理方式
+    ///
+    /// # 规则
+    ///
+    /// - `format=raw` 始终返回原始文件
+    /// - 指定了受支持的格式时转码到该格式 (与源格式相同且码率未超限时直接返回)
+    /// - 未指定格式但源码率超过上限时，转码到默认格式
+    /// - 其他情况返回原始文件
+    pub fn decide(
+        &self,
+        format: Option<&str>,
+        requested_bit_rate: Option<i32>,
+        user_bit_rate_limit: Option<i32>,
+        source: SourceInfo<'_>,
+    ) -> StreamDecision {
+        let format = format.map(|f| f.trim().to_lowercase());
+        if format.as_deref() == Some(RAW_FORMAT) {
+            return StreamDecision::Raw;
+        }
+
+        let limit = Self::effective_bit_rate(requested_bit_rate, user_bit_rate_limit);
+        let source_kbps = source
+            .bit_rate
+            .filter(|rate| *rate > 0)
+            .map(|rate| (rate as u32).div_ceil(1000));
+        let exceeds_limit = match (limit, source_kbps) {
+            (Some(limit), Some(source_kbps)) => source_kbps > limit,
+            // 源码率未知时，只要有上限就转码，保证不超出限制
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+
+        let target = match format.filter(|f| self.supports(f)) {
+            Some(target) => {
+                if target == source.suffix.to_lowercase() && !exceeds_limit {
+                    return StreamDecision::Raw;
+                }
+                target
+            }
+            None if exceeds_limit && self.supports(&self.default_format) => {
+                self.default_format.clone()
+            }
+            None => return StreamDecision::Raw,
+        };
+
+        // 转码码率不超过源码率，避免无意义的放大
+        let bit_rate = limit
+            .unwrap_or(DEFAULT_BIT_RATE)
+            .min(source_kbps.unwrap_or(u32::MAX));
+
+        StreamDecision::Transcode(TranscodeProfile {
+            format: target,
+            bit_rate,
+        })
+    }
+
+    /// 根据命令模板构建转码命令
+    pub fn build_command(
+        &self,
+        profile: &TranscodeProfile,
+        input: &Path,
+    ) -> Result<Command, AppError> {
+        let template = self.commands.get(&profile.format).ok_or_else(|| {
+            AppError::ValidationError(format!("Unsupported transcode format: {}", profile.format))
+        })?;
+
+        command_from_template(template, input, profile.bit_rate, None)
+    }
+
+    /// 启动转码进程
+    ///
+    /// 进程在后台等待退出；客户端断开后 stdout 被关闭，转码进程会随之结束。
+    pub fn transcode(
+        &self,
+        profile: &TranscodeProfile,
+        input: &Path,
+    ) -> Result<TranscodeProcess, AppError> {
+        spawn_transcoder(self.build_command(profile, input)?, &profile.format)
+    }
+
+    /// 启动 HLS 分片转码进程，只输出指定时间片段
+    pub fn transcode_segment(
+        &self,
+        input: &Path,
+        bit_rate: u32,
+        slice: TimeSlice,
+    ) -> Result<TranscodeProcess, AppError> {
+        let template = self
+            .segment_command
+            .as_ref()
+            .ok_or_else(|| AppError::ValidationError("HLS transcoding is disabled".to_string()))?;
+
+        let command = command_from_template(template, input, bit_rate, Some(slice))?;
+        spawn_transcoder(command, "hls")
+    }
+}
+
+/// 根据命令模板构建命令
+///
+/// 模板按空白拆分为参数后再替换占位符，因此路径中的空格不会破坏参数。
+/// 占位符: `%s` 源文件路径，`%b` 码率 (kbps)，`%t` 起始秒数，`%l` 时长秒数。
+/// 每个参数只从左到右替换一遍，替换进来的内容 (例如含 `%b` 的文件名) 不会再被替换。
+fn command_from_template(
+    template: &str,
+    input: &Path,
+    bit_rate: u32,
+    slice: Option<TimeSlice>,
+) -> Result<Command, AppError> {
+    let input = input.to_string_lossy();
+    let bit_rate = bit_rate.to_string();
+    let (start, length) = match slice {
+        Some(slice) => (
+            format!("{:.3}", slice.start),
+            format!("{:.3}", slice.length),
+        ),
+        None => (String::from("0"), String::new()),
+    };
+
+    let mut args = template.split_whitespace().map(|arg| {
+        substitute_placeholders(arg, |placeholder| match placeholder {
+            's' => Some(&input),
+            'b' => Some(&bit_rate),
+            't' => Some(&start),
+            'l' => Some(&length),
+            _ => None,
+        })
+    });
+
+    let program = args
+        .next()
+        .ok_or_else(|| AppError::ConfigError(format!("Empty transcoder: {}", template)))?;
+
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+
+    Ok(command)
+}
+
+/// 从左到右替换参数中的 `%x` 占位符，未知的占位符原样保留
+fn substitute_placeholders<'a>(arg: &str, value: impl Fn(char) -> Option<&'a str>) -> String {
+    let mut result = String::with_capacity(arg.len());
+    let mut chars = arg.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(placeholder) => match value(placeholder) {
+                Some(value) => result.push_str(value),
+                None => {
+                    result.push('%');
+                    result.push(placeholder);
+                }
+            },
+            None => result.push('%'),
+        }
+    }
+    result
+}
+
+/// 启动转码进程并在后台等待退出
+fn spawn_transcoder(mut command: Command, label: &str) -> Result<TranscodeProcess, AppError> {
+    let mut child = command.spawn()?;
+    let stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| AppError::server_busy("Failed to capture transcoder output"))?;
+
+    let label = label.to_string();
+    let exit = tokio::spawn(async move {
+        match child.wait().await {
+            Ok(status) if status.success() => true,
+            Ok(status) => {
+                tracing::warn!("Transcoder for {} exited with {}", label, status);
+                false
+            }
+            Err(e) => {
+                tracing::warn!("Failed to wait transcoder for {}: {}", label, e);
+                false
+            }
+        }
+    });
+
+    Ok(TranscodeProcess { stdout, exit })
+}
+
+/// 根据格式获取 Content-Type
+pub fn content_type_for_format(format: &str) -> &'static str {
+    match format.to_lowercase().as_str() {
+        "mp3" => "audio/mpeg",
+        "opus" | "ogg" | "oga" => "audio/ogg",
+        "aac" => "audio/aac",
+        "m4a" | "mp4" => "audio/mp4",
+        "flac" => "audio/flac",
+        "wav" => "audio/wav",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+
+    fn stub_service() -> TranscodeService {
+        // 使用 shell 命令模拟转码器: mp3 原样输出文件，opus 输出码率
+        TranscodeService::new(
+            HashMap::from([
+                ("mp3".to_string(), "cat %s".to_string()),
+                ("OPUS".to_string(), "echo %b".to_string()),
+            ]),
+            "mp3",
+        )
+    }
+
+    fn flac_source() -> SourceInfo<'static> {
+        SourceInfo {
+            suffix: "flac",
+            bit_rate: Some(1_000_000),
+        }
+    }
+
+    #[test]
+    fn test_substitute_placeholders() {
+        let value = |placeholder| match placeholder {
+            's' => Some("/music/100%b pure.flac"),
+            'b' => Some("128"),
+            _ => None,
+        };
+        // 替换进来的路径中的 %b 不会再被替换
+        assert_eq!(
+            substitute_placeholders("file:%s", value),
+            "file:/music/100%b pure.flac"
+        );
+        assert_eq!(substitute_placeholders("%bk", value), "128k");
+        assert_eq!(substitute_placeholders("50%x%", value), "50%x%");
+    }
+
+    #[test]
+    fn test_effective_bit_rate() {
+        assert_eq!(TranscodeService::effective_bit_rate(None, None), None);
+        assert_eq!(
+            TranscodeService::effective_bit_rate(Some(128), None),
+            Some(128)
+        );
+        assert_eq!(
+            TranscodeService::effective_bit_rate(Some(320), Some(192)),
+            Some(192)
+        );
+        assert_eq!(
+            TranscodeService::effective_bit_rate(Some(96), Some(192)),
+            Some(96)
+        );
+        // 0 表示不限制
+        assert_eq!(
+            TranscodeService::effective_bit_rate(Some(0), Some(192)),
+            Some(192)
+        );
+        assert_eq!(TranscodeService::effective_bit_rate(Some(0), Some(0)), None);
+    }
+
+    #[test]
+    fn test_decide() {
+        let service = stub_service();
+
+        // raw 始终直通
+        assert_eq!(
+            service.decide(Some("raw"), Some(64), Some(64), flac_source()),
+            StreamDecision::Raw
+        );
+
+        // 仅限制码率时使用默认格式
+        assert_eq!(
+            service.decide(None, Some(128), Some(320), flac_source()),
+            StreamDecision::Transcode(TranscodeProfile {
+                format: "mp3".to_string(),
+                bit_rate: 128,
+            })
+        );
+
+        // 用户上限低于请求码率
+        assert_eq!(
+            service.decide(Some("opus"), Some(320), Some(96), flac_source()),
+            StreamDecision::Transcode(TranscodeProfile {
+                format: "opus".to_string(),
+                bit_rate: 96,
+            })
+        );
+
+        // 未超限时不转码
+        let mp3_source = SourceInfo {
+            suffix: "mp3",
+            bit_rate: Some(128_000),
+        };
+        assert_eq!(
+            service.decide(None, Some(320), Some(0), mp3_source),
+            StreamDecision::Raw
+        );
+        assert_eq!(
+            service.decide(Some("mp3"), None, Some(320), mp3_source),
+            StreamDecision::Raw
+        );
+
+        // 转码码率不超过源码率
+        assert_eq!(
+            service.decide(Some("opus"), None, None, mp3_source),
+            StreamDecision::Transcode(TranscodeProfile {
+                format: "opus".to_string(),
+                bit_rate: 128,
+            })
+        );
+
+        // 不支持的格式且未超限时直通
+        assert_eq!(
+            service.decide(Some("wma"), None, None, flac_source()),
+            StreamDecision::Raw
+        );
+    }
+
+    #[tokio::test]
+    async fn test_transcode_with_stub() {
+        let service = stub_service();
+        let path = std::env::temp_dir().join(format!(
+            "musicflow transcode {}.flac",
+            crate::utils::id_builder::generate_id()
+        ));
+        tokio::fs::write(&path, b"fake flac data").await.unwrap();
+
+        // 路径包含空格时依然作为单个参数传递
+        let mut output = Vec::new();
+        let profile = TranscodeProfile {
+            format: "mp3".to_string(),
+            bit_rate: 128,
+        };
+        let mut process = service.transcode(&profile, &path).unwrap();
+        process.stdout.read_to_end(&mut output).await.unwrap();
+        assert_eq!(output, b"fake flac data");
+        assert!(process.exit.await.unwrap());
+        assert_eq!(profile.content_type(), "audio/mpeg");
+
+        let mut output = String::new();
+        let profile = TranscodeProfile {
+            format: "opus".to_string(),
+            bit_rate: 96,
+        };
+        service
+            .transcode(&profile, &path)
+            .unwrap()
+            .stdout
+            .read_to_string(&mut output)
+            .await
+            .unwrap();
+        assert_eq!(output.trim(), "96");
+
+        // 未配置的格式返回错误
+        let profile = TranscodeProfile {
+            format: "aac".to_string(),
+            bit_rate: 96,
+        };
+        assert!(service.transcode(&profile, &path).is_err());
+
+        // 分片转码替换时间占位符
+        assert!(service
+            .transcode_segment(
+                &path,
+                96,
+                TimeSlice {
+                    start: 0.0,
+                    length: 10.0
+                }
+            )
+            .is_err());
+        let service = service.with_segment_command("echo %t %l %b");
+        let mut output = String::new();
+        service
+            .transcode_segment(
+                &path,
+                96,
+                TimeSlice {
+                    start: 20.0,
+                    length: 5.5,
+                },
+            )
+            .unwrap()
+            .stdout
+            .read_to_string(&mut output)
+            .await
+            .unwrap();
+        assert_eq!(output.trim(), "20.000 5.500 96");
+
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+}
@@ -8,7 +8,7 @@
 use crate::error::AppError;
 use crate::models::dto::{CreateUserRequest, UpdateUserRequest};
 use crate::models::entities::User;
-use crate::services::{AuthService, ServiceContext};
+use crate::services::{AuthService, FolderScope, MusicFolderService, ServiceContext};
 use std::sync::Arc;
 
 /// 用户管理服务
@@ -91,8 +91,27 @@ impl UserService {
             return Err(AppError::validation_error("Username already exists"));
         }
 
-        // 使用 AuthService 注册用户
-        self.auth_service.register(request).await?;
+        let folder_ids = request.music_folder_id.clone();
+        self.music_folder_service()
+            .validate_folder_ids(&folder_ids)
+            .await?;
+
+        let user = self.auth_service.register(request).await?;
+
+        // 未指定 musicFolderId 时可访问当前全部音乐文件夹
+        let folder_ids = if folder_ids.is_empty() {
+            self.music_folder_service()
+                .get_music_folders_in_scope(&FolderScope::All)
+                .await?
+                .into_iter()
+                .map(|folder| folder.id)
+                .collect()
+        } else {
+            folder_ids
+        };
+        self.music_folder_service()
+            .set_user_folders(&user.id, &folder_ids)
+            .await?;
 
         Ok(())
     }
@@ -108,7 +127,12 @@ impl UserService {
     /// # 权限
     ///
     /// 需要管理员权限,且不能删除自己
-    pub async fn delete_user(&self, is_admin: bool, requester_username: &str, username: &str) -> Result<(), AppError> {
+    pub async fn delete_user(
+        &self,
+        is_admin: bool,
+        requester_username: &str,
+        username: &str,
+    ) -> Result<(), AppError> {
         // 权限检查
         if !is_admin {
             return Err(AppError::access_denied("Admin only"));
@@ -150,6 +174,14 @@ impl UserService {
             return Err(AppError::access_denied("Admin only"));
         }
 
+        // 更新可访问的音乐文件夹
+        if !request.music_folder_id.is_empty() {
+            let user = self.get_user(username).await?;
+            self.music_folder_service()
+                .set_user_folders(&user.id, &request.music_folder_id)
+                .await?;
+        }
+
         // 构建动态 UPDATE 查询
         let mut query_parts = Vec::new();
 
@@ -183,6 +215,9 @@ impl UserService {
         if request.video_conversion_role.is_some() {
             query_parts.push("video_conversion_role = ?");
         }
+        if request.jukebox_role.is_some() {
+            query_parts.push("jukebox_role = ?");
+        }
         if request.scrobbling_enabled.is_some() {
             query_parts.push("scrobbling_enabled = ?");
         }
@@ -231,6 +266,9 @@ impl UserService {
         if let Some(video_conversion_role) = request.video_conversion_role {
             query = query.bind(video_conversion_role);
         }
+        if let Some(jukebox_role) = request.jukebox_role {
+            query = query.bind(jukebox_role);
+        }
         if let Some(scrobbling_enabled) = request.scrobbling_enabled {
             query = query.bind(scrobbling_enabled);
         }
@@ -241,6 +279,17 @@ impl UserService {
         Ok(())
     }
 
+    /// 获取用户可访问的音乐文件夹 ID
+    pub async fn get_user_folder_ids(&self, user_id: &str) -> Result<Vec<i64>, AppError> {
+        self.music_folder_service()
+            .get_user_folder_ids(user_id)
+            .await
+    }
+
+    fn music_folder_service(&self) -> MusicFolderService {
+        MusicFolderService::new(self.ctx.clone())
+    }
+
     /// 修改密码
     ///
     /// # 参数
@@ -289,6 +338,7 @@ impl UserService {
 mod tests {
     use super::*;
     use crate::models::dto::CreateUserRequest;
+    use crate::utils::password_cipher::PasswordCipher;
     use sqlx::SqlitePool;
 
     async fn setup_test_db() -> SqlitePool {
@@ -311,6 +361,7 @@ mod tests {
                 podcast_role INTEGER DEFAULT 0,
                 share_role INTEGER DEFAULT 1,
                 video_conversion_role INTEGER DEFAULT 0,
+                jukebox_role BOOLEAN NOT NULL DEFAULT 0,
                 scrobbling_enabled INTEGER DEFAULT 1,
                 created_at TEXT DEFAULT CURRENT_TIMESTAMP,
                 updated_at TEXT DEFAULT CURRENT_TIMESTAMP
@@ -353,7 +404,8 @@ mod tests {
 
     fn create_service(pool: SqlitePool) -> UserService {
         let ctx = Arc::new(ServiceContext::new(pool.clone()));
-        let auth_service = Arc::new(AuthService::new(pool));
+        let cipher = Arc::new(PasswordCipher::new("test-password-key", &[]));
+        let auth_service = Arc::new(AuthService::new(pool, cipher));
         UserService::new(ctx, auth_service)
     }
 
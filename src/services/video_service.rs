@@ -0,0 +1,203 @@
+//! 视频服务
+//!
+//! 负责视频相关的业务逻辑:
+//! - 查询用户可访问的视频 (视频由扫描服务从音乐文件夹中索引)
+//! - 查找并读取视频的外挂字幕，按需转换 SRT / WebVTT 格式
+
+use crate::error::AppError;
+use crate::models::entities::Video;
+use crate::services::{FolderScope, ServiceContext};
+use crate::utils::captions::{self, CaptionFile, CaptionFormat};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// 视频服务
+pub struct VideoService {
+    ctx: Arc<ServiceContext>,
+}
+
+impl VideoService {
+    /// 创建新的 VideoService
+    pub fn new(ctx: Arc<ServiceContext>) -> Self {
+        Self { ctx }
+    }
+
+    /// 分页获取可访问的视频 (按标题排序)
+    pub async fn get_videos(
+        &self,
+        scope: &FolderScope,
+        size: i32,
+        offset: i32,
+    ) -> Result<Vec<Video>, AppError> {
+        let videos = sqlx::query_as::<_, Video>(&format!(
+            "SELECT * FROM videos WHERE {}
+             ORDER BY title COLLATE NOCASE
+             LIMIT ? OFFSET ?",
+            scope.sql_filter("music_folder_id")
+        ))
+        .bind(size)
+        .bind(offset)
+        .fetch_all(&self.ctx.pool)
+        .await?;
+
+        Ok(videos)
+    }
+
+    /// 获取可访问的视频
+    pub async fn get_video(&self, id: &str, scope: &FolderScope) -> Result<Video, AppError> {
+        sqlx::query_as::<_, Video>(&format!(
+            "SELECT * FROM videos WHERE id = ? AND {}",
+            scope.sql_filter("music_folder_id")
+        ))
+        .bind(id)
+        .fetch_optional(&self.ctx.pool)
+        .await?
+        .ok_or_else(|| AppError::not_found("Video"))
+    }
+
+    /// 获取视频的外挂字幕列表，字幕 ID 为列表中的序号
+    pub async fn get_captions(
+        &self,
+        id: &str,
+        scope: &FolderScope,
+    ) -> Result<(Video, Vec<CaptionFile>), AppError> {
+        let video = self.get_video(id, scope).await?;
+        // 遍历视频所在目录是阻塞操作
+        let video_path = PathBuf::from(&video.file_path);
+        let captions = tokio::task::spawn_blocking(move || captions::find_captions(&video_path))
+            .await
+            .map_err(std::io::Error::other)?;
+        Ok((video, captions))
+    }
+
+    /// 读取字幕内容
+    ///
+    /// `caption_id` 为空时使用第一个字幕；`format` 为空时保持原始格式，
+    /// 否则转换为指定格式。返回实际格式和字幕文本。
+    pub async fn read_caption(
+        &self,
+        id: &str,
+        caption_id: Option<usize>,
+        format: Option<CaptionFormat>,
+        scope: &FolderScope,
+    ) -> Result<(CaptionFormat, String), AppError> {
+        let (_, captions) = self.get_captions(id, scope).await?;
+        let caption = captions
+            .into_iter()
+            .nth(caption_id.unwrap_or(0))
+            .ok_or_else(|| AppError::not_found("Captions"))?;
+
+        let bytes = tokio::fs::read(&caption.path).await?;
+        let text = String::from_utf8_lossy(&bytes);
+        let format = format.unwrap_or(caption.format);
+
+        Ok((format, captions::convert(&text, caption.format, format)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::SqlitePool;
+
+    async fn setup_test_db(name: &str) -> (VideoService, std::path::PathBuf) {
+        let pool = SqlitePool::connect("sqlite::memory:").await.unwrap();
+        sqlx::query(
+            "CREATE TABLE videos (
+                id TEXT PRIMARY KEY,
+                title TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                content_type TEXT NOT NULL,
+                file_size BIGINT,
+                duration INTEGER,
+                bit_rate INTEGER,
+                width INTEGER,
+                height INTEGER,
+                music_folder_id INTEGER,
+                created_at TEXT NOT NULL DEFAULT (datetime('now')),
+                updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+            )",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let dir =
+            std::env::temp_dir().join(format!("video_service_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("b.srt"), "1\n00:00:01,000 --> 00:00:02,000\nHi\n").unwrap();
+
+        for (id, title, folder) in [("v1", "b", 1), ("v2", "a", 2)] {
+            sqlx::query(
+                "INSERT INTO videos (id, title, file_path, content_type, width, height, music_folder_id)
+                 VALUES (?, ?, ?, 'video/mp4', 1280, 720, ?)",
+            )
+            .bind(id)
+            .bind(title)
+            .bind(dir.join(format!("{}.mp4", title)).to_string_lossy().to_string())
+            .bind(folder)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        (VideoService::new(Arc::new(ServiceContext::new(pool))), dir)
+    }
+
+    #[tokio::test]
+    async fn test_videos_in_scope() {
+        let (service, dir) = setup_test_db("scope").await;
+
+        let videos = service.get_videos(&FolderScope::All, 10, 0).await.unwrap();
+        let titles: Vec<&str> = videos.iter().map(|v| v.title.as_str()).collect();
+        assert_eq!(titles, vec!["a", "b"]);
+        assert_eq!(videos[0].width, Some(1280));
+
+        let scope = FolderScope::Folders(vec![1]);
+        assert_eq!(service.get_videos(&scope, 10, 0).await.unwrap().len(), 1);
+        assert!(service.get_video("v1", &scope).await.is_ok());
+        assert!(matches!(
+            service.get_video("v2", &scope).await,
+            Err(AppError::NotFound(_))
+        ));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_read_caption() {
+        let (service, dir) = setup_test_db("captions").await;
+        let scope = FolderScope::All;
+
+        let (_, captions) = service.get_captions("v1", &scope).await.unwrap();
+        assert_eq!(captions.len(), 1);
+        assert_eq!(captions[0].name, "b");
+
+        let (format, text) = service
+            .read_caption("v1", None, None, &scope)
+            .await
+            .unwrap();
+        assert_eq!(format, CaptionFormat::Srt);
+        assert!(text.contains("00:00:01,000"));
+
+        let (format, text) = service
+            .read_caption("v1", Some(0), Some(CaptionFormat::Vtt), &scope)
+            .await
+            .unwrap();
+        assert_eq!(format, CaptionFormat::Vtt);
+        assert!(text.starts_with("WEBVTT"));
+        assert!(text.contains("00:00:01.000"));
+
+        // 没有字幕的视频和不存在的字幕序号
+        assert!(service
+            .read_caption("v2", None, None, &scope)
+            .await
+            .is_err());
+        assert!(service
+            .read_caption("v1", Some(1), None, &scope)
+            .await
+            .is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
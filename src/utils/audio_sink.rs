@@ -0,0 +1,315 @@
+//! 点唱机音频输出
+//!
+//! 播放器将解码后的交错 f32 采样写入 `AudioSink`:
+//! - `NullSink` 丢弃音频，按实时速度等待，用于没有声卡的服务器
+//! - `WavFileSink` 写入 16 位 PCM WAV 文件，不限速，便于测试
+//! - `DeviceSink` 通过声卡播放，需要启用 `jukebox-output` 特性
+
+use anyhow::{anyhow, Result};
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::config::JukeboxSink;
+
+/// 输出缓冲的最大时长，模拟声卡缓冲区
+const BUFFER_DURATION: Duration = Duration::from_millis(200);
+
+/// 音频格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AudioSpec {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl AudioSpec {
+    /// 采样数对应的播放时长
+    pub fn duration_of(&self, samples: usize) -> Duration {
+        let frames = samples / self.channels.max(1) as usize;
+        Duration::from_secs_f64(frames as f64 / self.sample_rate.max(1) as f64)
+    }
+}
+
+/// 音频输出
+pub trait AudioSink {
+    /// 写入交错的采样，输出缓冲区已满时阻塞
+    fn write(&mut self, spec: AudioSpec, samples: &[f32]) -> Result<()>;
+
+    /// 丢弃尚未播放的缓冲数据 (暂停或切换歌曲时调用)
+    fn clear(&mut self) {}
+}
+
+/// 根据配置打开音频输出
+pub fn open(sink: &JukeboxSink) -> Result<Box<dyn AudioSink>> {
+    match sink {
+        JukeboxSink::Null => Ok(Box::new(NullSink::default())),
+        JukeboxSink::WavFile(path) => Ok(Box::new(WavFileSink::create(path)?)),
+        #[cfg(feature = "jukebox-output")]
+        JukeboxSink::Device => Ok(Box::new(device::DeviceSink::open()?)),
+        #[cfg(not(feature = "jukebox-output"))]
+        JukeboxSink::Device => Err(anyhow!(
+            "Audio device output requires the jukebox-output feature"
+        )),
+    }
+}
+
+/// 丢弃音频的输出，按实时速度阻塞
+#[derive(Debug, Default)]
+pub struct NullSink {
+    /// 已写入的音频播放完毕的时间
+    deadline: Option<Instant>,
+}
+
+impl AudioSink for NullSink {
+    fn write(&mut self, spec: AudioSpec, samples: &[f32]) -> Result<()> {
+        let now = Instant::now();
+        let deadline =
+            self.deadline.filter(|d| *d > now).unwrap_or(now) + spec.duration_of(samples.len());
+        if let Some(wait) = deadline.checked_duration_since(now + BUFFER_DURATION) {
+            std::thread::sleep(wait);
+        }
+        self.deadline = Some(deadline);
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        self.deadline = None;
+    }
+}
+
+/// 写入 WAV 文件的输出
+///
+/// 文件格式取自第一首歌曲，之后格式不同的歌曲写入失败
+pub struct WavFileSink {
+    file: BufWriter<File>,
+    spec: Option<AudioSpec>,
+    /// 数据块字节数
+    data_len: u32,
+}
+
+impl WavFileSink {
+    /// 创建 (或覆盖) WAV 文件
+    pub fn create(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        Ok(Self {
+            file: BufWriter::new(File::create(path)?),
+            spec: None,
+            data_len: 0,
+        })
+    }
+
+    /// 写入 44 字节的 RIFF 头
+    fn write_header(&mut self, spec: AudioSpec) -> Result<()> {
+        let block_align = spec.channels as u32 * 2;
+        let file = &mut self.file;
+        file.write_all(b"RIFF")?;
+        file.write_all(&(36 + self.data_len).to_le_bytes())?;
+        file.write_all(b"WAVEfmt ")?;
+        file.write_all(&16u32.to_le_bytes())?;
+        file.write_all(&1u16.to_le_bytes())?;
+        file.write_all(&spec.channels.to_le_bytes())?;
+        file.write_all(&spec.sample_rate.to_le_bytes())?;
+        file.write_all(&(spec.sample_rate * block_align).to_le_bytes())?;
+        file.write_all(&(block_align as u16).to_le_bytes())?;
+        file.write_all(&16u16.to_le_bytes())?;
+        file.write_all(b"data")?;
+        file.write_all(&self.data_len.to_le_bytes())?;
+        Ok(())
+    }
+}
+
+impl AudioSink for WavFileSink {
+    fn write(&mut self, spec: AudioSpec, samples: &[f32]) -> Result<()> {
+        match self.spec {
+            None => {
+                self.write_header(spec)?;
+                self.spec = Some(spec);
+            }
+            Some(current) if current != spec => {
+                return Err(anyhow!(
+                    "WAV output is {} Hz / {} channels, got {} Hz / {} channels",
+                    current.sample_rate,
+                    current.channels,
+                    spec.sample_rate,
+                    spec.channels
+                ));
+            }
+            Some(_) => {}
+        }
+
+        for sample in samples {
+            let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+            self.file.write_all(&value.to_le_bytes())?;
+        }
+        self.data_len = self.data_len.saturating_add(samples.len() as u32 * 2);
+
+        // 每次写入后更新头部的长度，进程中断时文件仍然有效
+        self.file.seek(SeekFrom::Start(4))?;
+        self.file.write_all(&(36 + self.data_len).to_le_bytes())?;
+        self.file.seek(SeekFrom::Start(40))?;
+        self.file.write_all(&self.data_len.to_le_bytes())?;
+        self.file.seek(SeekFrom::End(0))?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+/// 声卡输出
+#[cfg(feature = "jukebox-output")]
+mod device {
+    use super::{AudioSink, AudioSpec, BUFFER_DURATION};
+    use anyhow::{anyhow, Result};
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// 通过系统默认输出设备播放，格式变化时重建输出流
+    pub struct DeviceSink {
+        device: cpal::Device,
+        stream: Option<(AudioSpec, cpal::Stream)>,
+        /// 等待输出的采样，由输出回调消费
+        buffer: Arc<Mutex<VecDeque<f32>>>,
+    }
+
+    impl DeviceSink {
+        pub fn open() -> Result<Self> {
+            let device = cpal::default_host()
+                .default_output_device()
+                .ok_or_else(|| anyhow!("No audio output device available"))?;
+            tracing::info!(
+                "Jukebox output device: {}",
+                device.name().unwrap_or_default()
+            );
+
+            Ok(Self {
+                device,
+                stream: None,
+                buffer: Arc::new(Mutex::new(VecDeque::new())),
+            })
+        }
+
+        fn buffered(&self) -> usize {
+            self.buffer.lock().map(|b| b.len()).unwrap_or(0)
+        }
+
+        fn ensure_stream(&mut self, spec: AudioSpec) -> Result<()> {
+            if matches!(&self.stream, Some((current, _)) if *current == spec) {
+                return Ok(());
+            }
+
+            // 等待上一首歌曲的缓冲播放完毕
+            while self.stream.is_some() && self.buffered() > 0 {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            self.stream = None;
+
+            let config = cpal::StreamConfig {
+                channels: spec.channels,
+                sample_rate: cpal::SampleRate(spec.sample_rate),
+                buffer_size: cpal::BufferSize::Default,
+            };
+            let buffer = self.buffer.clone();
+            let stream = self.device.build_output_stream(
+                &config,
+                move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                    let Ok(mut buffer) = buffer.lock() else {
+                        return;
+                    };
+                    for sample in data.iter_mut() {
+                        *sample = buffer.pop_front().unwrap_or(0.0);
+                    }
+                },
+                |e| tracing::warn!("Jukebox audio output error: {}", e),
+                None,
+            )?;
+            stream.play()?;
+            self.stream = Some((spec, stream));
+
+            Ok(())
+        }
+    }
+
+    impl AudioSink for DeviceSink {
+        fn write(&mut self, spec: AudioSpec, samples: &[f32]) -> Result<()> {
+            self.ensure_stream(spec)?;
+
+            let limit = spec.sample_rate as usize
+                * spec.channels as usize
+                * BUFFER_DURATION.as_millis() as usize
+                / 1000;
+            while self.buffered() > limit {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            if let Ok(mut buffer) = self.buffer.lock() {
+                buffer.extend(samples);
+            }
+
+            Ok(())
+        }
+
+        fn clear(&mut self) {
+            if let Ok(mut buffer) = self.buffer.lock() {
+                buffer.clear();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEREO: AudioSpec = AudioSpec {
+        sample_rate: 8000,
+        channels: 2,
+    };
+
+    #[test]
+    fn test_wav_file_sink() {
+        let path = std::env::temp_dir().join(format!("jukebox_sink_{}.wav", std::process::id()));
+        let mut sink = WavFileSink::create(&path).unwrap();
+
+        sink.write(STEREO, &[0.0, 0.5, -0.5, 2.0]).unwrap();
+        sink.write(STEREO, &[1.0, -1.0]).unwrap();
+        assert!(sink
+            .write(
+                AudioSpec {
+                    sample_rate: 44100,
+                    channels: 2
+                },
+                &[0.0, 0.0]
+            )
+            .is_err());
+        drop(sink);
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(bytes.len(), 44 + 12);
+        assert_eq!(&bytes[0..4], b"RIFF");
+        assert_eq!(u32::from_le_bytes(bytes[4..8].try_into().unwrap()), 36 + 12);
+        assert_eq!(u32::from_le_bytes(bytes[24..28].try_into().unwrap()), 8000);
+        assert_eq!(u32::from_le_bytes(bytes[40..44].try_into().unwrap()), 12);
+        // 超出范围的采样被截断
+        assert_eq!(i16::from_le_bytes([bytes[50], bytes[51]]), i16::MAX);
+    }
+
+    #[test]
+    fn test_null_sink_paces_output() {
+        let mut sink = NullSink::default();
+        let started = Instant::now();
+
+        // 0.1 秒的音频，缓冲满 0.2 秒后开始阻塞
+        let samples = vec![0.0; 1600];
+        for _ in 0..5 {
+            sink.write(STEREO, &samples).unwrap();
+        }
+
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(250), "{:?}", elapsed);
+        assert!(elapsed < Duration::from_millis(450), "{:?}", elapsed);
+    }
+}
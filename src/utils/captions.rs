@@ -0,0 +1,197 @@
+//! 视频外挂字幕
+//!
+//! 字幕与视频位于同一目录，文件名为 `<视频名>.srt` / `<视频名>.vtt`，
+//! 或带语言标签的 `<视频名>.<标签>.srt` (例如 `movie.zh.srt`)。
+//! SRT 与 WebVTT 之间可以互相转换。
+
+use std::path::{Path, PathBuf};
+
+/// 字幕格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptionFormat {
+    Srt,
+    Vtt,
+}
+
+impl CaptionFormat {
+    /// 解析格式名称 (不区分大小写)
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_lowercase().as_str() {
+            "srt" => Some(Self::Srt),
+            "vtt" => Some(Self::Vtt),
+            _ => None,
+        }
+    }
+
+    /// 响应的 Content-Type (转换后的文本均为 UTF-8)
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Srt => "application/x-subrip; charset=utf-8",
+            Self::Vtt => "text/vtt; charset=utf-8",
+        }
+    }
+}
+
+/// 字幕文件
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptionFile {
+    pub path: PathBuf,
+    pub format: CaptionFormat,
+    /// 显示名称: 语言标签，没有标签时为视频文件名
+    pub name: String,
+}
+
+/// 查找视频的外挂字幕 (按文件名排序)
+pub fn find_captions(video_path: &Path) -> Vec<CaptionFile> {
+    let (Some(dir), Some(stem)) = (
+        video_path.parent(),
+        video_path.file_stem().and_then(|s| s.to_str()),
+    ) else {
+        return vec![];
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+
+    let mut captions: Vec<CaptionFile> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_file()))
+        .filter_map(|entry| {
+            let path = entry.path();
+            let format = CaptionFormat::parse(path.extension()?.to_str()?)?;
+            let rest = path.file_stem()?.to_str()?.strip_prefix(stem)?;
+            let name = match rest.strip_prefix('.') {
+                Some(label) if !label.is_empty() => label.to_string(),
+                _ if rest.is_empty() => stem.to_string(),
+                _ => return None,
+            };
+            Some(CaptionFile { path, format, name })
+        })
+        .collect();
+
+    captions.sort_by(|a, b| a.path.cmp(&b.path));
+    captions
+}
+
+/// 转换字幕格式
+pub fn convert(text: &str, from: CaptionFormat, to: CaptionFormat) -> String {
+    match (from, to) {
+        (CaptionFormat::Srt, CaptionFormat::Vtt) => srt_to_vtt(text),
+        (CaptionFormat::Vtt, CaptionFormat::Srt) => vtt_to_srt(text),
+        _ => text.to_string(),
+    }
+}
+
+/// SRT 转 WebVTT: 添加文件头，时间戳的毫秒分隔符改为 `.`
+fn srt_to_vtt(text: &str) -> String {
+    let mut vtt = String::from("WEBVTT\n\n");
+    for line in text.trim_start_matches('\u{feff}').lines() {
+        if line.contains("-->") {
+            vtt.push_str(&line.replace(',', "."));
+        } else {
+            vtt.push_str(line);
+        }
+        vtt.push('\n');
+    }
+    vtt
+}
+
+/// WebVTT 转 SRT: 去掉文件头和注释块，重新编号，时间戳补全小时并改用 `,`
+fn vtt_to_srt(text: &str) -> String {
+    let mut srt = String::new();
+    let mut index = 0;
+
+    for block in text
+        .trim_start_matches('\u{feff}')
+        .replace("\r\n", "\n")
+        .split("\n\n")
+    {
+        let lines: Vec<&str> = block.lines().collect();
+        let Some(timing) = lines.iter().position(|line| line.contains("-->")) else {
+            // 文件头、NOTE、STYLE 等非字幕块
+            continue;
+        };
+
+        index += 1;
+        let (start, end) = lines[timing].split_once("-->").unwrap_or_default();
+        // 去掉时间戳后的位置设置
+        let end = end.split_whitespace().next().unwrap_or_default();
+        srt.push_str(&format!(
+            "{}\n{} --> {}\n",
+            index,
+            srt_timestamp(start.trim()),
+            srt_timestamp(end)
+        ));
+        for line in &lines[timing + 1..] {
+            srt.push_str(line);
+            srt.push('\n');
+        }
+        srt.push('\n');
+    }
+
+    srt
+}
+
+/// `mm:ss.ttt` 或 `hh:mm:ss.ttt` 转为 `hh:mm:ss,ttt`
+fn srt_timestamp(value: &str) -> String {
+    let value = value.replace('.', ",");
+    if value.matches(':').count() == 1 {
+        format!("00:{}", value)
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SRT: &str = "1\n00:00:01,000 --> 00:00:02,500\nHello, world\n\n2\n00:01:00,000 --> 00:01:03,000\nSecond\n";
+
+    #[test]
+    fn test_convert_captions() {
+        let vtt = convert(SRT, CaptionFormat::Srt, CaptionFormat::Vtt);
+        assert!(vtt.starts_with("WEBVTT\n\n1\n00:00:01.000 --> 00:00:02.500\n"));
+        // 字幕正文中的逗号保持不变
+        assert!(vtt.contains("Hello, world"));
+
+        let vtt = "WEBVTT\n\nNOTE comment\n\ncue-1\n00:01.000 --> 00:02.500 align:start\nHello, world\n\n01:00:00.000 --> 01:00:03.000\nSecond\n";
+        let srt = convert(vtt, CaptionFormat::Vtt, CaptionFormat::Srt);
+        assert_eq!(
+            srt,
+            "1\n00:00:01,000 --> 00:00:02,500\nHello, world\n\n2\n01:00:00,000 --> 01:00:03,000\nSecond\n\n"
+        );
+    }
+
+    #[test]
+    fn test_find_captions() {
+        let dir = std::env::temp_dir().join(format!("captions_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "movie.mp4",
+            "movie.vtt",
+            "movie.zh.srt",
+            "movie.en.SRT",
+            "movie2.srt",
+            "movie.txt",
+        ] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+
+        let captions = find_captions(&dir.join("movie.mp4"));
+        std::fs::remove_dir_all(&dir).ok();
+
+        let found: Vec<(&str, CaptionFormat)> = captions
+            .iter()
+            .map(|c| (c.name.as_str(), c.format))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("en", CaptionFormat::Srt),
+                ("movie", CaptionFormat::Vtt),
+                ("zh", CaptionFormat::Srt),
+            ]
+        );
+    }
+}
@@ -2,6 +2,7 @@
 #![allow(dead_code)]
 
 use md5;
+use sha2::{Digest, Sha256};
 
 /// 生成 Subsonic MD5 令牌 (MD5(password + salt))
 pub fn generate_subsonic_token(password: &str, salt: &str) -> String {
@@ -9,6 +10,33 @@ pub fn generate_subsonic_token(password: &str, salt: &str) -> String {
     format!("{:x}", md5::compute(combined))
 }
 
+/// 解码 Subsonic 客户端发送的密码
+///
+/// `p=enc:<hex>` 为十六进制编码的明文密码，其余情况原样返回
+pub fn decode_subsonic_password(password: &str) -> String {
+    password
+        .strip_prefix("enc:")
+        .and_then(decode_hex)
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .unwrap_or_else(|| password.to_string())
+}
+
+/// 十六进制编码
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 十六进制解码，格式非法时返回 None
+pub fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
 /// 生成随机 Salt
 pub fn generate_salt() -> String {
     use rand::Rng;
@@ -19,6 +47,21 @@ pub fn generate_salt() -> String {
         .collect()
 }
 
+/// 生成随机 API 密钥 (64 位十六进制)
+pub fn generate_api_key() -> String {
+    use rand::Rng;
+    let mut rng = rand::thread_rng();
+    rng.gen::<[u8; 32]>()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 计算 API 密钥哈希 (SHA-256)，数据库中只保存哈希
+pub fn hash_api_key(key: &str) -> String {
+    format!("{:x}", Sha256::digest(key.as_bytes()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,4 +82,29 @@ mod tests {
         assert_eq!(salt1.len(), 32);
         assert_ne!(salt1, salt2); // 应该是随机的
     }
+
+    #[test]
+    fn test_api_key() {
+        let key = generate_api_key();
+        assert_eq!(key.len(), 64);
+        assert_ne!(key, generate_api_key());
+
+        let hash = hash_api_key(&key);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_api_key(&key));
+        assert_ne!(hash, key);
+    }
+
+    #[test]
+    fn test_decode_subsonic_password() {
+        // "sesame" 的十六进制编码
+        assert_eq!(decode_subsonic_password("enc:736573616d65"), "sesame");
+        assert_eq!(decode_subsonic_password("sesame"), "sesame");
+        // 非法十六进制按明文处理
+        assert_eq!(decode_subsonic_password("enc:zz"), "enc:zz");
+        assert_eq!(
+            decode_hex(&encode_hex(&[0, 15, 255])),
+            Some(vec![0, 15, 255])
+        );
+    }
 }
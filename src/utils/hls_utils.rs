@@ -0,0 +1,185 @@
+//! HLS 播放列表工具
+//!
+//! 根据歌曲时长生成 m3u8 媒体播放列表与多码率主播放列表。
+#![allow(dead_code)]
+
+use crate::services::transcode_service::TimeSlice;
+
+/// m3u8 播放列表 Content-Type
+pub const MPEGURL_CONTENT_TYPE: &str = "application/vnd.apple.mpegurl";
+
+/// 默认分片时长 (秒)
+pub const HLS_SEGMENT_SECONDS: u32 = 10;
+
+/// 需要透传到分片 URL 的客户端参数
+///
+/// 播放器请求分片时不会自动携带播放列表的查询参数，必须写入 URL。
+/// 用户凭据 (u/p/t/s/apiKey) 不写入播放列表，改用短期流媒体令牌。
+pub const CLIENT_QUERY_KEYS: &[&str] = &["v", "c"];
+
+/// 分片 URL 中的流媒体令牌参数
+pub const TOKEN_QUERY_KEY: &str = "hlsToken";
+
+/// 流媒体令牌在歌曲时长之外的有效时间 (秒)，覆盖暂停、缓冲等情况
+pub const TOKEN_GRACE_SECONDS: i64 = 3600;
+
+/// 按时长切分分片，最后一个分片为剩余时长
+pub fn segment_slices(duration_secs: i64, segment_secs: u32) -> Vec<TimeSlice> {
+    if duration_secs <= 0 || segment_secs == 0 {
+        return vec![];
+    }
+
+    let duration = duration_secs as f64;
+    let segment = segment_secs as f64;
+    let count = (duration_secs as u64).div_ceil(segment_secs as u64);
+
+    (0..count)
+        .map(|i| {
+            let start = i as f64 * segment;
+            TimeSlice {
+                start,
+                length: segment.min(duration - start),
+            }
+        })
+        .collect()
+}
+
+/// 生成媒体播放列表
+///
+/// # 参数
+///
+/// * `slices` - 分片列表
+/// * `segment_url` - 根据分片序号生成分片 URL
+pub fn media_playlist(slices: &[TimeSlice], segment_url: impl Fn(usize) -> String) -> String {
+    let target_duration = slices
+        .iter()
+        .map(|slice| slice.length.ceil() as u64)
+        .max()
+        .unwrap_or(HLS_SEGMENT_SECONDS as u64);
+
+    let mut playlist = String::from("#EXTM3U\n#EXT-X-VERSION:3\n");
+    playlist.push_str(&format!("#EXT-X-TARGETDURATION:{}\n", target_duration));
+    playlist.push_str("#EXT-X-MEDIA-SEQUENCE:0\n#EXT-X-PLAYLIST-TYPE:VOD\n");
+
+    for (index, slice) in slices.iter().enumerate() {
+        playlist.push_str(&format!("#EXTINF:{:.3},\n", slice.length));
+        playlist.push_str(&segment_url(index));
+        playlist.push('\n');
+    }
+
+    playlist.push_str("#EXT-X-ENDLIST\n");
+    playlist
+}
+
+/// 生成多码率主播放列表
+///
+/// # 参数
+///
+/// * `variants` - (码率 kbps, 子播放列表 URL) 列表
+pub fn master_playlist(variants: &[(u32, String)]) -> String {
+    let mut playlist = String::from("#EXTM3U\n#EXT-X-VERSION:3\n");
+
+    for (bit_rate, url) in variants {
+        playlist.push_str(&format!(
+            "#EXT-X-STREAM-INF:BANDWIDTH={}\n",
+            *bit_rate as u64 * 1000
+        ));
+        playlist.push_str(url);
+        playlist.push('\n');
+    }
+
+    playlist
+}
+
+/// 生成分片 URL 的认证参数: 原始查询字符串中的客户端参数加上流媒体令牌
+///
+/// 返回值不含前导 `&`。
+pub fn auth_query(raw_query: Option<&str>, token: &str) -> String {
+    let params: Vec<(String, String)> =
+        serde_urlencoded::from_str(raw_query.unwrap_or("")).unwrap_or_default();
+
+    let auth_params: Vec<(String, String)> = params
+        .into_iter()
+        .filter(|(key, _)| CLIENT_QUERY_KEYS.contains(&key.as_str()))
+        .chain(std::iter::once((
+            TOKEN_QUERY_KEY.to_string(),
+            token.to_string(),
+        )))
+        .collect();
+
+    serde_urlencoded::to_string(auth_params).unwrap_or_default()
+}
+
+/// 拼接查询参数
+pub fn append_query(url: String, query: &str) -> String {
+    if query.is_empty() {
+        url
+    } else if url.contains('?') {
+        format!("{}&{}", url, query)
+    } else {
+        format!("{}?{}", url, query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_slices() {
+        let slices = segment_slices(25, 10);
+        assert_eq!(slices.len(), 3);
+        assert_eq!(
+            slices[2],
+            TimeSlice {
+                start: 20.0,
+                length: 5.0
+            }
+        );
+
+        assert_eq!(segment_slices(20, 10).len(), 2);
+        assert!(segment_slices(0, 10).is_empty());
+    }
+
+    #[test]
+    fn test_media_playlist() {
+        let slices = segment_slices(25, 10);
+        let playlist = media_playlist(&slices, |i| format!("hlsSegment?id=1&index={}", i));
+
+        assert!(playlist.starts_with("#EXTM3U\n"));
+        assert!(playlist.contains("#EXT-X-TARGETDURATION:10\n"));
+        assert_eq!(playlist.matches("#EXTINF:").count(), 3);
+        assert!(playlist.contains("#EXTINF:5.000,\nhlsSegment?id=1&index=2\n"));
+        assert!(playlist.ends_with("#EXT-X-ENDLIST\n"));
+    }
+
+    #[test]
+    fn test_master_playlist() {
+        let playlist = master_playlist(&[
+            (128, "hls.m3u8?id=1&bitRate=128".to_string()),
+            (320, "hls.m3u8?id=1&bitRate=320".to_string()),
+        ]);
+
+        assert!(
+            playlist.contains("#EXT-X-STREAM-INF:BANDWIDTH=128000\nhls.m3u8?id=1&bitRate=128\n")
+        );
+        assert!(
+            playlist.contains("#EXT-X-STREAM-INF:BANDWIDTH=320000\nhls.m3u8?id=1&bitRate=320\n")
+        );
+    }
+
+    #[test]
+    fn test_auth_query() {
+        // 用户凭据不写入分片 URL
+        assert_eq!(
+            auth_query(
+                Some("id=1&u=alice&t=abc&s=x%20y&apiKey=k&v=1.16.1&c=my%20app&bitRate=128"),
+                "tok.sig"
+            ),
+            "v=1.16.1&c=my+app&hlsToken=tok.sig"
+        );
+        assert_eq!(auth_query(None, "tok.sig"), "hlsToken=tok.sig");
+        assert_eq!(append_query("a?id=1".to_string(), "u=x"), "a?id=1&u=x");
+        assert_eq!(append_query("a".to_string(), ""), "a");
+    }
+}
@@ -38,7 +38,6 @@ impl CoverArt {
     }
 }
 
-
 #[test]
 fn test_generate_id() {
     for l in 8..=32 {
@@ -54,7 +53,7 @@ fn test_generate_id() {
     let al_id = CoverArt::Album.get_id(&id);
     println!("album id: {}", al_id);
     assert_eq!(al_id.len(), 19);
-    
+
     let ar_id = CoverArt::Artist.get_id(&id);
     println!("artist id: {}", ar_id);
     assert_eq!(ar_id.len(), 19);
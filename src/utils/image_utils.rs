@@ -98,17 +98,19 @@ pub fn resize_and_convert_to_webp(
     let resized = img.resize_exact(new_width, new_height, FilterType::Lanczos3);
 
     // 转换为 WebP
-    let webp = compress_img(&resized, config.quality);
-    if webp.is_err() {
-        // 压缩失败，保存为不压缩的 WebP
-        resized
-            .save_with_format(output_path, ImageFormat::WebP)
-            .map_err(|e| std::io::Error::other(format!("Failed to save WebP: {}", e)))?;
-    } else {
-        let output_path = output_path.to_string_lossy().to_string();
-        write_image_to_file(&webp.unwrap(), &output_path).map_err(|e| {
-            std::io::Error::other(format!("Failed to compress and save WebP: {}", e))
-        })?;
+    match compress_img(&resized, config.quality) {
+        Ok(webp) => {
+            let output_path = output_path.to_string_lossy().to_string();
+            write_image_to_file(&webp, &output_path).map_err(|e| {
+                std::io::Error::other(format!("Failed to compress and save WebP: {}", e))
+            })?;
+        }
+        Err(_) => {
+            // 压缩失败，保存为不压缩的 WebP
+            resized
+                .save_with_format(output_path, ImageFormat::WebP)
+                .map_err(|e| std::io::Error::other(format!("Failed to save WebP: {}", e)))?;
+        }
     }
 
     Ok(())
@@ -140,17 +142,19 @@ pub fn resize_and_convert_to_webp_by_data(
     let resized = img.resize_exact(new_width, new_height, FilterType::Lanczos3);
 
     // 转换为 WebP
-    let webp = compress_img(&resized, config.quality);
-    if webp.is_err() {
-        // 压缩失败，保存为不压缩的 WebP
-        resized
-            .save_with_format(output_path, ImageFormat::WebP)
-            .map_err(|e| std::io::Error::other(format!("Failed to save WebP: {}", e)))?;
-    } else {
-        let output_path = output_path.to_string_lossy().to_string();
-        write_image_to_file(&webp.unwrap(), &output_path).map_err(|e| {
-            std::io::Error::other(format!("Failed to compress and save WebP: {}", e))
-        })?;
+    match compress_img(&resized, config.quality) {
+        Ok(webp) => {
+            let output_path = output_path.to_string_lossy().to_string();
+            write_image_to_file(&webp, &output_path).map_err(|e| {
+                std::io::Error::other(format!("Failed to compress and save WebP: {}", e))
+            })?;
+        }
+        Err(_) => {
+            // 压缩失败，保存为不压缩的 WebP
+            resized
+                .save_with_format(output_path, ImageFormat::WebP)
+                .map_err(|e| std::io::Error::other(format!("Failed to save WebP: {}", e)))?;
+        }
     }
 
     Ok(())
@@ -266,8 +270,8 @@ pub fn extract_audio_metadata_static(path: &Path) -> Result<AudioMetadata, AppEr
 
     // 估算比特率:如果有文件大小和时长
     if let Some(size) = file_size {
-        if metadata.duration_secs > 0 {
-            metadata.bit_rate = Some(((size * 8) / metadata.duration_secs) as i32);
+        if let Some(bit_rate) = (size * 8).checked_div(metadata.duration_secs) {
+            metadata.bit_rate = Some(bit_rate as i32);
         }
     }
 
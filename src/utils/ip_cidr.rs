@@ -0,0 +1,167 @@
+//! IP 网段 (CIDR) 匹配
+//!
+//! 用于判断请求是否来自受信任的反向代理，支持 IPv4 与 IPv6，
+//! 不带前缀长度的地址视为单个主机。
+
+use std::fmt;
+use std::net::IpAddr;
+
+/// IP 网段，例如 `10.0.0.0/8`、`fd00::/8`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpCidr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpCidr {
+    /// 解析 `地址/前缀长度`，省略前缀长度时匹配单个地址
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (addr, prefix_len) = match value.split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len.parse::<u8>().ok()?)),
+            None => (value, None),
+        };
+
+        let addr: IpAddr = addr.parse().ok()?;
+        let max_len = Self::max_len(&addr);
+        let prefix_len = prefix_len.unwrap_or(max_len);
+        if prefix_len > max_len {
+            return None;
+        }
+
+        Some(Self { addr, prefix_len })
+    }
+
+    /// 检查地址是否属于该网段
+    ///
+    /// IPv4 映射的 IPv6 地址 (`::ffff:a.b.c.d`) 按 IPv4 地址匹配
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.addr, ip.to_canonical()) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => Self::prefix_matches(
+                u32::from(net).into(),
+                u32::from(ip).into(),
+                32,
+                self.prefix_len,
+            ),
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                Self::prefix_matches(u128::from(net), u128::from(ip), 128, self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+
+    fn max_len(addr: &IpAddr) -> u8 {
+        match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        }
+    }
+
+    fn prefix_matches(net: u128, ip: u128, bits: u8, prefix_len: u8) -> bool {
+        if prefix_len == 0 {
+            return true;
+        }
+        let shift = bits - prefix_len;
+        (net >> shift) == (ip >> shift)
+    }
+}
+
+/// 检查地址是否属于任一受信任网段
+pub fn is_trusted(ip: &IpAddr, trusted: &[IpCidr]) -> bool {
+    trusted.iter().any(|cidr| cidr.contains(ip))
+}
+
+/// 根据 `X-Forwarded-For` 计算客户端 IP
+///
+/// 只有对端地址是受信任的代理时才采信请求头：从右向左跳过受信任的代理，
+/// 第一个不受信任的地址即为客户端；请求头缺失或无法解析时返回对端地址。
+pub fn forwarded_client_ip(
+    peer: IpAddr,
+    forwarded_for: Option<&str>,
+    trusted: &[IpCidr],
+) -> IpAddr {
+    if !is_trusted(&peer, trusted) {
+        return peer;
+    }
+
+    let mut client = peer;
+    for hop in forwarded_for.unwrap_or_default().rsplit(',') {
+        let Ok(ip) = hop.trim().parse::<IpAddr>() else {
+            break;
+        };
+        client = ip;
+        if !is_trusted(&ip, trusted) {
+            break;
+        }
+    }
+    client
+}
+
+impl fmt::Display for IpCidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            IpCidr::parse("10.0.0.0/8").unwrap().to_string(),
+            "10.0.0.0/8"
+        );
+        assert_eq!(
+            IpCidr::parse(" 127.0.0.1 ").unwrap().to_string(),
+            "127.0.0.1/32"
+        );
+        assert_eq!(IpCidr::parse("fd00::/8").unwrap().to_string(), "fd00::/8");
+        assert!(IpCidr::parse("10.0.0.0/33").is_none());
+        assert!(IpCidr::parse("proxy.local").is_none());
+        assert!(IpCidr::parse("10.0.0.0/x").is_none());
+    }
+
+    #[test]
+    fn test_contains() {
+        let net = IpCidr::parse("172.16.0.0/12").unwrap();
+        assert!(net.contains(&ip("172.20.1.2")));
+        assert!(!net.contains(&ip("172.32.0.1")));
+        assert!(net.contains(&ip("::ffff:172.20.1.2")));
+        assert!(!net.contains(&ip("fd00::1")));
+
+        let host = IpCidr::parse("127.0.0.1").unwrap();
+        assert!(host.contains(&ip("127.0.0.1")));
+        assert!(!host.contains(&ip("127.0.0.2")));
+
+        let v6 = IpCidr::parse("fd00::/8").unwrap();
+        assert!(v6.contains(&ip("fd12:3456::1")));
+        assert!(!v6.contains(&ip("fe80::1")));
+
+        assert!(IpCidr::parse("0.0.0.0/0").unwrap().contains(&ip("8.8.8.8")));
+    }
+
+    #[test]
+    fn test_forwarded_client_ip() {
+        let trusted = vec![IpCidr::parse("10.0.0.0/8").unwrap()];
+        let proxy = ip("10.0.0.1");
+
+        // 不受信任的对端伪造的请求头被忽略
+        assert_eq!(
+            forwarded_client_ip(ip("8.8.8.8"), Some("1.2.3.4"), &trusted),
+            ip("8.8.8.8")
+        );
+        // 跳过受信任的代理链，客户端自行添加的最左侧地址被忽略
+        assert_eq!(
+            forwarded_client_ip(proxy, Some("6.6.6.6, 1.2.3.4, 10.0.0.2"), &trusted),
+            ip("1.2.3.4")
+        );
+        assert_eq!(forwarded_client_ip(proxy, None, &trusted), proxy);
+        assert_eq!(forwarded_client_ip(proxy, Some("garbage"), &trusted), proxy);
+    }
+}
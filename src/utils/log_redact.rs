@@ -0,0 +1,89 @@
+//! 日志脱敏
+//!
+//! Subsonic 客户端通过查询参数传递凭据 (`p`、`t`、`s`、`apiKey`)，
+//! 记录请求 URI 前需要先隐藏这些参数的值。
+
+use axum::http::Uri;
+
+use crate::utils::hls_utils;
+
+/// 需要隐藏取值的查询参数
+///
+/// `password` 用于 createUser / changePassword 等端点，
+/// `hlsToken` 是 HLS 播放列表中分片 URL 的流媒体令牌
+const SENSITIVE_PARAMS: &[&str] = &[
+    "p",
+    "t",
+    "s",
+    "apiKey",
+    "password",
+    hls_utils::TOKEN_QUERY_KEY,
+];
+
+/// 替换后的取值
+const REDACTED: &str = "***";
+
+/// 隐藏查询字符串中的凭据参数，其余参数保持原样
+///
+/// 参数名按解码后的结果匹配，`%70=secret` 与 `p=secret` 同样会被隐藏
+pub fn redact_query(query: &str) -> String {
+    query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((name, _)) if is_sensitive(name) => format!("{}={}", name, REDACTED),
+            _ => pair.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// 解码参数名后检查是否为凭据参数
+fn is_sensitive(name: &str) -> bool {
+    let decoded: Vec<(String, String)> = serde_urlencoded::from_str(name).unwrap_or_default();
+    match decoded.first() {
+        Some((name, _)) => SENSITIVE_PARAMS.contains(&name.as_str()),
+        None => false,
+    }
+}
+
+/// 隐藏 URI 中的凭据参数
+pub fn redact_uri(uri: &Uri) -> String {
+    match uri.query() {
+        Some(query) => format!("{}?{}", uri.path(), redact_query(query)),
+        None => uri.path().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_query() {
+        assert_eq!(
+            redact_query("u=alice&p=secret&v=1.16.1&c=app"),
+            "u=alice&p=***&v=1.16.1&c=app"
+        );
+        assert_eq!(
+            redact_query("u=alice&t=abc&s=xyz&f=json"),
+            "u=alice&t=***&s=***&f=json"
+        );
+        assert_eq!(redact_query("apiKey=k&id=1"), "apiKey=***&id=1");
+        assert_eq!(redact_query("size=10&sort=t"), "size=10&sort=t");
+        assert_eq!(
+            redact_query("id=1&hlsToken=abc.def&v=1.16.1"),
+            "id=1&hlsToken=***&v=1.16.1"
+        );
+        // 参数名经过百分号编码
+        assert_eq!(redact_query("u=alice&%70=secret"), "u=alice&%70=***");
+        assert_eq!(redact_query("%61piKey=k&id=1"), "%61piKey=***&id=1");
+
+        let uri: Uri = "/rest/changePassword?username=bob&password=pw"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            redact_uri(&uri),
+            "/rest/changePassword?username=bob&password=***"
+        );
+    }
+}
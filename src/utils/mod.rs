@@ -1,13 +1,26 @@
 //! 工具函数模块
 #![allow(unused_imports)]
 
+pub mod audio_sink;
 pub mod auth_utils;
+pub mod captions;
 pub mod hash_utils;
+pub mod hls_utils;
 pub mod id_builder;
 pub mod image_utils;
+pub mod ip_cidr;
+pub mod log_redact;
 pub mod meta_fetch;
+pub mod password_cipher;
 pub mod pinyin_utils;
+pub mod podcast_feed;
+pub mod radio_playlist;
+pub mod rate_limiter;
+pub mod session_token;
 pub mod sql_utils;
+pub mod stream_utils;
+pub mod transcode_cache;
+pub mod video_probe;
 
 pub use hash_utils::*;
 pub use id_builder::*;
@@ -0,0 +1,288 @@
+//! 密码加密存储
+//!
+//! Subsonic 的 MD5 token 认证需要服务端持有明文密码，因此无法使用单向哈希。
+//! 这里使用 AES-256-GCM 加密后存储，密钥由配置提供。
+//!
+//! 存储格式: `aes:<密钥ID>:<nonce 十六进制>:<密文 十六进制>`
+//!
+//! 密钥轮换: 当前密钥用于加密，旧密钥仅用于解密，启动时会把旧密钥加密的
+//! 密码和历史明文密码重新加密为当前密钥。此后未加密的密码不再被接受。
+//!
+//! 未配置密钥时使用密钥文件，首次启动时生成随机密钥。
+#![allow(dead_code)]
+
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::io::{self, Write};
+use std::path::Path;
+use subtle::ConstantTimeEq;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::utils::hash_utils::{decode_hex, encode_hex};
+
+/// 加密密码前缀
+const ENCRYPTED_PREFIX: &str = "aes:";
+
+/// 旧版本未配置密钥时使用的内置密钥，仅用于解密并重新加密历史数据
+pub const LEGACY_DEFAULT_PASSWORD_KEY: &str = "musicflow-default-password-key";
+
+/// 已公开的密钥 (SHA-256)，任何人都能用它解密密码或伪造会话令牌
+const COMPROMISED_KEY_HASHES: &[&str] =
+    &["407a2c399e1864599cd18206dbfa8cdc49c6fc0819b62cc158f890b380a62d9c"];
+
+/// 检查密钥是否已公开，已公开的密钥不能用于加密或签名
+pub fn is_compromised_key(key: &str) -> bool {
+    key_hash_listed(key, COMPROMISED_KEY_HASHES)
+}
+
+fn key_hash_listed(key: &str, hashes: &[&str]) -> bool {
+    let hash = encode_hex(&Sha256::digest(key.trim().as_bytes()));
+    hashes.contains(&hash.as_str())
+}
+
+/// 读取密钥文件，文件不存在时生成随机密钥并以 0600 权限保存
+pub fn load_or_create_key_file(path: &Path) -> io::Result<String> {
+    match std::fs::read_to_string(path) {
+        Ok(key) if !key.trim().is_empty() => return Ok(key.trim().to_string()),
+        Ok(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "password key file is empty",
+            ))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e),
+    }
+
+    let mut bytes = [0u8; 32];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    let key = encode_hex(&bytes);
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(key.as_bytes())?;
+    file.sync_all()?;
+
+    Ok(key)
+}
+
+/// 单个加密密钥
+struct CipherKey {
+    id: String,
+    cipher: Aes256Gcm,
+}
+
+impl CipherKey {
+    /// 从配置的密钥字符串派生 AES-256 密钥，密钥 ID 为派生密钥哈希的前 8 位
+    fn derive(secret: &str) -> Self {
+        let key_bytes = Sha256::digest(secret.as_bytes());
+        let id = encode_hex(&Sha256::digest(key_bytes)[..4]);
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_bytes));
+        Self { id, cipher }
+    }
+}
+
+/// 密码加解密器
+pub struct PasswordCipher {
+    current: CipherKey,
+    previous: Vec<CipherKey>,
+}
+
+impl PasswordCipher {
+    /// 创建加解密器
+    ///
+    /// # 参数
+    ///
+    /// * `key` - 当前密钥，用于加密和解密
+    /// * `old_keys` - 轮换前的旧密钥，仅用于解密
+    pub fn new(key: &str, old_keys: &[String]) -> Self {
+        Self {
+            current: CipherKey::derive(key),
+            previous: old_keys.iter().map(|k| CipherKey::derive(k)).collect(),
+        }
+    }
+
+    /// 从应用配置创建
+    pub fn from_config(config: &AppConfig) -> Self {
+        Self::new(
+            &config.password_encryption_key,
+            &config.password_encryption_old_keys,
+        )
+    }
+
+    /// 加密明文密码
+    pub fn encrypt(&self, password: &str) -> Result<String, AppError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .current
+            .cipher
+            .encrypt(&nonce, password.as_bytes())
+            .map_err(|_| AppError::ConfigError("Failed to encrypt password".to_string()))?;
+
+        Ok(format!(
+            "{}{}:{}:{}",
+            ENCRYPTED_PREFIX,
+            self.current.id,
+            encode_hex(&nonce),
+            encode_hex(&ciphertext)
+        ))
+    }
+
+    /// 解密存储的密码
+    ///
+    /// 未加密的密码 (应已在启动时重新加密) 返回错误
+    pub fn decrypt(&self, stored: &str) -> Result<String, AppError> {
+        let Some(payload) = stored.strip_prefix(ENCRYPTED_PREFIX) else {
+            return Err(AppError::ConfigError(
+                "Password is not encrypted".to_string(),
+            ));
+        };
+
+        let invalid = || AppError::ConfigError("Invalid encrypted password".to_string());
+
+        let mut parts = payload.splitn(3, ':');
+        let (key_id, nonce, ciphertext) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(key_id), Some(nonce), Some(ciphertext)) => (key_id, nonce, ciphertext),
+            _ => return Err(invalid()),
+        };
+
+        let key = self.find_key(key_id).ok_or_else(|| {
+            AppError::ConfigError(format!("Unknown password encryption key: {}", key_id))
+        })?;
+
+        let nonce = decode_hex(nonce)
+            .filter(|n| n.len() == 12)
+            .ok_or_else(invalid)?;
+        let ciphertext = decode_hex(ciphertext).ok_or_else(invalid)?;
+
+        let plaintext = key
+            .cipher
+            .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+            .map_err(|_| invalid())?;
+
+        String::from_utf8(plaintext).map_err(|_| invalid())
+    }
+
+    /// 是否为加密存储的密码
+    pub fn is_encrypted(stored: &str) -> bool {
+        stored.starts_with(ENCRYPTED_PREFIX)
+    }
+
+    /// 是否需要重新加密 (历史明文或旧密钥加密)
+    pub fn needs_upgrade(&self, stored: &str) -> bool {
+        match stored.strip_prefix(ENCRYPTED_PREFIX) {
+            Some(payload) => !payload.starts_with(&format!("{}:", self.current.id)),
+            None => true,
+        }
+    }
+
+    /// 校验明文密码与存储的密码是否一致 (常量时间比较)
+    pub fn verify(&self, password: &str, stored: &str) -> bool {
+        matches!(
+            self.decrypt(stored),
+            Ok(plain) if bool::from(plain.as_bytes().ct_eq(password.as_bytes()))
+        )
+    }
+
+    fn find_key(&self, key_id: &str) -> Option<&CipherKey> {
+        std::iter::once(&self.current)
+            .chain(self.previous.iter())
+            .find(|key| key.id == key_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt() {
+        let cipher = PasswordCipher::new("secret", &[]);
+
+        let stored = cipher.encrypt("sesame").unwrap();
+        assert!(stored.starts_with(ENCRYPTED_PREFIX));
+        assert!(!stored.contains("sesame"));
+        assert_ne!(stored, cipher.encrypt("sesame").unwrap());
+
+        assert_eq!(cipher.decrypt(&stored).unwrap(), "sesame");
+        assert!(cipher.verify("sesame", &stored));
+        assert!(!cipher.verify("wrong", &stored));
+        assert!(!cipher.needs_upgrade(&stored));
+    }
+
+    #[test]
+    fn test_legacy_plaintext() {
+        let cipher = PasswordCipher::new("secret", &[]);
+
+        // 明文密码只能由启动时的升级重新加密，不能直接用于认证
+        assert!(cipher.decrypt("sesame").is_err());
+        assert!(!cipher.verify("sesame", "sesame"));
+        assert!(cipher.needs_upgrade("sesame"));
+        assert!(!PasswordCipher::is_encrypted("sesame"));
+    }
+
+    #[test]
+    fn test_key_file() {
+        let dir = std::env::temp_dir().join(format!("password_key_{}", std::process::id()));
+        let path = dir.join("password.key");
+        std::fs::remove_dir_all(&dir).ok();
+
+        let key = load_or_create_key_file(&path).unwrap();
+        assert_eq!(key.len(), 64);
+        assert_eq!(load_or_create_key_file(&path).unwrap(), key);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        std::fs::write(&path, "").unwrap();
+        assert!(load_or_create_key_file(&path).is_err());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_compromised_key() {
+        let hash = encode_hex(&Sha256::digest(b"leaked"));
+        assert!(key_hash_listed("leaked", &[&hash]));
+        assert!(key_hash_listed(" leaked\n", &[&hash]));
+        assert!(!key_hash_listed("other", &[&hash]));
+
+        assert!(!is_compromised_key("test-password-key"));
+    }
+
+    #[test]
+    fn test_key_rotation() {
+        let old = PasswordCipher::new("old-secret", &[]);
+        let stored = old.encrypt("sesame").unwrap();
+
+        // 轮换后旧密钥仍可解密，但需要重新加密
+        let rotated = PasswordCipher::new("new-secret", &["old-secret".to_string()]);
+        assert_eq!(rotated.decrypt(&stored).unwrap(), "sesame");
+        assert!(rotated.needs_upgrade(&stored));
+
+        // 未保留旧密钥时无法解密
+        let without_old = PasswordCipher::new("new-secret", &[]);
+        assert!(without_old.decrypt(&stored).is_err());
+        assert!(!without_old.verify("sesame", &stored));
+
+        // 篡改密文
+        let mut tampered = rotated.encrypt("sesame").unwrap();
+        let last = tampered.pop().unwrap();
+        tampered.push(if last == '0' { '1' } else { '0' });
+        assert!(rotated.decrypt(&tampered).is_err());
+    }
+}
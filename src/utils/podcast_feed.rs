@@ -0,0 +1,294 @@
+//! 播客 RSS 订阅源解析
+//!
+//! 支持 RSS 2.0 与 iTunes 扩展 (`itunes:image`、`itunes:duration`、`itunes:summary`)。
+//! 没有 `enclosure` 音频地址的条目被忽略；缺少 `guid` 时使用音频地址作为唯一标识。
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// 播客频道
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PodcastFeed {
+    pub title: String,
+    pub description: Option<String>,
+    pub image_url: Option<String>,
+    pub episodes: Vec<FeedEpisode>,
+}
+
+/// 播客单集
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedEpisode {
+    pub guid: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub enclosure_url: String,
+    pub content_type: Option<String>,
+    /// 文件大小 (字节)
+    pub size: Option<i64>,
+    /// 时长 (秒)
+    pub duration: Option<i64>,
+    pub publish_date: Option<DateTime<Utc>>,
+}
+
+/// 解析过程中的单集字段
+#[derive(Default)]
+struct ItemBuilder {
+    guid: Option<String>,
+    title: Option<String>,
+    description: Option<String>,
+    summary: Option<String>,
+    enclosure_url: Option<String>,
+    content_type: Option<String>,
+    size: Option<i64>,
+    duration: Option<i64>,
+    publish_date: Option<DateTime<Utc>>,
+}
+
+impl ItemBuilder {
+    fn build(self) -> Option<FeedEpisode> {
+        let enclosure_url = self.enclosure_url?;
+        Some(FeedEpisode {
+            guid: self.guid.unwrap_or_else(|| enclosure_url.clone()),
+            title: self.title.unwrap_or_else(|| enclosure_url.clone()),
+            description: self.description.or(self.summary),
+            enclosure_url,
+            content_type: self.content_type,
+            size: self.size,
+            duration: self.duration,
+            publish_date: self.publish_date,
+        })
+    }
+}
+
+/// 解析 RSS 订阅源
+pub fn parse_feed(xml: &str) -> Result<PodcastFeed> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut feed = PodcastFeed::default();
+    let mut has_channel = false;
+    let mut title: Option<String> = None;
+    let mut item: Option<ItemBuilder> = None;
+    // 当前元素路径 (含命名空间前缀)
+    let mut path: Vec<String> = Vec::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                let name = element_name(&e);
+                match name.as_str() {
+                    "channel" => has_channel = true,
+                    "item" => item = Some(ItemBuilder::default()),
+                    _ => handle_empty(&e, &name, &mut feed, item.as_mut()),
+                }
+                path.push(name);
+            }
+            Ok(Event::Empty(e)) => {
+                let name = element_name(&e);
+                handle_empty(&e, &name, &mut feed, item.as_mut());
+            }
+            Ok(Event::Text(e)) => {
+                let text = e.unescape()?.trim().to_string();
+                handle_text(&path, text, &mut title, &mut feed, item.as_mut());
+            }
+            Ok(Event::CData(e)) => {
+                let text = String::from_utf8_lossy(&e.into_inner()).trim().to_string();
+                handle_text(&path, text, &mut title, &mut feed, item.as_mut());
+            }
+            Ok(Event::End(_)) => {
+                if path.pop().as_deref() == Some("item") {
+                    if let Some(episode) = item.take().and_then(ItemBuilder::build) {
+                        feed.episodes.push(episode);
+                    }
+                }
+            }
+            Ok(Event::Eof) => break,
+            Ok(_) => {}
+            Err(e) => {
+                return Err(anyhow!(
+                    "Invalid feed at position {}: {}",
+                    reader.buffer_position(),
+                    e
+                ))
+            }
+        }
+    }
+
+    if !has_channel {
+        return Err(anyhow!("Not an RSS feed: missing <channel>"));
+    }
+    feed.title = title.unwrap_or_default();
+
+    Ok(feed)
+}
+
+fn element_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.name().as_ref()).into_owned()
+}
+
+fn attribute(e: &BytesStart, name: &str) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == name.as_bytes())
+        .and_then(|attr| attr.unescape_value().ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// 处理以属性携带数据的元素 (`enclosure`、`itunes:image`)
+fn handle_empty(
+    e: &BytesStart,
+    name: &str,
+    feed: &mut PodcastFeed,
+    item: Option<&mut ItemBuilder>,
+) {
+    match (name, item) {
+        ("enclosure", Some(item)) => {
+            item.enclosure_url = attribute(e, "url");
+            item.content_type = attribute(e, "type");
+            item.size = attribute(e, "length")
+                .and_then(|length| length.parse().ok())
+                .filter(|length| *length > 0);
+        }
+        ("itunes:image", None) => {
+            if let Some(href) = attribute(e, "href") {
+                feed.image_url = Some(href);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 处理元素文本，`path` 为当前元素路径
+fn handle_text(
+    path: &[String],
+    text: String,
+    title: &mut Option<String>,
+    feed: &mut PodcastFeed,
+    item: Option<&mut ItemBuilder>,
+) {
+    if text.is_empty() {
+        return;
+    }
+    let Some((current, parents)) = path.split_last() else {
+        return;
+    };
+    let parent = parents.last().map(String::as_str);
+
+    match (item, parent) {
+        (Some(item), Some("item")) => match current.as_str() {
+            "guid" => item.guid = Some(text),
+            "title" => item.title = Some(text),
+            "description" => item.description = Some(text),
+            "itunes:summary" => item.summary = Some(text),
+            "itunes:duration" => item.duration = parse_duration(&text),
+            "pubDate" => item.publish_date = parse_date(&text),
+            _ => {}
+        },
+        (None, Some("channel")) => match current.as_str() {
+            "title" => *title = Some(text),
+            "description" => feed.description = Some(text),
+            "itunes:summary" if feed.description.is_none() => feed.description = Some(text),
+            _ => {}
+        },
+        // <image><url>...</url></image>，itunes:image 优先
+        (None, Some("image")) if current == "url" && feed.image_url.is_none() => {
+            feed.image_url = Some(text);
+        }
+        _ => {}
+    }
+}
+
+/// 解析时长: `HH:MM:SS`、`MM:SS` 或秒数
+fn parse_duration(value: &str) -> Option<i64> {
+    value
+        .split(':')
+        .try_fold(0i64, |total, part| {
+            let part = part.trim().split('.').next()?.parse::<i64>().ok()?;
+            Some(total * 60 + part)
+        })
+        .filter(|seconds| *seconds >= 0)
+}
+
+/// 解析 RFC 2822 发布时间
+fn parse_date(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(value)
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Test &amp; Cast</title>
+    <description><![CDATA[A <b>test</b> podcast]]></description>
+    <image><url>http://example.com/fallback.jpg</url><title>ignored</title></image>
+    <itunes:image href="http://example.com/cover.jpg"/>
+    <item>
+      <title>Episode 2</title>
+      <guid isPermaLink="false">ep-2</guid>
+      <pubDate>Tue, 02 Jan 2024 08:00:00 +0800</pubDate>
+      <itunes:duration>01:02:03</itunes:duration>
+      <itunes:summary>Second</itunes:summary>
+      <enclosure url="http://example.com/ep2.mp3" length="2048" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title>Episode 1</title>
+      <itunes:duration>95</itunes:duration>
+      <enclosure url="http://example.com/ep1.mp3" type="audio/mpeg"></enclosure>
+    </item>
+    <item>
+      <title>Text only</title>
+    </item>
+  </channel>
+</rss>"#;
+
+    #[test]
+    fn test_parse_feed() {
+        let feed = parse_feed(FEED).unwrap();
+
+        assert_eq!(feed.title, "Test & Cast");
+        assert_eq!(feed.description.as_deref(), Some("A <b>test</b> podcast"));
+        assert_eq!(
+            feed.image_url.as_deref(),
+            Some("http://example.com/cover.jpg")
+        );
+        assert_eq!(feed.episodes.len(), 2);
+
+        let episode = &feed.episodes[0];
+        assert_eq!(episode.guid, "ep-2");
+        assert_eq!(episode.title, "Episode 2");
+        assert_eq!(episode.description.as_deref(), Some("Second"));
+        assert_eq!(episode.enclosure_url, "http://example.com/ep2.mp3");
+        assert_eq!(episode.content_type.as_deref(), Some("audio/mpeg"));
+        assert_eq!(episode.size, Some(2048));
+        assert_eq!(episode.duration, Some(3723));
+        assert_eq!(
+            episode.publish_date.unwrap().to_rfc3339(),
+            "2024-01-02T00:00:00+00:00"
+        );
+
+        // 缺少 guid 时使用音频地址
+        assert_eq!(feed.episodes[1].guid, "http://example.com/ep1.mp3");
+        assert_eq!(feed.episodes[1].duration, Some(95));
+    }
+
+    #[test]
+    fn test_parse_invalid_feed() {
+        assert!(parse_feed("<html><body>Not a feed</body></html>").is_err());
+        assert!(parse_feed("<rss><channel><title>x</channel></rss>").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("45:10"), Some(2710));
+        assert_eq!(parse_duration("300.5"), Some(300));
+        assert_eq!(parse_duration("abc"), None);
+    }
+}
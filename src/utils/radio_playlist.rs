@@ -0,0 +1,224 @@
+//! 网络电台播放列表工具
+//!
+//! 电台列表以 M3U (`#EXTINF:-1,名称` + URL) 或 PLS (`FileN=`/`TitleN=`) 格式导入导出。
+//! 导入时自动识别格式，无名称的条目使用 URL 作为名称。
+
+/// M3U 播放列表 Content-Type
+pub const M3U_CONTENT_TYPE: &str = "audio/x-mpegurl";
+
+/// PLS 播放列表 Content-Type
+pub const PLS_CONTENT_TYPE: &str = "audio/x-scpls";
+
+/// 播放列表条目
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RadioEntry {
+    pub name: String,
+    pub stream_url: String,
+}
+
+/// 播放列表格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RadioPlaylistFormat {
+    M3u,
+    Pls,
+}
+
+impl RadioPlaylistFormat {
+    /// 从参数解析格式 (`m3u` / `m3u8` / `pls`)
+    pub fn from_param(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "m3u" | "m3u8" => Some(Self::M3u),
+            "pls" => Some(Self::Pls),
+            _ => None,
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::M3u => M3U_CONTENT_TYPE,
+            Self::Pls => PLS_CONTENT_TYPE,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::M3u => "m3u",
+            Self::Pls => "pls",
+        }
+    }
+}
+
+/// 解析播放列表，自动识别 M3U 或 PLS 格式
+pub fn parse(content: &str) -> Vec<RadioEntry> {
+    let is_pls = content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .is_some_and(|line| line.eq_ignore_ascii_case("[playlist]"));
+
+    if is_pls {
+        parse_pls(content)
+    } else {
+        parse_m3u(content)
+    }
+}
+
+/// 解析 M3U 播放列表
+fn parse_m3u(content: &str) -> Vec<RadioEntry> {
+    let mut entries = Vec::new();
+    let mut title: Option<String> = None;
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            // #EXTINF:-1,电台名称
+            title = info
+                .split_once(',')
+                .map(|(_, name)| name.trim().to_string())
+                .filter(|name| !name.is_empty());
+            continue;
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+
+        entries.push(RadioEntry {
+            name: title.take().unwrap_or_else(|| line.to_string()),
+            stream_url: line.to_string(),
+        });
+    }
+
+    entries
+}
+
+/// 解析 PLS 播放列表 (按 FileN 的序号排序)
+fn parse_pls(content: &str) -> Vec<RadioEntry> {
+    let mut files: Vec<(u32, String)> = Vec::new();
+    let mut titles: Vec<(u32, String)> = Vec::new();
+
+    for line in content.lines().map(str::trim) {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim().to_string();
+
+        if let Some(index) = key.strip_prefix("file").and_then(|i| i.parse().ok()) {
+            files.push((index, value));
+        } else if let Some(index) = key.strip_prefix("title").and_then(|i| i.parse().ok()) {
+            titles.push((index, value));
+        }
+    }
+
+    files.sort_by_key(|(index, _)| *index);
+    files
+        .into_iter()
+        .filter(|(_, url)| !url.is_empty())
+        .map(|(index, stream_url)| {
+            let name = titles
+                .iter()
+                .find(|(i, title)| *i == index && !title.is_empty())
+                .map(|(_, title)| title.clone())
+                .unwrap_or_else(|| stream_url.clone());
+            RadioEntry { name, stream_url }
+        })
+        .collect()
+}
+
+/// 生成播放列表
+pub fn write(format: RadioPlaylistFormat, entries: &[RadioEntry]) -> String {
+    match format {
+        RadioPlaylistFormat::M3u => write_m3u(entries),
+        RadioPlaylistFormat::Pls => write_pls(entries),
+    }
+}
+
+/// 生成 M3U 播放列表
+fn write_m3u(entries: &[RadioEntry]) -> String {
+    let mut playlist = String::from("#EXTM3U\n");
+    for entry in entries {
+        playlist.push_str(&format!(
+            "#EXTINF:-1,{}\n{}\n",
+            single_line(&entry.name),
+            single_line(&entry.stream_url)
+        ));
+    }
+    playlist
+}
+
+/// 生成 PLS 播放列表
+fn write_pls(entries: &[RadioEntry]) -> String {
+    let mut playlist = String::from("[playlist]\n");
+    for (i, entry) in entries.iter().enumerate() {
+        let index = i + 1;
+        playlist.push_str(&format!(
+            "File{}={}\n",
+            index,
+            single_line(&entry.stream_url)
+        ));
+        playlist.push_str(&format!("Title{}={}\n", index, single_line(&entry.name)));
+        playlist.push_str(&format!("Length{}=-1\n", index));
+    }
+    playlist.push_str(&format!("NumberOfEntries={}\nVersion=2\n", entries.len()));
+    playlist
+}
+
+/// 去除换行，避免破坏播放列表结构
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, stream_url: &str) -> RadioEntry {
+        RadioEntry {
+            name: name.to_string(),
+            stream_url: stream_url.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_m3u() {
+        let content = "#EXTM3U\r\n#EXTINF:-1,Jazz FM\r\nhttp://jazz.example/stream\r\n\r\nhttp://rock.example/live\n";
+
+        assert_eq!(
+            parse(content),
+            vec![
+                entry("Jazz FM", "http://jazz.example/stream"),
+                entry("http://rock.example/live", "http://rock.example/live"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_pls() {
+        let content = "[playlist]\nFile2=http://b.example/\nTitle2=B\nFile1=http://a.example/\nNumberOfEntries=2\n";
+
+        assert_eq!(
+            parse(content),
+            vec![
+                entry("http://a.example/", "http://a.example/"),
+                entry("B", "http://b.example/"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_write_round_trip() {
+        let entries = vec![
+            entry("Jazz\nFM", "http://jazz.example/stream"),
+            entry("Rock", "https://rock.example/live?q=1"),
+        ];
+
+        for format in [RadioPlaylistFormat::M3u, RadioPlaylistFormat::Pls] {
+            let parsed = parse(&write(format, &entries));
+            assert_eq!(parsed.len(), 2);
+            assert_eq!(parsed[0].name, "Jazz FM");
+            assert_eq!(parsed[1], entries[1]);
+        }
+    }
+}
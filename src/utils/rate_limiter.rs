@@ -0,0 +1,159 @@
+//! 按客户端 IP 的请求限流
+//!
+//! 固定时间窗口计数: 窗口内请求数超过上限时拒绝，窗口结束后重新计数。
+//! 计数只保存在内存中，服务重启后清空。
+//!
+//! [`KeyedRecords`] 是限流器共用的内存记录表，登录限流也基于它保存失败记录。
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::net::IpAddr;
+use std::sync::{Mutex, MutexGuard};
+
+/// 带时间戳的限流记录
+pub trait TimedRecord {
+    /// 最近一次更新的时间，超过保留时长后记录被清理
+    fn updated_at(&self) -> DateTime<Utc>;
+}
+
+/// 按键保存在内存中的限流记录
+///
+/// 写入前清理超过保留时长的记录，避免占用过多内存
+pub struct KeyedRecords<K, V> {
+    retention: Duration,
+    records: Mutex<HashMap<K, V>>,
+}
+
+impl<K: Eq + Hash, V: TimedRecord> KeyedRecords<K, V> {
+    /// 创建新的 KeyedRecords
+    pub fn new(retention: Duration) -> Self {
+        Self {
+            retention,
+            records: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 获取记录表
+    pub fn lock(&self) -> MutexGuard<'_, HashMap<K, V>> {
+        self.records.lock().unwrap()
+    }
+
+    /// 清理过期记录后获取记录表
+    pub fn lock_pruned(&self, now: DateTime<Utc>) -> MutexGuard<'_, HashMap<K, V>> {
+        let mut records = self.lock();
+        records.retain(|_, record| now - record.updated_at() < self.retention);
+        records
+    }
+}
+
+/// 时间窗口内的请求计数
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    started_at: DateTime<Utc>,
+    count: u32,
+}
+
+impl TimedRecord for Window {
+    fn updated_at(&self) -> DateTime<Utc> {
+        self.started_at
+    }
+}
+
+/// 请求限流器，默认按客户端 IP 计数
+pub struct RateLimiter<K = IpAddr> {
+    /// 每个窗口允许的请求数，0 表示不限流
+    max_requests: u32,
+    window: Duration,
+    windows: KeyedRecords<K, Window>,
+}
+
+impl<K: Eq + Hash> RateLimiter<K> {
+    /// 创建新的 RateLimiter
+    pub fn new(max_requests: u32, window_seconds: i64) -> Self {
+        let window = Duration::seconds(window_seconds);
+        Self {
+            max_requests,
+            window,
+            windows: KeyedRecords::new(window),
+        }
+    }
+
+    /// 记录一次请求，超过上限时返回需等待的秒数
+    pub fn check(&self, key: K) -> Result<(), i64> {
+        self.check_at(key, Utc::now())
+    }
+
+    fn check_at(&self, key: K, now: DateTime<Utc>) -> Result<(), i64> {
+        if self.max_requests == 0 {
+            return Ok(());
+        }
+
+        let mut windows = self.windows.lock_pruned(now);
+        let window = windows.entry(key).or_insert(Window {
+            started_at: now,
+            count: 0,
+        });
+        if window.count >= self.max_requests {
+            let retry_after = window.started_at + self.window - now;
+            return Err(retry_after.num_seconds().max(1));
+        }
+        window.count += 1;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn test_limit_per_ip() {
+        let limiter = RateLimiter::new(2, 60);
+        let now = Utc::now();
+
+        assert!(limiter.check_at(ip("10.0.0.1"), now).is_ok());
+        assert!(limiter.check_at(ip("10.0.0.1"), now).is_ok());
+        assert_eq!(limiter.check_at(ip("10.0.0.1"), now), Err(60));
+        // 其他 IP 不受影响
+        assert!(limiter.check_at(ip("10.0.0.2"), now).is_ok());
+
+        // 窗口结束后重新计数
+        let later = now + Duration::seconds(60);
+        assert!(limiter.check_at(ip("10.0.0.1"), later).is_ok());
+    }
+
+    #[test]
+    fn test_keyed_records_pruned() {
+        let records: KeyedRecords<&str, Window> = KeyedRecords::new(Duration::seconds(60));
+        let now = Utc::now();
+        for (key, age) in [("old", 60), ("new", 59)] {
+            records.lock().insert(
+                key,
+                Window {
+                    started_at: now - Duration::seconds(age),
+                    count: 1,
+                },
+            );
+        }
+
+        let records = records.lock_pruned(now);
+        assert!(!records.contains_key("old"));
+        assert!(records.contains_key("new"));
+    }
+
+    #[test]
+    fn test_disabled() {
+        let limiter = RateLimiter::new(0, 60);
+        let now = Utc::now();
+
+        for _ in 0..100 {
+            assert!(limiter.check_at(ip("10.0.0.1"), now).is_ok());
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! 会话令牌签名
+//!
+//! 管理面板通过 `/api/auth/login` 获取会话令牌，之后以 `Authorization: Bearer <令牌>`
+//! 访问接口，无需在客户端保存明文密码。
+//!
+//! 令牌格式: `<载荷 十六进制>.<HMAC-SHA256 签名 十六进制>`，载荷为 JSON 编码的
+//! [`SessionTokenClaims`]。签名只保证令牌未被篡改，吊销状态以数据库中的会话记录为准。
+//!
+//! HLS 播放列表中的 URL 使用相同格式的短期流媒体令牌 ([`StreamTokenClaims`])，
+//! 只能访问签发时的歌曲，不会把用户凭据写入播放列表。
+#![allow(dead_code)]
+
+use axum::http::{header, HeaderMap};
+use hmac::{Hmac, Mac};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::error::AppError;
+use crate::utils::hash_utils::{decode_hex, encode_hex};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// 令牌载荷
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionTokenClaims {
+    /// 会话 ID
+    pub sid: String,
+    /// 用户 ID
+    pub sub: String,
+    /// 签发时间 (Unix 时间戳，秒)
+    pub iat: i64,
+    /// 过期时间 (Unix 时间戳，秒)
+    pub exp: i64,
+}
+
+/// 流媒体令牌载荷
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamTokenClaims {
+    /// 用户 ID
+    pub sub: String,
+    /// 可访问的歌曲 ID
+    pub id: String,
+    /// 过期时间 (Unix 时间戳，秒)
+    pub exp: i64,
+}
+
+/// 带有效期的令牌载荷
+pub trait TokenClaims: Serialize + DeserializeOwned {
+    /// 过期时间 (Unix 时间戳，秒)
+    fn expires_at(&self) -> i64;
+}
+
+impl TokenClaims for SessionTokenClaims {
+    fn expires_at(&self) -> i64 {
+        self.exp
+    }
+}
+
+impl TokenClaims for StreamTokenClaims {
+    fn expires_at(&self) -> i64 {
+        self.exp
+    }
+}
+
+/// 令牌签名器
+///
+/// 不同用途的令牌使用不同的签名器 (密钥)，一种令牌不能冒充另一种
+pub struct SessionTokenSigner {
+    key: Vec<u8>,
+}
+
+impl SessionTokenSigner {
+    /// 创建签名器
+    pub fn new(secret: &str) -> Self {
+        Self {
+            key: secret.as_bytes().to_vec(),
+        }
+    }
+
+    /// 签发令牌
+    pub fn sign<T: TokenClaims>(&self, claims: &T) -> Result<String, AppError> {
+        let payload = serde_json::to_vec(claims)
+            .map_err(|e| AppError::ConfigError(format!("Failed to encode session token: {}", e)))?;
+        let payload = encode_hex(&payload);
+        Ok(format!(
+            "{}.{}",
+            payload,
+            encode_hex(&self.signature(&payload))
+        ))
+    }
+
+    /// 校验令牌签名与有效期，返回载荷
+    pub fn verify<T: TokenClaims>(&self, token: &str, now: i64) -> Result<T, AppError> {
+        let invalid = || AppError::auth_failed("Invalid session token");
+
+        let (payload, signature) = token.split_once('.').ok_or_else(invalid)?;
+        let signature = decode_hex(signature).ok_or_else(invalid)?;
+
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&signature).map_err(|_| invalid())?;
+
+        let claims: T = decode_hex(payload)
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(invalid)?;
+
+        if claims.expires_at() <= now {
+            return Err(AppError::auth_failed("Session token expired"));
+        }
+
+        Ok(claims)
+    }
+
+    fn signature(&self, payload: &str) -> Vec<u8> {
+        let mut mac = self.mac();
+        mac.update(payload.as_bytes());
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    fn mac(&self) -> HmacSha256 {
+        HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length")
+    }
+}
+
+/// 从 `Authorization: Bearer <令牌>` 请求头中提取令牌
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then(|| token.trim())
+        .filter(|token| !token.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(exp: i64) -> SessionTokenClaims {
+        SessionTokenClaims {
+            sid: "s1".to_string(),
+            sub: "u1".to_string(),
+            iat: 100,
+            exp,
+        }
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = SessionTokenSigner::new("secret");
+        let token = signer.sign(&claims(200)).unwrap();
+
+        assert_eq!(
+            signer.verify::<SessionTokenClaims>(&token, 150).unwrap(),
+            claims(200)
+        );
+
+        // 过期
+        assert!(signer.verify::<SessionTokenClaims>(&token, 200).is_err());
+        // 其他密钥签发
+        assert!(SessionTokenSigner::new("other")
+            .verify::<SessionTokenClaims>(&token, 150)
+            .is_err());
+        // 篡改载荷
+        let forged = signer.sign(&claims(999)).unwrap();
+        let tampered = format!(
+            "{}.{}",
+            forged.split_once('.').unwrap().0,
+            token.split_once('.').unwrap().1
+        );
+        assert!(signer.verify::<SessionTokenClaims>(&tampered, 150).is_err());
+        assert!(signer.verify::<SessionTokenClaims>("garbage", 150).is_err());
+    }
+
+    #[test]
+    fn test_stream_token() {
+        let signer = SessionTokenSigner::new("secret");
+        let stream = StreamTokenClaims {
+            sub: "u1".to_string(),
+            id: "song1".to_string(),
+            exp: 200,
+        };
+        let token = signer.sign(&stream).unwrap();
+        assert_eq!(
+            signer.verify::<StreamTokenClaims>(&token, 150).unwrap(),
+            stream
+        );
+        assert!(signer.verify::<StreamTokenClaims>(&token, 200).is_err());
+
+        // 会话令牌不能当作流媒体令牌使用
+        let session = signer.sign(&claims(200)).unwrap();
+        assert!(signer.verify::<StreamTokenClaims>(&session, 150).is_err());
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(bearer_token(&headers), None);
+
+        headers.insert(header::AUTHORIZATION, "Bearer abc.def".parse().unwrap());
+        assert_eq!(bearer_token(&headers), Some("abc.def"));
+
+        headers.insert(header::AUTHORIZATION, "Basic abc".parse().unwrap());
+        assert_eq!(bearer_token(&headers), None);
+    }
+}
@@ -0,0 +1,368 @@
+//! 文件流式传输工具
+//!
+//! 为 /rest/stream 和 /rest/download 提供统一的文件响应:
+//! - 解析 `Range` / `If-Range` 请求头，返回 206 Partial Content
+//! - 多段 Range 或越界 Range 返回 416 Range Not Satisfiable
+//! - 根据文件元数据生成 `ETag` 和 `Last-Modified`
+#![allow(dead_code)]
+
+use axum::body::Body;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use chrono::{DateTime, Utc};
+use std::io::SeekFrom;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio_util::io::ReaderStream;
+
+use crate::error::AppError;
+
+/// HTTP 日期格式 (RFC 7231 IMF-fixdate)
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// Range 解析结果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// 返回完整文件
+    Full,
+    /// 返回指定区间 [start, end] (包含两端)
+    Partial { start: u64, end: u64 },
+    /// 无法满足的 Range (越界或多段)
+    Unsatisfiable,
+}
+
+/// 文件校验信息，用于 ETag / Last-Modified / If-Range
+#[derive(Debug, Clone)]
+pub struct FileValidators {
+    pub etag: String,
+    pub last_modified: Option<DateTime<Utc>>,
+}
+
+impl FileValidators {
+    /// 根据文件元数据生成校验信息
+    pub fn from_metadata(metadata: &std::fs::Metadata) -> Self {
+        let mtime_secs = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+
+        let etag = format!("\"{:x}-{:x}\"", metadata.len(), mtime_secs.unwrap_or(0));
+        let last_modified =
+            mtime_secs.and_then(|secs| DateTime::<Utc>::from_timestamp(secs as i64, 0));
+
+        Self {
+            etag,
+            last_modified,
+        }
+    }
+
+    /// 格式化 Last-Modified 头
+    pub fn last_modified_header(&self) -> Option<String> {
+        self.last_modified
+            .map(|t| t.format(HTTP_DATE_FORMAT).to_string())
+    }
+
+    /// 判断 If-Range 条件是否成立
+    ///
+    /// If-Range 可以是 ETag (强比较) 或 HTTP 日期 (与 Last-Modified 精确相等)
+    pub fn if_range_matches(&self, if_range: &str) -> bool {
+        let if_range = if_range.trim();
+
+        if if_range.starts_with('"') || if_range.starts_with("W/") {
+            // 弱 ETag 不能用于 If-Range
+            return !if_range.starts_with("W/") && if_range == self.etag;
+        }
+
+        match (DateTime::parse_from_rfc2822(if_range), self.last_modified) {
+            (Ok(date), Some(last_modified)) => date.with_timezone(&Utc) == last_modified,
+            _ => false,
+        }
+    }
+}
+
+/// 解析 Range 请求头
+///
+/// 语法错误或非 bytes 单位的 Range 会被忽略 (返回完整文件)，
+/// 多段 Range 不支持，直接视为无法满足。
+pub fn parse_range(range: &str, file_len: u64) -> ByteRange {
+    let Some(spec) = range.trim().strip_prefix("bytes=") else {
+        return ByteRange::Full;
+    };
+
+    if spec.contains(',') {
+        return ByteRange::Unsatisfiable;
+    }
+
+    let Some((start, end)) = spec.trim().split_once('-') else {
+        return ByteRange::Full;
+    };
+    let (start, end) = (start.trim(), end.trim());
+
+    if start.is_empty() {
+        // 后缀形式: bytes=-500 表示最后 500 字节
+        let Ok(suffix) = end.parse::<u64>() else {
+            return ByteRange::Full;
+        };
+        if suffix == 0 || file_len == 0 {
+            return ByteRange::Unsatisfiable;
+        }
+        let start = file_len.saturating_sub(suffix);
+        return ByteRange::Partial {
+            start,
+            end: file_len - 1,
+        };
+    }
+
+    let Ok(start) = start.parse::<u64>() else {
+        return ByteRange::Full;
+    };
+    let end = if end.is_empty() {
+        file_len.saturating_sub(1)
+    } else {
+        match end.parse::<u64>() {
+            Ok(end) if end >= start => end.min(file_len.saturating_sub(1)),
+            _ => return ByteRange::Full,
+        }
+    };
+
+    if start >= file_len {
+        return ByteRange::Unsatisfiable;
+    }
+
+    ByteRange::Partial { start, end }
+}
+
+/// 以流的方式返回文件，支持 Range 请求
+///
+/// # 参数
+///
+/// * `path` - 文件路径
+/// * `content_type` - 响应 Content-Type
+/// * `request_headers` - 请求头 (用于读取 Range / If-Range)
+/// * `extra_headers` - 额外的响应头 (例如 Content-Disposition)
+pub async fn serve_file(
+    path: &Path,
+    content_type: &str,
+    request_headers: &HeaderMap,
+    extra_headers: HeaderMap,
+) -> Result<Response, AppError> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let metadata = file.metadata().await?;
+    let file_len = metadata.len();
+    let validators = FileValidators::from_metadata(&metadata);
+
+    // If-Range 不匹配时忽略 Range，返回完整文件
+    let range = match request_headers
+        .get(header::RANGE)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(range) => {
+            let if_range_ok = request_headers
+                .get(header::IF_RANGE)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| validators.if_range_matches(v))
+                .unwrap_or(true);
+            if if_range_ok {
+                parse_range(range, file_len)
+            } else {
+                ByteRange::Full
+            }
+        }
+        None => ByteRange::Full,
+    };
+
+    let mut headers = extra_headers;
+    headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    if let Ok(etag) = HeaderValue::from_str(&validators.etag) {
+        headers.insert(header::ETAG, etag);
+    }
+    if let Some(last_modified) = validators
+        .last_modified_header()
+        .and_then(|v| HeaderValue::from_str(&v).ok())
+    {
+        headers.insert(header::LAST_MODIFIED, last_modified);
+    }
+
+    match range {
+        ByteRange::Unsatisfiable => {
+            headers.insert(
+                header::CONTENT_RANGE,
+                HeaderValue::from_str(&format!("bytes */{}", file_len)).unwrap(),
+            );
+            Ok((StatusCode::RANGE_NOT_SATISFIABLE, headers).into_response())
+        }
+        ByteRange::Full => {
+            headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_str(content_type)
+                    .unwrap_or(HeaderValue::from_static("application/octet-stream")),
+            );
+            headers.insert(header::CONTENT_LENGTH, HeaderValue::from(file_len));
+
+            let body = Body::from_stream(ReaderStream::new(file));
+            Ok((StatusCode::OK, headers, body).into_response())
+        }
+        ByteRange::Partial { start, end } => {
+            let length = end - start + 1;
+            file.seek(SeekFrom::Start(start)).await?;
+
+            headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_str(content_type)
+                    .unwrap_or(HeaderValue::from_static("application/octet-stream")),
+            );
+            headers.insert(header::CONTENT_LENGTH, HeaderValue::from(length));
+            headers.insert(
+                header::CONTENT_RANGE,
+                HeaderValue::from_str(&format!("bytes {}-{}/{}", start, end, file_len)).unwrap(),
+            );
+
+            let body = Body::from_stream(ReaderStream::new(file.take(length)));
+            Ok((StatusCode::PARTIAL_CONTENT, headers, body).into_response())
+        }
+    }
+}
+
+/// 返回文件中 [start, end) 区间的字节
+///
+/// 用于 HLS 分片直接切分原始文件，响应为 200 而不是 206。
+pub async fn serve_file_slice(
+    path: &Path,
+    content_type: &str,
+    start: u64,
+    end: u64,
+) -> Result<Response, AppError> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let file_len = file.metadata().await?.len();
+    let end = end.min(file_len);
+    let start = start.min(end);
+    let length = end - start;
+    file.seek(SeekFrom::Start(start)).await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_str(content_type)
+            .unwrap_or(HeaderValue::from_static("application/octet-stream")),
+    );
+    headers.insert(header::CONTENT_LENGTH, HeaderValue::from(length));
+
+    let body = Body::from_stream(ReaderStream::new(file.take(length)));
+    Ok((StatusCode::OK, headers, body).into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(
+            parse_range("bytes=0-99", 1000),
+            ByteRange::Partial { start: 0, end: 99 }
+        );
+        assert_eq!(
+            parse_range("bytes=500-", 1000),
+            ByteRange::Partial {
+                start: 500,
+                end: 999
+            }
+        );
+        assert_eq!(
+            parse_range("bytes=-100", 1000),
+            ByteRange::Partial {
+                start: 900,
+                end: 999
+            }
+        );
+        // 结束位置超出文件长度时截断
+        assert_eq!(
+            parse_range("bytes=900-5000", 1000),
+            ByteRange::Partial {
+                start: 900,
+                end: 999
+            }
+        );
+        assert_eq!(parse_range("bytes=1000-", 1000), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range("bytes=0-1,5-6", 1000), ByteRange::Unsatisfiable);
+        assert_eq!(parse_range("bytes=-0", 1000), ByteRange::Unsatisfiable);
+        // 语法错误或未知单位时忽略 Range
+        assert_eq!(parse_range("items=0-1", 1000), ByteRange::Full);
+        assert_eq!(parse_range("bytes=abc", 1000), ByteRange::Full);
+        assert_eq!(parse_range("bytes=10-5", 1000), ByteRange::Full);
+    }
+
+    #[test]
+    fn test_if_range_matches() {
+        let validators = FileValidators {
+            etag: "\"3e8-5f5e100\"".to_string(),
+            last_modified: DateTime::<Utc>::from_timestamp(1_445_412_480, 0),
+        };
+
+        assert!(validators.if_range_matches("\"3e8-5f5e100\""));
+        assert!(!validators.if_range_matches("\"other\""));
+        assert!(!validators.if_range_matches("W/\"3e8-5f5e100\""));
+        assert!(validators.if_range_matches("Wed, 21 Oct 2015 07:28:00 GMT"));
+        assert!(!validators.if_range_matches("Wed, 21 Oct 2015 07:28:01 GMT"));
+    }
+
+    #[tokio::test]
+    async fn test_serve_file_partial() {
+        let path = std::env::temp_dir().join(format!(
+            "musicflow_stream_utils_{}.bin",
+            crate::utils::id_builder::generate_id()
+        ));
+        tokio::fs::write(&path, b"0123456789").await.unwrap();
+
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert(header::RANGE, HeaderValue::from_static("bytes=2-5"));
+        let response = serve_file(&path, "audio/mpeg", &request_headers, HeaderMap::new())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(
+            response.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes 2-5/10"
+        );
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"2345");
+
+        // If-Range 不匹配时返回完整文件
+        request_headers.insert(header::IF_RANGE, HeaderValue::from_static("\"stale\""));
+        let response = serve_file(&path, "audio/mpeg", &request_headers, HeaderMap::new())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_LENGTH).unwrap(),
+            "10"
+        );
+
+        // 多段 Range 返回 416
+        request_headers.remove(header::IF_RANGE);
+        request_headers.insert(header::RANGE, HeaderValue::from_static("bytes=0-1,3-4"));
+        let response = serve_file(&path, "audio/mpeg", &request_headers, HeaderMap::new())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(
+            response.headers().get(header::CONTENT_RANGE).unwrap(),
+            "bytes */10"
+        );
+
+        // 区间切片
+        let response = serve_file_slice(&path, "audio/mpeg", 3, 7).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"3456");
+
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+}
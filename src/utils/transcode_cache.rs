@@ -0,0 +1,516 @@
+//! 转码结果磁盘缓存
+//!
+//! 缓存 /rest/stream 的转码输出，避免同一首歌同一参数反复转码:
+//! - 缓存键由歌曲 ID、`updated_at` 与转码参数组成，歌曲更新后自动失效
+//! - 总大小超过上限时按最近最少使用 (LRU) 淘汰
+//! - 同一缓存键只允许一个写入者，其余请求等待写入完成后直接读取缓存
+//!
+//! 默认缓存目录为 `./coverArt/transcode`，与封面缓存 `./coverArt/webp` 并列
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use axum::body::{Body, Bytes};
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{mpsc, Mutex, OwnedMutexGuard};
+use tokio_util::io::ReaderStream;
+
+use crate::services::transcode_service::{TranscodeProcess, TranscodeProfile};
+
+/// 写入中的临时文件后缀
+const PART_SUFFIX: &str = "part";
+
+// 防止同一缓存键被多次转码写入
+// Key: 缓存文件路径，没有持有者和等待者时移除 (见 GenerationGuard)
+type GenerationLock = Lazy<std::sync::Mutex<HashMap<String, Arc<Mutex<()>>>>>;
+static TRANSCODE_GENERATION_LOCKS: GenerationLock =
+    Lazy::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// 缓存键的写入锁，释放时清理不再使用的锁
+pub struct GenerationGuard {
+    lock_key: String,
+    lock: Arc<Mutex<()>>,
+    guard: Option<OwnedMutexGuard<()>>,
+}
+
+impl Drop for GenerationGuard {
+    fn drop(&mut self) {
+        self.guard.take();
+        let mut locks = TRANSCODE_GENERATION_LOCKS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        // 只剩锁表和本守卫持有时说明没有等待者
+        if Arc::strong_count(&self.lock) == 2 {
+            locks.remove(&self.lock_key);
+        }
+    }
+}
+
+/// 生成缓存键 (即缓存文件名)
+pub fn cache_key(song_id: &str, updated_at: &str, profile: &TranscodeProfile) -> String {
+    let digest = md5::compute(format!(
+        "{}|{}|{}|{}",
+        song_id, updated_at, profile.format, profile.bit_rate
+    ));
+    format!("{:x}.{}", digest, profile.format)
+}
+
+/// 缓存条目
+#[derive(Debug, Clone, Copy)]
+struct CacheEntry {
+    size: u64,
+    last_access: u64,
+}
+
+/// LRU 索引
+#[derive(Debug, Default)]
+struct CacheIndex {
+    entries: HashMap<String, CacheEntry>,
+    total_size: u64,
+    tick: u64,
+}
+
+impl CacheIndex {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn insert(&mut self, key: String, size: u64) {
+        let last_access = self.next_tick();
+        if let Some(old) = self.entries.insert(key, CacheEntry { size, last_access }) {
+            self.total_size -= old.size;
+        }
+        self.total_size += size;
+    }
+
+    fn remove(&mut self, key: &str) -> Option<CacheEntry> {
+        let entry = self.entries.remove(key)?;
+        self.total_size -= entry.size;
+        Some(entry)
+    }
+
+    /// 淘汰最久未使用的条目直到总大小不超过上限，返回被淘汰的键
+    fn evict(&mut self, max_size: u64) -> Vec<String> {
+        let mut evicted = Vec::new();
+        while self.total_size > max_size {
+            let Some(key) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_access)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            self.remove(&key);
+            evicted.push(key);
+        }
+        evicted
+    }
+}
+
+/// 缓存统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub files: usize,
+    pub size: u64,
+}
+
+/// 转码结果磁盘缓存
+pub struct TranscodeCache {
+    dir: PathBuf,
+    max_size: u64,
+    index: Mutex<CacheIndex>,
+}
+
+impl TranscodeCache {
+    /// 打开缓存目录并根据已有文件重建索引 (启动时调用)
+    ///
+    /// `max_size` 为 0 时禁用缓存。残留的临时文件会被清理，
+    /// 已有文件按修改时间恢复访问顺序。
+    pub fn open(dir: impl Into<PathBuf>, max_size: u64) -> io::Result<Self> {
+        let dir = dir.into();
+        let mut files = Vec::new();
+
+        if max_size > 0 {
+            std::fs::create_dir_all(&dir)?;
+            for entry in std::fs::read_dir(&dir)? {
+                let entry = entry?;
+                let path = entry.path();
+                let metadata = entry.metadata()?;
+                if !metadata.is_file() {
+                    continue;
+                }
+                if path.extension().and_then(|e| e.to_str()) == Some(PART_SUFFIX) {
+                    let _ = std::fs::remove_file(&path);
+                    continue;
+                }
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                files.push((
+                    modified,
+                    entry.file_name().to_string_lossy().to_string(),
+                    metadata.len(),
+                ));
+            }
+        }
+
+        files.sort();
+        let mut index = CacheIndex::default();
+        for (_, key, size) in files {
+            index.insert(key, size);
+        }
+
+        // 上限调小后启动时立即淘汰
+        for key in index.evict(max_size) {
+            let _ = std::fs::remove_file(dir.join(&key));
+        }
+
+        Ok(Self {
+            dir,
+            max_size,
+            index: Mutex::new(index),
+        })
+    }
+
+    /// 是否启用缓存
+    pub fn is_enabled(&self) -> bool {
+        self.max_size > 0
+    }
+
+    /// 缓存文件路径
+    pub fn path_for(&self, key: &str) -> PathBuf {
+        self.dir.join(key)
+    }
+
+    fn part_path_for(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.{}", key, PART_SUFFIX))
+    }
+
+    /// 查询缓存，命中时更新访问顺序
+    pub async fn lookup(&self, key: &str) -> Option<PathBuf> {
+        {
+            let mut index = self.index.lock().await;
+            let tick = index.next_tick();
+            index.entries.get_mut(key)?.last_access = tick;
+        }
+
+        // 同步修改时间，重启后仍能恢复大致的访问顺序 (不持有索引锁)
+        let path = self.path_for(key);
+        let touch_path = path.clone();
+        let exists = tokio::task::spawn_blocking(move || {
+            match std::fs::File::options().append(true).open(&touch_path) {
+                Ok(file) => {
+                    let _ = file.set_modified(SystemTime::now());
+                    true
+                }
+                Err(e) => e.kind() != io::ErrorKind::NotFound,
+            }
+        })
+        .await
+        .unwrap_or(false);
+
+        if !exists {
+            // 文件被外部删除
+            self.index.lock().await.remove(key);
+            return None;
+        }
+
+        Some(path)
+    }
+
+    /// 获取缓存键的写入锁
+    pub async fn lock(&self, key: &str) -> GenerationGuard {
+        let lock_key = self.path_for(key).to_string_lossy().to_string();
+        let lock = {
+            let mut locks = TRANSCODE_GENERATION_LOCKS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            locks
+                .entry(lock_key.clone())
+                .or_insert_with(|| Arc::new(Mutex::new(())))
+                .clone()
+        };
+
+        let guard = lock.clone().lock_owned().await;
+        GenerationGuard {
+            lock_key,
+            lock,
+            guard: Some(guard),
+        }
+    }
+
+    /// 将转码输出同时写入缓存并返回给客户端
+    ///
+    /// 写入在后台任务中进行并持有写入锁，客户端中途断开时仍会写完缓存；
+    /// 转码失败或写入失败时丢弃临时文件。
+    pub fn stream_and_store(
+        self: Arc<Self>,
+        key: String,
+        guard: GenerationGuard,
+        process: TranscodeProcess,
+    ) -> Body {
+        let (tx, rx) = mpsc::channel::<io::Result<Bytes>>(16);
+
+        tokio::spawn(async move {
+            let _guard = guard;
+            let part_path = self.part_path_for(&key);
+            let mut file = match tokio::fs::File::create(&part_path).await {
+                Ok(file) => Some(file),
+                Err(e) => {
+                    tracing::warn!("Failed to create transcode cache file: {}", e);
+                    None
+                }
+            };
+
+            let mut reader = ReaderStream::new(process.stdout);
+            let mut client_connected = true;
+            let mut size = 0u64;
+            let mut read_ok = true;
+
+            while let Some(chunk) = reader.next().await {
+                let bytes = match chunk {
+                    Ok(bytes) => bytes,
+                    Err(e) => {
+                        read_ok = false;
+                        let _ = tx.send(Err(e)).await;
+                        break;
+                    }
+                };
+
+                if let Some(f) = file.as_mut() {
+                    if let Err(e) = f.write_all(&bytes).await {
+                        tracing::warn!("Failed to write transcode cache: {}", e);
+                        file = None;
+                    }
+                }
+                size += bytes.len() as u64;
+
+                if client_connected && tx.send(Ok(bytes)).await.is_err() {
+                    client_connected = false;
+                }
+                if !client_connected && file.is_none() {
+                    break;
+                }
+            }
+            drop(tx);
+
+            let exit_ok = process.exit.await.unwrap_or(false);
+            let stored = match file {
+                Some(mut f) if read_ok && exit_ok && size > 0 => f.flush().await.is_ok(),
+                _ => false,
+            };
+
+            if stored
+                && tokio::fs::rename(&part_path, self.path_for(&key))
+                    .await
+                    .is_ok()
+            {
+                self.insert(key, size).await;
+            } else {
+                let _ = tokio::fs::remove_file(&part_path).await;
+            }
+        });
+
+        let stream = futures::stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|item| (item, rx))
+        });
+        Body::from_stream(stream)
+    }
+
+    /// 登记新缓存文件并按需淘汰
+    async fn insert(&self, key: String, size: u64) {
+        let evicted = {
+            let mut index = self.index.lock().await;
+            index.insert(key, size);
+            index.evict(self.max_size)
+        };
+
+        for key in evicted {
+            tracing::debug!("Evict transcode cache: {}", key);
+            let _ = tokio::fs::remove_file(self.path_for(&key)).await;
+        }
+    }
+
+    /// 当前缓存统计
+    pub async fn stats(&self) -> CacheStats {
+        let index = self.index.lock().await;
+        CacheStats {
+            files: index.entries.len(),
+            size: index.total_size,
+        }
+    }
+
+    /// 清空缓存，返回被清理的文件统计
+    pub async fn purge(&self) -> io::Result<CacheStats> {
+        let keys: Vec<String> = {
+            let mut index = self.index.lock().await;
+            let keys = index.entries.keys().cloned().collect();
+            *index = CacheIndex {
+                tick: index.tick,
+                ..Default::default()
+            };
+            keys
+        };
+
+        let mut stats = CacheStats::default();
+        for key in keys {
+            let path = self.path_for(&key);
+            if let Ok(metadata) = tokio::fs::metadata(&path).await {
+                match tokio::fs::remove_file(&path).await {
+                    Ok(()) => {
+                        stats.files += 1;
+                        stats.size += metadata.len();
+                    }
+                    Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// 缓存目录
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::TranscodeService;
+
+    fn temp_cache_dir() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "musicflow_transcode_cache_{}",
+            crate::utils::id_builder::generate_id()
+        ))
+    }
+
+    fn profile(bit_rate: u32) -> TranscodeProfile {
+        TranscodeProfile {
+            format: "mp3".to_string(),
+            bit_rate,
+        }
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let key = cache_key("song1", "2024-01-01 00:00:00", &profile(128));
+        assert!(key.ends_with(".mp3"));
+        assert_eq!(
+            key,
+            cache_key("song1", "2024-01-01 00:00:00", &profile(128))
+        );
+        // 歌曲更新或参数变化后键不同
+        assert_ne!(
+            key,
+            cache_key("song1", "2024-02-01 00:00:00", &profile(128))
+        );
+        assert_ne!(key, cache_key("song1", "2024-01-01 00:00:00", &profile(96)));
+    }
+
+    #[test]
+    fn test_lru_evict() {
+        let mut index = CacheIndex::default();
+        index.insert("a".to_string(), 40);
+        index.insert("b".to_string(), 40);
+        index.insert("c".to_string(), 40);
+
+        // 访问 a 后 b 成为最久未使用
+        let tick = index.next_tick();
+        index.entries.get_mut("a").unwrap().last_access = tick;
+
+        assert_eq!(index.evict(100), vec!["b".to_string()]);
+        assert_eq!(index.total_size, 80);
+        assert!(index.entries.contains_key("a"));
+        assert!(index.entries.contains_key("c"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_and_store() {
+        let dir = temp_cache_dir();
+        let cache = Arc::new(TranscodeCache::open(&dir, 25).unwrap());
+        let service = TranscodeService::new(
+            HashMap::from([("mp3".to_string(), "cat %s".to_string())]),
+            "mp3",
+        );
+
+        let source = dir.with_extension("flac");
+        tokio::fs::write(&source, b"0123456789").await.unwrap();
+
+        // 写入两个缓存文件
+        for (key, bit_rate) in [("first.mp3", 128), ("second.mp3", 96)] {
+            let guard = cache.lock(key).await;
+            let process = service.transcode(&profile(bit_rate), &source).unwrap();
+            let body = cache
+                .clone()
+                .stream_and_store(key.to_string(), guard, process);
+            let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+            assert_eq!(&bytes[..], b"0123456789");
+
+            // 写入锁释放即表示缓存写入完成
+            drop(cache.lock(key).await);
+        }
+        assert_eq!(cache.stats().await, CacheStats { files: 2, size: 20 });
+        assert!(cache.lookup("first.mp3").await.is_some());
+
+        // 超出上限时淘汰最久未使用的 second.mp3
+        let guard = cache.lock("third.mp3").await;
+        let process = service.transcode(&profile(64), &source).unwrap();
+        let body = cache
+            .clone()
+            .stream_and_store("third.mp3".to_string(), guard, process);
+        axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        drop(cache.lock("third.mp3").await);
+
+        assert!(cache.lookup("second.mp3").await.is_none());
+        assert!(!cache.path_for("second.mp3").exists());
+        assert!(cache.lookup("first.mp3").await.is_some());
+        assert!(cache.lookup("third.mp3").await.is_some());
+
+        // 重新打开时从磁盘恢复索引
+        let reopened = TranscodeCache::open(&dir, 25).unwrap();
+        assert_eq!(reopened.stats().await.files, 2);
+
+        let purged = cache.purge().await.unwrap();
+        assert_eq!(purged, CacheStats { files: 2, size: 20 });
+        assert_eq!(cache.stats().await, CacheStats::default());
+        assert!(!cache.path_for("first.mp3").exists());
+
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+        let _ = tokio::fs::remove_file(&source).await;
+    }
+
+    #[tokio::test]
+    async fn test_generation_locks_pruned() {
+        let cache = Arc::new(TranscodeCache::open(temp_cache_dir(), 0).unwrap());
+        let lock_key = cache.path_for("song.mp3").to_string_lossy().to_string();
+        let is_locked = || {
+            TRANSCODE_GENERATION_LOCKS
+                .lock()
+                .unwrap()
+                .contains_key(&lock_key)
+        };
+
+        let guard = cache.lock("song.mp3").await;
+        let waiter = tokio::spawn({
+            let cache = cache.clone();
+            async move { cache.lock("song.mp3").await }
+        });
+        tokio::task::yield_now().await;
+
+        // 有等待者时保留锁，最后一个持有者释放后移除
+        drop(guard);
+        assert!(is_locked());
+        drop(waiter.await.unwrap());
+        assert!(!is_locked());
+    }
+}
@@ -0,0 +1,517 @@
+//! 视频文件信息探测
+//!
+//! symphonia 只处理音频，这里直接解析容器头部获取时长和画面尺寸:
+//! - MP4: `moov/mvhd` 的时长，第一个带尺寸的 `trak/tkhd` 的宽高
+//! - Matroska/WebM: `Segment/Info` 的时长，第一个 `TrackEntry/Video` 的宽高
+//!
+//! 只读取头部元素，不扫描媒体数据；缺失的字段返回 `None`。
+
+use anyhow::{anyhow, Result};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// 支持的视频文件后缀
+pub const VIDEO_EXTENSIONS: &[&str] = &["mp4", "m4v", "mkv", "webm"];
+
+/// 单个头部元素的大小上限，避免损坏的文件导致大量内存分配
+const MAX_HEADER_SIZE: u64 = 64 * 1024 * 1024;
+
+/// 视频信息
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VideoMetadata {
+    pub content_type: String,
+    pub file_size: u64,
+    /// 时长 (秒)
+    pub duration_secs: Option<i64>,
+    /// 平均码率 (kbps)，由文件大小和时长估算
+    pub bit_rate: Option<i32>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+}
+
+/// 是否为支持的视频文件
+pub fn is_video_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| VIDEO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// 根据后缀获取 Content-Type
+pub fn content_type_for(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match ext.as_str() {
+        "mkv" => "video/x-matroska",
+        "webm" => "video/webm",
+        _ => "video/mp4",
+    }
+}
+
+/// 探测视频文件信息
+pub fn probe(path: &Path) -> Result<VideoMetadata> {
+    let file = File::open(path)?;
+    let file_size = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+
+    let mut magic = [0u8; 8];
+    reader.read_exact(&mut magic)?;
+    reader.seek(SeekFrom::Start(0))?;
+
+    let header = if magic[..4] == [0x1A, 0x45, 0xDF, 0xA3] {
+        probe_matroska(&mut reader)?
+    } else if &magic[4..8] == b"ftyp" {
+        probe_mp4(&mut reader, file_size)?
+    } else {
+        return Err(anyhow!("Unrecognized video container"));
+    };
+
+    let duration_secs = header
+        .duration
+        .filter(|d| d.is_finite() && *d > 0.0)
+        .map(|d| d.round() as i64);
+    let bit_rate = duration_secs
+        .filter(|d| *d > 0)
+        .map(|d| (file_size * 8 / 1000 / d as u64) as i32);
+
+    Ok(VideoMetadata {
+        content_type: content_type_for(path).to_string(),
+        file_size,
+        duration_secs,
+        bit_rate,
+        width: header.width,
+        height: header.height,
+    })
+}
+
+/// 容器头部信息
+#[derive(Debug, Default)]
+struct ContainerHeader {
+    /// 时长 (秒)
+    duration: Option<f64>,
+    width: Option<i32>,
+    height: Option<i32>,
+}
+
+// ============================================================================
+// MP4
+// ============================================================================
+
+/// 在顶层 box 中找到 `moov` 并解析
+fn probe_mp4<R: Read + Seek>(reader: &mut R, file_size: u64) -> Result<ContainerHeader> {
+    let mut offset: u64 = 0;
+    while file_size.saturating_sub(offset) >= 8 {
+        reader.seek(SeekFrom::Start(offset))?;
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+
+        let mut size = u32::from_be_bytes(header[..4].try_into()?) as u64;
+        let mut header_len = 8;
+        if size == 1 {
+            let mut large = [0u8; 8];
+            reader.read_exact(&mut large)?;
+            size = u64::from_be_bytes(large);
+            header_len = 16;
+        } else if size == 0 {
+            size = file_size - offset;
+        }
+        if size < header_len {
+            return Err(anyhow!("Invalid MP4 box size at offset {}", offset));
+        }
+
+        if &header[4..8] == b"moov" {
+            let len = size - header_len;
+            if len > MAX_HEADER_SIZE {
+                return Err(anyhow!("MP4 moov box too large: {} bytes", len));
+            }
+            let mut moov = vec![0u8; len as usize];
+            reader.read_exact(&mut moov)?;
+            return Ok(parse_moov(&moov));
+        }
+
+        // 损坏的 box 大小可能溢出或使偏移不再前进
+        offset = match offset.checked_add(size) {
+            Some(next) if next > offset => next,
+            _ => return Err(anyhow!("Invalid MP4 box size at offset {}", offset)),
+        };
+    }
+
+    Err(anyhow!("MP4 file has no moov box"))
+}
+
+/// 遍历 box 列表，返回 (类型, 内容)
+fn mp4_boxes(mut data: &[u8]) -> impl Iterator<Item = (&[u8], &[u8])> {
+    std::iter::from_fn(move || {
+        if data.len() < 8 {
+            return None;
+        }
+        let size = u32::from_be_bytes(data[..4].try_into().ok()?) as usize;
+        let kind = &data[4..8];
+        let (header_len, size) = match size {
+            0 => (8, data.len()),
+            1 => {
+                let large = u64::from_be_bytes(data.get(8..16)?.try_into().ok()?);
+                (16, usize::try_from(large).ok()?)
+            }
+            size => (8, size),
+        };
+        if size < header_len || size > data.len() {
+            return None;
+        }
+        let body = &data[header_len..size];
+        data = &data[size..];
+        Some((kind, body))
+    })
+}
+
+fn parse_moov(moov: &[u8]) -> ContainerHeader {
+    let mut header = ContainerHeader::default();
+
+    for (kind, body) in mp4_boxes(moov) {
+        match kind {
+            b"mvhd" => header.duration = parse_mvhd(body),
+            b"trak" if header.width.is_none() => {
+                let tkhd = mp4_boxes(body).find(|(kind, _)| kind == b"tkhd");
+                if let Some((width, height)) = tkhd.and_then(|(_, body)| parse_tkhd(body)) {
+                    header.width = Some(width);
+                    header.height = Some(height);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    header
+}
+
+/// 解析 `mvhd` 的时长 (秒)
+fn parse_mvhd(body: &[u8]) -> Option<f64> {
+    let version = *body.first()?;
+    let (timescale, duration) = if version == 1 {
+        (
+            be_u32(body, 20)?,
+            u64::from_be_bytes(body.get(24..32)?.try_into().ok()?),
+        )
+    } else {
+        (be_u32(body, 12)?, be_u32(body, 16)? as u64)
+    };
+    (timescale > 0).then(|| duration as f64 / timescale as f64)
+}
+
+/// 解析 `tkhd` 的画面尺寸，音频轨道的宽高为 0
+fn parse_tkhd(body: &[u8]) -> Option<(i32, i32)> {
+    let version = *body.first()?;
+    // 宽高位于矩阵之后；版本 1 的时间字段为 8 字节，偏移增加 12
+    let offset = if version == 1 { 88 } else { 76 };
+    // 16.16 定点数
+    let width = (be_u32(body, offset)? >> 16) as i32;
+    let height = (be_u32(body, offset + 4)? >> 16) as i32;
+    (width > 0 && height > 0).then_some((width, height))
+}
+
+fn be_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+// ============================================================================
+// Matroska / WebM
+// ============================================================================
+
+const EBML_HEADER: u32 = 0x1A45DFA3;
+const SEGMENT: u32 = 0x18538067;
+const INFO: u32 = 0x1549A966;
+const TIMECODE_SCALE: u32 = 0x2AD7B1;
+const DURATION: u32 = 0x4489;
+const TRACKS: u32 = 0x1654AE6B;
+const TRACK_ENTRY: u32 = 0xAE;
+const VIDEO: u32 = 0xE0;
+const PIXEL_WIDTH: u32 = 0xB0;
+const PIXEL_HEIGHT: u32 = 0xBA;
+const CLUSTER: u32 = 0x1F43B675;
+
+/// 未知长度 (直播流写出的 Segment 常见)
+const UNKNOWN_SIZE: u64 = u64::MAX;
+
+/// 依次读取 Segment 的子元素，找到 Info 和 Tracks 后停止
+fn probe_matroska<R: Read + Seek>(reader: &mut R) -> Result<ContainerHeader> {
+    let (id, size) = read_element_header(reader)?;
+    if id != EBML_HEADER || size == UNKNOWN_SIZE {
+        return Err(anyhow!("Invalid EBML header"));
+    }
+    reader.seek(SeekFrom::Current(size as i64))?;
+
+    let (id, _) = read_element_header(reader)?;
+    if id != SEGMENT {
+        return Err(anyhow!("Matroska file has no Segment"));
+    }
+
+    let mut header = ContainerHeader::default();
+    let mut has_info = false;
+    let mut has_tracks = false;
+
+    while !(has_info && has_tracks) {
+        let Ok((id, size)) = read_element_header(reader) else {
+            break;
+        };
+        // 媒体数据开始后不再有需要的头部元素
+        if id == CLUSTER || size == UNKNOWN_SIZE {
+            break;
+        }
+
+        match id {
+            INFO | TRACKS => {
+                if size > MAX_HEADER_SIZE {
+                    return Err(anyhow!("Matroska header element too large: {} bytes", size));
+                }
+                let mut body = vec![0u8; size as usize];
+                reader.read_exact(&mut body)?;
+                if id == INFO {
+                    header.duration = parse_info(&body);
+                    has_info = true;
+                } else {
+                    if let Some((width, height)) = parse_tracks(&body) {
+                        header.width = Some(width);
+                        header.height = Some(height);
+                    }
+                    has_tracks = true;
+                }
+            }
+            _ => {
+                reader.seek(SeekFrom::Current(size as i64))?;
+            }
+        }
+    }
+
+    if !has_info && !has_tracks {
+        return Err(anyhow!("Matroska file has no Info or Tracks"));
+    }
+
+    Ok(header)
+}
+
+/// 读取元素 ID 和长度
+fn read_element_header<R: Read>(reader: &mut R) -> Result<(u32, u64)> {
+    let (id, _) = read_vint(reader, true)?;
+    let (size, len) = read_vint(reader, false)?;
+    // 数据位全为 1 表示未知长度
+    let size = if size == (1u64 << (7 * len)) - 1 {
+        UNKNOWN_SIZE
+    } else {
+        size
+    };
+    Ok((id as u32, size))
+}
+
+/// 读取 EBML 变长整数，返回 (值, 字节数)
+///
+/// 元素 ID 保留长度标记位，元素长度去掉标记位
+fn read_vint<R: Read>(reader: &mut R, keep_marker: bool) -> Result<(u64, usize)> {
+    let mut first = [0u8; 1];
+    reader.read_exact(&mut first)?;
+    let len = first[0].leading_zeros() as usize + 1;
+    if len > 8 {
+        return Err(anyhow!("Invalid EBML variable-length integer"));
+    }
+
+    let mut value = if keep_marker {
+        first[0] as u64
+    } else {
+        (first[0] as u64) & (0xFF >> len)
+    };
+    let mut rest = [0u8; 7];
+    reader.read_exact(&mut rest[..len - 1])?;
+    for byte in &rest[..len - 1] {
+        value = (value << 8) | *byte as u64;
+    }
+
+    Ok((value, len))
+}
+
+/// 遍历内存中的 EBML 元素，返回 (ID, 内容)
+fn ebml_elements(mut data: &[u8]) -> impl Iterator<Item = (u32, &[u8])> {
+    std::iter::from_fn(move || {
+        let mut cursor = data;
+        let (id, size) = read_element_header(&mut cursor).ok()?;
+        let size = usize::try_from(size).ok().filter(|s| *s <= cursor.len())?;
+        let body = &cursor[..size];
+        data = &cursor[size..];
+        Some((id, body))
+    })
+}
+
+fn parse_info(info: &[u8]) -> Option<f64> {
+    let mut timecode_scale = 1_000_000u64;
+    let mut duration = None;
+
+    for (id, body) in ebml_elements(info) {
+        match id {
+            TIMECODE_SCALE => timecode_scale = ebml_uint(body),
+            DURATION => duration = ebml_float(body),
+            _ => {}
+        }
+    }
+
+    // Duration 以 TimecodeScale 纳秒为单位
+    duration.map(|d| d * timecode_scale as f64 / 1_000_000_000.0)
+}
+
+fn parse_tracks(tracks: &[u8]) -> Option<(i32, i32)> {
+    ebml_elements(tracks)
+        .filter(|(id, _)| *id == TRACK_ENTRY)
+        .flat_map(|(_, entry)| ebml_elements(entry))
+        .filter(|(id, _)| *id == VIDEO)
+        .find_map(|(_, video)| {
+            let mut width = 0;
+            let mut height = 0;
+            for (id, body) in ebml_elements(video) {
+                match id {
+                    PIXEL_WIDTH => width = ebml_uint(body) as i32,
+                    PIXEL_HEIGHT => height = ebml_uint(body) as i32,
+                    _ => {}
+                }
+            }
+            (width > 0 && height > 0).then_some((width, height))
+        })
+}
+
+fn ebml_uint(body: &[u8]) -> u64 {
+    body.iter()
+        .take(8)
+        .fold(0u64, |value, byte| (value << 8) | *byte as u64)
+}
+
+fn ebml_float(body: &[u8]) -> Option<f64> {
+    match body.len() {
+        4 => Some(f32::from_be_bytes(body.try_into().ok()?) as f64),
+        8 => Some(f64::from_be_bytes(body.try_into().ok()?)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mp4_box(kind: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut data = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(kind);
+        data.extend_from_slice(body);
+        data
+    }
+
+    fn tkhd(width: u32, height: u32) -> Vec<u8> {
+        let mut body = vec![0u8; 76];
+        body.extend_from_slice(&(width << 16).to_be_bytes());
+        body.extend_from_slice(&(height << 16).to_be_bytes());
+        mp4_box(b"tkhd", &body)
+    }
+
+    fn ebml(id: u32, body: &[u8]) -> Vec<u8> {
+        let mut data: Vec<u8> = id
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|byte| *byte == 0)
+            .collect();
+        // 8 字节长度
+        data.push(0x01);
+        data.extend_from_slice(&(body.len() as u64).to_be_bytes()[1..]);
+        data.extend_from_slice(body);
+        data
+    }
+
+    fn write_temp(name: &str, data: &[u8]) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("video_probe_{}_{}", std::process::id(), name));
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_probe_mp4() {
+        // mvhd 版本 0: timescale 1000, duration 90500
+        let mut mvhd = vec![0u8; 12];
+        mvhd.extend_from_slice(&1000u32.to_be_bytes());
+        mvhd.extend_from_slice(&90500u32.to_be_bytes());
+        mvhd.extend_from_slice(&[0u8; 80]);
+
+        let mut moov = mp4_box(b"mvhd", &mvhd);
+        // 音频轨道在前，宽高为 0
+        moov.extend(mp4_box(b"trak", &tkhd(0, 0)));
+        moov.extend(mp4_box(b"trak", &tkhd(1920, 1080)));
+
+        let mut data = mp4_box(b"ftyp", b"isom\0\0\0\0");
+        data.extend(mp4_box(b"mdat", &[0u8; 2000]));
+        data.extend(mp4_box(b"moov", &moov));
+
+        let path = write_temp("a.mp4", &data);
+        let metadata = probe(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(metadata.content_type, "video/mp4");
+        assert_eq!(metadata.file_size, data.len() as u64);
+        assert_eq!(metadata.duration_secs, Some(91));
+        assert_eq!(metadata.width, Some(1920));
+        assert_eq!(metadata.height, Some(1080));
+        assert!(metadata.bit_rate.is_some());
+    }
+
+    #[test]
+    fn test_probe_mp4_invalid_box_size() {
+        // 64 位 box 大小使偏移溢出
+        let mut data = mp4_box(b"ftyp", b"isom\0\0\0\0");
+        data.extend_from_slice(&1u32.to_be_bytes());
+        data.extend_from_slice(b"mdat");
+        data.extend_from_slice(&u64::MAX.to_be_bytes());
+        data.extend_from_slice(&[0u8; 16]);
+
+        let path = write_temp("d.mp4", &data);
+        let error = probe(&path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+        assert!(error.to_string().contains("Invalid MP4 box size"));
+    }
+
+    #[test]
+    fn test_probe_webm() {
+        let mut info = ebml(TIMECODE_SCALE, &1_000_000u32.to_be_bytes()[1..]);
+        info.extend(ebml(DURATION, &12_345.0f64.to_be_bytes()));
+
+        let mut audio = ebml(0xE1, &[]);
+        audio = ebml(TRACK_ENTRY, &audio);
+        let mut video = ebml(PIXEL_WIDTH, &[0x02, 0x80]);
+        video.extend(ebml(PIXEL_HEIGHT, &[0x01, 0x68]));
+        let mut tracks = audio;
+        tracks.extend(ebml(TRACK_ENTRY, &ebml(VIDEO, &video)));
+
+        let mut segment = ebml(0x114D9B74, &[0u8; 16]); // SeekHead
+        segment.extend(ebml(INFO, &info));
+        segment.extend(ebml(TRACKS, &tracks));
+        segment.extend(ebml(CLUSTER, &[0u8; 32]));
+
+        let mut data = ebml(EBML_HEADER, &ebml(0x4282, b"webm"));
+        data.extend(ebml(SEGMENT, &segment));
+
+        let path = write_temp("b.webm", &data);
+        let metadata = probe(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(metadata.content_type, "video/webm");
+        assert_eq!(metadata.duration_secs, Some(12));
+        assert_eq!(metadata.width, Some(640));
+        assert_eq!(metadata.height, Some(360));
+    }
+
+    #[test]
+    fn test_probe_unknown_container() {
+        let path = write_temp("c.mkv", b"not a video file");
+        assert!(probe(&path).is_err());
+        std::fs::remove_file(&path).ok();
+
+        assert!(is_video_file(Path::new("/a/b.MKV")));
+        assert!(!is_video_file(Path::new("/a/b.mp3")));
+    }
+}